@@ -0,0 +1,376 @@
+use std::{
+    env, io,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(not(feature = "json-store"))]
+use std::sync::Mutex;
+#[cfg(not(feature = "json-store"))]
+use rusqlite::{Connection, params};
+
+/// Feedback the user gave on a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rating {
+    Up,
+    Down,
+}
+
+impl Rating {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Rating::Up => "up",
+            Rating::Down => "down",
+        }
+    }
+
+    #[cfg(not(feature = "json-store"))]
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "up" => Some(Rating::Up),
+            "down" => Some(Rating::Down),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredConversation {
+    pub session_id: String,
+    /// Milliseconds since the unix epoch.
+    pub timestamp_ms: i64,
+    /// `None` for the copy saved after every answer.
+    pub rating: Option<Rating>,
+    /// The conversation as returned by the core's `export_conversation` tool.
+    pub messages_json: String,
+}
+
+impl StoredConversation {
+    pub fn new(session_id: &str, rating: Option<Rating>, messages_json: String) -> Self {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or_default();
+        Self {
+            session_id: session_id.to_string(),
+            timestamp_ms,
+            rating,
+            messages_json,
+        }
+    }
+}
+
+/// Where exported conversations and their ratings are kept.
+pub trait ConversationStore: Send + Sync {
+    fn save(&self, conversation: &StoredConversation) -> io::Result<()>;
+
+    /// Conversations with the given rating, oldest first. Not read by the
+    /// server itself; it is how the feedback is queried for analysis.
+    #[allow(dead_code)]
+    fn rated(&self, rating: Rating) -> io::Result<Vec<StoredConversation>>;
+
+    /// Number of conversations with the given rating.
+    fn count(&self, rating: Rating) -> io::Result<usize>;
+}
+
+/// The store conversations are written to, opened on first use. `None` when
+/// neither `CONVERSATION_DB` nor `SAVE_PATH` is set to a non-empty value,
+/// or the store could not be opened.
+pub fn conversation_store() -> Option<&'static dyn ConversationStore> {
+    static STORE: OnceLock<Option<Box<dyn ConversationStore>>> = OnceLock::new();
+    STORE.get_or_init(open_store).as_deref()
+}
+
+#[cfg(not(feature = "json-store"))]
+fn open_store() -> Option<Box<dyn ConversationStore>> {
+    let path = sqlite_path(non_empty_var("CONVERSATION_DB"), non_empty_var("SAVE_PATH"))?;
+    match SqliteConversationStore::open(&path) {
+        Ok(store) => Some(Box::new(store)),
+        Err(e) => {
+            eprintln!("Failed to open conversation store {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// The variable's value, `None` when it is unset or blank. The sample
+/// `.env` leaves variables empty, and SQLite opens an empty path as a
+/// temporary database that is lost on restart.
+fn non_empty_var(key: &str) -> Option<String> {
+    env::var(key).ok().filter(|v| !v.trim().is_empty())
+}
+
+/// `CONVERSATION_DB`, or `conversations.sqlite` inside `SAVE_PATH`.
+#[cfg(not(feature = "json-store"))]
+fn sqlite_path(conversation_db: Option<String>, save_path: Option<String>) -> Option<String> {
+    conversation_db.or_else(|| save_path.map(|dir| format!("{}/conversations.sqlite", dir)))
+}
+
+#[cfg(feature = "json-store")]
+fn open_store() -> Option<Box<dyn ConversationStore>> {
+    let out_dir = non_empty_var("SAVE_PATH")?;
+    Some(Box::new(JsonFileConversationStore { out_dir }))
+}
+
+/* --------------------------------------------------------------------- */
+/*  SQLite                                                               */
+/* --------------------------------------------------------------------- */
+
+/// One row per saved conversation; a rated conversation is saved again with
+/// its rating, so the unrated rows hold every answered conversation.
+#[cfg(not(feature = "json-store"))]
+pub struct SqliteConversationStore {
+    connection: Mutex<Connection>,
+}
+
+#[cfg(not(feature = "json-store"))]
+impl SqliteConversationStore {
+    pub fn open(path: &str) -> io::Result<Self> {
+        if let Some((dir, _)) = path.rsplit_once('/') {
+            std::fs::create_dir_all(dir)?;
+        }
+        let connection = Connection::open(path).map_err(io::Error::other)?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS conversations (
+                    id            INTEGER PRIMARY KEY,
+                    session_id    TEXT NOT NULL,
+                    timestamp     INTEGER NOT NULL,
+                    rating        TEXT,
+                    messages_json TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS conversations_rating ON conversations (rating);",
+            )
+            .map_err(io::Error::other)?;
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+}
+
+#[cfg(not(feature = "json-store"))]
+impl ConversationStore for SqliteConversationStore {
+    fn save(&self, conversation: &StoredConversation) -> io::Result<()> {
+        let connection = self.connection.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        connection
+            .execute(
+                "INSERT INTO conversations (session_id, timestamp, rating, messages_json)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    conversation.session_id,
+                    conversation.timestamp_ms,
+                    conversation.rating.map(|r| r.as_str()),
+                    conversation.messages_json,
+                ],
+            )
+            .map_err(io::Error::other)?;
+        Ok(())
+    }
+
+    fn rated(&self, rating: Rating) -> io::Result<Vec<StoredConversation>> {
+        let connection = self.connection.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        let mut statement = connection
+            .prepare(
+                "SELECT session_id, timestamp, rating, messages_json FROM conversations
+                 WHERE rating = ?1 ORDER BY timestamp, id",
+            )
+            .map_err(io::Error::other)?;
+        let rows = statement
+            .query_map([rating.as_str()], |row| {
+                Ok(StoredConversation {
+                    session_id: row.get(0)?,
+                    timestamp_ms: row.get(1)?,
+                    rating: row.get::<_, Option<String>>(2)?.as_deref().and_then(Rating::parse),
+                    messages_json: row.get(3)?,
+                })
+            })
+            .map_err(io::Error::other)?;
+        rows.collect::<Result<_, _>>().map_err(io::Error::other)
+    }
+
+    fn count(&self, rating: Rating) -> io::Result<usize> {
+        let connection = self.connection.lock().map_err(|e| io::Error::other(e.to_string()))?;
+        connection
+            .query_row(
+                "SELECT COUNT(*) FROM conversations WHERE rating = ?1",
+                [rating.as_str()],
+                |row| row.get(0),
+            )
+            .map_err(io::Error::other)
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  JSON files                                                           */
+/* --------------------------------------------------------------------- */
+
+/// The original layout: `{SAVE_PATH}/all/{id}.json` after every answer and
+/// `{SAVE_PATH}/up/up_{id}.json` or `{SAVE_PATH}/down/down_{id}.json` on
+/// feedback, each overwritten by the latest save of the session.
+#[cfg(feature = "json-store")]
+pub struct JsonFileConversationStore {
+    out_dir: String,
+}
+
+#[cfg(feature = "json-store")]
+impl JsonFileConversationStore {
+    fn dir(&self, rating: Option<Rating>) -> String {
+        match rating {
+            Some(rating) => format!("{}/{}", self.out_dir, rating.as_str()),
+            None => format!("{}/all", self.out_dir),
+        }
+    }
+
+    fn file_prefix(rating: Option<Rating>) -> String {
+        rating.map(|r| format!("{}_", r.as_str())).unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "json-store")]
+impl ConversationStore for JsonFileConversationStore {
+    fn save(&self, conversation: &StoredConversation) -> io::Result<()> {
+        let dir = self.dir(conversation.rating);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(
+            format!(
+                "{}/{}{}.json",
+                dir,
+                Self::file_prefix(conversation.rating),
+                conversation.session_id
+            ),
+            &conversation.messages_json,
+        )
+    }
+
+    fn rated(&self, rating: Rating) -> io::Result<Vec<StoredConversation>> {
+        let prefix = Self::file_prefix(Some(rating));
+        let entries = match std::fs::read_dir(self.dir(Some(rating))) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+        let mut conversations = vec![];
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some(session_id) = name
+                .strip_prefix(&prefix)
+                .and_then(|n| n.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            let timestamp_ms = entry
+                .metadata()?
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or_default();
+            conversations.push(StoredConversation {
+                session_id: session_id.to_string(),
+                timestamp_ms,
+                rating: Some(rating),
+                messages_json: std::fs::read_to_string(entry.path())?,
+            });
+        }
+        conversations.sort_by_key(|c| c.timestamp_ms);
+        Ok(conversations)
+    }
+
+    fn count(&self, rating: Rating) -> io::Result<usize> {
+        let prefix = Self::file_prefix(Some(rating));
+        let entries = match std::fs::read_dir(self.dir(Some(rating))) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let mut count = 0;
+        for entry in entries {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if name.starts_with(&prefix) && name.ends_with(".json") {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation(session_id: &str, timestamp_ms: i64, rating: Option<Rating>) -> StoredConversation {
+        StoredConversation {
+            session_id: session_id.to_string(),
+            timestamp_ms,
+            rating,
+            messages_json: format!(r#"[{{"role":"user","content":"question of {}"}}]"#, session_id),
+        }
+    }
+
+    #[cfg(not(feature = "json-store"))]
+    fn store() -> impl ConversationStore {
+        SqliteConversationStore::open(":memory:").unwrap()
+    }
+
+    #[cfg(feature = "json-store")]
+    fn store() -> impl ConversationStore {
+        let out_dir = env::temp_dir().join(format!("urska-store-{}", uuid::Uuid::new_v4()));
+        JsonFileConversationStore {
+            out_dir: out_dir.to_string_lossy().to_string(),
+        }
+    }
+
+    #[cfg(not(feature = "json-store"))]
+    #[test]
+    fn sqlite_path_prefers_conversation_db() {
+        assert_eq!(
+            sqlite_path(Some("/data/urska.sqlite".into()), Some("/data".into())).as_deref(),
+            Some("/data/urska.sqlite")
+        );
+        assert_eq!(
+            sqlite_path(None, Some("/data".into())).as_deref(),
+            Some("/data/conversations.sqlite")
+        );
+        assert_eq!(sqlite_path(None, None), None);
+    }
+
+    #[test]
+    fn rated_conversation_round_trips() {
+        let store = store();
+        let saved = conversation("session-a", 1_000, Some(Rating::Up));
+        store.save(&conversation("session-a", 900, None)).unwrap();
+        store.save(&saved).unwrap();
+
+        let up = store.rated(Rating::Up).unwrap();
+        assert_eq!(up.len(), 1);
+        assert_eq!(up[0].session_id, "session-a");
+        assert_eq!(up[0].rating, Some(Rating::Up));
+        assert_eq!(up[0].messages_json, saved.messages_json);
+        assert!(store.rated(Rating::Down).unwrap().is_empty());
+        assert_eq!(store.count(Rating::Up).unwrap(), 1);
+        assert_eq!(store.count(Rating::Down).unwrap(), 0);
+    }
+
+    #[test]
+    fn rated_conversations_are_oldest_first() {
+        let store = store();
+        for (session_id, timestamp_ms) in [("session-b", 2_000), ("session-a", 1_000), ("session-c", 3_000)] {
+            store.save(&conversation(session_id, timestamp_ms, Some(Rating::Down))).unwrap();
+            // the file store orders by modification time
+            #[cfg(feature = "json-store")]
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
+
+        let sessions: Vec<String> = store
+            .rated(Rating::Down)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.session_id)
+            .collect();
+        #[cfg(not(feature = "json-store"))]
+        assert_eq!(sessions, ["session-a", "session-b", "session-c"]);
+        #[cfg(feature = "json-store")]
+        assert_eq!(sessions, ["session-b", "session-a", "session-c"]);
+        assert_eq!(store.count(Rating::Down).unwrap(), 3);
+    }
+}
@@ -1,18 +1,24 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::AtomicUsize;
 use actix_web::{Error, web, App, HttpRequest, HttpResponse, HttpServer};
 use actix_web_actors::ws;
 use rmcp::{model::ProgressNotificationParam, service::RunningService, transport::StreamableHttpClientTransport, ServiceExt};
 use tokio::sync::{mpsc, Mutex};
 use uuid::Uuid;
 
+use crate::conversation_store::{Rating, conversation_store};
 use crate::profile::Profile;
+use crate::rate_limit::{RateLimiter, SharedRateLimiter};
 use crate::session::{ChatSession, ProgressHandler};
+mod conversation_store;
 mod session;
 mod queue;
 mod ldap;
 mod profile;
 mod messages;
+mod notification_log;
+mod rate_limit;
 
 type SessionStore = Arc<Mutex<HashMap<String, Profile>>>;
 
@@ -21,12 +27,27 @@ async fn main() -> std::io::Result<()> {
     let _  = dotenv::dotenv();
     let queue_addr = Arc::new(Mutex::new(queue::QueueManager::new()));
     let sessions: SessionStore = Arc::new(Mutex::new(HashMap::new()));
+    let rate_limiter: SharedRateLimiter = Arc::new(std::sync::Mutex::new(RateLimiter::from_env()));
+
+    let counts = web::block(|| {
+        conversation_store().map(|store| {
+            (
+                store.count(Rating::Up).unwrap_or_default(),
+                store.count(Rating::Down).unwrap_or_default(),
+            )
+        })
+    })
+    .await;
+    if let Ok(Some((up, down))) = counts {
+        println!("Conversation store: {} rated up, {} rated down", up, down);
+    }
 
     println!("Starting Urska proxy on http://127.0.0.1:8080/ws");
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(queue_addr.clone()))
             .app_data(web::Data::new(sessions.clone()))
+            .app_data(web::Data::new(rate_limiter.clone()))
             .route("/ws", web::get().to(ws_index))
     })
     .bind(("127.0.0.1", 8080))?
@@ -40,6 +61,7 @@ pub async fn ws_index(
     stream: web::Payload,
     queue: web::Data<Arc<Mutex<queue::QueueManager>>>,
     sessions: web::Data<SessionStore>,
+    rate_limiter: web::Data<SharedRateLimiter>,
 ) -> Result<HttpResponse, Error> {
     
     // 1) channel for progress notifications
@@ -49,8 +71,10 @@ pub async fn ws_index(
     let transport = StreamableHttpClientTransport::from_uri("http://localhost:8004/mcp");
 
 
+    let pending_notifications = Arc::new(AtomicUsize::new(0));
     let handler = ProgressHandler { 
-        notification_tx 
+        notification_tx,
+        pending: pending_notifications.clone(),
     };
     let client: RunningService<_, _> = handler
         .serve(transport)
@@ -62,16 +86,22 @@ pub async fn ws_index(
 
     let queue = queue.get_ref().clone();
     let sessions = sessions.get_ref().clone();
+    let rate_limiter = rate_limiter.get_ref().clone();
     let authenticated_as = None;
     // 3) hand off to our ChatSession actor
     ws::start(
         ChatSession {
             id: Uuid::new_v4().to_string(),
-            mcp_client: client,
+            mcp_client: Arc::new(client),
+            current_job: Arc::new(std::sync::Mutex::new(None)),
             notification_reciever: Arc::new(Mutex::new(notif_rx)),
+            pending_notifications,
+            notification_log: Arc::new(Mutex::new(notification_log::NotificationLog::default())),
             queue,
             sessions,
+            rate_limiter,
             authenticated_as,
+            share_profile_context: false,
         },
         &req,
         stream,
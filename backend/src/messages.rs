@@ -1,9 +1,10 @@
 use actix::{Addr, Handler, Message};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{
     profile::Profile, 
-    queue::PositionInQueue, 
+    queue::{JobId, QueuePosition}, 
     session::ChatSession
 };
 
@@ -17,6 +18,12 @@ pub enum MessageType {
     ThumbsDown,
     Logout,
     Prompt,
+    /// Content is "true" or "false". Profile context is only forwarded to
+    /// Urška after the user opted in.
+    ShareProfileContext,
+    /// Content is a `ResumeRequest`. Sent after reconnecting to receive the
+    /// answer that was in progress.
+    ResumeJob,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,6 +33,16 @@ pub struct FrontendMessage {
 }
 
 
+/// Identifies the job to resume and the user resuming it, who must be the
+/// one that asked.
+#[derive(Debug, Deserialize)]
+pub struct ResumeRequest {
+    /// Id from `JobStarted`.
+    pub job_id: JobId,
+    /// Login token from `LoginToken`.
+    pub session_token: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LoginCredentials {
     pub username: String,
@@ -36,10 +53,17 @@ pub struct LoginCredentials {
 #[serde(tag = "type", content = "data")]
 pub enum BackendMessage {
     Chunk(String),
+    /// The finished answer as returned by Urška, after the rewrites applied
+    /// to it once it was streamed. Replaces the streamed chunks.
+    Answer(String),
     Notification(String),
-    QueuePosition(PositionInQueue),
+    QueuePosition(QueuePosition),
     LoginProfile(Profile),
     LoginToken(String),
+    /// Heuristic support score of the last answer, as computed by Urška.
+    Confidence(Value),
+    /// Id of the job answering the last prompt, for `ResumeJob`.
+    JobStarted(JobId),
     Error(String),
     End,
 }
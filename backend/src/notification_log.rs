@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::{
+    env,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::fs;
+
+/// Notifications shown to the user while one request was processed,
+/// persisted when `NOTIFICATION_LOG=true` so a problematic query can be
+/// replayed exactly as the user saw it.
+#[derive(Debug, Default, Serialize)]
+pub struct NotificationLog {
+    pub entries: Vec<LoggedNotification>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoggedNotification {
+    /// Milliseconds since the unix epoch.
+    pub timestamp_ms: u128,
+    pub message: String,
+}
+
+pub fn notification_log_enabled() -> bool {
+    env::var("NOTIFICATION_LOG")
+        .map(|v| v.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+impl NotificationLog {
+    pub fn record(&mut self, message: &str) {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        self.entries.push(LoggedNotification {
+            timestamp_ms,
+            message: message.to_string(),
+        });
+    }
+
+    /// Writes the log to `{NOTIFICATION_LOG_PATH}/{session_id}_{request_id}.json`
+    /// (falling back to `{SAVE_PATH}/notifications`) and clears it.
+    pub async fn flush(&mut self, session_id: &str, request_id: &str) -> std::io::Result<()> {
+        let dir = match env::var("NOTIFICATION_LOG_PATH") {
+            Ok(dir) => dir,
+            Err(_) => match env::var("SAVE_PATH") {
+                Ok(save_path) => format!("{}/notifications", save_path),
+                Err(_) => {
+                    self.entries.clear();
+                    return Ok(());
+                }
+            },
+        };
+        self.flush_to(&dir, session_id, request_id).await
+    }
+
+    async fn flush_to(&mut self, dir: &str, session_id: &str, request_id: &str) -> std::io::Result<()> {
+        let entries = std::mem::take(&mut self.entries);
+        fs::create_dir_all(dir).await?;
+        let content = serde_json::to_string_pretty(&NotificationLog { entries })?;
+        fs::write(format!("{}/{}_{}.json", dir, session_id, request_id), content).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn completed_request_is_written_in_order() {
+        let dir = env::temp_dir().join(format!("notification_log_{}", uuid::Uuid::new_v4()));
+        let dir = dir.to_str().unwrap();
+        let mut log = NotificationLog::default();
+        for message in ["Preparing...", "Calling ask_about_programmes", "Writing the answer..."] {
+            log.record(message);
+        }
+
+        log.flush_to(dir, "session1", "request1").await.unwrap();
+
+        assert!(log.entries.is_empty());
+        let saved = std::fs::read_to_string(format!("{}/session1_request1.json", dir)).unwrap();
+        let saved: serde_json::Value = serde_json::from_str(&saved).unwrap();
+        let entries = saved["entries"].as_array().unwrap();
+        let messages: Vec<&str> = entries.iter().map(|e| e["message"].as_str().unwrap()).collect();
+        assert_eq!(messages, ["Preparing...", "Calling ask_about_programmes", "Writing the answer..."]);
+        let timestamps: Vec<u64> = entries.iter().map(|e| e["timestamp_ms"].as_u64().unwrap()).collect();
+        assert!(timestamps.is_sorted());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
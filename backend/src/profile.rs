@@ -34,19 +34,21 @@ impl Profile {
         Ok(Self { role: ProfileRole::Employee, username, raw_attributes: attrs })
     }
 
-    /// Returns a clean, LLM-friendly context with only meaningful fields.
+    /// Returns a clean, LLM-friendly context with only non-sensitive fields
+    /// (role, programme, study level and enrolment year). Identity fields such
+    /// as username, name or email are never included.
     pub fn sanitized_context(&self) -> serde_json::Value {
         let get = |key: &str| self.raw_attributes.get(key).and_then(|v| v.first()).map(|s| s.as_str());
 
         let mut ctx = serde_json::Map::new();
         ctx.insert("role".into(), serde_json::json!(self.role));
-        ctx.insert("username".into(), serde_json::json!(self.username));
-        ctx.insert("name".into(), serde_json::json!(get("cn").or_else(|| get("displayName")).unwrap_or("")));
-        ctx.insert("email".into(), serde_json::json!(get("mail").unwrap_or("")));
-        ctx.insert("country".into(), serde_json::json!(get("schacCountryOfCitizenship").unwrap_or("")));
 
-        if let ProfileRole::Student = self.role {
-            if let Some(info) = self.decode_student_number() {
+        if let Some(programme) = get("ou").filter(|p| !p.is_empty()) {
+            ctx.insert("programme".into(), serde_json::json!(programme));
+        }
+
+        if let ProfileRole::Student = self.role
+            && let Some(info) = self.decode_student_number() {
                 let level = match info.study_level {
                     1 => "bachelor",
                     2 => "master",
@@ -56,7 +58,6 @@ impl Profile {
                 ctx.insert("enrolment_year".into(), serde_json::json!(info.enrolment_year));
                 ctx.insert("study_level".into(), serde_json::json!(level));
             }
-        }
 
         serde_json::Value::Object(ctx)
     }
@@ -81,9 +82,47 @@ impl Profile {
 }
 
 fn extract_uid(ldap_resp: &str) -> Result<String, String> {
-    let uid_chunk = ldap_resp.split(",").nth(0)
+    let uid_chunk = ldap_resp.split(",").next()
         .ok_or_else(|| String::from("Could not extract uid chunk from ldap string"))?;
     let username = uid_chunk.split("=").nth(1)
         .ok_or_else(|| String::from("Could not extract uid value from ldap string"))?;
     Ok(username.into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn student() -> Profile {
+        let attrs = HashMap::from([
+            ("uid".to_string(), vec!["89231125".to_string()]),
+            ("cn".to_string(), vec!["Ana Novak".to_string()]),
+            ("mail".to_string(), vec!["89231125@student.upr.si".to_string()]),
+            ("ou".to_string(), vec!["Computer Science".to_string()]),
+        ]);
+        Profile::try_from_student_string("uid=89231125,ou=students,dc=upr,dc=si".into(), attrs).unwrap()
+    }
+
+    #[test]
+    fn context_carries_the_programme_for_ambiguous_questions() {
+        // "my programme" and "my second year" can only be resolved with these
+        let context = student().sanitized_context();
+
+        assert_eq!(context["role"], "Student");
+        assert_eq!(context["programme"], "Computer Science");
+        assert_eq!(context["enrolment_year"], 2023);
+        assert_eq!(context["study_level"], "bachelor");
+    }
+
+    #[test]
+    fn context_drops_username_name_and_email() {
+        let profile = student();
+        assert_eq!(profile.username, "89231125");
+
+        let context = profile.sanitized_context().to_string();
+
+        assert!(!context.contains("89231125"));
+        assert!(!context.contains("Ana Novak"));
+        assert!(!context.contains("student.upr.si"));
+    }
+}
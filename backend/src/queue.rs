@@ -1,43 +1,185 @@
 use actix::prelude::*;
-use std::collections::VecDeque;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+    time::{Duration, Instant},
+};
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use uuid::Uuid;
 
+use crate::messages::{BackendMessage, SendWsText};
+
 pub const MAX_CONCURRENT: usize = 1; 
+/// Number of recent job durations the wait estimate is averaged over.
+const DURATION_WINDOW: usize = 20;
+/// How long the output of a finished job can still be resumed.
+const DEFAULT_JOB_BUFFER_TTL_SECS: u64 = 300;
 
 pub type PositionInQueue = usize;
 pub type JobId = Uuid;
 pub enum QueueMessage {
-    PositionUpade(PositionInQueue),
+    PositionUpade(QueuePosition),
     StartJob(JobId)
 }
 
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuePosition {
+    pub position: PositionInQueue,
+    /// `None` until a job has finished since startup.
+    pub estimated_wait_secs: Option<u64>,
+}
+
 
 #[derive(Debug)]
 struct QueueItem {
     job_id: JobId,
     sender: Sender<QueueMessage>,
+    started: Option<Instant>,
+}
+
+/// Everything sent to the client for one job, so a client that reconnects
+/// mid-answer can be sent it again and receive the rest.
+#[derive(Debug)]
+struct JobBuffer {
+    /// Username of the user who asked; only they can resume the job.
+    owner: String,
+    /// Serialized `BackendMessage`s, in the order they were sent.
+    messages: Vec<String>,
+    listener: Option<Recipient<SendWsText>>,
+    finished_at: Option<Instant>,
 }
 
 #[derive(Debug)]
 pub struct QueueManager {
     waiting: VecDeque<QueueItem>,
     running: Vec<QueueItem>,
+    recent_durations: VecDeque<Duration>,
+    job_buffers: HashMap<JobId, JobBuffer>,
+    job_buffer_ttl: Duration,
 }
 
 impl QueueManager {
     pub fn new() -> Self {
+        let ttl_secs = env::var("JOB_BUFFER_TTL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_JOB_BUFFER_TTL_SECS);
         Self {
             waiting: VecDeque::new(),
             running: Vec::new(),
+            recent_durations: VecDeque::new(),
+            job_buffers: HashMap::new(),
+            job_buffer_ttl: Duration::from_secs(ttl_secs),
+        }
+    }
+
+    /// Starts buffering the output of `owner`'s job `job_id`, sent live to
+    /// `listener`.
+    pub fn open_job_buffer(
+        &mut self,
+        job_id: JobId,
+        owner: String,
+        listener: Recipient<SendWsText>,
+    ) {
+        self.evict_job_buffers();
+        self.job_buffers.insert(
+            job_id,
+            JobBuffer {
+                owner,
+                messages: vec![],
+                listener: Some(listener),
+                finished_at: None,
+            },
+        );
+    }
+
+    /// Sends `message` to the job's current listener and keeps it for a
+    /// client that resumes the job later.
+    pub fn publish(&mut self, job_id: JobId, message: &BackendMessage) {
+        let Some(buffer) = self.job_buffers.get_mut(&job_id) else {
+            return;
+        };
+        let Ok(content) = serde_json::to_string(message) else {
+            return;
+        };
+        if let Some(listener) = &buffer.listener {
+            if listener.connected() {
+                listener.do_send(SendWsText(content.clone()));
+            } else {
+                buffer.listener = None;
+            }
+        }
+        buffer.messages.push(content);
+    }
+
+    /// Marks the job's output complete; it stays resumable for
+    /// `JOB_BUFFER_TTL_SECS`.
+    pub fn close_job_buffer(&mut self, job_id: JobId) {
+        if let Some(buffer) = self.job_buffers.get_mut(&job_id) {
+            buffer.finished_at = Some(Instant::now());
+        }
+        self.evict_job_buffers();
+    }
+
+    /// Sends everything the job produced so far to `listener` and makes it
+    /// the receiver of the rest. `false` if the job is unknown, expired or
+    /// was not started by `owner`.
+    pub fn resume_job(
+        &mut self,
+        job_id: JobId,
+        owner: &str,
+        listener: Recipient<SendWsText>,
+    ) -> bool {
+        self.evict_job_buffers();
+        let Some(buffer) = self.job_buffers.get_mut(&job_id) else {
+            return false;
+        };
+        if buffer.owner != owner {
+            return false;
+        }
+        for content in &buffer.messages {
+            listener.do_send(SendWsText(content.clone()));
         }
+        buffer.listener = Some(listener);
+        true
+    }
+
+    fn evict_job_buffers(&mut self) {
+        let ttl = self.job_buffer_ttl;
+        self.job_buffers
+            .retain(|_, buffer| buffer.finished_at.is_none_or(|finished| finished.elapsed() < ttl));
+    }
+
+    fn record_duration(&mut self, duration: Duration) {
+        if self.recent_durations.len() == DURATION_WINDOW {
+            self.recent_durations.pop_front();
+        }
+        self.recent_durations.push_back(duration);
+    }
+
+    fn average_duration(&self) -> Option<Duration> {
+        if self.recent_durations.is_empty() {
+            return None;
+        }
+        let total: Duration = self.recent_durations.iter().sum();
+        Some(total / self.recent_durations.len() as u32)
+    }
+
+    /// Expected wait for the job at `position`: one average job per position.
+    fn estimated_wait(&self, position: PositionInQueue) -> Option<Duration> {
+        self.average_duration().map(|avg| avg * position as u32)
     }
 
     async fn broadcast_positions(&self) {
         for (idx, item) in self.waiting.iter().enumerate() {
+            let position = QueuePosition {
+                position: idx + 1,
+                estimated_wait_secs: self.estimated_wait(idx + 1).map(|d| d.as_secs()),
+            };
             if let Err(e) = item
                 .sender
-                .send(QueueMessage::PositionUpade(idx + 1))
+                .send(QueueMessage::PositionUpade(position))
                 .await 
             {
                 println!("Failed to send position message: {:#?}", e)
@@ -50,7 +192,8 @@ impl QueueManager {
         let (sender, reciever) = mpsc::channel::<QueueMessage>(10);
         let item = QueueItem {
             job_id,
-            sender
+            sender,
+            started: None,
         };
         self.waiting.push_back(item);
         self.queue_update().await;
@@ -63,13 +206,14 @@ impl QueueManager {
                 break;
             }
 
-            let Some(next_item_to_run) = self.waiting.pop_front() else {
+            let Some(mut next_item_to_run) = self.waiting.pop_front() else {
                 break;
             };
+            next_item_to_run.started = Some(Instant::now());
 
             match next_item_to_run
                 .sender
-                .send(QueueMessage::StartJob(next_item_to_run.job_id.clone()))
+                .send(QueueMessage::StartJob(next_item_to_run.job_id))
                 .await 
             {
                 Ok(_) => self.running.push(next_item_to_run),
@@ -94,7 +238,10 @@ impl QueueManager {
             }
         }
         if let Some(i) = index {
-            self.running.remove(i);
+            let job = self.running.remove(i);
+            if let Some(started) = job.started {
+                self.record_duration(started.elapsed());
+            }
         }
         self.queue_update().await;
     }
@@ -104,3 +251,159 @@ impl QueueManager {
 impl Actor for QueueManager {
     type Context = Context<Self>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Stands in for a client socket and keeps what it was sent.
+    struct Client(Arc<Mutex<Vec<String>>>);
+
+    impl Actor for Client {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<SendWsText> for Client {
+        type Result = ();
+
+        fn handle(&mut self, msg: SendWsText, _: &mut Self::Context) {
+            self.0.lock().unwrap().push(msg.0);
+        }
+    }
+
+    fn client() -> (Recipient<SendWsText>, Arc<Mutex<Vec<String>>>) {
+        let received = Arc::new(Mutex::new(vec![]));
+        (Client(received.clone()).start().recipient(), received)
+    }
+
+    async fn settle() {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    fn finished_job(queue: &mut QueueManager, owner: &str) -> JobId {
+        let job_id = Uuid::new_v4();
+        let (first, _) = client();
+        queue.open_job_buffer(job_id, owner.to_string(), first);
+        queue.publish(job_id, &BackendMessage::Chunk("Hello".into()));
+        queue.publish(job_id, &BackendMessage::Answer("Hello there".into()));
+        queue.publish(job_id, &BackendMessage::End);
+        queue.close_job_buffer(job_id);
+        job_id
+    }
+
+    #[actix::test]
+    async fn resume_within_ttl_replays_the_answer() {
+        let mut queue = QueueManager::new();
+        queue.job_buffer_ttl = Duration::from_secs(60);
+        let job_id = finished_job(&mut queue, "student1");
+
+        let (second, received) = client();
+        assert!(queue.resume_job(job_id, "student1", second));
+        settle().await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 3);
+        assert!(received[1].contains("Hello there"));
+        assert!(received[2].contains("End"));
+    }
+
+    #[actix::test]
+    async fn resume_continues_with_later_output() {
+        let mut queue = QueueManager::new();
+        let job_id = Uuid::new_v4();
+        let (first, _) = client();
+        queue.open_job_buffer(job_id, "student1".into(), first);
+        queue.publish(job_id, &BackendMessage::Chunk("Hel".into()));
+
+        let (second, received) = client();
+        assert!(queue.resume_job(job_id, "student1", second));
+        queue.publish(job_id, &BackendMessage::End);
+        settle().await;
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received[1].contains("End"));
+    }
+
+    #[actix::test]
+    async fn resume_after_ttl_is_rejected() {
+        let mut queue = QueueManager::new();
+        queue.job_buffer_ttl = Duration::from_millis(10);
+        let job_id = finished_job(&mut queue, "student1");
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let (second, received) = client();
+        assert!(!queue.resume_job(job_id, "student1", second));
+        settle().await;
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[actix::test]
+    async fn resume_by_another_user_is_rejected() {
+        let mut queue = QueueManager::new();
+        let job_id = finished_job(&mut queue, "student1");
+
+        let (second, received) = client();
+        assert!(!queue.resume_job(job_id, "student2", second));
+        settle().await;
+        assert!(received.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn no_estimate_before_a_job_has_finished() {
+        let queue = QueueManager::new();
+        assert_eq!(queue.estimated_wait(1), None);
+    }
+
+    #[test]
+    fn estimate_is_the_average_duration_per_position() {
+        let mut queue = QueueManager::new();
+        queue.record_duration(Duration::from_secs(10));
+        queue.record_duration(Duration::from_secs(20));
+
+        assert_eq!(queue.estimated_wait(1), Some(Duration::from_secs(15)));
+        assert_eq!(queue.estimated_wait(3), Some(Duration::from_secs(45)));
+    }
+
+    #[test]
+    fn estimate_only_averages_the_recent_window() {
+        let mut queue = QueueManager::new();
+        for _ in 0..DURATION_WINDOW {
+            queue.record_duration(Duration::from_secs(100));
+        }
+        for _ in 0..DURATION_WINDOW {
+            queue.record_duration(Duration::from_secs(10));
+        }
+
+        assert_eq!(queue.recent_durations.len(), DURATION_WINDOW);
+        assert_eq!(queue.estimated_wait(1), Some(Duration::from_secs(10)));
+    }
+
+    #[actix::test]
+    async fn waiting_jobs_get_an_estimate_once_a_job_is_done() {
+        let mut queue = QueueManager::new();
+        let mut first = queue.enter_queue().await;
+        let Some(QueueMessage::StartJob(first_id)) = first.recv().await else {
+            panic!("first job did not start");
+        };
+        let _second = queue.enter_queue().await;
+        let mut third = queue.enter_queue().await;
+        let Some(QueueMessage::PositionUpade(position)) = third.recv().await else {
+            panic!("third job got no position");
+        };
+        assert_eq!(position.position, 2);
+        assert_eq!(position.estimated_wait_secs, None);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        queue.notify_done(first_id).await;
+
+        let mut latest = None;
+        while let Ok(QueueMessage::PositionUpade(position)) = third.try_recv() {
+            latest = Some(position);
+        }
+        let position = latest.expect("third job got no update");
+        assert_eq!(position.position, 1);
+        assert_eq!(position.estimated_wait_secs, Some(1));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+const DEFAULT_BURST: f64 = 5.0;
+const DEFAULT_PER_MINUTE: f64 = 10.0;
+/// Full buckets are dropped once this many keys are tracked.
+const PRUNE_AFTER: usize = 1024;
+
+pub type SharedRateLimiter = Arc<Mutex<RateLimiter>>;
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket per user: each prompt takes a token, a user holds at most
+/// `RATE_LIMIT_BURST` tokens and gets `RATE_LIMIT_PER_MINUTE` back per minute.
+#[derive(Debug)]
+pub struct RateLimiter {
+    burst: f64,
+    refill_per_sec: f64,
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn new(burst: f64, per_minute: f64) -> Self {
+        Self {
+            burst: burst.max(1.0),
+            refill_per_sec: per_minute.max(0.0) / 60.0,
+            buckets: HashMap::new(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let setting = |key: &str, default: f64| {
+            env::var(key)
+                .ok()
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|v| v.is_finite() && *v > 0.0)
+                .unwrap_or(default)
+        };
+        Self::new(
+            setting("RATE_LIMIT_BURST", DEFAULT_BURST),
+            setting("RATE_LIMIT_PER_MINUTE", DEFAULT_PER_MINUTE),
+        )
+    }
+
+    /// Takes a token from `key`'s bucket. `false` when it is empty.
+    pub fn try_acquire(&mut self, key: &str) -> bool {
+        self.try_acquire_at(key, Instant::now())
+    }
+
+    fn try_acquire_at(&mut self, key: &str, now: Instant) -> bool {
+        if self.buckets.len() > PRUNE_AFTER {
+            self.prune(now);
+        }
+        let (burst, refill_per_sec) = (self.burst, self.refill_per_sec);
+        let bucket = self.buckets.entry(key.to_string()).or_insert(TokenBucket {
+            tokens: burst,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * refill_per_sec).min(burst);
+        bucket.updated = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    /// Seconds until `key` has a token again.
+    pub fn retry_after_secs(&self, key: &str) -> u64 {
+        let Some(bucket) = self.buckets.get(key) else {
+            return 0;
+        };
+        if bucket.tokens >= 1.0 || self.refill_per_sec == 0.0 {
+            return 0;
+        }
+        ((1.0 - bucket.tokens) / self.refill_per_sec).ceil() as u64
+    }
+
+    fn prune(&mut self, now: Instant) {
+        let (burst, refill_per_sec) = (self.burst, self.refill_per_sec);
+        self.buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens + elapsed * refill_per_sec < burst
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn burst_is_allowed_then_limited() {
+        let mut limiter = RateLimiter::new(3.0, 6.0);
+        let now = Instant::now();
+        for _ in 0..3 {
+            assert!(limiter.try_acquire_at("student1", now));
+        }
+        assert!(!limiter.try_acquire_at("student1", now));
+        assert_eq!(limiter.retry_after_secs("student1"), 10);
+    }
+
+    #[test]
+    fn tokens_refill_over_time_up_to_the_burst() {
+        let mut limiter = RateLimiter::new(2.0, 6.0);
+        let start = Instant::now();
+        assert!(limiter.try_acquire_at("student1", start));
+        assert!(limiter.try_acquire_at("student1", start));
+        assert!(!limiter.try_acquire_at("student1", start + Duration::from_secs(5)));
+        assert!(limiter.try_acquire_at("student1", start + Duration::from_secs(10)));
+
+        // an hour idle refills no more than the burst
+        let later = start + Duration::from_secs(3600);
+        assert!(limiter.try_acquire_at("student1", later));
+        assert!(limiter.try_acquire_at("student1", later));
+        assert!(!limiter.try_acquire_at("student1", later));
+    }
+
+    #[test]
+    fn users_have_separate_buckets() {
+        let mut limiter = RateLimiter::new(1.0, 6.0);
+        let now = Instant::now();
+        assert!(limiter.try_acquire_at("student1", now));
+        assert!(!limiter.try_acquire_at("student1", now));
+        assert!(limiter.try_acquire_at("student2", now));
+        assert_eq!(limiter.retry_after_secs("student3"), 0);
+    }
+
+    #[test]
+    fn full_buckets_are_pruned() {
+        let mut limiter = RateLimiter::new(1.0, 60.0);
+        let start = Instant::now();
+        for i in 0..=PRUNE_AFTER {
+            limiter.try_acquire_at(&format!("user{}", i), start);
+        }
+        assert_eq!(limiter.buckets.len(), PRUNE_AFTER + 1);
+
+        limiter.try_acquire_at("late", start + Duration::from_secs(2));
+        assert_eq!(limiter.buckets.len(), 1);
+    }
+}
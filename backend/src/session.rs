@@ -1,25 +1,32 @@
 use crate::{
+    conversation_store::{Rating, StoredConversation, conversation_store},
     ldap::{employee_ldap_login, stdent_ldap_login},
-    messages::{BackendMessage, FrontendMessage, LoginCredentials, MessageType, SendMessage},
+    messages::{
+        BackendMessage, FrontendMessage, LoginCredentials, MessageType, ResumeRequest, SendMessage,
+    },
+    notification_log::{NotificationLog, notification_log_enabled},
     profile::Profile,
-    queue::{self, QueueManager, QueueMessage},
+    queue::{self, JobId, QueueManager, QueueMessage},
+    rate_limit::SharedRateLimiter,
 };
 use actix::prelude::*;
+use actix_web::web;
 use actix_web_actors::ws;
 use rmcp::{
-    ClientHandler,
-    model::{CallToolRequestParam, ProgressNotificationParam},
-    service::RunningService,
+    ClientHandler, Peer, RoleClient,
+    model::{CallToolRequestParam, CallToolResult, ProgressNotificationParam},
+    service::{RunningService, ServiceError},
 };
 use serde_json::{Map, Value};
 use std::collections::HashMap;
-use std::{env, sync::Arc};
-use tokio::{
-    fs,
-    sync::{
-        Mutex,
-        mpsc::{self, Receiver},
-    },
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+use std::time::{Duration, Instant};
+use tokio::sync::{
+    Mutex,
+    mpsc::{self, Receiver},
 };
 use uuid::Uuid;
 
@@ -27,6 +34,8 @@ pub type SessionStore = Arc<Mutex<HashMap<String, Profile>>>;
 #[derive(Debug)]
 pub struct ProgressHandler {
     pub notification_tx: mpsc::Sender<ProgressNotificationParam>,
+    /// Notifications received but not yet forwarded to the client.
+    pub pending: Arc<AtomicUsize>,
 }
 
 impl ClientHandler for ProgressHandler {
@@ -35,10 +44,56 @@ impl ClientHandler for ProgressHandler {
         params: ProgressNotificationParam,
         _ctx: rmcp::service::NotificationContext<rmcp::RoleClient>,
     ) {
-        let _ = self.notification_tx.send(params).await;
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        if self.notification_tx.send(params).await.is_err() {
+            self.pending.fetch_sub(1, Ordering::SeqCst);
+        }
     }
 }
 
+/// Longest the end of an answer waits for its notifications to be forwarded.
+const NOTIFICATION_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Waits until every notification received so far was forwarded, so that
+/// the last streamed tokens reach the client before the `End` marker.
+async fn drain_notifications(pending: &AtomicUsize) {
+    let deadline = Instant::now() + NOTIFICATION_DRAIN_TIMEOUT;
+    while pending.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// The text of a streamed answer token. Urška's tokens are relayed as
+/// `Chunk`s; tokens of the agents she calls stay notifications.
+fn answer_token(notification: &str) -> Option<String> {
+    let value: Value = serde_json::from_str(notification).ok()?;
+    if value.get("agent")?.as_str()? != "Urška" {
+        return None;
+    }
+    Some(value.get("content")?.get("Token")?.get("value")?.as_str()?.to_string())
+}
+
+/// Messages sent once `ask_urska` returned: the final answer, which may
+/// differ from the streamed tokens (citations removed, notices and caveats
+/// appended), followed by its confidence.
+fn answer_messages(result: &CallToolResult) -> Vec<BackendMessage> {
+    let text = |index: usize| {
+        result
+            .content
+            .get(index)
+            .and_then(|c| c.as_text())
+            .map(|t| t.text.clone())
+    };
+    let mut messages = vec![];
+    if let Some(answer) = text(0) {
+        messages.push(BackendMessage::Answer(answer));
+    }
+    if let Some(confidence) = text(1).and_then(|t| serde_json::from_str::<Value>(&t).ok()) {
+        messages.push(BackendMessage::Confidence(confidence));
+    }
+    messages
+}
+
 #[derive(Message)]
 #[rtype(result = "()")]
 struct Authenticated(Profile);
@@ -50,11 +105,23 @@ struct Logout;
 #[derive(Debug)]
 pub struct ChatSession {
     pub id: String,
-    pub mcp_client: RunningService<rmcp::RoleClient, ProgressHandler>,
+    /// Shared with the session's running job, which keeps the MCP
+    /// connection alive when the socket drops mid-answer.
+    pub mcp_client: Arc<RunningService<rmcp::RoleClient, ProgressHandler>>,
+    /// Job whose output notifications are relayed to, while one runs.
+    pub current_job: Arc<std::sync::Mutex<Option<JobId>>>,
     pub notification_reciever: Arc<Mutex<mpsc::Receiver<ProgressNotificationParam>>>,
+    /// Shared with the session's `ProgressHandler`.
+    pub pending_notifications: Arc<AtomicUsize>,
+    /// Notifications of the request in progress, kept only when
+    /// `NOTIFICATION_LOG` is enabled.
+    pub notification_log: Arc<Mutex<NotificationLog>>,
     pub queue: Arc<Mutex<QueueManager>>,
     pub sessions: SessionStore,
+    /// Shared by all sessions, so a user's limit holds across sockets.
+    pub rate_limiter: SharedRateLimiter,
     pub authenticated_as: Option<Profile>,
+    pub share_profile_context: bool,
 }
 
 impl Handler<Authenticated> for ChatSession {
@@ -70,6 +137,7 @@ impl Handler<Logout> for ChatSession {
 
     fn handle(&mut self, _msg: Logout, _: &mut Self::Context) {
         self.authenticated_as = None;
+        self.share_profile_context = false;
         println!("Logged out...")
     }
 }
@@ -84,22 +152,89 @@ impl Actor for ChatSession {
 
         // 2) clone your receiver
         let notification_reciever = self.notification_reciever.clone();
+        let notification_log = self.notification_log.clone();
+        let pending = self.pending_notifications.clone();
+        let current_job = self.current_job.clone();
+        let queue = self.queue.clone();
 
         // 3) spawn a tokio task (or actix::spawn) that lives 'static
         // thread that forwards notifications | mcp -> BE -(here)> client
-        actix::spawn(async move {
-            while let Some(notification_params) = notification_reciever.lock().await.recv().await {
-                let text = notification_params
-                    .message
-                    .clone()
-                    .unwrap_or_else(|| "▱".to_string());
-                let msg = BackendMessage::Notification(text);
+        actix::spawn(relay_notifications(
+            notification_reciever,
+            notification_log,
+            pending,
+            current_job,
+            queue,
+            move |msg| {
                 let _ = addr.send_message_to_client(msg);
-            }
-        });
+            },
+        ));
+    }
+}
+
+/// Forwards the core's notifications to the running job, or through
+/// `send_idle` while no job runs.
+async fn relay_notifications(
+    notification_reciever: Arc<Mutex<Receiver<ProgressNotificationParam>>>,
+    notification_log: Arc<Mutex<NotificationLog>>,
+    pending: Arc<AtomicUsize>,
+    current_job: Arc<std::sync::Mutex<Option<JobId>>>,
+    queue: Arc<Mutex<QueueManager>>,
+    send_idle: impl Fn(BackendMessage),
+) {
+    while let Some(notification_params) = notification_reciever.lock().await.recv().await {
+        let text = notification_params
+            .message
+            .clone()
+            .unwrap_or_else(|| "▱".to_string());
+        if notification_log_enabled() {
+            notification_log.lock().await.record(&text);
+        }
+        let msg = match answer_token(&text) {
+            Some(token) => BackendMessage::Chunk(token),
+            None => BackendMessage::Notification(text),
+        };
+        // read before awaiting the queue lock
+        let job = *current_job.lock().unwrap_or_else(|e| e.into_inner());
+        match job {
+            Some(job_id) => queue.lock().await.publish(job_id, &msg),
+            None => send_idle(msg),
+        }
+        pending.fetch_sub(1, Ordering::SeqCst);
     }
 }
 
+/// Publishes the outcome of `ask_urska` after the tokens streamed so far,
+/// then ends the job.
+async fn finish_job(
+    queue: &Mutex<QueueManager>,
+    current_job: &std::sync::Mutex<Option<JobId>>,
+    pending: &AtomicUsize,
+    job: JobId,
+    result: &Result<CallToolResult, ServiceError>,
+) {
+    drain_notifications(pending).await;
+
+    if let Err(e) = result {
+        let error_conetent = format!("[Tool error] {}", e);
+        let err_msg = BackendMessage::Chunk(error_conetent);
+        queue.lock().await.publish(job, &err_msg);
+    }
+
+    if let Ok(result) = result {
+        let mut queue = queue.lock().await;
+        for message in answer_messages(result) {
+            queue.publish(job, &message);
+        }
+    }
+
+    // once done, send the End marker
+    *current_job.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    let mut queue = queue.lock().await;
+    queue.publish(job, &BackendMessage::End);
+    queue.close_job_buffer(job);
+}
+
 impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for ChatSession {
     fn handle(&mut self, item: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
         match item {
@@ -128,9 +263,11 @@ impl ChatSession {
             MessageType::EmployeeLogin => self.employee_login(ctx, message.content),
             MessageType::StudentLogin => self.student_login(ctx, message.content),
             MessageType::RestoreSession => self.restore_session(ctx, message.content),
+            MessageType::ResumeJob => self.resume_job(ctx, message.content),
             MessageType::Logout => self.logout(ctx, message),
             MessageType::ThumbsUp => self.save_thumbs_up(ctx, message),
             MessageType::ThumbsDown => self.save_thumbs_down(ctx, message),
+            MessageType::ShareProfileContext => self.set_profile_context_consent(message),
         }
     }
 
@@ -160,7 +297,7 @@ impl ChatSession {
             println!("Checking credential validity");
 
             let Some(mut attrs) = resp else {
-                let end = BackendMessage::Error(format!("Invalid credentials"));
+                let end = BackendMessage::Error("Invalid credentials".to_string());
                 let _ = addr.send_message_to_client(end);
                 return;
             };
@@ -212,7 +349,7 @@ impl ChatSession {
             println!("Checking credential validity");
 
             let Some(mut attrs) = resp else {
-                let end = BackendMessage::Error(format!("LDAP invalid credentials"));
+                let end = BackendMessage::Error("LDAP invalid credentials".to_string());
                 let _ = addr.send_message_to_client(end);
                 return;
             };
@@ -253,18 +390,76 @@ impl ChatSession {
         });
     }
 
+    /// Re-attaches a reconnected client to the job it was waiting for. Only
+    /// the user who asked can resume a job.
+    ///
+    /// The reconnected socket has its own MCP session, so the resumed answer
+    /// is delivered but Urška does not remember it: the next question starts
+    /// a new conversation.
+    fn resume_job(&self, ctx: &mut ws::WebsocketContext<ChatSession>, message: String) {
+        let addr = ctx.address();
+        let queue = self.queue.clone();
+        let sessions = self.sessions.clone();
+        actix::spawn(async move {
+            let resumed = match serde_json::from_str::<ResumeRequest>(&message) {
+                Ok(request) => {
+                    let owner = sessions
+                        .lock()
+                        .await
+                        .get(&request.session_token)
+                        .map(|profile| profile.username.clone());
+                    match owner {
+                        Some(owner) => queue.lock().await.resume_job(
+                            request.job_id,
+                            &owner,
+                            addr.clone().recipient(),
+                        ),
+                        None => false,
+                    }
+                }
+                Err(_) => false,
+            };
+            if !resumed {
+                let _ = addr.send_message_to_client(BackendMessage::Error(
+                    "The answer is no longer available. Please ask again.".into(),
+                ));
+            }
+        });
+    }
+
     fn prompt(&mut self, ctx: &mut ws::WebsocketContext<ChatSession>, message: String) {
         println!("Prompt");
         let client = self.mcp_client.clone();
         let addr = ctx.address();
         let queue = self.queue.clone();
         let session_id = self.id.clone();
+        let share_profile_context = self.share_profile_context;
+        let notification_log = self.notification_log.clone();
+        let pending = self.pending_notifications.clone();
+        let current_job = self.current_job.clone();
 
         let Some(profile) = self.authenticated_as.clone() else {
             let _ = addr.send_message_to_client(BackendMessage::Error("Not logged in...".into()));
             return;
         };
 
+        // only prompts that would be answered use up the user's tokens
+        let retry_after = {
+            let mut limiter = self.rate_limiter.lock().unwrap_or_else(|e| e.into_inner());
+            if limiter.try_acquire(&profile.username) {
+                None
+            } else {
+                Some(limiter.retry_after_secs(&profile.username))
+            }
+        };
+        if let Some(secs) = retry_after {
+            let _ = addr.send_message_to_client(BackendMessage::Error(format!(
+                "Too many questions at once. Please wait {} seconds before asking again.",
+                secs.max(1)
+            )));
+            return;
+        }
+
         actix::spawn(async move {
             let mut job_id: Option<uuid::Uuid> = None;
 
@@ -275,6 +470,15 @@ impl ChatSession {
                     match message {
                         queue::QueueMessage::StartJob(uuid) => {
                             job_id = Some(uuid);
+                            // drop anything left over from an earlier request
+                            notification_log.lock().await.entries.clear();
+                            queue.lock().await.open_job_buffer(
+                                uuid,
+                                profile.username.clone(),
+                                addr.clone().recipient(),
+                            );
+                            *current_job.lock().unwrap_or_else(|e| e.into_inner()) = Some(uuid);
+                            let _ = addr.send_message_to_client(BackendMessage::JobStarted(uuid));
                             break;
                         }
                         queue::QueueMessage::PositionUpade(position) => {
@@ -286,12 +490,12 @@ impl ChatSession {
                 }
             }
 
-            let user_context = profile.sanitized_context();
-
             let mut urska_argument_map = Map::new();
 
             urska_argument_map.insert("question".to_string(), Value::String(message.clone()));
-            urska_argument_map.insert("user_context".to_string(), user_context);
+            if share_profile_context {
+                urska_argument_map.insert("user_context".to_string(), profile.sanitized_context());
+            }
 
             let fn_call_request = CallToolRequestParam {
                 name: "ask_urska".into(),
@@ -299,16 +503,7 @@ impl ChatSession {
             };
 
             let result = client.call_tool(fn_call_request).await;
-
-            if let Err(e) = &result {
-                let error_conetent = format!("[Tool error] {}", e);
-                let err_msg = BackendMessage::Chunk(error_conetent);
-                let _ = addr.send_message_to_client(err_msg);
-            }
-
-            // once done, send the End marker
-            let end = BackendMessage::End;
-            let _ = addr.send_message_to_client(end);
+            finish_job(&queue, &current_job, &pending, job_id.unwrap(), &result).await;
 
             queue
                 .clone()
@@ -317,22 +512,21 @@ impl ChatSession {
                 .notify_done(job_id.unwrap())
                 .await;
 
-            let fn_call_request = CallToolRequestParam {
-                name: "export_conversation".into(),
-                arguments: None,
-            };
+            if notification_log_enabled() {
+                let request_id = job_id.unwrap().to_string();
+                if let Err(e) = notification_log.lock().await.flush(&session_id, &request_id).await {
+                    eprintln!("Failed to persist notifications: {}", e);
+                }
+            }
 
-            let result = client.call_tool(fn_call_request).await;
-            let binding = result.unwrap().content.clone();
-            let content = &binding[0].as_text().unwrap().text;
-            let out_dir = match env::var("SAVE_PATH") {
-                Ok(v) => v,
-                Err(_e) => return,
-            };
-            let _ = fs::write(format!("{}/all/{}.json", out_dir, session_id), content).await;
+            save_conversation(client.peer(), &session_id, None).await;
         });
     }
 
+    fn set_profile_context_consent(&mut self, message: FrontendMessage) {
+        self.share_profile_context = message.content.trim().eq_ignore_ascii_case("true");
+    }
+
     fn logout(&self, ctx: &mut ws::WebsocketContext<ChatSession>, _: FrontendMessage) {
         let addr = ctx.address();
         addr.do_send(Logout);
@@ -340,56 +534,171 @@ impl ChatSession {
 
     fn save_thumbs_up(
         &self,
-        ctx: &mut ws::WebsocketContext<ChatSession>,
+        _ctx: &mut ws::WebsocketContext<ChatSession>,
         _message: FrontendMessage,
     ) {
         let client = self.mcp_client.clone();
         let session_id = self.id.clone();
 
         actix::spawn(async move {
-            let fn_call_request = CallToolRequestParam {
-                name: "export_conversation".into(),
-                arguments: None,
-            };
-
-            let result = client.call_tool(fn_call_request).await;
-            let binding = result.unwrap().content.clone();
-            let content = &binding[0].as_text().unwrap().text;
-            let out_dir = match env::var("SAVE_PATH") {
-                Ok(v) => v,
-                Err(_e) => return,
-            };
-            let _ = fs::write(format!("{}/up/up_{}.json", out_dir, session_id), content).await;
+            save_conversation(client.peer(), &session_id, Some(Rating::Up)).await;
         });
     }
 
     fn save_thumbs_down(
         &self,
-        ctx: &mut ws::WebsocketContext<ChatSession>,
+        _ctx: &mut ws::WebsocketContext<ChatSession>,
         _message: FrontendMessage,
     ) {
         let client = self.mcp_client.clone();
         let session_id = self.id.clone();
 
         actix::spawn(async move {
-            let fn_call_request = CallToolRequestParam {
-                name: "export_conversation".into(),
-                arguments: None,
-            };
+            save_conversation(client.peer(), &session_id, Some(Rating::Down)).await;
+        });
+    }
+}
 
-            let result = client.call_tool(fn_call_request).await;
+/// Exports the session's conversation from the core and writes it to the
+/// conversation store, if one is configured.
+async fn save_conversation(
+    client: &Peer<RoleClient>,
+    session_id: &str,
+    rating: Option<Rating>,
+) {
+    let fn_call_request = CallToolRequestParam {
+        name: "export_conversation".into(),
+        arguments: None,
+    };
+    let result = client.call_tool(fn_call_request).await;
+    let binding = result.unwrap().content.clone();
+    let content = binding[0].as_text().unwrap().text.clone();
+    let conversation = StoredConversation::new(session_id, rating, content);
+    // the store does blocking file and SQLite I/O
+    let saved = web::block(move || match conversation_store() {
+        Some(store) => store.save(&conversation),
+        None => Ok(()),
+    })
+    .await;
+    match saved {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => eprintln!("Failed to save conversation {}: {}", session_id, e),
+        Err(e) => eprintln!("Failed to save conversation {}: {}", session_id, e),
+    }
+}
 
-            let binding = result.unwrap().content.clone();
-            let content = &binding[0].as_text().unwrap().text;
-            let out_dir = match env::var("SAVE_PATH") {
-                Ok(v) => v,
-                Err(_e) => return,
-            };
-            let _ = fs::write(
-                format!("{}/down/down_{}.json", out_dir, session_id),
-                content,
-            )
-            .await;
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::messages::SendWsText;
+    use rmcp::model::{Content, NumberOrString, ProgressToken};
+
+    /// Stands in for a client socket and keeps what it was sent.
+    struct Client(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Actor for Client {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<SendWsText> for Client {
+        type Result = ();
+
+        fn handle(&mut self, msg: SendWsText, _: &mut Self::Context) {
+            self.0.lock().unwrap().push(msg.0);
+        }
+    }
+
+    fn notification(message: &str) -> ProgressNotificationParam {
+        ProgressNotificationParam {
+            progress_token: ProgressToken(NumberOrString::Number(0)),
+            progress: 0,
+            total: None,
+            message: Some(message.to_string()),
+        }
+    }
+
+    fn token(agent: &str, value: &str) -> String {
+        serde_json::json!({ "agent": agent, "content": { "Token": { "value": value } } })
+            .to_string()
+    }
+
+    #[test]
+    fn answer_messages_carry_the_post_processed_answer() {
+        let final_answer = "Tuition is 3,5 EUR.\n\n_Some statements could not be verified._";
+        let result = CallToolResult::success(vec![
+            Content::text(final_answer),
+            Content::text(r#"{"score":0.4}"#),
+        ]);
+
+        let messages: Vec<Value> = answer_messages(&result)
+            .iter()
+            .map(|m| serde_json::to_value(m).unwrap())
+            .collect();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["type"], "Answer");
+        assert_eq!(messages[0]["data"], final_answer);
+        assert_eq!(messages[1]["type"], "Confidence");
+        assert_eq!(messages[1]["data"]["score"], 0.4);
+    }
+
+    #[test]
+    fn answer_messages_skip_missing_confidence() {
+        let result = CallToolResult::success(vec![Content::text("Only the answer.")]);
+        let messages = answer_messages(&result);
+        assert_eq!(messages.len(), 1);
+        assert!(matches!(&messages[0], BackendMessage::Answer(a) if a == "Only the answer."));
+    }
+
+    #[actix::test]
+    async fn streamed_chunks_arrive_in_order_before_the_answer() {
+        let (tx, rx) = mpsc::channel(16);
+        let pending = Arc::new(AtomicUsize::new(0));
+        let current_job = Arc::new(std::sync::Mutex::new(None));
+        let queue = Arc::new(Mutex::new(QueueManager::new()));
+        let received = Arc::new(std::sync::Mutex::new(vec![]));
+
+        let job = Uuid::new_v4();
+        let listener = Client(received.clone()).start().recipient();
+        queue.lock().await.open_job_buffer(job, "student1".into(), listener);
+        *current_job.lock().unwrap() = Some(job);
+
+        actix::spawn(relay_notifications(
+            Arc::new(Mutex::new(rx)),
+            Arc::new(Mutex::new(NotificationLog::default())),
+            pending.clone(),
+            current_job.clone(),
+            queue.clone(),
+            |_| panic!("no message should bypass the running job"),
+        ));
+
+        // as `ProgressHandler::on_progress` does, without waiting for the relay
+        for message in [
+            token("Urška", "Tuition "),
+            token("Planner", "step"),
+            token("Urška", "is 3.5 EUR"),
+        ] {
+            pending.fetch_add(1, Ordering::SeqCst);
+            tx.send(notification(&message)).await.unwrap();
+        }
+        let result = Ok(CallToolResult::success(vec![Content::text("Tuition is 3,5 EUR.")]));
+        finish_job(&queue, &current_job, &pending, job, &result).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let types: Vec<(String, Value)> = received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| {
+                let value: Value = serde_json::from_str(m).unwrap();
+                (value["type"].as_str().unwrap().to_string(), value["data"].clone())
+            })
+            .collect();
+        let kinds: Vec<&str> = types.iter().map(|(kind, _)| kind.as_str()).collect();
+        assert_eq!(kinds, ["Chunk", "Notification", "Chunk", "Answer", "End"]);
+        assert_eq!(types[0].1, "Tuition ");
+        assert_eq!(types[2].1, "is 3.5 EUR");
+        assert_eq!(types[3].1, "Tuition is 3,5 EUR.");
+        assert_eq!(*current_job.lock().unwrap(), None);
     }
 }
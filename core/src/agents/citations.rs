@@ -0,0 +1,168 @@
+use std::env;
+
+use reqwest::Url;
+
+/// Whether citations are checked after synthesis. On unless
+/// `CITATION_GUARD=off`.
+pub fn citation_guard_enabled() -> bool {
+    !env::var("CITATION_GUARD")
+        .map(|v| v.trim().eq_ignore_ascii_case("off"))
+        .unwrap_or(false)
+}
+
+/// A `[label](target)` link: byte range in its line, label and target.
+struct Link<'a> {
+    start: usize,
+    end: usize,
+    label: &'a str,
+    target: &'a str,
+}
+
+fn links(line: &str) -> Vec<Link<'_>> {
+    let mut links = vec![];
+    let mut from = 0;
+    while let Some(open) = line[from..].find('[').map(|i| from + i) {
+        let Some(close) = line[open..].find("](").map(|i| open + i) else {
+            break;
+        };
+        let label = &line[open + 1..close];
+        // "[see [1](url)" - the link starts at the inner bracket
+        if label.contains('[') {
+            from = open + 1;
+            continue;
+        }
+        let target_start = close + 2;
+        let Some(end) = line[target_start..].find(')').map(|i| target_start + i) else {
+            break;
+        };
+        links.push(Link {
+            start: open,
+            end: end + 1,
+            label,
+            target: line[target_start..end].trim(),
+        });
+        from = end + 1;
+    }
+    links
+}
+
+/// A link target the answer may cite: an http(s) URL with a host that
+/// appears verbatim in a tool output. Memory ids, Qdrant point ids and
+/// other internal identifiers fail the first part.
+fn is_citable(target: &str, tool_outputs: &[String]) -> bool {
+    let well_formed = !target.contains(char::is_whitespace)
+        && Url::parse(target)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.host_str().is_some());
+    well_formed && tool_outputs.iter().any(|o| o.contains(target))
+}
+
+/// The line without its uncitable links and the number of links removed. A
+/// numbered citation (`[2](id)`) is removed entirely; any other link keeps
+/// its label as plain text.
+fn strip_line(line: &str, tool_outputs: &[String]) -> (String, usize) {
+    let mut out = String::new();
+    let mut removed = 0;
+    let mut last = 0;
+    for link in links(line) {
+        if is_citable(link.target, tool_outputs) {
+            continue;
+        }
+        removed += 1;
+        let before = &line[last..link.start];
+        let label = link.label.trim();
+        if label.is_empty() || label.chars().all(|c| c.is_ascii_digit()) {
+            out.push_str(before.strip_suffix(' ').unwrap_or(before));
+        } else {
+            out.push_str(before);
+            out.push_str(label);
+        }
+        last = link.end;
+    }
+    out.push_str(&line[last..]);
+    (out, removed)
+}
+
+/// Removes every link whose target is not a well-formed http(s) URL found
+/// verbatim in the tool outputs. Reference entries left without a link are
+/// dropped, and so is a references heading left without entries.
+pub fn strip_invalid_citations(answer: &str, tool_outputs: &[String]) -> String {
+    let mut lines: Vec<String> = vec![];
+    let mut in_references = false;
+    let mut removed_any = false;
+    for line in answer.lines() {
+        if line.trim_start().starts_with('#') {
+            in_references = line.to_lowercase().contains("references");
+            lines.push(line.to_string());
+            continue;
+        }
+        let (guarded, removed) = strip_line(line, tool_outputs);
+        if removed == 0 {
+            lines.push(guarded);
+            continue;
+        }
+        removed_any = true;
+        if in_references && links(&guarded).is_empty() {
+            continue;
+        }
+        lines.push(guarded);
+    }
+    if !removed_any {
+        return answer.to_string();
+    }
+
+    if let Some(heading) = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with('#') && l.to_lowercase().contains("references"))
+    {
+        let entries = lines[heading + 1..]
+            .iter()
+            .take_while(|l| !l.trim_start().starts_with('#'))
+            .count();
+        if lines[heading + 1..heading + 1 + entries].iter().all(|l| l.trim().is_empty()) {
+            lines.drain(heading..heading + 1 + entries);
+        }
+    }
+
+    lines.join("\n").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "https://www.famnit.upr.si/en/education/undergraduate/computer-science";
+
+    fn outputs() -> Vec<String> {
+        vec![format!("Computer Science lasts three years.\nSource: {}", PAGE)]
+    }
+
+    #[test]
+    fn answer_with_valid_citations_is_unchanged() {
+        let answer = format!(
+            "Computer Science lasts three years [1]({}).\n\n## References\n1. [{}]({})",
+            PAGE, PAGE, PAGE
+        );
+        assert_eq!(strip_invalid_citations(&answer, &outputs()), answer);
+    }
+
+    #[test]
+    fn internal_ids_and_unseen_urls_are_removed() {
+        let answer = format!(
+            "Computer Science lasts three years [1]({}) [2](memory:42). See the [rules](https://www.famnit.upr.si/en/rules).",
+            PAGE
+        );
+        assert_eq!(
+            strip_invalid_citations(&answer, &outputs()),
+            format!("Computer Science lasts three years [1]({}). See the rules.", PAGE)
+        );
+    }
+
+    #[test]
+    fn emptied_references_section_is_dropped() {
+        let answer = "Computer Science lasts three years [1](7f3c2a1e).\n\n## References\n1. [7f3c2a1e](7f3c2a1e)\n\n## Next Steps\nAsk the student office.";
+        assert_eq!(
+            strip_invalid_citations(answer, &outputs()),
+            "Computer Science lasts three years.\n\n## Next Steps\nAsk the student office."
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::env;
+
+use serde::Serialize;
+
+use crate::agents::grounding::{factual_sentences, markdown_link_targets};
+
+const DEFAULT_LOW_CONFIDENCE_THRESHOLD: f32 = 0.45;
+
+/// Heuristic estimate of how well an answer is backed by the tool results it
+/// was synthesized from. Returned next to the answer so clients can flag
+/// weakly supported responses.
+#[derive(Debug, Clone, Serialize)]
+pub struct AnswerConfidence {
+    /// 0.0 (unsupported) to 1.0 (fully supported).
+    pub score: f32,
+    /// `score` is below `CONFIDENCE_LOW_THRESHOLD`.
+    pub low: bool,
+    pub factual_claims: usize,
+    pub sourced_claims: usize,
+    pub tools_called: usize,
+    pub tools_with_data: usize,
+    pub unverified_urls: usize,
+}
+
+/// Tool results that carry no usable data: errors and explicit "nothing
+/// found" responses.
+fn is_empty_result(output: &str) -> bool {
+    let lower = output.trim().to_lowercase();
+    lower.is_empty()
+        || lower.starts_with("error")
+        || lower.starts_with("could not")
+        || lower.starts_with("no ")
+        || lower.contains("tool not found")
+        || lower.contains("execution error")
+        || lower.contains("\"not_found\":true")
+}
+
+pub fn answer_confidence(answer: &str, tool_outputs: &[String]) -> AnswerConfidence {
+    let claims = factual_sentences(answer);
+    let sourced_claims = claims.iter().filter(|c| c.contains("](")).count();
+    let tools_with_data = tool_outputs.iter().filter(|o| !is_empty_result(o)).count();
+    let unverified_urls = markdown_link_targets(answer)
+        .iter()
+        .filter(|url| !tool_outputs.iter().any(|o| o.contains(url.as_str())))
+        .count();
+
+    // an answer without concrete facts has nothing left uncited
+    let claim_coverage = if claims.is_empty() {
+        1.0
+    } else {
+        sourced_claims as f32 / claims.len() as f32
+    };
+    let data_coverage = if tool_outputs.is_empty() {
+        0.0
+    } else {
+        tools_with_data as f32 / tool_outputs.len() as f32
+    };
+    let url_penalty = (unverified_urls as f32 * 0.1).min(0.3);
+
+    let score = (0.5 * claim_coverage + 0.5 * data_coverage - url_penalty).clamp(0.0, 1.0);
+    let threshold = env::var("CONFIDENCE_LOW_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse::<f32>().ok())
+        .unwrap_or(DEFAULT_LOW_CONFIDENCE_THRESHOLD);
+
+    AnswerConfidence {
+        score,
+        low: score < threshold,
+        factual_claims: claims.len(),
+        sourced_claims,
+        tools_called: tool_outputs.len(),
+        tools_with_data,
+        unverified_urls,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "https://www.famnit.upr.si/en/education/enrolment";
+
+    #[test]
+    fn rich_evidence_scores_higher_than_sparse() {
+        let rich_answer = format!(
+            "Enrolment opens on 1 July 2025 [1]({}). The fee is 120 EUR per year [1]({}).",
+            PAGE, PAGE
+        );
+        let rich = answer_confidence(
+            &rich_answer,
+            &[
+                format!("Enrolment opens on 1 July 2025, the fee is 120 EUR. {}", PAGE),
+                "Computer Science has 180 ECTS.".to_string(),
+            ],
+        );
+        let sparse = answer_confidence(
+            "Enrolment opens on 1 July 2025. The fee is 120 EUR per year.",
+            &["No results found".to_string(), "Error: connection refused".to_string()],
+        );
+
+        assert!(rich.score > sparse.score);
+        assert_eq!((rich.factual_claims, rich.sourced_claims), (2, 2));
+        assert_eq!(rich.tools_with_data, 2);
+        assert!(!rich.low);
+        assert_eq!(sparse.sourced_claims, 0);
+        assert_eq!(sparse.tools_with_data, 0);
+        assert!(sparse.low);
+    }
+
+    #[test]
+    fn unverified_urls_lower_the_score() {
+        let outputs = [format!("Enrolment opens on 1 July 2025. {}", PAGE)];
+        let verified = answer_confidence(&format!("Enrolment opens on 1 July 2025 [1]({}).", PAGE), &outputs);
+        let invented = answer_confidence(
+            "Enrolment opens on 1 July 2025 [1](https://www.famnit.upr.si/en/invented).",
+            &outputs,
+        );
+
+        assert_eq!(invented.unverified_urls, 1);
+        assert!(invented.score < verified.score);
+    }
+}
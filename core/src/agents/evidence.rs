@@ -0,0 +1,144 @@
+use std::env;
+
+use reagent_rs::Message;
+
+/// Tools answering from generic retrieval (similarity search over FAQ,
+/// pages and rules, or stored memories). Their output is less
+/// authoritative than a structured lookup on the live site.
+const RETRIEVAL_TOOLS: [&str; 4] = [
+    "retrieve_similar_FAQ",
+    "ask_about_general_information",
+    "ask_about_rules_and_acts",
+    "query_memory",
+];
+const PAGE_TOOLS: [&str; 1] = ["get_web_page_content"];
+
+/// Authority of the tools an executor step relied on. Variants are ordered
+/// so that a higher value means more authoritative evidence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EvidencePriority {
+    /// The step used no tool or failed.
+    None,
+    Retrieval,
+    Page,
+    /// Direct programme/staff tools reading the official pages.
+    Direct,
+}
+
+impl EvidencePriority {
+    fn from_str(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "none" => Some(Self::None),
+            "retrieval" => Some(Self::Retrieval),
+            "page" => Some(Self::Page),
+            "direct" => Some(Self::Direct),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EvidencePriority::None => "none",
+            EvidencePriority::Retrieval => "low (retrieved passages)",
+            EvidencePriority::Page => "medium (web page)",
+            EvidencePriority::Direct => "high (official structured data)",
+        }
+    }
+}
+
+/// Priority of a single tool. `EVIDENCE_PRIORITIES` overrides the defaults
+/// per tool, e.g. `get_regulation_article=direct,query_memory=none`.
+pub fn tool_priority(tool: &str) -> EvidencePriority {
+    let configured = env::var("EVIDENCE_PRIORITIES").ok().and_then(|v| {
+        v.split(',')
+            .filter_map(|entry| entry.split_once('='))
+            .find(|(name, _)| name.trim() == tool)
+            .and_then(|(_, priority)| EvidencePriority::from_str(priority))
+    });
+    if let Some(priority) = configured {
+        return priority;
+    }
+    if RETRIEVAL_TOOLS.contains(&tool) {
+        EvidencePriority::Retrieval
+    } else if PAGE_TOOLS.contains(&tool) {
+        EvidencePriority::Page
+    } else {
+        EvidencePriority::Direct
+    }
+}
+
+/// Outcome of one executor step with the tools it called.
+#[derive(Debug, Clone)]
+pub struct Evidence {
+    pub task: String,
+    pub response: String,
+    pub tools: Vec<String>,
+}
+
+impl Evidence {
+    pub fn new(task: String, response: String, step_history: &[Message]) -> Self {
+        let mut tools: Vec<String> = vec![];
+        for call in step_history.iter().flat_map(|m| m.tool_calls.iter().flatten()) {
+            if !tools.contains(&call.function.name) {
+                tools.push(call.function.name.clone());
+            }
+        }
+        Self { task, response, tools }
+    }
+
+    /// Highest priority among the tools the step used.
+    pub fn priority(&self) -> EvidencePriority {
+        if self.response.starts_with("Execution Error") {
+            return EvidencePriority::None;
+        }
+        self.tools
+            .iter()
+            .map(|t| tool_priority(t))
+            .max()
+            .unwrap_or(EvidencePriority::None)
+    }
+}
+
+/// Orders evidence from most to least authoritative, keeping the branch
+/// order among equals.
+pub fn prioritize(evidence: &mut [Evidence]) {
+    evidence.sort_by_key(|e| std::cmp::Reverse(e.priority()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evidence(task: &str, tools: &[&str]) -> Evidence {
+        Evidence {
+            task: task.to_string(),
+            response: format!("Answer to {}", task),
+            tools: tools.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn step_takes_the_priority_of_its_best_tool() {
+        assert_eq!(
+            evidence("fees", &["retrieve_similar_FAQ", "get_web_page_content"]).priority(),
+            EvidencePriority::Page
+        );
+        assert_eq!(evidence("greeting", &[]).priority(), EvidencePriority::None);
+    }
+
+    #[test]
+    fn higher_authority_is_ordered_first() {
+        let mut log = vec![
+            evidence("faq", &["retrieve_similar_FAQ"]),
+            evidence("nothing", &[]),
+            evidence("programme", &["get_programme_info"]),
+            evidence("page", &["get_web_page_content"]),
+            evidence("rules", &["ask_about_rules_and_acts"]),
+        ];
+
+        prioritize(&mut log);
+
+        let order: Vec<&str> = log.iter().map(|e| e.task.as_str()).collect();
+        assert_eq!(order, ["programme", "page", "faq", "rules", "nothing"]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, time::Duration};
 
 use reagent_rs::{
     Agent, AgentBuildError, AgentBuilder, AgentError, InvocationBuilder, Message, Notification,
@@ -6,6 +6,21 @@ use reagent_rs::{
 };
 use tokio::sync::mpsc::Receiver;
 
+use crate::agents::ollama_limit::limited;
+
+const DEFAULT_STEP_TIMEOUT_SECS: u64 = 60;
+
+/// Longest a single plan step may run before it is cancelled, read from
+/// `EXECUTOR_STEP_TIMEOUT_SECS`.
+pub fn step_timeout() -> Duration {
+    let secs = env::var("EXECUTOR_STEP_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(DEFAULT_STEP_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
 pub async fn create_single_task_agent(
     ref_agent: &Agent,
 ) -> Result<(Agent, Receiver<Notification>), AgentBuildError> {
@@ -91,7 +106,7 @@ async fn executor_flow(agent: &mut Agent, prompt: String) -> Result<Message, Age
     agent.history.push(Message::user(prompt));
 
     // let mut resp = invoke_with_tool_calls(agent).await?;
-    let mut response = InvocationBuilder::default().invoke_with(agent).await?;
+    let mut response = limited(InvocationBuilder::default().invoke_with(agent)).await?;
     if let Some(tc) = response.message.tool_calls.clone() {
         for tool_msg in call_tools(agent, &tc).await {
             agent.history.push(tool_msg);
@@ -101,7 +116,7 @@ async fn executor_flow(agent: &mut Agent, prompt: String) -> Result<Message, Age
         if response.message.tool_calls.is_none() {
             break;
         }
-        response = InvocationBuilder::default().invoke_with(agent).await?;
+        response = limited(InvocationBuilder::default().invoke_with(agent)).await?;
         if let Some(tc) = response.message.tool_calls.clone() {
             for tool_msg in call_tools(agent, &tc).await {
                 agent.history.push(tool_msg);
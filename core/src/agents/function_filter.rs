@@ -1,6 +1,10 @@
 use std::{collections::BTreeMap, env};
 
-use reagent_rs::{Agent, AgentBuildError, Notification, StatelessPrebuild, Template};
+use futures::future::join_all;
+use reagent_rs::{
+    Agent, AgentBuildError, FunctionParameters, Notification, NotificationHandler,
+    StatelessPrebuild, Template, ToolCall, ToolExecutionError,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Receiver;
@@ -13,6 +17,109 @@ pub struct Requirement {
 
 type FreeObject = BTreeMap<String, serde_json::Value>;
 
+/// Removes argument keys the tool's schema does not declare, so stale keys
+/// from an outdated schema do not make the tool call fail. Returns the
+/// removed keys. With `TOOL_ARGS_UNKNOWN_KEYS=warn` the keys are only
+/// reported and kept.
+pub fn strip_unknown_arguments(
+    params: &mut serde_json::Map<String, serde_json::Value>,
+    schema: &FunctionParameters,
+) -> Vec<String> {
+    let unknown: Vec<String> = params
+        .keys()
+        .filter(|k| !schema.properties.contains_key(*k))
+        .cloned()
+        .collect();
+
+    let warn_only = env::var("TOOL_ARGS_UNKNOWN_KEYS")
+        .map(|v| v.eq_ignore_ascii_case("warn"))
+        .unwrap_or(false);
+    if !warn_only {
+        params.retain(|k, _| schema.properties.contains_key(k));
+    }
+    unknown
+}
+
+/// Runs the tool calls the filter selected and returns each tool's name with
+/// its own outcome, so an expert that is down only fails its own call.
+pub async fn call_selected_tools(
+    agent: &Agent,
+    tool_calls: &[ToolCall],
+) -> Vec<(String, Result<String, ToolExecutionError>)> {
+    let calls = tool_calls.iter().map(|call| async move {
+        let name = call.function.name.clone();
+        let Some(tool) = agent.tools.iter().flatten().find(|t| t.name() == name) else {
+            return (name.clone(), Err(ToolExecutionError::ToolNotFound(name)));
+        };
+        agent.notify_tool_request(call.clone()).await;
+        let result = tool.execute(call.function.arguments.clone()).await;
+        match &result {
+            Ok(output) => agent.notify_tool_success(output.clone()).await,
+            Err(e) => agent.notify_tool_error(e.to_string()).await,
+        };
+        (name, result)
+    });
+    join_all(calls).await
+}
+
+/// Synthesis context for one tool's outcome. A failed call is reported as
+/// unavailable so the answer can say that source could not be consulted.
+pub fn tool_context_chunk(name: &str, result: &Result<String, ToolExecutionError>) -> String {
+    match result {
+        Ok(output) => format!("# Tool resulted in:\n\n{}", output),
+        Err(e) => {
+            println!("Tool {} failed: {}", name, e);
+            format!("# Tool unavailable: {}", name)
+        }
+    }
+}
+
+/// Reply when the filter selected no tool and `NO_TOOLS_MODE=decline`.
+pub const OUT_OF_SCOPE_RESPONSE: &str = "That seems to be outside the topics I can help with. \
+I can answer questions about UP FAMNIT, such as study programmes, staff, enrolment, \
+rules and student life.";
+
+/// `Agent::state` key of the agent's `NO_TOOLS_MODE`.
+pub const NO_TOOLS_MODE_STATE: &str = "no_tools_mode";
+
+/// Records `NO_TOOLS_MODE` in the agent's state when it is built, so each
+/// flow reads the mode of its own agent.
+pub fn load_no_tools_mode(agent: &mut Agent) {
+    if let Ok(mode) = env::var("NO_TOOLS_MODE") {
+        agent
+            .state
+            .insert(NO_TOOLS_MODE_STATE.into(), serde_json::Value::String(mode));
+    }
+}
+
+/// Whether a query no tool was selected for is declined with
+/// `OUT_OF_SCOPE_RESPONSE` instead of answered conversationally.
+pub fn decline_without_tools(agent: &Agent) -> bool {
+    agent
+        .state
+        .get(NO_TOOLS_MODE_STATE)
+        .and_then(|mode| mode.as_str())
+        .is_some_and(|mode| mode.eq_ignore_ascii_case("decline"))
+}
+
+/// Prompt for a query the filter selected no tool for. Without FAMNIT data
+/// the model may only handle small talk and must not state facts about
+/// the faculty.
+pub fn no_tools_prompt(prompt: &str) -> String {
+    format!(
+        r#"No FAMNIT tool was found relevant to the user query below, so there is no retrieved information to rely on.
+
+If the query is a greeting, thanks or small talk, reply briefly and offer help with UP FAMNIT topics.
+Otherwise, politely explain that the question seems to be outside UP FAMNIT topics and mention what you can help with (study programmes, staff, enrolment, rules, student life).
+Do not state any facts about UP FAMNIT, its staff, programmes, dates or rules, and do not include links.
+
+User query:
+
+{}"#,
+        prompt
+    )
+}
+
 pub async fn build_function_filter_agent(
     urska: &mut Agent,
 ) -> Result<(Agent, Receiver<Notification>), AgentBuildError> {
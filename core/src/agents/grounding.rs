@@ -0,0 +1,285 @@
+use std::{env, fmt, future::Future};
+
+use reagent_rs::{Agent, AgentError};
+
+/// A problem found in a synthesized answer by `check_grounding`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroundingViolation {
+    /// A cited URL that does not appear verbatim in any tool output.
+    InvalidUrl(String),
+    /// A sentence stating a concrete fact (number, date, amount) without a citation.
+    UncitedClaim(String),
+}
+
+impl fmt::Display for GroundingViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GroundingViolation::InvalidUrl(url) => write!(
+                f,
+                "The URL `{}` does not appear in any tool result. Remove it or replace it with a URL that does.",
+                url
+            ),
+            GroundingViolation::UncitedClaim(claim) => write!(
+                f,
+                "The claim \"{}\" has no citation. Cite the source it comes from, or remove it if no source supports it.",
+                claim
+            ),
+        }
+    }
+}
+
+/// Checks a synthesized answer against the tool outputs it was based on.
+///
+/// Every markdown link target must appear verbatim in a tool output. When the
+/// tool outputs contain URLs, sentences with concrete facts must carry a
+/// citation.
+pub fn check_grounding(answer: &str, tool_outputs: &[String]) -> Vec<GroundingViolation> {
+    let mut violations = vec![];
+
+    for url in markdown_link_targets(answer) {
+        if !tool_outputs.iter().any(|o| o.contains(&url)) {
+            let violation = GroundingViolation::InvalidUrl(url);
+            if !violations.contains(&violation) {
+                violations.push(violation);
+            }
+        }
+    }
+
+    let sources_available = tool_outputs.iter().any(|o| o.contains("http"));
+    if !sources_available {
+        return violations;
+    }
+
+    for sentence in factual_sentences(answer) {
+        if !sentence.contains("](") {
+            violations.push(GroundingViolation::UncitedClaim(sentence));
+        }
+    }
+
+    violations
+}
+
+/// Sentences of the answer body (before the references) that state a
+/// concrete fact.
+pub(crate) fn factual_sentences(answer: &str) -> Vec<String> {
+    let mut sentences = vec![];
+    let body = answer.split("## References").next().unwrap_or(answer);
+    for line in body.lines() {
+        let line = strip_list_marker(line.trim());
+        if line.is_empty() || line.starts_with('#') || line.starts_with('|') {
+            continue;
+        }
+        for sentence in split_sentences(line) {
+            if is_factual(&sentence) {
+                sentences.push(sentence);
+            }
+        }
+    }
+    sentences
+}
+
+/// Splits a line on ". ". Citations placed right after a sentence's period
+/// ("... 180 ECTS. [1](url) The ...") stay with the sentence they support.
+fn split_sentences(line: &str) -> Vec<String> {
+    let mut sentences: Vec<String> = vec![];
+    for part in line.split_inclusive(". ") {
+        let part = part.trim();
+        let cited = leading_citations_len(part);
+        let rest = match sentences.last_mut() {
+            Some(previous) if cited > 0 => {
+                previous.push(' ');
+                previous.push_str(part[..cited].trim());
+                part[cited..].trim()
+            }
+            _ => part,
+        };
+        if rest.chars().any(|c| c.is_alphanumeric()) {
+            sentences.push(rest.to_string());
+        }
+    }
+    sentences
+}
+
+/// Length of the `[n](url)` citations the text starts with.
+fn leading_citations_len(text: &str) -> usize {
+    let mut end = 0;
+    loop {
+        let rest = &text[end..];
+        let trimmed = rest.trim_start();
+        let Some(label) = trimmed.strip_prefix('[') else {
+            break;
+        };
+        let Some(label_end) = label.find("](") else {
+            break;
+        };
+        if label[..label_end].contains(']') {
+            break;
+        }
+        let Some(url_end) = label[label_end + 2..].find(')') else {
+            break;
+        };
+        end += rest.len() - trimmed.len() + 1 + label_end + 2 + url_end + 1;
+    }
+    end
+}
+
+/// Builds the follow-up instruction for a re-synthesis pass.
+pub fn retry_instructions(violations: &[GroundingViolation]) -> String {
+    let issues: Vec<String> = violations.iter().map(|v| format!("- {}", v)).collect();
+    format!(
+        "Your previous answer failed the source check:\n\n{}\n\nRewrite the full answer fixing exactly these issues. Use only URLs that appear in the tool results above and keep everything else that was correct.",
+        issues.join("\n")
+    )
+}
+
+/// `Agent::state` key of the agent's `SYNTHESIS_RETRY`.
+pub const SYNTHESIS_RETRY_STATE: &str = "synthesis_retry";
+
+/// Records `SYNTHESIS_RETRY` in the agent's state when it is built.
+pub fn load_synthesis_retry(agent: &mut Agent) {
+    if let Ok(retry) = env::var("SYNTHESIS_RETRY") {
+        agent
+            .state
+            .insert(SYNTHESIS_RETRY_STATE.into(), serde_json::Value::String(retry));
+    }
+}
+
+/// Whether a synthesized answer that fails the grounding check gets one
+/// retry, as set by `SYNTHESIS_RETRY=true`.
+pub fn synthesis_retry_enabled(agent: &Agent) -> bool {
+    agent
+        .state
+        .get(SYNTHESIS_RETRY_STATE)
+        .and_then(|retry| retry.as_str())
+        .is_some_and(|retry| retry.eq_ignore_ascii_case("true"))
+}
+
+/// Gives the model one chance to fix the violations of `answer` when
+/// `retry_enabled`. `resynthesize` receives the `retry_instructions` and
+/// returns the new answer. Returns the answer to ship and the violations it
+/// still has.
+pub async fn synthesize_with_retry<F, Fut>(
+    answer: String,
+    tool_outputs: &[String],
+    retry_enabled: bool,
+    resynthesize: F,
+) -> Result<(String, Vec<GroundingViolation>), AgentError>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String, AgentError>>,
+{
+    let violations = check_grounding(&answer, tool_outputs);
+    if violations.is_empty() || !retry_enabled {
+        return Ok((answer, violations));
+    }
+    println!("Grounding check failed, retrying synthesis: {:#?}", violations);
+    let retried = resynthesize(retry_instructions(&violations)).await?;
+    let violations = check_grounding(&retried, tool_outputs);
+    Ok((retried, violations))
+}
+
+pub const GROUNDING_NOTICE: &str = "\n\n> Note: some statements in this answer could not be matched to a cited source. Please verify them on the official UP FAMNIT pages.";
+
+pub(crate) fn markdown_link_targets(text: &str) -> Vec<String> {
+    text.split("](")
+        .skip(1)
+        .filter_map(|rest| rest.split(')').next())
+        .map(|url| url.trim().to_string())
+        .filter(|url| url.starts_with("http"))
+        .collect()
+}
+
+fn strip_list_marker(line: &str) -> &str {
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    if rest.len() != line.len() && rest.starts_with(". ") {
+        return rest[2..].trim_start();
+    }
+    line.strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .unwrap_or(line)
+}
+
+fn is_factual(sentence: &str) -> bool {
+    // short fragments ("1.", "Yes.") are not claims
+    sentence.split_whitespace().count() >= 4 && sentence.chars().any(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs() -> Vec<String> {
+        vec!["The programme has 180 ECTS. https://www.famnit.upr.si/en/programme".to_string()]
+    }
+
+    #[test]
+    fn citation_after_the_period_counts_for_the_sentence() {
+        let answer = "The programme has 180 ECTS in total. [1](https://www.famnit.upr.si/en/programme) It lasts 3 years in Koper [1](https://www.famnit.upr.si/en/programme).";
+        assert!(check_grounding(answer, &outputs()).is_empty());
+    }
+
+    #[test]
+    fn sentence_without_citation_is_reported() {
+        let answer = "The programme has 180 ECTS in total [1](https://www.famnit.upr.si/en/programme). It lasts 3 years in Koper.";
+        assert_eq!(
+            check_grounding(answer, &outputs()),
+            vec![GroundingViolation::UncitedClaim(
+                "It lasts 3 years in Koper.".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn invented_url_is_reported() {
+        let answer = "See the programme page [1](https://www.famnit.upr.si/en/other).";
+        assert_eq!(
+            check_grounding(answer, &outputs()),
+            vec![GroundingViolation::InvalidUrl(
+                "https://www.famnit.upr.si/en/other".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn claims_are_not_required_without_source_urls() {
+        let outputs = vec!["The programme has 180 ECTS.".to_string()];
+        assert!(check_grounding("The programme has 180 ECTS in total.", &outputs).is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_replaces_an_uncited_answer() {
+        let first = "The programme has 180 ECTS in total.".to_string();
+        let cited = "The programme has 180 ECTS in total [1](https://www.famnit.upr.si/en/programme).";
+
+        let (answer, violations) = synthesize_with_retry(first, &outputs(), true, |instructions| async move {
+            assert!(instructions.contains("The programme has 180 ECTS in total."));
+            Ok(cited.to_string())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(answer, cited);
+        assert!(violations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_is_skipped_when_disabled_or_grounded() {
+        let uncited = "The programme has 180 ECTS in total.".to_string();
+        let (answer, violations) = synthesize_with_retry(uncited.clone(), &outputs(), false, |_| async {
+            panic!("retry must not run when disabled")
+        })
+        .await
+        .unwrap();
+        assert_eq!(answer, uncited);
+        assert_eq!(violations.len(), 1);
+
+        let cited = "The programme has 180 ECTS in total [1](https://www.famnit.upr.si/en/programme).".to_string();
+        let (answer, violations) = synthesize_with_retry(cited.clone(), &outputs(), true, |_| async {
+            panic!("retry must not run for a grounded answer")
+        })
+        .await
+        .unwrap();
+        assert_eq!(answer, cited);
+        assert!(violations.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+use std::{collections::HashMap, env};
+
+use reagent_rs::{
+    Agent, AgentBuildError, AgentError, Message, Notification, NotificationHandler, Role,
+    StatelessPrebuild, Template,
+};
+use tokio::sync::mpsc::Receiver;
+
+use crate::agents::{ollama_limit::limited, usrka::history_to_prompt};
+
+/// Start of the system message that replaces the summarized turns.
+pub const SUMMARY_PREFIX: &str = "Summary of the earlier conversation:";
+
+const DEFAULT_MAX_HISTORY_MESSAGES: usize = 24;
+const DEFAULT_RECENT_MESSAGES: usize = 8;
+
+fn env_usize(key: &str, default: usize) -> usize {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(default)
+}
+
+pub fn is_summary(message: &Message) -> bool {
+    message.role == Role::System
+        && message
+            .content
+            .as_deref()
+            .is_some_and(|c| c.starts_with(SUMMARY_PREFIX))
+}
+
+/// Index of the first message kept verbatim when `history` is longer than
+/// `max_messages`, or `None` when it does not need compacting. The system
+/// prompt and a previous summary are never counted as turns, and the kept
+/// part always starts at a user message so tool calls stay with their
+/// results.
+pub fn compaction_split(history: &[Message], max_messages: usize, recent: usize) -> Option<usize> {
+    if history.len() <= max_messages {
+        return None;
+    }
+    let first_turn = if history.get(1).is_some_and(is_summary) { 2 } else { 1 };
+    let mut split = history.len().saturating_sub(recent.max(1));
+    while split > first_turn && history[split].role != Role::User {
+        split -= 1;
+    }
+    (split > first_turn).then_some(split)
+}
+
+/// Replaces the older turns of a long conversation with a summary, keeping
+/// the last `HISTORY_RECENT_MESSAGES` messages verbatim once the history
+/// exceeds `HISTORY_MAX_MESSAGES`.
+pub async fn compact_history(agent: &mut Agent) -> Result<(), AgentError> {
+    let max_messages = env_usize("HISTORY_MAX_MESSAGES", DEFAULT_MAX_HISTORY_MESSAGES);
+    let recent = env_usize("HISTORY_RECENT_MESSAGES", DEFAULT_RECENT_MESSAGES);
+    let Some(split) = compaction_split(&agent.history, max_messages, recent) else {
+        return Ok(());
+    };
+
+    let (mut summarizer_agent, summarizer_notification_channel) =
+        create_history_summarizer_agent(agent).await?;
+    agent.forward_notifications(summarizer_notification_channel);
+
+    let older = history_to_prompt(&agent.history[..split]);
+    let response = limited(
+        summarizer_agent.invoke_flow_with_template(HashMap::from([("conversation", older)])),
+    )
+    .await?;
+    let Some(summary) = response.content.filter(|s| !s.trim().is_empty()) else {
+        return Ok(());
+    };
+
+    let mut compacted = vec![
+        agent.history[0].clone(),
+        Message::system(format!("{}\n{}", SUMMARY_PREFIX, summary.trim())),
+    ];
+    compacted.extend(agent.history[split..].iter().cloned());
+    println!(
+        "Compacted history from {} to {} messages",
+        agent.history.len(),
+        compacted.len()
+    );
+    agent.history = compacted;
+    Ok(())
+}
+
+async fn create_history_summarizer_agent(
+    ref_agent: &Agent,
+) -> Result<(Agent, Receiver<Notification>), AgentBuildError> {
+    let ollama_config = ref_agent.export_client_config();
+    let model_config = ref_agent.export_model_config();
+    let prompt_config = ref_agent.export_prompt_config().await.unwrap_or_default();
+
+    let system_prompt = r#"You summarize conversations between a student and Urška, the assistant of UP FAMNIT.
+
+Write a compact summary of the conversation you receive, so the assistant can continue it without the full transcript:
+- Keep what the user asked about and what they said about themselves (programme, year, status, language).
+- Keep the concrete facts, names, numbers, dates and URLs the assistant gave.
+- Keep open questions and anything the user still expects an answer to.
+- Leave out greetings, filler and tool details.
+
+Respond only with the summary, as short bullet points."#;
+
+    let template = Template::simple(
+        r#"
+    # Conversation:
+
+    {{conversation}}
+
+    ---
+
+    Summarize the conversation above.
+    "#,
+    );
+
+    StatelessPrebuild::reply_without_tools()
+        .import_client_config(ollama_config)
+        .import_model_config(model_config)
+        .import_prompt_config(prompt_config)
+        .set_name("History summarizer")
+        .set_system_prompt(system_prompt)
+        .set_template(template)
+        .set_clear_history_on_invocation(true)
+        .strip_thinking(true)
+        .build_with_notification()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A system prompt followed by `turns` question and answer pairs.
+    fn conversation(turns: usize) -> Vec<Message> {
+        let mut history = vec![Message::system("You are Urška.")];
+        for turn in 0..turns {
+            history.push(Message::user(format!("Question {}", turn)));
+            history.push(Message::assistant(format!("Answer {}", turn)));
+        }
+        history
+    }
+
+    #[test]
+    fn short_history_is_not_compacted() {
+        assert_eq!(compaction_split(&conversation(11), 24, 8), None);
+    }
+
+    #[test]
+    fn long_history_keeps_the_recent_turns() {
+        let history = conversation(15);
+        let split = compaction_split(&history, 24, 8).unwrap();
+        assert_eq!(split, 23);
+        assert_eq!(history[split].content.as_deref(), Some("Question 11"));
+    }
+
+    #[test]
+    fn kept_part_starts_at_a_user_message() {
+        let mut history = conversation(15);
+        history.insert(25, Message::tool("Tool output", "call_0"));
+        let split = compaction_split(&history, 24, 8).unwrap();
+        assert_eq!(history[split].role, Role::User);
+        assert!(history[split..].iter().any(|m| m.role == Role::Tool));
+    }
+
+    #[test]
+    fn summary_alone_before_the_recent_turns_is_kept() {
+        let mut history = conversation(2);
+        history.insert(1, Message::system(format!("{}\n- Earlier turns", SUMMARY_PREFIX)));
+        assert_eq!(compaction_split(&history, 4, 4), None);
+    }
+}
@@ -0,0 +1,82 @@
+use std::fmt;
+
+/// Language of a user prompt or an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Slovenian,
+    English,
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::Slovenian => write!(f, "Slovenian"),
+            Language::English => write!(f, "English"),
+        }
+    }
+}
+
+const SLOVENIAN_WORDS: [&str; 24] = [
+    "je", "in", "za", "na", "so", "ali", "se", "da", "ki", "pri", "ter", "lahko", "tudi", "oz",
+    "kako", "kdaj", "kje", "kaj", "kdo", "koliko", "sem", "mi", "moj", "kateri",
+];
+const ENGLISH_WORDS: [&str; 20] = [
+    "the", "and", "is", "are", "of", "to", "for", "you", "with", "can", "on", "in", "how",
+    "when", "where", "what", "who", "do", "i", "my",
+];
+
+/// Whether `text` is Slovenian or English, by counting common function words
+/// of each language and words with Slovenian letters (č, š, ž). Ties, and
+/// text with neither, count as English.
+pub fn detect_language(text: &str) -> Language {
+    let mut slovenian = 0;
+    let mut english = 0;
+    for word in text.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()) {
+        let word = word.to_lowercase();
+        if word.contains(['č', 'š', 'ž']) || SLOVENIAN_WORDS.contains(&word.as_str()) {
+            slovenian += 1;
+        }
+        if ENGLISH_WORDS.contains(&word.as_str()) {
+            english += 1;
+        }
+    }
+    if slovenian > english {
+        Language::Slovenian
+    } else {
+        Language::English
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slovenian_prompts_are_detected() {
+        for prompt in [
+            "Kdaj je rok za vpis v drugi letnik?",
+            "Koliko stane šolnina za izredne študente?",
+            "Kdo je nosilec predmeta Podatkovne baze?",
+            "Ali lahko opravljam izpit tudi v septembru?",
+        ] {
+            assert_eq!(detect_language(prompt), Language::Slovenian, "{}", prompt);
+        }
+    }
+
+    #[test]
+    fn english_prompts_are_detected() {
+        for prompt in [
+            "When is the enrolment deadline for the second year?",
+            "How much is the tuition fee for part-time students?",
+            "Who teaches Databases?",
+            "Can I take the exam in September?",
+        ] {
+            assert_eq!(detect_language(prompt), Language::English, "{}", prompt);
+        }
+    }
+
+    #[test]
+    fn prompt_without_function_words_counts_as_english() {
+        assert_eq!(detect_language("FAMNIT 2025"), Language::English);
+    }
+}
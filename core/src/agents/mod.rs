@@ -1,10 +1,22 @@
 pub mod blueprint;
+pub mod citations;
+pub mod confidence;
+pub mod evidence;
 pub mod executor;
 pub mod function_filter;
+pub mod grounding;
+pub mod history_summary;
+pub mod language;
+pub mod notification_dedup;
+pub mod numbers;
+pub mod ollama_limit;
+pub mod persona;
 pub mod planner;
 pub mod prompt_reconstuct;
 pub mod quick_responder;
 pub mod replanner;
+pub mod sources;
+pub mod staleness;
 pub mod urska_v2;
 pub mod urska_v3;
 pub mod usrka;
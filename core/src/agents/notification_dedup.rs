@@ -0,0 +1,107 @@
+use std::env;
+
+use reagent_rs::{Notification, NotificationContent};
+
+const DEFAULT_DEDUP_WINDOW_MILLIS: u128 = 2000;
+
+/// Drops a notification that repeats the previous one, e.g. the same step
+/// text reported by several branches of a plan, so the client only sees
+/// distinct progress updates.
+pub struct NotificationDedup {
+    window_millis: u128,
+    last: Option<(String, u128)>,
+}
+
+impl NotificationDedup {
+    /// Window read from `NOTIFICATION_DEDUP_WINDOW_MS` (`0` forwards every
+    /// notification).
+    pub fn from_env() -> Self {
+        let window_millis = env::var("NOTIFICATION_DEDUP_WINDOW_MS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u128>().ok())
+            .unwrap_or(DEFAULT_DEDUP_WINDOW_MILLIS);
+        Self { window_millis, last: None }
+    }
+
+    /// Whether `notification` should be forwarded. It is suppressed when the
+    /// same agent sent the same content as the last forwarded notification
+    /// less than the window ago.
+    pub fn should_forward(&mut self, notification: &Notification) -> bool {
+        // streamed tokens repeat legitimately ("\n\n", " the") and are
+        // relayed to the client as answer text
+        if self.window_millis == 0 || matches!(notification.content, NotificationContent::Token(_)) {
+            return true;
+        }
+        let key = format!(
+            "{}:{}",
+            notification.agent,
+            serde_json::to_string(&notification.content).unwrap_or_default()
+        );
+        if let Some((last_key, sent_at)) = &self.last {
+            let elapsed = notification.timestamp_millis.saturating_sub(*sent_at);
+            if *last_key == key && elapsed < self.window_millis {
+                return false;
+            }
+        }
+        self.last = Some((key, notification.timestamp_millis));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reagent_rs::Token;
+    use serde_json::json;
+
+    use super::*;
+
+    fn custom(agent: &str, message: &str, timestamp_millis: u128) -> Notification {
+        Notification {
+            agent: agent.to_string(),
+            content: NotificationContent::Custom(json!({ "message": message })),
+            mcp_envelope: None,
+            timestamp_millis,
+        }
+    }
+
+    fn dedup() -> NotificationDedup {
+        NotificationDedup { window_millis: 2000, last: None }
+    }
+
+    #[test]
+    fn duplicate_consecutive_notifications_collapse() {
+        let mut dedup = dedup();
+        let forwarded: Vec<bool> = [
+            custom("Urška", "Executing plan...", 0),
+            custom("Urška", "Executing plan...", 300),
+            custom("Urška", "Executing plan...", 600),
+            custom("Urška", "Writing the answer...", 900),
+        ]
+        .iter()
+        .map(|n| dedup.should_forward(n))
+        .collect();
+
+        assert_eq!(forwarded, [true, false, false, true]);
+    }
+
+    #[test]
+    fn repeat_after_the_window_or_from_another_agent_is_forwarded() {
+        let mut dedup = dedup();
+        assert!(dedup.should_forward(&custom("Urška", "Executing plan...", 0)));
+        assert!(dedup.should_forward(&custom("Executor", "Executing plan...", 100)));
+        assert!(dedup.should_forward(&custom("Executor", "Executing plan...", 2500)));
+    }
+
+    #[test]
+    fn repeated_tokens_are_always_forwarded() {
+        let mut dedup = dedup();
+        let token = Notification {
+            agent: "Urška".into(),
+            content: NotificationContent::Token(Token { tag: None, value: "\n\n".into() }),
+            mcp_envelope: None,
+            timestamp_millis: 0,
+        };
+        assert!(dedup.should_forward(&token));
+        assert!(dedup.should_forward(&token));
+    }
+}
@@ -0,0 +1,240 @@
+use std::env;
+
+use crate::agents::language::{Language, detect_language};
+
+/// Decimal separator convention of an answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberStyle {
+    /// `3,5` and `3.500,00` (Slovenian).
+    DecimalComma,
+    /// `3.5` and `3,500.00` (English).
+    DecimalDot,
+}
+
+impl NumberStyle {
+    fn separators(self) -> (char, char) {
+        match self {
+            NumberStyle::DecimalComma => (',', '.'),
+            NumberStyle::DecimalDot => ('.', ','),
+        }
+    }
+}
+
+/// Words before a `HH.MM` number that make it a time of day rather than a
+/// decimal ("ob 12.30", "from 9.15").
+const TIME_WORDS: [&str; 9] = ["ob", "od", "do", "at", "from", "to", "until", "between", "med"];
+
+/// Convention the answer is normalized to: `NUMBER_FORMAT=comma` or `dot`
+/// forces one, `off` disables the pass, and anything else picks the decimal
+/// comma for Slovenian answers and the dot otherwise.
+pub fn number_style(answer: &str) -> Option<NumberStyle> {
+    match env::var("NUMBER_FORMAT")
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+        .as_str()
+    {
+        "comma" => Some(NumberStyle::DecimalComma),
+        "dot" => Some(NumberStyle::DecimalDot),
+        "off" => None,
+        _ if detect_language(answer) == Language::Slovenian => Some(NumberStyle::DecimalComma),
+        _ => Some(NumberStyle::DecimalDot),
+    }
+}
+
+/// Rewrites a run of digits and separators in `style`, or returns `None`
+/// when the run is not clearly a decimal or grouped number. Dates
+/// (`1.2.2024`), versions and a lone separator followed by three digits
+/// (`3.500` could be either) are left alone.
+fn convert_number(run: &str, style: NumberStyle) -> Option<String> {
+    let dots = run.matches('.').count();
+    let commas = run.matches(',').count();
+    let (decimal, group) = match (dots, commas) {
+        (0, 0) => return None,
+        (1, 0) | (0, 1) => {
+            let separator = if dots == 1 { '.' } else { ',' };
+            let (_, fraction) = run.split_once(separator)?;
+            if fraction.len() == 3 {
+                return None;
+            }
+            (separator, None)
+        }
+        _ if dots > 0 && commas > 0 => {
+            let decimal = if run.rfind('.') > run.rfind(',') { '.' } else { ',' };
+            let group = if decimal == '.' { ',' } else { '.' };
+            if run.matches(decimal).count() != 1 {
+                return None;
+            }
+            let (whole, _) = run.split_once(decimal)?;
+            let mut groups = whole.split(group);
+            let first = groups.next()?;
+            if first.is_empty() || first.len() > 3 || !groups.all(|g| g.len() == 3) {
+                return None;
+            }
+            (decimal, Some(group))
+        }
+        _ => return None,
+    };
+
+    let (target_decimal, target_group) = style.separators();
+    Some(
+        run.chars()
+            .map(|c| {
+                if c == decimal {
+                    target_decimal
+                } else if Some(c) == group {
+                    target_group
+                } else {
+                    c
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Whether `run` reads as a time of day given the text `before` and
+/// `after` it and the `previous` word.
+fn is_time(run: &str, previous: Option<&str>, before: &str, after: &str) -> bool {
+    let Some((hours, minutes)) = run.split_once('.') else {
+        return false;
+    };
+    let is_clock = minutes.len() == 2
+        && hours.parse::<u32>().is_ok_and(|h| h <= 23)
+        && minutes.parse::<u32>().is_ok_and(|m| m <= 59);
+    if !is_clock {
+        return false;
+    }
+    previous.is_some_and(|w| TIME_WORDS.contains(&w.to_lowercase().as_str()))
+        || before.ends_with(['-', '–'])
+        || after.trim_start().starts_with(['h', '-', '–'])
+}
+
+/// Normalizes the numbers in one whitespace-free token. `previous` is the
+/// token before it and `next` the one after.
+fn normalize_token(token: &str, previous: Option<&str>, next: Option<&str>, style: NumberStyle) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    let mut out = String::with_capacity(token.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_number = chars[i].is_ascii_digit()
+            && (i == 0 || !(chars[i - 1].is_alphanumeric() || matches!(chars[i - 1], '.' | ',' | '_' | '#')));
+        if !starts_number {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let mut end = i;
+        while end < chars.len() && (chars[end].is_ascii_digit() || matches!(chars[end], '.' | ',')) {
+            end += 1;
+        }
+        // a trailing separator is punctuation, not part of the number
+        while end > i && matches!(chars[end - 1], '.' | ',') {
+            end -= 1;
+        }
+        let run: String = chars[i..end].iter().collect();
+        let rest: String = chars[end..].iter().collect();
+        let after = format!("{} {}", rest, next.unwrap_or_default());
+
+        match convert_number(&run, style) {
+            Some(converted) if !is_time(&run, previous, &out, &after) => out.push_str(&converted),
+            _ => out.push_str(&run),
+        }
+        i = end;
+    }
+    out
+}
+
+/// Whether a token is a link, path, address or code that must keep its
+/// digits as they are.
+fn is_protected(token: &str) -> bool {
+    token.contains("://")
+        || token.starts_with("www.")
+        || token.contains(['/', '\\', '@', '='])
+}
+
+/// Rewrites decimal numbers in `text` to `style`. URLs, paths (such as
+/// `/static/3775`), e-mail addresses and code are left untouched.
+pub fn normalize_numbers(text: &str, style: NumberStyle) -> String {
+    let mut in_fence = false;
+    text.split('\n')
+        .map(|line| {
+            if line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                return line.to_string();
+            }
+            if in_fence {
+                return line.to_string();
+            }
+            let tokens: Vec<&str> = line.split(' ').collect();
+            let mut in_code = false;
+            tokens
+                .iter()
+                .enumerate()
+                .map(|(i, token)| {
+                    let ticks = token.matches('`').count();
+                    let protected = in_code || ticks > 0 || is_protected(token);
+                    if ticks % 2 == 1 {
+                        in_code = !in_code;
+                    }
+                    if protected {
+                        return token.to_string();
+                    }
+                    let previous = i.checked_sub(1).and_then(|p| tokens.get(p)).copied();
+                    normalize_token(token, previous, tokens.get(i + 1).copied(), style)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Post-synthesis pass that gives every answer one number convention.
+pub fn normalize_answer(answer: &str) -> String {
+    match number_style(answer) {
+        Some(style) => normalize_numbers(answer, style),
+        None => answer.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_and_grouped_numbers_follow_the_style() {
+        assert_eq!(
+            normalize_numbers("The fee is 3,500.50 EUR and the course has 7.5 ECTS.", NumberStyle::DecimalComma),
+            "The fee is 3.500,50 EUR and the course has 7,5 ECTS."
+        );
+        assert_eq!(
+            normalize_numbers("Šolnina znaša 3.500,50 EUR, predmet ima 7,5 ECTS.", NumberStyle::DecimalDot),
+            "Šolnina znaša 3,500.50 EUR, predmet ima 7.5 ECTS."
+        );
+    }
+
+    #[test]
+    fn dates_times_and_ambiguous_numbers_are_kept() {
+        let text = "Enrolment ends 1.2.2024, lectures start at 9.15 and the fee is 3.500 EUR.";
+        assert_eq!(normalize_numbers(text, NumberStyle::DecimalComma), text);
+        let text = "Konzultacije so 10.30-12.00 v kabinetu.";
+        assert_eq!(normalize_numbers(text, NumberStyle::DecimalDot), text);
+    }
+
+    #[test]
+    fn links_paths_and_code_are_kept() {
+        let text = "See [rules](https://www.famnit.upr.si/static/3775?v=2.5), `version 1.5` and a.b@famnit.upr.si.";
+        assert_eq!(normalize_numbers(text, NumberStyle::DecimalComma), text);
+        let fenced = "```\nratio = 2.5\n```";
+        assert_eq!(normalize_numbers(fenced, NumberStyle::DecimalComma), fenced);
+    }
+
+    #[test]
+    fn trailing_punctuation_is_not_part_of_the_number() {
+        assert_eq!(
+            normalize_numbers("The average grade was 8.25.", NumberStyle::DecimalComma),
+            "The average grade was 8,25."
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::{env, future::Future, sync::LazyLock};
+
+use tokio::sync::Semaphore;
+
+const DEFAULT_MAX_CONCURRENT_GENERATIONS: usize = 4;
+
+/// Permits for model calls, shared by every agent of the process. The size
+/// is read from `OLLAMA_MAX_CONCURRENCY` (`0` removes the cap).
+static GENERATION_PERMITS: LazyLock<Semaphore> = LazyLock::new(|| {
+    let limit = env::var("OLLAMA_MAX_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_GENERATIONS);
+    Semaphore::new(if limit == 0 { Semaphore::MAX_PERMITS } else { limit })
+});
+
+/// Runs a single model call once a permit is free, so the sub-agents of all
+/// requests together never exceed the configured number of concurrent
+/// generations. Wrap only calls that reach the model directly; a flow that
+/// makes wrapped calls itself must not be wrapped, or it would wait on its
+/// own permit.
+pub async fn limited<F: Future>(call: F) -> F::Output {
+    with_permit(&GENERATION_PERMITS, call).await
+}
+
+async fn with_permit<F: Future>(permits: &Semaphore, call: F) -> F::Output {
+    let _permit = permits
+        .acquire()
+        .await
+        .expect("generation semaphore is never closed");
+    call.await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use futures::future::join_all;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn simultaneous_generations_never_exceed_the_limit() {
+        let permits = Semaphore::new(2);
+        let running = AtomicUsize::new(0);
+        let most_running = AtomicUsize::new(0);
+
+        let generations = (0..8).map(|_| {
+            with_permit(&permits, async {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                most_running.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+        join_all(generations).await;
+
+        assert_eq!(most_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn shared_limit_still_completes_every_generation() {
+        let answers = join_all((0..3).map(|i| limited(async move { i * 2 }))).await;
+        assert_eq!(answers, [0, 2, 4]);
+    }
+}
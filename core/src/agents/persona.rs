@@ -0,0 +1,57 @@
+use std::env;
+
+/// Tone of the synthesized answer. Only affects wording; the grounding and
+/// citation rules of the system prompt stay the same for every persona.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Persona {
+    #[default]
+    FriendlyAdvisor,
+    FormalAdministrative,
+}
+
+impl Persona {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "friendly-advisor" | "friendly" => Some(Self::FriendlyAdvisor),
+            "formal-administrative" | "formal" => Some(Self::FormalAdministrative),
+            _ => None,
+        }
+    }
+
+    /// Persona requested for a single query, falling back to `URSKA_PERSONA`
+    /// and then to the default.
+    pub fn resolve(requested: Option<&str>) -> Self {
+        requested
+            .and_then(Self::parse)
+            .or_else(|| env::var("URSKA_PERSONA").ok().as_deref().and_then(Self::parse))
+            .unwrap_or_default()
+    }
+
+    pub fn instructions(&self) -> &'static str {
+        match self {
+            Persona::FriendlyAdvisor => {
+                "Write like a friendly student advisor: warm, encouraging, address the user directly and keep sentences short."
+            }
+            Persona::FormalAdministrative => {
+                "Write in a formal, administrative register: neutral and precise, no greetings or emoji, use complete sentences."
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persona_names_and_aliases_are_parsed() {
+        assert_eq!(Persona::parse(" Formal "), Some(Persona::FormalAdministrative));
+        assert_eq!(Persona::parse("friendly-advisor"), Some(Persona::FriendlyAdvisor));
+        assert_eq!(Persona::parse("pirate"), None);
+    }
+
+    #[test]
+    fn requested_persona_wins() {
+        assert_eq!(Persona::resolve(Some("formal")), Persona::FormalAdministrative);
+    }
+}
@@ -1,6 +1,6 @@
-use std::env;
+use std::{env, fmt, future::Future};
 
-use reagent_rs::{Agent, AgentBuildError, Notification, StatelessPrebuild, Template};
+use reagent_rs::{Agent, AgentBuildError, AgentError, Notification, StatelessPrebuild, Template};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Receiver;
@@ -10,6 +10,149 @@ pub struct Plan {
     pub steps: Vec<Vec<String>>,
 }
 
+/// Most steps the planner may chain in one block.
+const MAX_STEPS_PER_BLOCK: usize = 2;
+
+/// A way in which planner output breaks the plan contract. Block and step
+/// numbers are 1-based, as the planner is told about them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanValidationError {
+    NoBlocks,
+    EmptyBlock { block: usize },
+    TooManySteps { block: usize, steps: usize },
+    EmptyStep { block: usize, step: usize },
+}
+
+impl fmt::Display for PlanValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanValidationError::NoBlocks => write!(f, "the plan has no blocks; it needs at least one"),
+            PlanValidationError::EmptyBlock { block } => {
+                write!(f, "block {} has no steps; each block needs one or two", block)
+            }
+            PlanValidationError::TooManySteps { block, steps } => write!(
+                f,
+                "block {} has {} steps; a block chains at most {}",
+                block, steps, MAX_STEPS_PER_BLOCK
+            ),
+            PlanValidationError::EmptyStep { block, step } => {
+                write!(f, "step {} of block {} is empty", step, block)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlanValidationError {}
+
+impl Plan {
+    /// Checks the contract of the planner prompt: at least one block, each
+    /// with one or two non-empty steps.
+    pub fn validate(&self) -> Result<(), PlanValidationError> {
+        if self.steps.is_empty() {
+            return Err(PlanValidationError::NoBlocks);
+        }
+        for (i, block) in self.steps.iter().enumerate() {
+            let block_no = i + 1;
+            if block.is_empty() {
+                return Err(PlanValidationError::EmptyBlock { block: block_no });
+            }
+            if block.len() > MAX_STEPS_PER_BLOCK {
+                return Err(PlanValidationError::TooManySteps { block: block_no, steps: block.len() });
+            }
+            if let Some(j) = block.iter().position(|step| step.trim().is_empty()) {
+                return Err(PlanValidationError::EmptyStep { block: block_no, step: j + 1 });
+            }
+        }
+        Ok(())
+    }
+
+    /// Degraded plan that hands the whole task to one executor.
+    pub fn single_step(task: &str) -> Self {
+        Self { steps: vec![vec![task.to_string()]] }
+    }
+}
+
+/// `plan` if it is valid. Otherwise the planner gets one more chance:
+/// `replan` receives the correction to add to its prompt. When that plan is
+/// invalid too, or the planner fails, the whole `task` runs as one step.
+pub async fn validated_plan<F, Fut>(plan: Plan, task: &str, replan: F) -> Plan
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<Plan, AgentError>>,
+{
+    let Err(e) = plan.validate() else {
+        return plan;
+    };
+    println!("Invalid plan ({}), asking the planner again", e);
+    let correction = format!("Your previous plan was invalid: {}. Return a corrected plan.", e);
+    match replan(correction).await {
+        Ok(retried) => match retried.validate() {
+            Ok(()) => retried,
+            Err(e) => {
+                println!("Plan still invalid ({}), falling back to a single step", e);
+                Plan::single_step(task)
+            }
+        },
+        Err(e) => {
+            println!("Planner failed on retry ({}), falling back to a single step", e);
+            Plan::single_step(task)
+        }
+    }
+}
+
+/// Phrases with which a user explicitly asks to see the plan instead of
+/// the answer.
+const PLAN_REQUESTS: [&str; 14] = [
+    "show me your plan",
+    "show your plan",
+    "show me the plan",
+    "what is your plan",
+    "what's your plan",
+    "show me your steps",
+    "show your steps",
+    "show me the steps you",
+    "which steps will you",
+    "what steps will you",
+    "pokaži načrt",
+    "pokaži mi načrt",
+    "kakšen je tvoj načrt",
+    "pokaži korake",
+];
+
+/// Whether the user explicitly asks to see the plan.
+pub fn asks_for_plan(prompt: &str) -> bool {
+    let lower = prompt.to_lowercase().replace('’', "'");
+    PLAN_REQUESTS.iter().any(|p| lower.contains(p))
+}
+
+/// Whether the plan is appended to every answer (`SHOW_PLAN=true`).
+pub fn show_plan_with_answer() -> bool {
+    env::var("SHOW_PLAN").is_ok_and(|v| matches!(v.trim(), "1" | "true" | "yes"))
+}
+
+/// Answer to a user who asked to see the plan: the plan itself.
+pub fn plan_answer(plan: &Plan) -> String {
+    format!("Here is the plan I would follow to answer this:\n\n{}", plan)
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.steps.is_empty() {
+            return write!(f, "*The plan has no steps.*");
+        }
+        for (i, sequence) in self.steps.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "**Track {}** (runs in parallel with the other tracks)", i + 1)?;
+            for (j, step) in sequence.iter().enumerate() {
+                writeln!(f, "{}. {}", j + 1, step.trim())?;
+            }
+        }
+        Ok(())
+    }
+}
+
 pub async fn create_planner_agent(
     ref_agent: &Agent,
 ) -> Result<(Agent, Receiver<Notification>), AgentBuildError> {
@@ -71,7 +214,7 @@ You are a meticulous Tactical Planner Agent. You will be given a high-level stra
 
 7. **No memory or synthesis**
 
-   * Do not use `query_memory` or `store_memory`.
+   * Do not use `query_memory`, `store_memory`, `update_memory` or `delete_memory`.
    * Do not summarize or give prescriptive advice inside the plan. Synthesis happens elsewhere.
 
 8. **No generic steps**
@@ -245,3 +388,114 @@ You are a meticulous Tactical Planner Agent. You will be given a high-level stra
         .build_with_notification()
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn plan(steps: &[&[&str]]) -> Plan {
+        Plan {
+            steps: steps
+                .iter()
+                .map(|block| block.iter().map(|s| s.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    const FAQ_STEP: &str = "Call retrieve_similar_FAQ with question=\"tuition\". Return any relevant passages.";
+
+    #[test]
+    fn plan_following_the_contract_is_valid() {
+        assert_eq!(plan(&[&[FAQ_STEP, "Call get_web_page_content with the link."], &[FAQ_STEP]]).validate(), Ok(()));
+    }
+
+    #[test]
+    fn plan_without_blocks_is_invalid() {
+        assert_eq!(plan(&[]).validate(), Err(PlanValidationError::NoBlocks));
+    }
+
+    #[test]
+    fn empty_block_is_invalid() {
+        assert_eq!(
+            plan(&[&[FAQ_STEP], &[]]).validate(),
+            Err(PlanValidationError::EmptyBlock { block: 2 })
+        );
+    }
+
+    #[test]
+    fn block_with_three_steps_is_invalid() {
+        assert_eq!(
+            plan(&[&[FAQ_STEP, FAQ_STEP, FAQ_STEP]]).validate(),
+            Err(PlanValidationError::TooManySteps { block: 1, steps: 3 })
+        );
+    }
+
+    #[test]
+    fn blank_step_is_invalid() {
+        assert_eq!(
+            plan(&[&[FAQ_STEP], &[FAQ_STEP, "  "]]).validate(),
+            Err(PlanValidationError::EmptyStep { block: 2, step: 2 })
+        );
+    }
+
+    #[tokio::test]
+    async fn valid_plan_is_kept_without_asking_again() {
+        let asked = Cell::new(false);
+        let result = validated_plan(plan(&[&[FAQ_STEP]]), "task", |_| async {
+            asked.set(true);
+            Ok(plan(&[]))
+        })
+        .await;
+        assert_eq!(result.steps, [[FAQ_STEP]]);
+        assert!(!asked.get());
+    }
+
+    #[tokio::test]
+    async fn invalid_plan_is_retried_with_the_problem() {
+        let result = validated_plan(plan(&[]), "task", |correction| async move {
+            assert!(correction.contains("the plan has no blocks"));
+            Ok(plan(&[&[FAQ_STEP]]))
+        })
+        .await;
+        assert_eq!(result.steps, [[FAQ_STEP]]);
+    }
+
+    #[tokio::test]
+    async fn plan_invalid_after_the_retry_falls_back_to_a_single_step() {
+        let task = "How much is the tuition?";
+        let result = validated_plan(plan(&[&[]]), task, |_| async { Ok(plan(&[&[" "]])) }).await;
+        assert_eq!(result.steps, [[task]]);
+
+        let result = validated_plan(plan(&[]), task, |_| async {
+            Err(AgentError::Runtime("planner unreachable".into()))
+        })
+        .await;
+        assert_eq!(result.steps, [[task]]);
+    }
+
+    #[test]
+    fn plan_requests_are_recognized() {
+        assert!(asks_for_plan("Before answering, show me your plan for finding the fees."));
+        assert!(asks_for_plan("What’s your plan?"));
+        assert!(asks_for_plan("Pokaži mi načrt za iskanje urnika."));
+        assert!(!asks_for_plan("What is the study plan of Computer Science?"));
+    }
+
+    #[test]
+    fn requested_plan_shows_its_tracks_and_steps() {
+        let answer = plan_answer(&plan(&[
+            &[FAQ_STEP, "Call get_web_page_content with the link. Return the fees."],
+            &["Call get_programme_info with name=\"Computer Science\". Return the fees."],
+        ]));
+
+        assert!(answer.starts_with("Here is the plan I would follow to answer this:"));
+        let track_one = answer.find("**Track 1**").unwrap();
+        let track_two = answer.find("**Track 2**").unwrap();
+        assert!(track_one < track_two);
+        assert!(answer.contains(&format!("1. {}", FAQ_STEP)));
+        assert!(answer.contains("2. Call get_web_page_content with the link. Return the fees."));
+        assert!(answer[track_two..].contains("1. Call get_programme_info"));
+    }
+}
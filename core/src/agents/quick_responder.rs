@@ -5,11 +5,49 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Receiver;
 
+const DEFAULT_QUICK_FAQ_MIN_SCORE: f32 = 0.75;
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Answerable {
     pub can_respond: bool,
 }
 
+/// Lowest similarity the best FAQ match needs before the quick responder may
+/// skip planning, from `QUICK_FAQ_MIN_SCORE`.
+pub fn quick_faq_min_score() -> f32 {
+    env::var("QUICK_FAQ_MIN_SCORE")
+        .ok()
+        .and_then(|v| v.trim().parse::<f32>().ok())
+        .unwrap_or(DEFAULT_QUICK_FAQ_MIN_SCORE)
+}
+
+/// Highest `Score:` of the FAQ entries returned by `retrieve_similar_FAQ`.
+pub fn top_faq_score(faq: &str) -> Option<f32> {
+    faq.lines()
+        .filter_map(|line| line.trim().strip_prefix("Score:"))
+        .filter_map(|score| score.trim().parse::<f32>().ok())
+        .reduce(f32::max)
+}
+
+/// Whether the best entry of a `retrieve_similar_FAQ` result is similar
+/// enough to the question to answer it from.
+pub fn faq_is_close(faq: &str, min_score: f32) -> bool {
+    top_faq_score(faq).is_some_and(|score| score >= min_score)
+}
+
+/// Name of the FAQ service's retrieval tool.
+pub const FAQ_TOOL: &str = "retrieve_similar_FAQ";
+
+/// Tool output standing in for FAQ entries that are only loosely related to
+/// the question, so the answer is looked up with the other tools instead.
+pub fn weak_faq_output(faq: &str, min_score: f32) -> String {
+    format!(
+        "# No close FAQ match\n\nThe closest FAQ entry has a similarity of {} (at least {:.2} is needed), so the FAQ does not answer this question. Use the other tools to find the answer.",
+        top_faq_score(faq).map_or("unknown".to_string(), |score| format!("{:.2}", score)),
+        min_score
+    )
+}
+
 pub async fn create_quick_response_agent(
     ref_agent: &Agent,
 ) -> Result<(Agent, Receiver<Notification>), AgentBuildError> {
@@ -28,6 +66,9 @@ pub async fn create_quick_response_agent(
 
     {{faq}}
 
+    Similarity of the closest FAQ entry to the question: {{top_score}} (1.0 is
+    an exact match). A low score means the entries are only loosely related.
+
     ---
 
     The question to answer:
@@ -54,3 +95,31 @@ pub async fn create_quick_response_agent(
         .build_with_notification()
         .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAQ: &str = "\n---\n\nScore: 0.41\n\nKeywords: fees\n\nQuestion:\nHow much is the tuition?\n\nAnswer:\nSee the price list.\n\n---\n\n---\n\nScore: 0.82\n\nKeywords: exams\n\nQuestion:\nWhen are exams?\n\nAnswer:\nIn June.\n\n---\n";
+
+    #[test]
+    fn top_score_is_the_best_entry() {
+        assert_eq!(top_faq_score(FAQ), Some(0.82));
+        assert_eq!(top_faq_score("No similar FAQ found"), None);
+    }
+
+    #[test]
+    fn faq_below_the_minimum_is_not_close() {
+        assert!(faq_is_close(FAQ, 0.75));
+        assert!(!faq_is_close(FAQ, 0.9));
+        assert!(!faq_is_close("No similar FAQ found", 0.0));
+    }
+
+    #[test]
+    fn weak_faq_output_drops_the_entries() {
+        let output = weak_faq_output(FAQ, 0.9);
+        assert!(output.contains("0.82"));
+        assert!(output.contains("0.90"));
+        assert!(!output.contains("June"));
+    }
+}
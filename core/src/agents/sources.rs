@@ -0,0 +1,94 @@
+use std::env;
+
+use crate::agents::grounding::markdown_link_targets;
+
+/// Whether answers get a "How I found this" section naming the tool behind
+/// each reference. This exposes internal tool names, so it is meant for
+/// admin/debug deployments only and is off unless `EXPLAIN_SOURCES=true`.
+pub fn explain_sources_enabled() -> bool {
+    env::var("EXPLAIN_SOURCES")
+        .map(|v| v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Maps every URL referenced in the answer to the tools whose output
+/// contains it. `tool_outputs` holds `(tool name, output)` pairs.
+///
+/// Returns `None` when the answer has no references.
+pub fn explain_sources(answer: &str, tool_outputs: &[(String, String)]) -> Option<String> {
+    let mut urls: Vec<String> = vec![];
+    for url in markdown_link_targets(answer) {
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    if urls.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<String> = urls
+        .iter()
+        .map(|url| {
+            let mut tools: Vec<&str> = vec![];
+            for (tool, output) in tool_outputs {
+                if output.contains(url.as_str()) && !tools.contains(&tool.as_str()) {
+                    tools.push(tool);
+                }
+            }
+            if tools.is_empty() {
+                format!("- {} — not found in any tool result", url)
+            } else {
+                let tools: Vec<String> = tools.iter().map(|t| format!("`{}`", t)).collect();
+                format!("- {} — {}", url, tools.join(", "))
+            }
+        })
+        .collect();
+
+    Some(format!("## How I found this\n\n{}", lines.join("\n")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAFF: &str = "https://www.famnit.upr.si/en/about-faculty/staff/jane.doe";
+    const RULES: &str = "https://www.famnit.upr.si/en/rules";
+
+    fn outputs() -> Vec<(String, String)> {
+        vec![
+            ("ask_about_staff".to_string(), format!("Jane Doe, office 12. {}", STAFF)),
+            ("ask_about_rules".to_string(), format!("Exams are held in June. {}", RULES)),
+            ("ask_about_general_information".to_string(), format!("Contact: {}", STAFF)),
+        ]
+    }
+
+    #[test]
+    fn answer_without_references_has_no_section() {
+        assert_eq!(explain_sources("Hello! How can I help?", &outputs()), None);
+    }
+
+    #[test]
+    fn each_reference_names_the_tools_that_returned_it() {
+        let answer = format!(
+            "Jane Doe is in office 12 [1]({}). Exams are in June [2]({}). Her office [1]({}).",
+            STAFF, RULES, STAFF
+        );
+        assert_eq!(
+            explain_sources(&answer, &outputs()).unwrap(),
+            format!(
+                "## How I found this\n\n- {} — `ask_about_staff`, `ask_about_general_information`\n- {} — `ask_about_rules`",
+                STAFF, RULES
+            )
+        );
+    }
+
+    #[test]
+    fn reference_missing_from_tool_results_is_flagged() {
+        let answer = "See [the calendar](https://www.famnit.upr.si/en/calendar).";
+        assert!(
+            explain_sources(answer, &outputs())
+                .unwrap()
+                .contains("https://www.famnit.upr.si/en/calendar — not found in any tool result")
+        );
+    }
+}
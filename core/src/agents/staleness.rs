@@ -0,0 +1,93 @@
+use crate::agents::grounding::markdown_link_targets;
+
+/// Prefix the RAG services put in front of passages indexed longer ago than
+/// their `SOURCE_MAX_AGE_DAYS`, followed by the indexing date and a link to
+/// the original page.
+const STALE_MARKER: &str = "Possibly outdated source:";
+
+/// Caveat for answers relying on possibly outdated passages.
+///
+/// Only stale sources the answer links to are named; an answer that links
+/// none of the tool sources is assumed to rely on all of them.
+pub fn staleness_caveat(answer: &str, tool_outputs: &[String]) -> Option<String> {
+    let mut stale_urls: Vec<String> = vec![];
+    for line in tool_outputs.iter().flat_map(|o| o.lines()) {
+        let Some(marker) = line.trim().strip_prefix(STALE_MARKER) else {
+            continue;
+        };
+        for url in markdown_link_targets(marker) {
+            if !stale_urls.contains(&url) {
+                stale_urls.push(url);
+            }
+        }
+    }
+    if stale_urls.is_empty() {
+        return None;
+    }
+
+    let cited = markdown_link_targets(answer);
+    let cites_tool_sources = cited
+        .iter()
+        .any(|url| tool_outputs.iter().any(|o| o.contains(url.as_str())));
+    if cites_tool_sources {
+        stale_urls.retain(|url| cited.contains(url));
+    }
+    if stale_urls.is_empty() {
+        return None;
+    }
+
+    let links: Vec<String> = stale_urls
+        .iter()
+        .map(|url| format!("[{}]({})", url, url))
+        .collect();
+    Some(format!(
+        "_This information may be outdated; verify at {}._",
+        links.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "https://www.famnit.upr.si/en/students/scholarships";
+    const FRESH: &str = "https://www.famnit.upr.si/en/students/enrolment";
+
+    fn outputs() -> Vec<String> {
+        vec![
+            format!(
+                "Scholarships are awarded in October.\nPossibly outdated source: indexed on 2023-01-10; verify at [{}]({})",
+                OLD, OLD
+            ),
+            format!("Enrolment closes in September.\nSource: {}", FRESH),
+        ]
+    }
+
+    #[test]
+    fn fresh_sources_need_no_caveat() {
+        let outputs = vec![format!("Enrolment closes in September.\nSource: {}", FRESH)];
+        assert_eq!(staleness_caveat("Enrolment closes in September.", &outputs), None);
+    }
+
+    #[test]
+    fn cited_stale_source_is_named() {
+        let answer = format!("Scholarships are awarded in October [1]({}).", OLD);
+        assert_eq!(
+            staleness_caveat(&answer, &outputs()),
+            Some(format!("_This information may be outdated; verify at [{}]({})._", OLD, OLD))
+        );
+    }
+
+    #[test]
+    fn answer_citing_only_fresh_sources_gets_no_caveat() {
+        let answer = format!("Enrolment closes in September [1]({}).", FRESH);
+        assert_eq!(staleness_caveat(&answer, &outputs()), None);
+    }
+
+    #[test]
+    fn uncited_answer_is_assumed_to_rely_on_stale_sources() {
+        let caveat = staleness_caveat("Scholarships are awarded in October.", &outputs()).unwrap();
+        assert!(caveat.contains(OLD));
+        assert!(!caveat.contains(FRESH));
+    }
+}
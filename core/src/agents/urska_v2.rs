@@ -10,8 +10,15 @@ use serde_json::to_value;
 
 use crate::{
     agents::{
-        function_filter::{Requirement, build_function_filter_agent},
+        function_filter::{
+            OUT_OF_SCOPE_RESPONSE, Requirement, build_function_filter_agent,
+            call_selected_tools, decline_without_tools, load_no_tools_mode, no_tools_prompt,
+            tool_context_chunk,
+        },
         prompt_reconstuct::create_prompt_restructor_agent,
+        history_summary::compact_history,
+        numbers::normalize_answer,
+        ollama_limit::limited,
         usrka::{UrskaNotification, history_to_prompt},
     },
     *,
@@ -19,24 +26,27 @@ use crate::{
 
 async fn urska_flow(urska: &mut Agent, mut prompt: String) -> Result<Message, AgentError> {
     send_notifcation(urska, "Preparing...").await;
-    let mut conversation = get_display_conversation(&urska);
+    let mut conversation = get_display_conversation(urska);
     conversation.push(Message::user(prompt.clone()));
 
     let (function_filter_agent, filter_notification_channel) =
         build_function_filter_agent(urska).await?;
     let (mut rephraser_agent, rephraser_notification_channel) =
-        create_prompt_restructor_agent(&urska).await?;
+        create_prompt_restructor_agent(urska).await?;
 
     urska.forward_notifications(filter_notification_channel);
     urska.forward_notifications(rephraser_notification_channel);
 
+    if let Err(e) = compact_history(urska).await {
+        println!("Could not summarize history: {:#?}", e);
+    }
+
     if urska.history.len() > 2 {
-        let rehprase_response = rephraser_agent
-            .invoke_flow_with_template(HashMap::from([
-                ("history", history_to_prompt(&urska.history)),
-                ("prompt", prompt.clone()),
-            ]))
-            .await?;
+        let rehprase_response = limited(rephraser_agent.invoke_flow_with_template(HashMap::from([
+            ("history", history_to_prompt(&urska.history)),
+            ("prompt", prompt.clone()),
+        ])))
+        .await?;
 
         if let Some(rephrased_prompt) = rehprase_response.content {
             prompt = rephrased_prompt;
@@ -59,9 +69,7 @@ async fn urska_flow(urska: &mut Agent, mut prompt: String) -> Result<Message, Ag
 
                 println!("ARGS: {:#?}", args);
 
-                let function_required: Requirement = match agent_clone
-                    .invoke_flow_with_template_structured_output(args)
-                    .await
+                let function_required: Requirement = match limited(agent_clone.invoke_flow_with_template_structured_output(args)).await
                 {
                     Ok(r) => r,
                     Err(e) => {
@@ -87,53 +95,69 @@ async fn urska_flow(urska: &mut Agent, mut prompt: String) -> Result<Message, Ag
     send_notifcation(urska, "Executing tools...").await;
 
     let mut tool_calls = vec![];
-    for result in filter_results {
-        if let Ok(res) = result {
-            let (tool_name, required) = res;
-
-            // skip tools the filter rejected
-            if !required.function_usage_required {
-                continue;
-            }
+    for (tool_name, required) in filter_results.into_iter().flatten() {
+        // skip tools the filter rejected
+        if !required.function_usage_required {
+            continue;
+        }
 
-            // try to parse whatever the model returned into real JSON
-            // let Some(recommended_params) = &required.recommended_params else {
-            //     println!("No params for required tool.");
-            //     continue;
-            // };
-
-            // let Some(arguments) = normalize_params(&recommended_params) else {
-            //     println!("Skipping {} due to unparseable params", tool_name);
-            //     continue;
-            // };
-            let Ok(arguments) = to_value(required.recommended_params) else {
-                continue;
-            };
+        // try to parse whatever the model returned into real JSON
+        // let Some(recommended_params) = &required.recommended_params else {
+        //     println!("No params for required tool.");
+        //     continue;
+        // };
+
+        // let Some(arguments) = normalize_params(&recommended_params) else {
+        //     println!("Skipping {} due to unparseable params", tool_name);
+        //     continue;
+        // };
+        let Ok(arguments) = to_value(required.recommended_params) else {
+            continue;
+        };
+
+        // send_notifcation(
+        //     urska,
+        //     format!("Checking for information with {}...", tool_name),
+        // )
+        // .await;
+
+        tool_calls.push(into_tool_call(ToolCallFunction {
+            name: tool_name.clone(),
+            arguments,
+        }));
+    }
 
-            // send_notifcation(
-            //     urska,
-            //     format!("Checking for information with {}...", tool_name),
-            // )
-            // .await;
 
-            tool_calls.push(into_tool_call(ToolCallFunction {
-                name: tool_name.clone(),
-                arguments,
-            }));
-        }
+    if tool_calls.is_empty() {
+        println!("No tools selected, answering without FAMNIT context");
+        let message = if decline_without_tools(urska) {
+            let message = Message::assistant(OUT_OF_SCOPE_RESPONSE);
+            urska.history.push(Message::user(prompt));
+            urska.history.push(message.clone());
+            message
+        } else {
+            urska.history.push(Message::user(no_tools_prompt(&prompt)));
+            limited(
+                InvocationBuilder::default()
+                    .use_tools(false)
+                    .invoke_with(urska),
+            )
+            .await?
+            .message
+        };
+        urska.notify_done(true, message.content.clone()).await;
+        conversation.push(message.clone());
+        store_display_conversation(urska, conversation);
+        return Ok(message);
     }
 
-
-    let tool_responses = call_tools(&urska, &tool_calls).await;
+    let tool_results = call_selected_tools(urska, &tool_calls).await;
     let mut context_chunks = vec![];
     send_notifcation(urska, "Gathering data...").await;
 
-    for tool_response in tool_responses {
+    for (tool_name, result) in &tool_results {
         send_notifcation(urska, "Checking tool retults...").await;
-        context_chunks.push(format!(
-            "# Tool resulted in:\n\n{}",
-            tool_response.content.unwrap_or_default()
-        ));
+        context_chunks.push(tool_context_chunk(tool_name, result));
     }
 
     let context = context_chunks.join("\n\n---\n\n");
@@ -158,6 +182,8 @@ Today: {{date}}
 
 ---
 
+If a source above is marked "Tool unavailable", it could not be consulted. Say so when it matters for the answer instead of guessing what it would have said.
+
 Given the above context respond to the following user query:
 
 {{prompt}}
@@ -168,22 +194,25 @@ Given the above context respond to the following user query:
     let final_prompt = template.compile(&args).await;
     urska.history.push(Message::user(final_prompt));
     // let out = invoke_without_tools(urska).await?.message;
-    let mut out = InvocationBuilder::default()
-        .use_tools(false)
-        .invoke_with(urska)
-        .await?;
+    let mut out = limited(
+        InvocationBuilder::default()
+            .use_tools(false)
+            .invoke_with(urska),
+    )
+    .await?;
 
     for _ in 0..urska.max_iterations.unwrap_or(5) {
         if out.message.tool_calls.is_none() {
             break;
         }
-        out = InvocationBuilder::default().invoke_with(urska).await?;
+        out = limited(InvocationBuilder::default().invoke_with(urska)).await?;
         if let Some(tc) = out.message.tool_calls.clone() {
             for tool_msg in call_tools(urska, &tc).await {
                 urska.history.push(tool_msg);
             }
         }
     }
+    out.message.content = out.message.content.map(|answer| normalize_answer(&answer));
     urska.notify_done(true, out.message.content.clone()).await;
     conversation.push(out.message.clone());
     store_display_conversation(urska, conversation);
@@ -270,7 +299,7 @@ The report must be faithful to the execution log, clear, and useful to the stude
 
 * **Style rules**
 
-* Output numbers with a decimal comma and never use dots in numbers (3.5k€ -> 3500,00€).
+* Write out abbreviated amounts in full (3.5k€ -> 3500€); decimal separators are normalized to the user's language afterwards.
 * Copy URLs exactly, including IDs or path segments (e.g. `/static/3775` - careful NOT to write 375 instead of 3775).
 
 ---
@@ -311,7 +340,7 @@ Admission requires a completed bachelor’s degree [2](http://example.com/admiss
 
     "#;
 
-    AgentBuilder::default()
+    let mut agent = AgentBuilder::default()
         .set_name("Urška")
         .set_model(env::var("MODEL").expect("MODEL not set"))
         .set_base_url(env::var("OLLAMA_ENDPOINT").expect("OLLAMA_ENDPOINT not set"))
@@ -332,7 +361,9 @@ Admission requires a completed bachelor’s degree [2](http://example.com/admiss
         .set_stream(true)
         .strip_thinking(true)
         .build()
-        .await
+        .await?;
+    load_no_tools_mode(&mut agent);
+    Ok(agent)
 }
 
 fn into_tool_call(function: ToolCallFunction) -> ToolCall {
@@ -1,195 +1,32 @@
 use std::{collections::HashMap, env};
 
-use chrono::Utc;
-use futures::future::join_all;
 use reagent_rs::{
     Agent, AgentBuildError, AgentBuilder, AgentError, InvocationBuilder, McpServerType, Message,
-    NotificationHandler, Provider, Template, ToolCall, ToolCallFunction, ToolType, call_tools,
-    flow,
+    NotificationHandler, Provider, Role, Template, ToolCall, flow,
 };
-use serde_json::to_value;
+use serde_json::Value;
 
 use crate::{
     agents::{
-        function_filter::{Requirement, build_function_filter_agent},
-        prompt_reconstuct::create_prompt_restructor_agent,
-        usrka::{UrskaNotification, history_to_prompt},
+        citations::{citation_guard_enabled, strip_invalid_citations},
+        function_filter::{
+            OUT_OF_SCOPE_RESPONSE, call_selected_tools, decline_without_tools, load_no_tools_mode,
+            no_tools_prompt, strip_unknown_arguments, tool_context_chunk,
+        },
+        grounding::{
+            GROUNDING_NOTICE, check_grounding, load_synthesis_retry, synthesis_retry_enabled,
+            synthesize_with_retry,
+        },
+        history_summary::compact_history,
+        numbers::normalize_answer,
+        ollama_limit::limited,
+        quick_responder::{FAQ_TOOL, faq_is_close, quick_faq_min_score, weak_faq_output},
+        sources::{explain_sources, explain_sources_enabled},
+        staleness::staleness_caveat,
     },
     *,
 };
 
-async fn urska_flow(urska: &mut Agent, mut prompt: String) -> Result<Message, AgentError> {
-    send_notifcation(urska, "Preparing...").await;
-    let mut conversation = get_display_conversation(&urska);
-    conversation.push(Message::user(prompt.clone()));
-
-    let (function_filter_agent, filter_notification_channel) =
-        build_function_filter_agent(urska).await?;
-    let (mut rephraser_agent, rephraser_notification_channel) =
-        create_prompt_restructor_agent(&urska).await?;
-
-    urska.forward_notifications(filter_notification_channel);
-    urska.forward_notifications(rephraser_notification_channel);
-
-    if urska.history.len() > 2 {
-        let rehprase_response = rephraser_agent
-            .invoke_flow_with_template(HashMap::from([
-                ("history", history_to_prompt(&urska.history)),
-                ("prompt", prompt.clone()),
-            ]))
-            .await?;
-
-        if let Some(rephrased_prompt) = rehprase_response.content {
-            prompt = rephrased_prompt;
-        }
-    }
-
-    send_notifcation(urska, "Searching for tools...").await;
-
-    let mut filter_futures = vec![];
-    if let Some(tools) = urska.tools.clone() {
-        for tool in tools {
-            let prompt_clone = prompt.clone();
-            let mut agent_clone = function_filter_agent.clone();
-
-            let filter_future = async move {
-                let args = HashMap::from([
-                    ("question", prompt_clone),
-                    ("function", format!("{:#?}", tool.function)),
-                ]);
-
-                println!("ARGS: {:#?}", args);
-
-                let function_required: Requirement = match agent_clone
-                    .invoke_flow_with_template_structured_output(args)
-                    .await
-                {
-                    Ok(r) => r,
-                    Err(e) => {
-                        println!("Could no assess: {:#?}", e);
-                        return Err(e);
-                    }
-                };
-
-                println!(
-                    "TOOL: ---\n{}\n{:#?}\n---",
-                    tool.function.name, function_required
-                );
-
-                Ok((tool.name().to_string(), function_required))
-            };
-            filter_futures.push(filter_future);
-        }
-    }
-
-    let filter_results: Vec<Result<(String, Requirement), AgentError>> =
-        join_all(filter_futures).await;
-
-    send_notifcation(urska, "Executing tools...").await;
-
-    let mut tool_calls = vec![];
-    for result in filter_results {
-        if let Ok(res) = result {
-            let (tool_name, required) = res;
-
-            // skip tools the filter rejected
-            if !required.function_usage_required {
-                continue;
-            }
-
-            // try to parse whatever the model returned into real JSON
-            // let Some(recommended_params) = &required.recommended_params else {
-            //     println!("No params for required tool.");
-            //     continue;
-            // };
-
-            // let Some(arguments) = normalize_params(&recommended_params) else {
-            //     println!("Skipping {} due to unparseable params", tool_name);
-            //     continue;
-            // };
-            let Ok(arguments) = to_value(required.recommended_params) else {
-                continue;
-            };
-
-            // send_notifcation(
-            //     urska,
-            //     format!("Checking for information with {}...", tool_name),
-            // )
-            // .await;
-
-            tool_calls.push(into_tool_call(ToolCallFunction {
-                name: tool_name.clone(),
-                arguments,
-            }));
-        }
-    }
-
-    let tool_responses = call_tools(&urska, &tool_calls).await;
-    let mut context_chunks = vec![];
-    send_notifcation(urska, "Gathering data...").await;
-
-    for tool_response in tool_responses {
-        send_notifcation(urska, "Checking tool retults...").await;
-        context_chunks.push(format!(
-            "# Tool resulted in:\n\n{}",
-            tool_response.content.unwrap_or_default()
-        ));
-    }
-
-    let context = context_chunks.join("\n\n---\n\n");
-
-    let now = Utc::now().to_rfc2822().to_string();
-
-    let args = HashMap::from([
-        ("date".to_string(), now),
-        ("context".to_string(), context),
-        ("prompt".to_string(), prompt),
-    ]);
-
-    let template = Template::simple(
-        r#"
-Below you are given context information to help you answer the user query.
-
-Today: {{date}}
-
----
-
-{{context}}
-
----
-
-Given the above context respond to the following user query:
-
-{{prompt}}
-
-    "#,
-    );
-
-    let final_prompt = template.compile(&args).await;
-    urska.history.push(Message::user(final_prompt));
-    // let out = invoke_without_tools(urska).await?.message;
-    let mut out = InvocationBuilder::default()
-        .use_tools(false)
-        .invoke_with(urska)
-        .await?;
-
-    for _ in 0..urska.max_iterations.unwrap_or(5) {
-        if out.message.tool_calls.is_none() {
-            break;
-        }
-        out = InvocationBuilder::default().invoke_with(urska).await?;
-        if let Some(tc) = out.message.tool_calls.clone() {
-            for tool_msg in call_tools(urska, &tc).await {
-                urska.history.push(tool_msg);
-            }
-        }
-    }
-    urska.notify_done(true, out.message.content.clone()).await;
-    conversation.push(out.message.clone());
-    store_display_conversation(urska, conversation);
-    Ok(out.message)
-}
-
 pub async fn build_urska_v3() -> Result<Agent, AgentBuildError> {
     //     let system_prompt = r#"
     // You are **Urška**, a helpful, knowledgeable, and reliable assistant for the University of Primorska's Faculty of Mathematics, Natural Sciences and Information Technologies (UP FAMNIT).
@@ -374,6 +211,8 @@ If tools do not return enough information, say:
 "I could not find this in the available official sources."
 Then give the closest confirmed information, if useful.
 
+A tool result of the form `# Tool unavailable: <name>` means that source could not be consulted. Say so when it matters for the answer instead of guessing what it would have said.
+
 ## Citation rules
 
 Citations are mandatory for official-source claims when a source URL is available.
@@ -431,26 +270,7 @@ For numbers in Slovene-style contexts, use a decimal comma for decimal values.
 
 "#;
 
-    let template = Template::simple(
-        r#"
-Date: {{date}}
-Current study year: {{study_year}}
-
-## Current user
-
-{{user_context}}
-
----
-
-Use the user context above to personalise your response. For example, greet the user by name, reference their role (student/employee), and tailor information to their study level or faculty. If the user context is empty or irrelevant, ignore it.
-
-Answer the user's question:
-
-{{question}}
-"#,
-    );
-
-    AgentBuilder::default()
+    let mut agent = AgentBuilder::default()
         .set_name("Urška")
         .set_provider(Provider::OpenAi)
         .set_base_url("https://hivecore.famnit.upr.si/v1")
@@ -463,36 +283,51 @@ Answer the user's question:
         .add_mcp_server(McpServerType::streamable_http(RAG_PAGE_SERVICE))
         .add_mcp_server(McpServerType::streamable_http(RAG_RULES_SERVICE))
         .add_mcp_server(McpServerType::streamable_http(RAG_FAQ_SERVICE))
-        // .set_flow(flow!(flow))
+        .set_flow(flow!(flow))
         .set_system_prompt(system_prompt)
         .set_temperature(0.5)
-        .set_template(template)
+        .set_template(urska_template())
         .set_stream(true)
         .strip_thinking(true)
         .build()
-        .await
+        .await?;
+    load_no_tools_mode(&mut agent);
+    load_synthesis_retry(&mut agent);
+    Ok(agent)
 }
 
-fn into_tool_call(function: ToolCallFunction) -> ToolCall {
-    ToolCall {
-        id: None,
-        tool_type: ToolType::Function,
-        function,
-    }
-}
+/// Template of Urška's prompt: the question with the user's profile, the
+/// requested tone and the answer language.
+fn urska_template() -> Template {
+    Template::simple(
+        r#"
+Date: {{date}}
+Current study year: {{study_year}}
+Programme: {{programme}}
 
-async fn send_notifcation<T>(agent: &mut Agent, message: T)
-where
-    T: Into<String>,
-{
-    agent
-        .notify_custom(
-            to_value(&UrskaNotification {
-                message: message.into(),
-            })
-            .unwrap(),
-        )
-        .await;
+## Current user
+
+{{user_context}}
+
+---
+
+Use the user context above to personalise your response. For example, reference their role (student/employee) and tailor information to their study level or faculty. If the user context is empty or irrelevant, ignore it.
+
+When the question refers to "my programme", "my year", "my third year courses" and similar, resolve it using the programme, study level and study year above instead of asking which programme is meant. If the programme is unknown, ask.
+
+For "what do I still need to graduate" questions, the student has completed the years before their current study year.
+
+## Tone
+
+{{persona}}
+
+The tone only changes how you phrase the answer. All source grounding and citation rules still apply unchanged.
+
+Answer the user's question in {{language}}, the language it is written in:
+
+{{question}}
+"#,
+    )
 }
 
 pub fn get_display_conversation(agent: &Agent) -> Vec<Message> {
@@ -502,6 +337,30 @@ pub fn get_display_conversation(agent: &Agent) -> Vec<Message> {
     }
 }
 
+/// The prompt without the profile of the user who asked it: the header lines
+/// with their programme and study year and the "Current user" section are
+/// dropped, so the display conversation can be exported and saved.
+fn redact_user_context(prompt: &str) -> String {
+    let mut lines = vec![];
+    let mut in_user_section = false;
+    for line in prompt.lines() {
+        let trimmed = line.trim();
+        if in_user_section {
+            in_user_section = trimmed != "---";
+            continue;
+        }
+        if trimmed == "## Current user" {
+            in_user_section = true;
+            continue;
+        }
+        if trimmed.starts_with("Current study year:") || trimmed.starts_with("Programme:") {
+            continue;
+        }
+        lines.push(line);
+    }
+    lines.join("\n").trim().to_string()
+}
+
 pub fn store_display_conversation(agent: &mut Agent, conversation: Vec<Message>) {
     let Ok(val) = serde_json::to_value(conversation) else {
         return;
@@ -513,47 +372,660 @@ pub fn store_display_conversation(agent: &mut Agent, conversation: Vec<Message>)
 const DEFAULT_MAX_ITERATIONS: usize = 50;
 
 pub async fn flow(agent: &mut Agent, prompt: String) -> Result<Message, AgentError> {
+    if let Err(e) = compact_history(agent).await {
+        println!("Could not summarize history: {:#?}", e);
+    }
+
+    let mut conversation = get_display_conversation(agent);
+    conversation.push(Message::user(redact_user_context(&prompt)));
+    let question_prompt = prompt.clone();
     agent.history.push(Message::user(prompt));
+    let history_start = agent.history.len();
     let max_iterations = agent
         .max_iterations
         .unwrap_or(DEFAULT_MAX_ITERATIONS)
         .max(1);
+    let faq_min_score = quick_faq_min_score();
     let mut response = None;
 
     for iteration in 0..max_iterations {
         let allow_tools = iteration + 1 < max_iterations;
-        let current = InvocationBuilder::default()
-            .use_tools(allow_tools)
-            .invoke_with(agent)
-            .await?;
+        let current = limited(
+            InvocationBuilder::default()
+                .use_tools(allow_tools)
+                .invoke_with(agent),
+        )
+        .await?;
 
         println!("{:#?}", current);
 
         let tool_calls = executable_tool_calls(&current.message, allow_tools);
         response = Some(current);
 
-        let Some(tool_calls) = tool_calls else {
+        let Some(mut tool_calls) = tool_calls else {
             println!("No tool calls. Stoippint iteration");
             break;
         };
+        redact_tool_arguments(&mut tool_calls);
+        strip_undeclared_arguments(agent, &mut tool_calls);
+        // the history keeps the calls as they were made
+        if let Some(call_message) = agent.history.last_mut() {
+            call_message.tool_calls = Some(tool_calls.clone());
+        }
 
-        for tool_msg in call_tools(agent, &tool_calls).await {
-            agent.history.push(tool_msg);
+        // each call fails on its own, so one expert being down does not
+        // lose the results of the others
+        let results = call_selected_tools(agent, &tool_calls).await;
+        for (call, (name, result)) in tool_calls.iter().zip(results) {
+            let content = match result {
+                // a loosely related FAQ would give a shallow answer
+                Ok(output) if name == FAQ_TOOL && !faq_is_close(&output, faq_min_score) => {
+                    weak_faq_output(&output, faq_min_score)
+                }
+                Ok(output) => output,
+                Err(_) => tool_context_chunk(&name, &result),
+            };
+            let id = call.id.clone().unwrap_or(name);
+            agent.history.push(Message::tool(content, id));
         }
     }
 
-    let message = response
+    let mut message = response
         .expect("default flow always performs at least one iteration")
         .message;
 
+    let named_tool_outputs = tool_outputs_by_name(&agent.history[history_start..]);
+    let has_tools = agent.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+    if named_tool_outputs.is_empty() && has_tools {
+        println!("No tools used, answering without FAMNIT context");
+        message = answer_without_tools(agent, history_start, &question_prompt).await?;
+    }
+    let tool_outputs: Vec<String> = named_tool_outputs
+        .iter()
+        .map(|(_, output)| output.clone())
+        .collect();
+
+    // SYNTHESIS_RETRY=true gives the model one chance to fix the violations
+    // before the answer is shipped with a notice.
+    let retry_enabled = synthesis_retry_enabled(agent);
+
+    let answer = message.content.clone().unwrap_or_default();
+    let (answer, mut violations) =
+        synthesize_with_retry(answer, &tool_outputs, retry_enabled, |instructions| async {
+            let retry_start = agent.history.len();
+            agent.history.push(Message::user(instructions));
+            // not streamed: the client would append it to the first answer,
+            // and it receives the final answer once the flow is done anyway
+            let retry = limited(
+                InvocationBuilder::default()
+                    .use_tools(false)
+                    .stream(false)
+                    .invoke_with(agent),
+            )
+            .await;
+            // the instructions are for this retry only; later turns see the
+            // retried answer in place of the rejected one
+            agent.history.truncate(retry_start);
+            let answer = retry?.message.content.unwrap_or_default();
+            if let Some(rejected) = agent
+                .history
+                .last_mut()
+                .filter(|m| m.role == Role::Assistant)
+            {
+                rejected.content = Some(answer.clone());
+            }
+            Ok(answer)
+        })
+        .await?;
+    message.content = Some(answer);
+
+    if citation_guard_enabled() {
+        let answer = message.content.clone().unwrap_or_default();
+        let guarded = strip_invalid_citations(&answer, &tool_outputs);
+        if guarded != answer {
+            println!("Removed uncitable links from the answer");
+            violations = check_grounding(&guarded, &tool_outputs);
+            message.content = Some(guarded);
+        }
+    }
+
+    if !violations.is_empty() {
+        message.content = Some(format!(
+            "{}{}",
+            message.content.unwrap_or_default(),
+            GROUNDING_NOTICE
+        ));
+    }
+
+    let answer = message.content.clone().unwrap_or_default();
+    if let Some(caveat) = staleness_caveat(&answer, &tool_outputs) {
+        message.content = Some(format!("{}\n\n{}", answer, caveat));
+    }
+
+    if explain_sources_enabled() {
+        let answer = message.content.clone().unwrap_or_default();
+        if let Some(section) = explain_sources(&answer, &named_tool_outputs) {
+            message.content = Some(format!("{}\n\n{}", answer, section));
+        }
+    }
+
+    message.content = message.content.map(|answer| normalize_answer(&answer));
+
+    // the profile was needed for this answer only
+    if let Some(question) = agent.history.get_mut(history_start - 1) {
+        question.content = Some(redact_user_context(&question_prompt));
+    }
+
     agent.notify_done(true, message.content.clone()).await;
+    conversation.push(message.clone());
+    store_display_conversation(agent, conversation);
     Ok(message)
 }
 
+/// Replaces an answer the model gave without consulting any tool, which has
+/// no FAMNIT grounding. With `NO_TOOLS_MODE=decline` the query is declined;
+/// otherwise it is answered again under `no_tools_prompt`, not streamed.
+async fn answer_without_tools(
+    agent: &mut Agent,
+    history_start: usize,
+    prompt: &str,
+) -> Result<Message, AgentError> {
+    if decline_without_tools(agent) {
+        agent.history.truncate(history_start);
+        let message = Message::assistant(OUT_OF_SCOPE_RESPONSE);
+        agent.history.push(message.clone());
+        return Ok(message);
+    }
+    agent.history.truncate(history_start - 1);
+    agent
+        .history
+        .push(Message::user(no_tools_prompt(&redact_user_context(prompt))));
+    let response = limited(
+        InvocationBuilder::default()
+            .use_tools(false)
+            .stream(false)
+            .invoke_with(agent),
+    )
+    .await?;
+    Ok(response.message)
+}
+
+/// Argument keys under which the model may copy the user's profile into a
+/// tool call.
+const PROFILE_ARGUMENT_KEYS: [&str; 2] = ["user_context", "profile"];
+
+/// Keeps the user's profile out of tool calls: profile objects are removed
+/// and string arguments lose the profile lines of the prompt. Tools only
+/// need the question, not who asked it.
+fn redact_tool_arguments(calls: &mut [ToolCall]) {
+    for call in calls {
+        let Some(arguments) = call.function.arguments.as_object_mut() else {
+            continue;
+        };
+        arguments.retain(|key, _| !PROFILE_ARGUMENT_KEYS.contains(&key.as_str()));
+        for value in arguments.values_mut() {
+            let Value::String(text) = value else {
+                continue;
+            };
+            let redacted = redact_user_context(text);
+            if redacted != text.trim() {
+                *text = redacted;
+            }
+        }
+    }
+}
+
+/// Drops arguments the called tool's schema does not declare, so a key left
+/// over from an outdated schema does not make the call fail.
+fn strip_undeclared_arguments(agent: &Agent, calls: &mut [ToolCall]) {
+    for call in calls {
+        let Some(tool) = agent
+            .tools
+            .iter()
+            .flatten()
+            .find(|t| t.name() == call.function.name)
+        else {
+            continue;
+        };
+        let Some(arguments) = call.function.arguments.as_object_mut() else {
+            continue;
+        };
+        let unknown = strip_unknown_arguments(arguments, &tool.function.parameters);
+        if !unknown.is_empty() {
+            println!(
+                "Arguments not in the schema of {}: {:?}",
+                tool.function.name, unknown
+            );
+        }
+    }
+}
+
+/// Pairs each tool result in `history` with the name of the tool that
+/// produced it.
+fn tool_outputs_by_name(history: &[Message]) -> Vec<(String, String)> {
+    let mut names: HashMap<String, String> = HashMap::new();
+    let mut outputs = vec![];
+    for message in history {
+        for call in message.tool_calls.iter().flatten() {
+            let id = call.id.clone().unwrap_or_else(|| call.function.name.clone());
+            names.insert(id, call.function.name.clone());
+        }
+        if message.role != Role::Tool {
+            continue;
+        }
+        let Some(content) = message.content.clone() else {
+            continue;
+        };
+        let name = message
+            .tool_call_id
+            .as_ref()
+            .map(|id| names.get(id).cloned().unwrap_or_else(|| id.clone()))
+            .unwrap_or_else(|| "unknown".to_string());
+        outputs.push((name, content));
+    }
+    outputs
+}
+
 fn executable_tool_calls(message: &Message, allow_tools: bool) -> Option<Vec<ToolCall>> {
     allow_tools
-        .then(|| message.tool_calls.as_ref())
+        .then_some(message.tool_calls.as_ref())
         .flatten()
         .filter(|calls| !calls.is_empty())
         .cloned()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use reagent_rs::{AsyncToolFn, Tool, ToolBuilder, ToolExecutionError};
+    use serde_json::json;
+
+    use super::*;
+    use crate::agents::{
+        function_filter::NO_TOOLS_MODE_STATE, grounding::SYNTHESIS_RETRY_STATE, persona::Persona,
+    };
+
+    /// Serves scripted chat completions at `{url}/chat/completions` and
+    /// keeps the request bodies it received.
+    async fn mock_model(replies: Vec<Value>) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let replies = Arc::new(Mutex::new(VecDeque::from(replies)));
+        let recorded = requests.clone();
+        let app = axum::Router::new().route(
+            "/v1/chat/completions",
+            axum::routing::post(move |axum::Json(body): axum::Json<Value>| async move {
+                recorded.lock().unwrap().push(body);
+                let message = replies.lock().unwrap().pop_front().expect("unexpected model call");
+                axum::Json(json!({
+                    "model": "test-model",
+                    "choices": [{ "message": message, "finish_reason": "stop" }],
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/v1", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, requests)
+    }
+
+    fn tool_call_reply(calls: &[(&str, Value)]) -> Value {
+        let calls: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(i, (name, arguments))| {
+                json!({
+                    "id": format!("call_{}", i),
+                    "type": "function",
+                    "function": { "name": name, "arguments": arguments.to_string() },
+                })
+            })
+            .collect();
+        json!({ "role": "assistant", "content": null, "tool_calls": calls })
+    }
+
+    fn answer_reply(answer: &str) -> Value {
+        json!({ "role": "assistant", "content": answer })
+    }
+
+    /// A tool taking a `question` that answers with `output`, or fails when
+    /// `output` is an error, and keeps the arguments it was called with.
+    fn recording_tool(name: &str, output: Result<&str, &str>) -> (Tool, Arc<Mutex<Vec<Value>>>) {
+        let calls = Arc::new(Mutex::new(vec![]));
+        let recorded = calls.clone();
+        let output = output.map(str::to_string).map_err(str::to_string);
+        let executor: AsyncToolFn = Arc::new(move |arguments: Value| {
+            recorded.lock().unwrap().push(arguments);
+            let output = output.clone();
+            Box::pin(async move { output.map_err(ToolExecutionError::ExecutionFailed) })
+        });
+        let tool = ToolBuilder::new()
+            .function_name(name)
+            .function_description("Answers questions about UP FAMNIT")
+            .add_required_property("question", "string", "The question")
+            .executor(executor)
+            .build()
+            .unwrap();
+        (tool, calls)
+    }
+
+    async fn test_agent(url: String, tools: Vec<Tool>) -> Agent {
+        let mut builder = AgentBuilder::default()
+            .set_name("Urška")
+            .set_provider(Provider::OpenAi)
+            .set_base_url(url)
+            .set_model("test-model")
+            .set_flow(flow!(flow));
+        for tool in tools {
+            builder = builder.add_tool(tool);
+        }
+        builder.build().await.unwrap()
+    }
+
+    const PROFILE_PROMPT: &str = "Date: Monday, 06 October 2025
+Current study year: 2
+Programme: Computer Science
+
+## Current user
+
+{\"role\": \"Student\", \"programme\": \"Computer Science\", \"enrolment_year\": 2024}
+
+---
+
+What are my second year courses?";
+
+    #[tokio::test]
+    async fn answer_without_tools_is_replaced() {
+        let (tool, tool_calls) = recording_tool("ask_about_general_information", Ok("unused"));
+        let (url, requests) = mock_model(vec![
+            answer_reply("The capital of France is Paris."),
+            answer_reply("That seems to be outside UP FAMNIT topics."),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![tool.clone()]).await;
+
+        let answer = agent.invoke_flow("What is the capital of France?").await.unwrap();
+
+        assert_eq!(answer.content.as_deref(), Some("That seems to be outside UP FAMNIT topics."));
+        assert!(tool_calls.lock().unwrap().is_empty());
+        {
+            let requests = requests.lock().unwrap();
+            assert_eq!(requests.len(), 2);
+            assert!(requests[1]["tools"].is_null());
+            let retry_prompt = requests[1]["messages"].as_array().unwrap().last().unwrap()["content"].to_string();
+            assert!(retry_prompt.contains("No FAMNIT tool was found relevant"));
+            assert!(retry_prompt.contains("What is the capital of France?"));
+        }
+        let history = serde_json::to_string(&agent.history).unwrap();
+        assert!(!history.contains("Paris"));
+
+        let (url, requests) = mock_model(vec![answer_reply("The capital of France is Paris.")]).await;
+        let mut agent = test_agent(url, vec![tool]).await;
+        agent
+            .state
+            .insert(NO_TOOLS_MODE_STATE.into(), Value::String("decline".into()));
+        let answer = agent.invoke_flow("What is the capital of France?").await;
+
+        assert_eq!(answer.unwrap().content.as_deref(), Some(OUT_OF_SCOPE_RESPONSE));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn undeclared_tool_arguments_are_dropped() {
+        let (tool, tool_calls) = recording_tool("ask_about_programmes", Ok("Computer Science lasts three years."));
+        let (url, _) = mock_model(vec![
+            tool_call_reply(&[(
+                "ask_about_programmes",
+                json!({ "question": "How long is Computer Science?", "level": "undergraduate" }),
+            )]),
+            answer_reply("Computer Science lasts three years."),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![tool]).await;
+
+        agent.invoke_flow("How long is Computer Science?").await.unwrap();
+
+        assert_eq!(
+            *tool_calls.lock().unwrap(),
+            [json!({ "question": "How long is Computer Science?" })]
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_expert_is_reported_as_unavailable() {
+        let (programmes, _) = recording_tool("ask_about_programmes", Ok("Computer Science lasts three years."));
+        let (staff, staff_calls) = recording_tool("ask_about_staff", Err("connection refused"));
+        let (url, requests) = mock_model(vec![
+            tool_call_reply(&[
+                ("ask_about_programmes", json!({ "question": "How long is Computer Science?" })),
+                ("ask_about_staff", json!({ "question": "Who leads Computer Science?" })),
+            ]),
+            answer_reply("Computer Science lasts three years; staff information could not be consulted."),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![programmes, staff]).await;
+
+        agent
+            .invoke_flow("How long is Computer Science and who leads it?")
+            .await
+            .unwrap();
+
+        assert_eq!(staff_calls.lock().unwrap().len(), 1);
+        let requests = requests.lock().unwrap();
+        let tool_messages: Vec<(String, String)> = requests[1]["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|m| m["role"] == "tool")
+            .map(|m| {
+                (
+                    m["tool_call_id"].as_str().unwrap().to_string(),
+                    m["content"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            tool_messages,
+            [
+                ("call_0".to_string(), "Computer Science lasts three years.".to_string()),
+                ("call_1".to_string(), "# Tool unavailable: ask_about_staff".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn profile_reaches_the_model_but_not_tools_or_later_turns() {
+        let (tool, tool_calls) = recording_tool("ask_about_general_information", Ok("Second year courses: Algorithms."));
+        let (url, requests) = mock_model(vec![
+            tool_call_reply(&[(
+                "ask_about_general_information",
+                json!({
+                    "question": PROFILE_PROMPT,
+                    "user_context": { "programme": "Computer Science" },
+                }),
+            )]),
+            answer_reply("Your second year includes Algorithms."),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![tool]).await;
+
+        agent.invoke_flow(PROFILE_PROMPT).await.unwrap();
+
+        let first_request = requests.lock().unwrap()[0].to_string();
+        assert!(first_request.contains("Programme: Computer Science"));
+
+        let tool_calls = tool_calls.lock().unwrap();
+        assert_eq!(tool_calls.len(), 1);
+        let arguments = tool_calls[0].as_object().unwrap();
+        assert_eq!(arguments.keys().collect::<Vec<_>>(), ["question"]);
+        let question = arguments["question"].as_str().unwrap();
+        assert!(question.ends_with("What are my second year courses?"));
+        assert!(!question.contains("Computer Science"));
+
+        let history = serde_json::to_string(&agent.history).unwrap();
+        assert!(!history.contains("Computer Science"));
+        let display = serde_json::to_string(&get_display_conversation(&agent)).unwrap();
+        assert!(!display.contains("Computer Science"));
+    }
+
+    #[tokio::test]
+    async fn grounding_retry_instructions_stay_out_of_later_turns() {
+        let source = "https://www.famnit.upr.si/en/programme";
+        let (tool, _) = recording_tool(
+            "ask_about_programmes",
+            Ok("Computer Science has 180 ECTS. https://www.famnit.upr.si/en/programme"),
+        );
+        let retried = format!("Computer Science has 180 ECTS in total [1]({}).", source);
+        let (url, requests) = mock_model(vec![
+            tool_call_reply(&[("ask_about_programmes", json!({ "question": "ECTS of Computer Science?" }))]),
+            answer_reply("Computer Science has 180 ECTS in total."),
+            answer_reply(&retried),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![tool]).await;
+        agent
+            .state
+            .insert(SYNTHESIS_RETRY_STATE.into(), Value::String("true".into()));
+
+        let answer = agent.invoke_flow("How many ECTS has Computer Science?").await.unwrap();
+
+        assert!(answer.content.unwrap().starts_with(&retried));
+        let retry_request = requests.lock().unwrap()[2]["messages"].to_string();
+        assert!(retry_request.contains("failed the source check"));
+        let history = serde_json::to_string(&agent.history).unwrap();
+        assert!(!history.contains("failed the source check"));
+        let last = agent.history.last().unwrap();
+        assert_eq!(last.role, Role::Assistant);
+        assert_eq!(last.content.as_deref(), Some(retried.as_str()));
+        assert_eq!(
+            agent.history.iter().filter(|m| m.role == Role::Assistant && m.tool_calls.is_none()).count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn long_history_is_summarized_before_answering() {
+        let (url, requests) = mock_model(vec![
+            answer_reply("- The user asked about Computer Science."),
+            answer_reply("Hello again!"),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![]).await;
+        agent.history = vec![Message::system("You are Urška.")];
+        for turn in 0..15 {
+            agent.history.push(Message::user(format!("Question {}", turn)));
+            agent.history.push(Message::assistant(format!("Answer {}", turn)));
+        }
+
+        agent.invoke_flow("Hello").await.unwrap();
+
+        let summarized = requests.lock().unwrap()[0]["messages"].to_string();
+        assert!(summarized.contains("Question 0"));
+        assert!(agent.history.len() < 24);
+        assert!(agent.history[1].content.as_deref().unwrap().contains("Computer Science"));
+        let kept: Vec<&str> = agent.history[2..]
+            .iter()
+            .filter_map(|m| m.content.as_deref())
+            .collect();
+        assert_eq!(&kept[..2], ["Question 11", "Answer 11"]);
+        assert_eq!(&kept[kept.len() - 4..], ["Question 14", "Answer 14", "Hello", "Hello again!"]);
+    }
+
+    #[tokio::test]
+    async fn low_score_faq_sends_the_model_to_the_other_tools() {
+        let (faq, _) = recording_tool(
+            FAQ_TOOL,
+            Ok("\n---\n\nScore: 0.31\n\nQuestion:\nWhere is the library?\n\nAnswer:\nIn Koper.\n\n---\n"),
+        );
+        let (programmes, programme_calls) = recording_tool("ask_about_programmes", Ok("Computer Science lasts three years."));
+        let (url, requests) = mock_model(vec![
+            tool_call_reply(&[(FAQ_TOOL, json!({ "question": "How long is Computer Science?" }))]),
+            tool_call_reply(&[("ask_about_programmes", json!({ "question": "How long is Computer Science?" }))]),
+            answer_reply("Computer Science lasts three years."),
+        ])
+        .await;
+        let mut agent = test_agent(url, vec![faq, programmes]).await;
+
+        agent.invoke_flow("How long is Computer Science?").await.unwrap();
+
+        let faq_result = requests.lock().unwrap()[1]["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .rfind(|m| m["role"] == "tool")
+            .unwrap()["content"]
+            .to_string();
+        assert!(faq_result.contains("No close FAQ match"));
+        assert!(!faq_result.contains("In Koper"));
+        assert_eq!(programme_calls.lock().unwrap().len(), 1);
+    }
+
+    /// Answers the same question from the same programme page under
+    /// `persona`, with the model replying `reply`. Returns the answer and
+    /// the first request sent to the model.
+    async fn answer_as(persona: Persona, reply: &str) -> (String, String) {
+        let (tool, _) = recording_tool(
+            "ask_about_programmes",
+            Ok("Computer Science has 180 ECTS. https://www.famnit.upr.si/en/programme"),
+        );
+        let (url, requests) = mock_model(vec![
+            tool_call_reply(&[("ask_about_programmes", json!({ "question": "ECTS of Computer Science?" }))]),
+            answer_reply(reply),
+        ])
+        .await;
+        let mut agent = AgentBuilder::default()
+            .set_name("Urška")
+            .set_provider(Provider::OpenAi)
+            .set_base_url(url)
+            .set_model("test-model")
+            .set_flow(flow!(flow))
+            .set_template(urska_template())
+            .add_tool(tool)
+            .build()
+            .await
+            .unwrap();
+
+        let prompt_data = HashMap::from([
+            ("date", "Monday, 06 October 2025".to_string()),
+            ("study_year", "0".to_string()),
+            ("programme", "unknown".to_string()),
+            ("user_context", String::new()),
+            ("persona", persona.instructions().to_string()),
+            ("language", "English".to_string()),
+            ("question", "How many ECTS has Computer Science?".to_string()),
+        ]);
+        let answer = agent.invoke_flow_with_template(prompt_data).await.unwrap();
+        let first_request = requests.lock().unwrap()[0]["messages"].to_string();
+        (answer.content.unwrap(), first_request)
+    }
+
+    #[tokio::test]
+    async fn personas_change_the_tone_but_keep_the_citations() {
+        let citation = "[1](https://www.famnit.upr.si/en/programme)";
+        let (friendly, friendly_request) = answer_as(
+            Persona::FriendlyAdvisor,
+            &format!("Good news: you will collect 180 ECTS in Computer Science {}.", citation),
+        )
+        .await;
+        let (formal, formal_request) = answer_as(
+            Persona::FormalAdministrative,
+            &format!("The Computer Science programme comprises 180 ECTS {}.", citation),
+        )
+        .await;
+
+        assert!(friendly_request.contains(Persona::FriendlyAdvisor.instructions()));
+        assert!(!friendly_request.contains(Persona::FormalAdministrative.instructions()));
+        assert!(formal_request.contains(Persona::FormalAdministrative.instructions()));
+        assert!(friendly_request.contains("source grounding and citation rules still apply"));
+        assert!(formal_request.contains("source grounding and citation rules still apply"));
+
+        assert_ne!(friendly, formal);
+        for answer in [&friendly, &formal] {
+            assert!(answer.contains(citation));
+            assert!(!answer.contains(GROUNDING_NOTICE.trim()));
+        }
+    }
+}
@@ -1,12 +1,10 @@
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, fmt::Display, str::FromStr, time::Duration};
 
 use futures::future::join_all;
 use reagent_rs::{
-    Agent, AgentBuildError, AgentBuilder, AgentError, Flow, FlowFuture, InvocationBuilder,
-    McpServerType, Message, Notification, NotificationContent, NotificationHandler, Role,
-    StatelessPrebuild, Template, flow,
+    Agent, AgentBuildError, AgentBuilder, AgentError, InvocationBuilder,
+    McpServerType, Message, NotificationHandler, Role, flow,
 };
-use rmcp::transport::worker;
 use serde::Serialize;
 use serde_json::{json, to_value};
 
@@ -14,10 +12,21 @@ use crate::{
     MEMORY_URL, PROGRAMME_AGENT_URL, RAG_FAQ_SERVICE, RAG_PAGE_SERVICE, RAG_RULES_SERVICE,
     SCRAPER_AGENT_URL, STAFF_AGENT_URL,
     agents::blueprint::create_blueprint_agent,
-    agents::executor::create_single_task_agent,
-    agents::planner::{Plan, create_planner_agent},
+    agents::evidence::{Evidence, prioritize},
+    agents::executor::{create_single_task_agent, step_timeout},
+    agents::history_summary::{compact_history, is_summary},
+    agents::language::detect_language,
+    agents::numbers::normalize_answer,
+    agents::ollama_limit::limited,
+    agents::planner::{
+        Plan, asks_for_plan, create_planner_agent, plan_answer, show_plan_with_answer,
+        validated_plan,
+    },
     agents::prompt_reconstuct::create_prompt_restructor_agent,
-    agents::quick_responder::{Answerable, create_quick_response_agent},
+    agents::quick_responder::{
+        Answerable, create_quick_response_agent, faq_is_close, quick_faq_min_score,
+        top_faq_score,
+    },
     agents::replanner::create_replanner_agent,
 };
 
@@ -40,21 +49,24 @@ pub async fn plan_and_execute_flow(
         .await;
 
     agent.history.push(Message::user(prompt.clone()));
-    let mut inner_iterations_bound = 100;
+    // detected before the prompt is rephrased, which may change its language
+    let language = detect_language(&prompt);
+    // an explicit request for the plan is answered with the plan itself
+    let plan_requested = asks_for_plan(&prompt);
+    let mut shown_plan = None;
 
-    let mut past_steps: Vec<(String, String)> = Vec::new();
     let mut flow_histroy: Vec<Message> = vec![Message::system(agent.system_prompt.clone())];
 
     let (mut quick_responder_agent, quick_responder_notification_channel) =
-        create_quick_response_agent(&agent).await?;
+        create_quick_response_agent(agent).await?;
     let (mut rephraser_agent, rephraser_notification_channel) =
-        create_prompt_restructor_agent(&agent).await?;
+        create_prompt_restructor_agent(agent).await?;
     let (mut blueprint_agent, blueprint_notification_channel) =
         create_blueprint_agent(agent).await?;
     let (mut planner_agent, planner_notification_channel) = create_planner_agent(agent).await?;
-    let (mut replanner_agent, replanner_notification_channel) =
+    let (_replanner_agent, replanner_notification_channel) =
         create_replanner_agent(agent).await?;
-    let (mut executor_agent, executor_notification_channel) =
+    let (executor_agent, executor_notification_channel) =
         create_single_task_agent(agent).await?;
 
     agent.forward_notifications(quick_responder_notification_channel);
@@ -64,6 +76,10 @@ pub async fn plan_and_execute_flow(
     agent.forward_notifications(replanner_notification_channel);
     agent.forward_notifications(executor_notification_channel);
 
+    if let Err(e) = compact_history(agent).await {
+        println!("Could not summarize history: {:#?}", e);
+    }
+
     // more than system + first prompt
     // query rewrite
     if agent.history.len() > 2 {
@@ -76,11 +92,11 @@ pub async fn plan_and_execute_flow(
             )
             .await;
 
-        let rehprase_response = rephraser_agent
-            .invoke_flow_with_template(HashMap::from([
+        let rehprase_response =
+            limited(rephraser_agent.invoke_flow_with_template(HashMap::from([
                 ("history", history_to_prompt(&agent.history)),
                 ("prompt", prompt.clone()),
-            ]))
+            ])))
             .await?;
 
         if let Some(rephrased_prompt) = rehprase_response.content {
@@ -89,7 +105,7 @@ pub async fn plan_and_execute_flow(
     }
 
     // if we have access to FAQ, check if we can answer right away using FAQ
-    let mut FAQ = None;
+    let mut matched_faq = None;
     if let Some(tool) = agent.get_tool_ref_by_name("retrieve_similar_FAQ") {
         quick_responder_agent
             .notify_custom(
@@ -113,33 +129,46 @@ pub async fn plan_and_execute_flow(
 
         quick_responder_agent.notify_tool_success(faq.clone()).await;
 
-        let input = HashMap::from([("prompt", prompt.clone()), ("faq", faq.clone().into())]);
+        flow_histroy.push(Message::tool(faq.clone(), "1"));
 
-        let answ: Answerable = quick_responder_agent
-            .invoke_flow_with_template_structured_output(input)
-            .await?;
+        // a loosely related FAQ is not worth asking about; plan instead
+        let top_score = top_faq_score(&faq);
+        let min_score = quick_faq_min_score();
+        if faq_is_close(&faq, min_score) {
+            let input = HashMap::from([
+                ("prompt", prompt.clone()),
+                ("faq", faq.clone()),
+                ("top_score", format!("{:.2}", top_score.unwrap_or_default())),
+            ]);
 
-        flow_histroy.push(Message::tool(faq.clone(), "1"));
+            let answ: Answerable =
+                limited(quick_responder_agent.invoke_flow_with_template_structured_output(input))
+                    .await?;
 
-        if answ.can_respond {
-            FAQ = Some(faq);
+            if answ.can_respond {
+                matched_faq = Some(faq);
+            }
+        } else {
+            println!(
+                "Top FAQ score {:?} below {}, skipping quick response",
+                top_score, min_score
+            );
         }
     };
 
     // create a general plan on how to tackle the problem
-    let blueprint = blueprint_agent
-        .invoke_flow_with_template(HashMap::from([
-            ("tools", format!("{:#?}", agent.tools)),
-            ("prompt", prompt.clone()),
-            ("faq", format!("{:#?}", FAQ)),
-        ]))
-        .await?;
+    let blueprint = limited(blueprint_agent.invoke_flow_with_template(HashMap::from([
+        ("tools", format!("{:#?}", agent.tools)),
+        ("prompt", prompt.clone()),
+        ("faq", format!("{:#?}", matched_faq)),
+    ])))
+    .await?;
 
     let Some(blueprint) = blueprint.content else {
         return Err(AgentError::Runtime("Blueprint was not created".into()));
     };
 
-    if FAQ.is_none() {
+    if matched_faq.is_none() || plan_requested {
         planner_agent
             .notify_custom(
                 to_value(&UrskaNotification {
@@ -150,50 +179,47 @@ pub async fn plan_and_execute_flow(
             .await;
 
         // create a detailed step by step plan on how to tackle the problem
-        let plan: Plan = planner_agent
-            .invoke_flow_with_template_structured_output(HashMap::from([
-                ("tools", format!("{:#?}", agent.tools)),
-                ("prompt", blueprint),
-            ]))
-            .await?;
+        let tools = format!("{:#?}", agent.tools);
+        let plan: Plan = limited(planner_agent.invoke_flow_with_template_structured_output(
+            HashMap::from([("tools", tools.clone()), ("prompt", blueprint.clone())]),
+        ))
+        .await?;
+
+        // one retry with the problem spelled out, then a single-step plan
+        let plan = validated_plan(plan, &prompt, |correction| {
+            limited(planner_agent.invoke_flow_with_template_structured_output(
+                HashMap::from([
+                    ("tools", tools),
+                    ("prompt", format!("{}\n\n{}", blueprint, correction)),
+                ]),
+            ))
+        })
+        .await;
 
         // save plan to file
         serde_json::to_writer_pretty(std::fs::File::create("last_plan.json").unwrap(), &plan)
             .unwrap();
 
-        let mut i = 0;
+        if plan_requested {
+            let content = plan_answer(&plan);
+            let message = Message::assistant(content.clone());
+            agent.history.push(message.clone());
+            agent.notify_done(true, Some(content)).await;
+            return Ok(message);
+        }
+        if show_plan_with_answer() {
+            shown_plan = Some(plan.to_string());
+        }
+
+        let step_timeout = step_timeout();
         let mut executor_fututres = vec![];
         for step_sequence in plan.steps.into_iter() {
             let worker_clone = executor_agent.clone();
             // let prompt_clone = prompt.clone();
             let executor_future = async move {
                 let mut worker = worker_clone;
-                let mut executor_task_log = vec![];
-
-                for step in step_sequence.into_iter() {
-                    worker
-                        .notify_custom(
-                            to_value(&UrskaNotification {
-                                message: step.clone(),
-                            })
-                            .unwrap(),
-                        )
-                        .await;
-
-                    let response = match worker.invoke_flow(step.clone()).await {
-                        Ok(resp) => resp,
-                        Err(e) => {
-                            println!("Error executing step `{}`: {}", step, e);
-                            continue;
-                        }
-                    };
-                    executor_task_log.push((Message::user(step.clone()), response));
-                }
-
-                let _ = worker.save_history(format!("executor_run_{}_conversation.json", i));
-                executor_task_log
+                execute_branch(&mut worker, step_sequence, step_timeout).await
             };
-            i += 1;
             executor_fututres.push(executor_future);
         }
 
@@ -207,57 +233,197 @@ pub async fn plan_and_execute_flow(
             .await;
 
         let executor_results = join_all(executor_fututres).await;
-        let mut past_steps: Vec<(String, String)> = Vec::new();
-
-        for executor_task_log in executor_results {
-            for (task, response) in executor_task_log {
-                past_steps.push((
-                    task.content.unwrap_or_default(),
-                    response.content.unwrap_or_default(),
-                ));
-            }
-        }
+        let mut past_steps: Vec<Evidence> = executor_results.into_iter().flatten().collect();
+        // most authoritative evidence first, so conflicts resolve in its favour
+        prioritize(&mut past_steps);
 
-        let aggregated_history = past_steps
-            .iter()
-            .enumerate()
-            .map(|(i, (task, response))| {
-                format!(
-                    "### Step {}\nUser Instruction:\n{}\n\nExecutor Response:\n{}\n ",
-                    i + 1,
-                    task.trim(),
-                    response.trim()
-                )
-            })
-            .collect::<Vec<String>>()
-            .join("\n\n---\n\n");
+        let aggregated_history = aggregate_history(&past_steps);
 
         flow_histroy.push(Message::tool(aggregated_history, "0"));
     }
 
-    flow_histroy.push(Message::user(prompt));
+    flow_histroy.push(Message::user(format!(
+        "{}\n\nRespond in {}.",
+        prompt, language
+    )));
 
     let mut conversation_history = agent.history.clone();
     agent.history = flow_histroy;
 
     // let response = invoke_without_tools(agent).await?;
-    let response = InvocationBuilder::default()
-        .use_tools(false)
-        .invoke_with(agent)
-        .await?;
-    conversation_history.push(response.message.clone());
-
-    let _ = agent.save_history("urska_conversation.json".to_string());
+    let response = limited(
+        InvocationBuilder::default()
+            .use_tools(false)
+            .invoke_with(agent),
+    )
+    .await?;
+    let mut response_message = response.message;
+    response_message.content = response_message
+        .content
+        .map(|answer| normalize_answer(&answer));
+    if let Some(plan) = shown_plan {
+        let answer = response_message.content.unwrap_or_default();
+        response_message.content = Some(format!("{}\n\n---\n**Plan:**\n\n{}", answer, plan));
+    }
+    conversation_history.push(response_message.clone());
 
     agent.history = conversation_history;
 
     agent
-        .notify_done(true, response.message.content.clone())
+        .notify_done(true, response_message.content.clone())
         .await;
-    Ok(response.message)
+    Ok(response_message)
+}
+
+/// Runs the steps of one plan branch in order. A step that fails or runs
+/// longer than `step_timeout` is recorded as an "Execution Error" so the
+/// synthesis can report the gap, and the branch continues with the next step.
+async fn execute_branch(
+    worker: &mut Agent,
+    steps: Vec<String>,
+    step_timeout: Duration,
+) -> Vec<Evidence> {
+    let mut executor_task_log = vec![];
+
+    for step in steps.into_iter() {
+        let step_start = worker.history.len();
+        worker
+            .notify_custom(
+                to_value(&UrskaNotification {
+                    message: step.clone(),
+                })
+                .unwrap(),
+            )
+            .await;
+
+        // a hung tool call is cancelled instead of stalling the whole answer
+        let response = match tokio::time::timeout(step_timeout, worker.invoke_flow(step.clone()))
+            .await
+        {
+            Ok(Ok(resp)) => resp,
+            Ok(Err(e)) => {
+                println!("Error executing step `{}`: {}", step, e);
+                Message::assistant(format!(
+                    "Execution Error: the step could not be completed ({}). No data was retrieved for it.",
+                    e
+                ))
+            }
+            Err(_) => {
+                println!("Step `{}` timed out after {:?}", step, step_timeout);
+                Message::assistant(format!(
+                    "Execution Error: the step timed out after {} seconds. No data was retrieved for it.",
+                    step_timeout.as_secs()
+                ))
+            }
+        };
+        let step_history = &worker.history[step_start.min(worker.history.len())..];
+        executor_task_log.push(Evidence::new(
+            step.clone(),
+            response.content.clone().unwrap_or_default(),
+            step_history,
+        ));
+    }
+
+    executor_task_log
+}
+
+/// The executor log as shown to the synthesis, one section per step in the
+/// given order.
+fn aggregate_history(past_steps: &[Evidence]) -> String {
+    past_steps
+        .iter()
+        .enumerate()
+        .map(|(i, evidence)| {
+            format!(
+                "### Step {}\nEvidence priority: {}\nUser Instruction:\n{}\n\nExecutor Response:\n{}\n ",
+                i + 1,
+                evidence.priority().label(),
+                evidence.task.trim(),
+                evidence.response.trim()
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n---\n\n")
+}
+
+/// Sampling and iteration settings of the Urška agent. Each is read from
+/// its `URSKA_*` variable and falls back to the value tuned for the
+/// default model.
+#[derive(Debug, Clone, PartialEq)]
+struct UrskaSettings {
+    /// `None` keeps the agent's default.
+    max_iterations: Option<usize>,
+    temperature: f32,
+    top_p: f32,
+    top_k: u32,
+    min_p: f32,
+    presence_penalty: f32,
+}
+
+/// Value of `key` parsed as `T` and checked to lie in `range`, `default`
+/// when unset or empty.
+fn env_setting<T>(key: &str, default: T, range: std::ops::RangeInclusive<T>) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Display + Copy,
+{
+    parse_setting(key, env::var(key).ok().as_deref(), default, range)
+}
+
+/// `raw`, the value of `key`, parsed as `T` and checked to lie in `range`;
+/// `default` when missing or empty.
+fn parse_setting<T>(
+    key: &str,
+    raw: Option<&str>,
+    default: T,
+    range: std::ops::RangeInclusive<T>,
+) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Display + Copy,
+{
+    let Some(raw) = raw.filter(|v| !v.trim().is_empty()) else {
+        return Ok(default);
+    };
+    let value: T = raw
+        .trim()
+        .parse()
+        .map_err(|_| format!("{} must be a number, got '{}'", key, raw))?;
+    if !range.contains(&value) {
+        return Err(format!(
+            "{} must be between {} and {}, got {}",
+            key,
+            range.start(),
+            range.end(),
+            value
+        ));
+    }
+    Ok(value)
+}
+
+impl UrskaSettings {
+    fn from_env() -> Result<Self, String> {
+        let max_iterations = match env::var("URSKA_MAX_ITERATIONS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+        {
+            Some(_) => Some(env_setting("URSKA_MAX_ITERATIONS", 1, 1..=100)?),
+            None => None,
+        };
+        Ok(Self {
+            max_iterations,
+            temperature: env_setting("URSKA_TEMPERATURE", 0.7, 0.0..=2.0)?,
+            top_p: env_setting("URSKA_TOP_P", 0.8, 0.0..=1.0)?,
+            top_k: env_setting("URSKA_TOP_K", 20, 0..=1000)?,
+            min_p: env_setting("URSKA_MIN_P", 0.0, 0.0..=1.0)?,
+            presence_penalty: env_setting("URSKA_PRESENCE_PENALTY", 0.1, -2.0..=2.0)?,
+        })
+    }
 }
 
 pub async fn build_urska() -> Result<Agent, AgentBuildError> {
+    let settings = UrskaSettings::from_env().map_err(|e| {
+        AgentBuildError::Unsupported(format!("Invalid Urška configuration: {}", e))
+    })?;
+
     let system_prompt = r#"
 You are **Urška**, a helpful, knowledgeable, and reliable assistant for the University of Primorska's Faculty of Mathematics, Natural Sciences and Information Technologies (UP FAMNIT).
 Your task is to help students access accurate knowledge and information about the university.
@@ -276,6 +442,8 @@ You will receive the results of the tasks, which should be enough to answer the
 
 The **final `User` message** in the log restates the objective and tells you to begin.
 
+Steps are ordered by the authority of their sources and carry an `Evidence priority`. When steps contradict each other, trust the higher-priority step and do not repeat the conflicting lower-priority claim.
+
 ---
 
 ## Your final task
@@ -350,7 +518,7 @@ The report must be faithful to the execution log, clear, and useful to the stude
 
 * **Style rules**
 
-  * Output numbers with a decimal comma and never use dots in numbers (3.5k€ -> 3500,00€).
+  * Write out abbreviated amounts in full (3.5k€ -> 3500€); decimal separators are normalized to the user's language afterwards.
   * Copy URLs exactly, including IDs or path segments (e.g. `/static/3775`).
 
 ---
@@ -363,7 +531,7 @@ The report must be faithful to the execution log, clear, and useful to the stude
 
     "#;
 
-    AgentBuilder::default()
+    let mut builder = AgentBuilder::default()
         .set_system_prompt(system_prompt)
         .set_flow(flow!(plan_and_execute_flow))
         .set_name("Urška")
@@ -377,17 +545,19 @@ The report must be faithful to the execution log, clear, and useful to the stude
         .add_mcp_server(McpServerType::streamable_http(RAG_PAGE_SERVICE))
         .add_mcp_server(McpServerType::streamable_http(RAG_RULES_SERVICE))
         .add_mcp_server(McpServerType::streamable_http(RAG_FAQ_SERVICE))
-        .set_temperature(0.7)
-        .set_top_p(0.8)
-        .set_top_k(20)
-        .set_min_p(0.0)
-        .set_presence_penalty(0.1)
-        .set_stream(true)
-        .build()
-        .await
+        .set_temperature(settings.temperature)
+        .set_top_p(settings.top_p)
+        .set_top_k(settings.top_k)
+        .set_min_p(settings.min_p)
+        .set_presence_penalty(settings.presence_penalty)
+        .set_stream(true);
+    if let Some(max_iterations) = settings.max_iterations {
+        builder = builder.set_max_iterations(max_iterations);
+    }
+    builder.build().await
 }
 
-pub fn history_to_prompt(history: &Vec<Message>) -> String {
+pub fn history_to_prompt(history: &[Message]) -> String {
     let mut prompt = String::from("Here is a summary of a conversation.");
     for msg in history.iter().skip(1) {
         let content = msg.content.clone().unwrap_or_default();
@@ -395,6 +565,7 @@ pub fn history_to_prompt(history: &Vec<Message>) -> String {
             Role::User => prompt.push_str(&format!("USER ASKED: {}\n\n", content)),
             Role::Assistant => prompt.push_str(&format!("ASSISTANT: {}\n\n", content)),
             Role::Tool => continue,
+            Role::System if is_summary(msg) => prompt.push_str(&format!("{}\n\n", content)),
             Role::System => continue,
             Role::Developer => continue,
         }
@@ -404,3 +575,130 @@ pub fn history_to_prompt(history: &Vec<Message>) -> String {
     println!("CONVO: {}", prompt);
     prompt
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for the executor model: steps mentioning "fail" error,
+    /// "slow" steps hang, and the rest are answered.
+    async fn stub_step(_agent: &mut Agent, step: String) -> Result<Message, AgentError> {
+        if step.contains("fail") {
+            return Err(AgentError::Runtime("staff expert unreachable".into()));
+        }
+        if step.contains("slow") {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+        Ok(Message::assistant(format!("Done: {}", step)))
+    }
+
+    async fn stub_worker() -> Agent {
+        AgentBuilder::default()
+            .set_model("test-model")
+            .set_flow(flow!(stub_step))
+            .build()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn failing_step_is_recorded_and_the_branch_continues() {
+        let mut worker = stub_worker().await;
+        let steps = vec![
+            "Find the dean, then fail".to_string(),
+            "Find the programme coordinator".to_string(),
+        ];
+
+        let log = execute_branch(&mut worker, steps, Duration::from_secs(5)).await;
+
+        assert_eq!(log.len(), 2);
+        assert!(log[0].response.starts_with("Execution Error"));
+        assert!(log[0].response.contains("staff expert unreachable"));
+        assert_eq!(log[1].response, "Done: Find the programme coordinator");
+    }
+
+    #[tokio::test]
+    async fn timed_out_step_is_recorded_and_the_branch_continues() {
+        let mut worker = stub_worker().await;
+        let steps = vec![
+            "Read the slow rules page".to_string(),
+            "Find the programme coordinator".to_string(),
+        ];
+
+        let log = execute_branch(&mut worker, steps, Duration::from_millis(50)).await;
+
+        assert_eq!(log.len(), 2);
+        assert!(log[0].response.starts_with("Execution Error"));
+        assert!(log[0].response.contains("timed out"));
+        assert_eq!(log[1].response, "Done: Find the programme coordinator");
+    }
+
+    #[tokio::test]
+    async fn failed_step_appears_in_the_aggregated_history() {
+        let mut worker = stub_worker().await;
+        let steps = vec![
+            "Find the programme coordinator".to_string(),
+            "Look up the enrolment fee, then fail".to_string(),
+        ];
+
+        let mut log = execute_branch(&mut worker, steps, Duration::from_secs(5)).await;
+        prioritize(&mut log);
+        let history = aggregate_history(&log);
+
+        assert!(history.contains("User Instruction:\nLook up the enrolment fee, then fail"));
+        assert!(
+            history
+                .contains("Executor Response:\nExecution Error: the step could not be completed")
+        );
+        assert!(history.contains("Evidence priority: none"));
+        assert!(history.contains("Done: Find the programme coordinator"));
+    }
+
+    #[test]
+    fn missing_or_empty_setting_falls_back_to_the_default() {
+        assert_eq!(parse_setting("URSKA_TOP_K", None, 20, 0..=1000), Ok(20));
+        assert_eq!(parse_setting("URSKA_TOP_K", Some("  "), 20, 0..=1000), Ok(20));
+    }
+
+    #[test]
+    fn setting_is_parsed_and_trimmed() {
+        assert_eq!(parse_setting("URSKA_TEMPERATURE", Some(" 0.3 "), 0.7, 0.0..=2.0), Ok(0.3));
+        assert_eq!(parse_setting("URSKA_TOP_K", Some("40"), 20, 0..=1000), Ok(40));
+    }
+
+    #[test]
+    fn invalid_setting_is_rejected() {
+        assert_eq!(
+            parse_setting("URSKA_TOP_P", Some("high"), 0.8, 0.0..=1.0),
+            Err("URSKA_TOP_P must be a number, got 'high'".to_string())
+        );
+        assert_eq!(
+            parse_setting("URSKA_TOP_P", Some("1.5"), 0.8, 0.0..=1.0),
+            Err("URSKA_TOP_P must be between 0 and 1, got 1.5".to_string())
+        );
+    }
+
+    #[test]
+    fn aggregated_log_lists_the_most_authoritative_step_first() {
+        let mut log = vec![
+            Evidence {
+                task: "Search the FAQ for the fee".into(),
+                response: "The FAQ mentions a fee.".into(),
+                tools: vec!["retrieve_similar_FAQ".into()],
+            },
+            Evidence {
+                task: "Read the programme page".into(),
+                response: "The fee is 120 EUR.".into(),
+                tools: vec!["get_programme_info".into()],
+            },
+        ];
+
+        prioritize(&mut log);
+        let history = aggregate_history(&log);
+
+        let direct = history.find("Read the programme page").unwrap();
+        let retrieval = history.find("Search the FAQ for the fee").unwrap();
+        assert!(direct < retrieval);
+        assert!(history.starts_with("### Step 1\nEvidence priority: high (official structured data)"));
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::{atomic::AtomicI32, Arc}, time::SystemTime
 
 use chrono::Utc;
 use dotenv::dotenv;
-use reagent_rs::{Agent};
+use reagent_rs::{Agent, Role};
 use rmcp::{
     handler::server::tool::{Parameters, ToolRouter}, model::{CallToolResult, Content, Meta, ProgressNotificationParam, ServerCapabilities, ServerInfo},
     schemars, tool, tool_handler, tool_router, Peer, RoleServer, ServerHandler
@@ -16,7 +16,8 @@ use rmcp::transport::streamable_http_server::{
     StreamableHttpService, session::local::LocalSessionManager,
 };
 
-use crate::agents::urska_v2::{build_urska_v2, get_display_conversation};
+use crate::agents::urska_v2::get_display_conversation;
+use crate::agents::{confidence::answer_confidence, language::detect_language, notification_dedup::NotificationDedup, persona::Persona};
 use crate::agents::urska_v3::build_urska_v3;
 
 pub mod agents;
@@ -69,10 +70,14 @@ async fn main() -> Result<()> {
 pub struct StructRequest {
     pub question: String,
     pub user_context: Option<Value>,
+    /// Optional answer tone: 'friendly-advisor' or 'formal-administrative'.
+    pub persona: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 struct Service {
+    /// Connection number, shown in the `Debug` output.
+    #[allow(dead_code)]
     id: String,
     agent: Arc<Mutex<Agent>>,
     tool_router: ToolRouter<Service>,
@@ -111,7 +116,11 @@ impl Service {
                     None,
                 )) {
                     let mut step = 1;
+                    let mut dedup = NotificationDedup::from_env();
                     while let Some(notification) = notification_channel.recv().await {
+                        if !dedup.should_forward(&notification) {
+                            continue;
+                        }
                         let _ = client
                             .notify_progress(ProgressNotificationParam {
                                 progress_token: progress_token.clone(),
@@ -148,15 +157,43 @@ impl Service {
             })
             .unwrap_or(0);
 
+        let programme = user_context_for_year
+            .as_ref()
+            .and_then(|uc| uc.get("programme"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+
         let mut prompt_data = HashMap::new();
         prompt_data.insert("question".to_string(), question.question.clone());
+        prompt_data.insert("language".to_string(), detect_language(&question.question).to_string());
         prompt_data.insert("user_context".to_string(), user_context_str);
         prompt_data.insert("date".to_string(), date_str);
         prompt_data.insert("study_year".to_string(), study_year.to_string());
+        prompt_data.insert("programme".to_string(), programme);
+        prompt_data.insert(
+            "persona".to_string(),
+            Persona::resolve(question.persona.as_deref()).instructions().to_string(),
+        );
 
+        let history_start = agent.history.len();
         let resp = agent.invoke_flow_with_template(prompt_data).await;
         println!("Time to answer query: {:?} | {}", start.elapsed(), question.question);
-        Ok(CallToolResult::success(vec![Content::text(resp.unwrap().content.unwrap())]))
+        let answer = resp.unwrap().content.unwrap();
+
+        let tool_outputs: Vec<String> = agent.history[history_start.min(agent.history.len())..]
+            .iter()
+            .filter(|m| m.role == Role::Tool)
+            .filter_map(|m| m.content.clone())
+            .collect();
+        let confidence = answer_confidence(&answer, &tool_outputs);
+        println!("Answer confidence: {:?}", confidence);
+
+        let mut content = vec![Content::text(answer)];
+        if let Ok(confidence) = Content::json(confidence) {
+            content.push(confidence);
+        }
+        Ok(CallToolResult::success(content))
     }
 
 
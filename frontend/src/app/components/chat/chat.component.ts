@@ -23,6 +23,9 @@ import { StateService } from "../../state/state.service";
 import { UserProfile } from "../../models/profile.model";
 import { LoginModalComponent } from "../login/login.component";
 
+const SOCKET_URL = "wss://urska.famnit.upr.si/ws";
+const RECONNECT_DELAY_MS = 2000;
+
 @Component({
   selector: "app-chat",
   standalone: true,
@@ -44,15 +47,18 @@ export class ChatComponent implements OnInit {
   public stateMessage: String | undefined;
   public errorMessage: CountedError | undefined;
   public queuePosition: number = 0;
+  public queueWaitSecs: number | null = null;
   public lastToken: CountedToken | undefined;
   public leftSideOpen = true;
   public rightSideOpen = true;
   public displayAdvanced = false;
   public isProcessing = false;
   public isLoggedIn = false;
-  public socket: WebSocket = new WebSocket("wss://urska.famnit.upr.si/ws");
+  public socket!: WebSocket;
   public socketStatus: "connecting" | "open" | "closed" = "connecting";
   public tokenCount: number = 0;
+  // job answering the last prompt, resumed if the socket drops before its End
+  private jobId: string | null = null;
 
   constructor(private cdr: ChangeDetectorRef) {
     effect(() => {
@@ -73,6 +79,19 @@ export class ChatComponent implements OnInit {
       this.cdr.detectChanges();
     });
 
+    effect(() => {
+      const share = StateService.shareProfileContext();
+      const loggedIn = !!StateService.userProfile();
+      if (loggedIn && this.socket?.readyState === WebSocket.OPEN) {
+        this.socket.send(
+          JSON.stringify({
+            message_type: "ShareProfileContext",
+            content: String(share),
+          }),
+        );
+      }
+    });
+
     effect(() => {
       StateService.resetChat();
       this.resetChat();
@@ -80,6 +99,13 @@ export class ChatComponent implements OnInit {
   }
 
   ngOnInit() {
+    this.connect();
+  }
+
+  connect() {
+    this.socket = new WebSocket(SOCKET_URL);
+    this.socketStatus = "connecting";
+
     this.socket.onopen = () => {
       this.socketStatus = "open";
 
@@ -93,11 +119,18 @@ export class ChatComponent implements OnInit {
         );
       }
 
+      if (this.jobId) {
+        this.resumeJob(this.jobId);
+      }
+
       this.cdr.detectChanges();
     };
 
     this.socket.onclose = () => {
       this.socketStatus = "closed";
+      if (this.jobId) {
+        setTimeout(() => this.connect(), RECONNECT_DELAY_MS);
+      }
       this.cdr.detectChanges();
     };
 
@@ -121,10 +154,26 @@ export class ChatComponent implements OnInit {
         this.handleLoginProfileMessage(msg);
       }
 
+      if (msg.type === "JobStarted") {
+        this.jobId = msg.data;
+      }
+
+      if (msg.type === "End") {
+        this.jobId = null;
+      }
+
       if (msg.type === "QueuePosition") {
         this.handleQueuePositionMessage(msg);
       }
 
+      if (msg.type === "Chunk") {
+        this.handleChunkMessage(msg);
+      }
+
+      if (msg.type === "Answer") {
+        this.handleAnswerMessage(msg);
+      }
+
       if (msg.type === "Notification") {
         this.handleNotificationMessage(msg);
       }
@@ -132,23 +181,48 @@ export class ChatComponent implements OnInit {
       this.cdr.detectChanges();
     };
   }
+  // the backend sends the job's output again from the start, only to the
+  // user who asked
+  resumeJob(jobId: string) {
+    const answer = this.messages[this.messages.length - 1];
+    if (answer?.role === "assistant") {
+      answer.content = "";
+    }
+    this.notifications = [];
+    this.resultNotifications = [];
+    this.socket.send(
+      JSON.stringify({
+        message_type: "ResumeJob",
+        content: JSON.stringify({
+          job_id: jobId,
+          session_token: localStorage.getItem("urska_session_token") ?? "",
+        }),
+      }),
+    );
+  }
+
+  handleChunkMessage(msg: any) {
+    this.queuePosition = 0;
+    this.lastToken = {
+      value: msg.data,
+      seq: this.tokenCount++,
+    } as CountedToken;
+  }
+
+  // the final answer can differ from the streamed tokens (removed citations,
+  // appended notices), so it replaces them
+  handleAnswerMessage(msg: any) {
+    const answer = this.messages[this.messages.length - 1];
+    if (answer?.role === "assistant") {
+      answer.content = msg.data;
+    }
+  }
+
   handleNotificationMessage(msg: any) {
     this.queuePosition = 0;
 
     const backendNotification = JSON.parse(msg.data) as BackendNotification;
     console.log("Received notification:", backendNotification);
-    if (
-      backendNotification.agent === "Urška" &&
-      "Token" in backendNotification.content
-    ) {
-      this.lastToken = {
-        value: backendNotification.content.Token.value,
-        seq: this.tokenCount++,
-      } as CountedToken;
-
-      this.cdr.detectChanges();
-      return;
-    }
 
     if ("Token" in backendNotification.content) {
       return;
@@ -289,7 +363,8 @@ export class ChatComponent implements OnInit {
   }
 
   handleQueuePositionMessage(msg: any) {
-    this.queuePosition = +msg.data;
+    this.queuePosition = +msg.data.position;
+    this.queueWaitSecs = msg.data.estimated_wait_secs ?? null;
   }
 
   handleErrorMessage(msg: any) {
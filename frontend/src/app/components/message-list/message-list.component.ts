@@ -36,6 +36,7 @@ export class MessageListComponent implements OnChanges {
   @Input() stateMessage: String | undefined;
   @Input() errorMessage: CountedError | undefined;
   @Input() queuePosition: number = 0;
+  @Input() queueWaitSecs: number | null = null;
   @Input() socket: WebSocket | undefined;
   @Output() sendPrompt = new EventEmitter<string>();
 
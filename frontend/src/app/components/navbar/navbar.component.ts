@@ -14,6 +14,7 @@ export class NavbarComponent {
   pageTitle: string = 'Univerzitetne Rešitve: Študentski Komunikacijski Agent';
   isLoggedIn: boolean = false;
   isAdvancedVisible: boolean = false;
+  isProfileShared: boolean = StateService.shareProfileContext();
 
   constructor(private cdr: ChangeDetectorRef) {
     effect(() => {
@@ -43,5 +44,11 @@ export class NavbarComponent {
     console.log(StateService.displayType())
   }
 
+  toggleProfileSharing(): void {
+    this.isProfileShared = !this.isProfileShared;
+    localStorage.setItem("urska_share_profile", String(this.isProfileShared));
+    StateService.shareProfileContext.set(this.isProfileShared);
+  }
+
  
 }
\ No newline at end of file
@@ -8,4 +8,5 @@ export class StateService {
   public static displayType: WritableSignal<'simple' | 'advanced'> = signal('simple')
   public static userProfile: WritableSignal<UserProfile | null> = signal(null);
   public static resetChat: WritableSignal<number> = signal(0);
+  public static shareProfileContext: WritableSignal<boolean> = signal(localStorage.getItem('urska_share_profile') === 'true');
 }
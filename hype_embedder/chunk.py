@@ -230,6 +230,20 @@ def extract_keywords(path: Path) -> list[str]:
     # return [t for t in tokens if t]
     return ["rules", "act", "upr.si"]
 
+ARTICLE_HEADING = re.compile(r"^#+\s*\**\s*(?:(?:Article|Člen)\s+(\d+)|(\d+)\.\s*člen)", re.IGNORECASE)
+
+
+def article_numbers(chunk: str) -> list[int]:
+    """Numbers of the articles whose heading appears in *chunk*."""
+    numbers = []
+    for ln in chunk.splitlines():
+        m = ARTICLE_HEADING.match(ln.strip())
+        if m:
+            n = int(m.group(1) or m.group(2))
+            if n not in numbers:
+                numbers.append(n)
+    return numbers
+
 OUTPUT_LOCK = Lock()  # put near your other globals
 
 def append_records(records: list[dict]) -> None:
@@ -248,6 +262,8 @@ def process_file(path: Path) -> str:
     summary = short_summary(text)
     link = parse_link_from_filename(path.name)
     keywords = extract_keywords(path)
+    # subfolders below the resource folder tag the document, e.g. ["rules"]
+    tags = list(path.relative_to(RESOURCE_FOLDER).parent.parts)
     records: list[dict] = []
     for seq, chunk in enumerate(split_into_hierarchical_chunks(link, text)):
         records.append(
@@ -257,8 +273,10 @@ def process_file(path: Path) -> str:
                 "link": link,
                 "seq_num": seq,
                 "chunk": chunk,
+                "articles": article_numbers(chunk),
                 "summary": summary,
                 "keywords": keywords,
+                "tags": tags,
             }
         )
     append_records(records)
@@ -10,7 +10,7 @@ Script that reads a JSONL file containing document chunks and generates HyPE que
 ❗ Environment variables are taken from .env (see sample provided by the user).
 """
 
-from datetime import datetime
+from datetime import date, datetime
 import os
 import json
 import uuid
@@ -268,9 +268,13 @@ def insert_points(
     document_id: str,
     document_name: str,
     seq_num: int,
+    articles: list[int],
+    tags: list[str],
 ) -> None:
     payloads = []
     ids = []
+    # lets the RAG services flag passages that may have gone stale
+    ingested_at = date.today().isoformat()
     for vec, q in zip(vectors, questions):
         ids.append(str(uuid.uuid4()))
         payloads.append(
@@ -280,6 +284,9 @@ def insert_points(
                 "document_id": document_id,
                 "document_name": document_name,
                 "seq_num": seq_num,
+                "articles": articles,
+                "tags": tags,
+                "ingested_at": ingested_at,
             }
         )
     qclient.upsert(
@@ -315,7 +322,9 @@ def process_chunk(chunk_data: dict) -> str:
 
     # Insert into Qdrant
     now = datetime.now()
-    insert_points(vectors, questions, chunk_text, document_id, document_name, seq_num)
+    articles = chunk_data.get("articles", [])
+    tags = chunk_data.get("tags", [])
+    insert_points(vectors, questions, chunk_text, document_id, document_name, seq_num, articles, tags)
     then = datetime.now()    
     tdelta = now - then
     seconds = tdelta.total_seconds()
@@ -10,20 +10,122 @@ pub struct Config {
     pub qdrant_collection_name: String,
     pub embedding_dimension: u64,
     pub bind_address: String,
+    pub min_memory_length: usize,
+    pub memory_dedup_threshold: f32,
+    /// Start anyway (with a warning) when stored memories were embedded with
+    /// another model, instead of refusing to serve.
+    pub warn_on_model_mismatch: bool,
+    /// Embedding requests sent to Ollama at once; `0` removes the cap.
+    pub ollama_max_concurrency: usize,
+}
+
+/// Output dimension of common Ollama embedding models, by name without tag.
+const KNOWN_EMBEDDING_DIMENSIONS: [(&str, u64); 6] = [
+    ("bge-m3", 1024),
+    ("bge-large", 1024),
+    ("mxbai-embed-large", 1024),
+    ("snowflake-arctic-embed", 1024),
+    ("nomic-embed-text", 768),
+    ("all-minilm", 384),
+];
+
+/// Known output dimension of `model`, e.g. `bge-m3:latest`.
+pub fn known_embedding_dimension(model: &str) -> Option<u64> {
+    let name = model.split(':').next().unwrap_or(model).trim();
+    KNOWN_EMBEDDING_DIMENSIONS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, dimension)| *dimension)
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, anyhow::Error> {
         dotenv().ok();
-        Ok(Self {
-            ollama_endpoint: env::var("OLLAMA_ENDPOINT").unwrap_or_else(|_| "http://localhost:11434".to_string()),
-            embedding_model: env::var("EMBEDDING_MODEL").unwrap_or_else(|_| "bge-m3".to_string()),
-            qdrant_endpoint: env::var("QDRANT_ENDPOINT").unwrap_or_else(|_| "http://localhost:6334".to_string()),
-            qdrant_collection_name: env::var("QDRANT_COLLECTION_NAME").unwrap_or_else(|_| "agent_memory_rmcp".to_string()),
-            embedding_dimension: env::var("EMBEDDING_DIMENSION")
-                .unwrap_or_else(|_| "1024".to_string())
+        Self::from_vars(|key| env::var(key).ok())
+    }
+
+    /// Config from the variables `var` looks up, with defaults for the
+    /// missing ones.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, anyhow::Error> {
+        let config = Self {
+            ollama_endpoint: var("OLLAMA_ENDPOINT").unwrap_or_else(|| "http://localhost:11434".to_string()),
+            embedding_model: var("EMBEDDING_MODEL").unwrap_or_else(|| "bge-m3".to_string()),
+            qdrant_endpoint: var("QDRANT_ENDPOINT").unwrap_or_else(|| "http://localhost:6334".to_string()),
+            qdrant_collection_name: var("QDRANT_COLLECTION_NAME").unwrap_or_else(|| "agent_memory_rmcp".to_string()),
+            embedding_dimension: var("EMBEDDING_DIMENSION")
+                .unwrap_or_else(|| "1024".to_string())
                 .parse::<u64>()?,
-            bind_address: env::var("BIND_ADDRESS").unwrap_or_else(|_| "127.0.0.1:8002".to_string()),
-        })
+            bind_address: var("BIND_ADDRESS").unwrap_or_else(|| "127.0.0.1:8002".to_string()),
+            min_memory_length: var("MIN_MEMORY_LENGTH")
+                .unwrap_or_else(|| "10".to_string())
+                .parse::<usize>()?,
+            memory_dedup_threshold: var("MEMORY_DEDUP_THRESHOLD")
+                .unwrap_or_else(|| "0.95".to_string())
+                .parse::<f32>()?,
+            warn_on_model_mismatch: var("EMBEDDING_MODEL_CHECK")
+                .is_some_and(|v| v.trim().eq_ignore_ascii_case("warn")),
+            ollama_max_concurrency: var("OLLAMA_MAX_CONCURRENCY")
+                .unwrap_or_else(|| "4".to_string())
+                .parse::<usize>()?,
+        };
+
+        // a collection created with the wrong size rejects every upsert
+        if let Some(dimension) = known_embedding_dimension(&config.embedding_model)
+            && dimension != config.embedding_dimension
+        {
+            anyhow::bail!(
+                "EMBEDDING_DIMENSION is {} but the embedding model '{}' produces {}-dimensional vectors",
+                config.embedding_dimension,
+                config.embedding_model,
+                dimension
+            );
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> Result<Config, anyhow::Error> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Config::from_vars(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn unset_variables_fall_back_to_the_defaults() {
+        let config = config(&[]).unwrap();
+
+        assert_eq!(config.ollama_endpoint, "http://localhost:11434");
+        assert_eq!(config.embedding_model, "bge-m3");
+        assert_eq!(config.embedding_dimension, 1024);
+        assert_eq!(config.qdrant_collection_name, "agent_memory_rmcp");
+        assert_eq!(config.min_memory_length, 10);
+        assert_eq!(config.memory_dedup_threshold, 0.95);
+        assert!(!config.warn_on_model_mismatch);
+        assert_eq!(config.ollama_max_concurrency, 4);
+    }
+
+    #[test]
+    fn model_dimension_must_match_the_configured_dimension() {
+        let err = config(&[("EMBEDDING_MODEL", "nomic-embed-text:latest")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "EMBEDDING_DIMENSION is 1024 but the embedding model 'nomic-embed-text:latest' produces 768-dimensional vectors"
+        );
+
+        let nomic = config(&[("EMBEDDING_MODEL", "nomic-embed-text:latest"), ("EMBEDDING_DIMENSION", "768")]).unwrap();
+        assert_eq!(nomic.embedding_dimension, 768);
+        // unknown models are trusted with the configured dimension
+        assert!(config(&[("EMBEDDING_MODEL", "my-embedder"), ("EMBEDDING_DIMENSION", "512")]).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn malformed_numbers_and_the_warn_switch_are_read() {
+        assert!(config(&[("MIN_MEMORY_LENGTH", "ten")]).is_err());
+        assert!(config(&[("EMBEDDING_MODEL_CHECK", " Warn ")]).unwrap().warn_on_model_mismatch);
+    }
+}
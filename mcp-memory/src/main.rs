@@ -34,7 +34,12 @@ async fn main() -> Result<()> {
     println!("Qdrant service configured for endpoint: {} and collection: {}", app_config.qdrant_endpoint, app_config.qdrant_collection_name);
 
     // 3. Create AppState
-    let app_state = Arc::new(AppState::new(ollama_serv, qdrant_serv));
+    let app_state = Arc::new(AppState::new(
+        ollama_serv,
+        qdrant_serv,
+        app_config.min_memory_length,
+        app_config.memory_dedup_threshold,
+    ));
     println!("Application state created.");
 
     // 4. Serve the service using rmcp's axum_server
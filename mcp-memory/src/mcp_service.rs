@@ -11,11 +11,13 @@ use rmcp::{
     tool, ServerHandler,
 };
 use crate::models::{
-    MemoryItem, AddMemoryParams, AddMemoryResponse,
-    QueryMemoryParams, QueryMemoryResponse,
+    MemoryItem, AddMemoryParams, AddMemoryResponse, AddMemoriesParams, AddMemoriesResponse,
+    UpdateMemoryParams, DeleteMemoryParams, DeleteMemoryResponse, MemoryResult, QueryMemoryParams,
+    QueryMemoryResponse, QueryResultItem,
 };
 use crate::ollama_service::OllamaService;
 use crate::qdrant_service::QdrantService;
+use uuid::Uuid;
 
 const STM_CAPACITY: usize = 20;
 
@@ -25,16 +27,118 @@ pub struct AppState {
     stm: Arc<Mutex<VecDeque<MemoryItem>>>, // Short-Term Memory (text-based for now)
     ollama: OllamaService,
     qdrant: QdrantService,
+    min_memory_length: usize, // memories shorter than this (in characters, trimmed) are rejected
+    dedup_threshold: f32, // memories at least this similar (cosine) to a stored one are not stored again
 }
 
 impl AppState {
-    pub fn new(ollama: OllamaService, qdrant: QdrantService) -> Self {
+    pub fn new(ollama: OllamaService, qdrant: QdrantService, min_memory_length: usize, dedup_threshold: f32) -> Self {
         Self {
             stm: Arc::new(Mutex::new(VecDeque::with_capacity(STM_CAPACITY))),
             ollama,
             qdrant,
+            min_memory_length,
+            dedup_threshold,
         }
     }
+
+    /// The stored memory that `embedding` duplicates, if the closest one is
+    /// at least `dedup_threshold` similar.
+    pub async fn duplicate_of(&self, embedding: &[f32]) -> anyhow::Result<Option<QueryResultItem>> {
+        let closest = self.qdrant.search_memories(embedding.to_vec(), 1).await?;
+        Ok(closest.into_iter().next().filter(|hit| hit.score >= self.dedup_threshold))
+    }
+
+    /// Why `memory` should not be stored, if it is empty or too short to be
+    /// worth recalling.
+    pub fn rejection_reason(&self, memory: &str) -> Option<String> {
+        rejection_reason(memory, self.min_memory_length)
+    }
+}
+
+fn rejection_reason(memory: &str, min_memory_length: usize) -> Option<String> {
+    let length = memory.trim().chars().count();
+    if length == 0 {
+        return Some("Memory not stored: the memory text is empty.".to_string());
+    }
+    if length < min_memory_length {
+        return Some(format!(
+            "Memory not stored: the memory text has {} characters, at least {} are required. Store a complete, self-contained fact.",
+            length, min_memory_length
+        ));
+    }
+    None
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Id of a stored memory as given by the agent, or the error result telling
+/// it where to find one.
+fn parse_memory_id(id: &str) -> Result<Uuid, CallToolResult> {
+    Uuid::parse_str(id.trim()).map_err(|_| {
+        CallToolResult::error(vec![Content::text(format!(
+            "'{}' is not a memory id. Use the 'id' of a 'query_memory' result.",
+            id
+        ))])
+    })
+}
+
+/// Embeds the `memories` worth storing and returns them with the reasons
+/// for the rejected ones. A memory is rejected when it is too short, when
+/// `duplicate_of` finds a stored memory it duplicates, or when it duplicates
+/// an earlier memory of the batch.
+async fn select_new_memories<E, EFut, D, DFut>(
+    memories: Vec<String>,
+    min_memory_length: usize,
+    dedup_threshold: f32,
+    embed: E,
+    duplicate_of: D,
+) -> Result<(Vec<MemoryItem>, Vec<String>), rmcp::Error>
+where
+    E: Fn(String) -> EFut,
+    EFut: Future<Output = anyhow::Result<Vec<f32>>>,
+    D: Fn(Vec<f32>) -> DFut,
+    DFut: Future<Output = anyhow::Result<Option<QueryResultItem>>>,
+{
+    let mut rejected = vec![];
+    let mut memory_items: Vec<MemoryItem> = vec![];
+    for memory in memories {
+        if let Some(reason) = rejection_reason(&memory, min_memory_length) {
+            println!("Rejected memory: {}", reason);
+            rejected.push(format!("{:.40}: {}", memory, reason));
+            continue;
+        }
+
+        let mut memory_item = MemoryItem::new(memory);
+        let embedding = embed(memory_item.text.clone()).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Ollama error: {}", e), None))?;
+
+        let duplicate = duplicate_of(embedding.clone()).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Qdrant search error: {}", e), None))?;
+        if let Some(existing) = duplicate {
+            println!("Suppressed duplicate of {} (score {:.3})", existing.id, existing.score);
+            rejected.push(format!("{:.40}: duplicates the stored memory '{}'", memory_item.text, existing.text));
+            continue;
+        }
+        // the batch is stored at once, so earlier items are not searchable yet
+        if let Some(earlier) = memory_items.iter().find(|m| {
+            m.embedding.as_deref().is_some_and(|e| cosine_similarity(e, &embedding) >= dedup_threshold)
+        }) {
+            rejected.push(format!("{:.40}: duplicates '{}' in the same batch", memory_item.text, earlier.text));
+            continue;
+        }
+        memory_item.embedding = Some(embedding);
+        memory_items.push(memory_item);
+    }
+    Ok((memory_items, rejected))
 }
 
 impl fmt::Display for AppState {
@@ -72,15 +176,39 @@ impl MemoryMcpService {
     }
 
 
-    #[tool(description = "Stores a memory into the agent's long-term memory. Use this to remember facts, details, or context for future recall. Provide the 'memory' to remember.")]
+    #[tool(description = "Stores a memory into the agent's long-term memory. Use this to remember facts, details, or context for future recall. Provide the 'memory' to remember. A memory that duplicates a stored one is not stored again; the response says so.")]
     pub async fn store_memory(
         &self,
         Parameters(params): Parameters<AddMemoryParams>,
     ) -> Result<CallToolResult, rmcp::Error> {
         println!("Tool 'store_memory' called with text: {:.20}...", params.memory);
+        if let Some(reason) = self.state.rejection_reason(&params.memory) {
+            println!("Rejected memory: {}", reason);
+            return Ok(CallToolResult::error(vec![Content::text(reason)]));
+        }
         let mut memory_item = MemoryItem::new(params.memory.clone());
 
-        // 1. (Optional) Add to STM (Short-Term Memory) - simple text store for now
+        // 1. Get embedding from Ollama
+        let embedding = self.state.ollama.get_embedding(&params.memory).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Ollama error: {}", e.to_string()), None))?;
+
+        // 2. Skip facts that are already stored
+        let duplicate = self.state.duplicate_of(&embedding).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Qdrant search error: {}", e.to_string()), None))?;
+        if let Some(existing) = duplicate {
+            println!("Suppressed duplicate of {} (score {:.3})", existing.id, existing.score);
+            let response = AddMemoryResponse {
+                id: existing.id,
+                status: format!("Memory not stored: it duplicates the stored memory '{}'", existing.text),
+                timestamp: existing.timestamp,
+            };
+            let response_json = serde_json::to_value(response)
+                .map_err(|e| rmcp::Error::internal_error(format!("Serealization error: {}", e.to_string()), None))?;
+            return Ok(CallToolResult::success(vec![Content::json(response_json)?]));
+        }
+        memory_item.embedding = Some(embedding);
+
+        // 3. (Optional) Add to STM (Short-Term Memory) - simple text store for now
         {
             let mut stm_guard = self.state.stm.lock().expect("STM lock poisoned");
             if stm_guard.len() >= STM_CAPACITY {
@@ -90,12 +218,7 @@ impl MemoryMcpService {
             println!("Added to STM: {}", memory_item.id);
         }
 
-        // 2. Get embedding from Ollama
-        let embedding = self.state.ollama.get_embedding(&params.memory).await
-            .map_err(|e| rmcp::Error::internal_error(format!("Ollama error: {}", e.to_string()), None))?;
-        memory_item.embedding = Some(embedding);
-
-        // 3. Store in Qdrant (LTM)
+        // 4. Store in Qdrant (LTM)
         self.state.qdrant.add_memory(&memory_item).await
             .map_err(|e| rmcp::Error::internal_error(format!("Qdrant error: {}", e.to_string()), None))?;  
         println!("Added to LTM (Qdrant): {}", memory_item.id);
@@ -112,7 +235,132 @@ impl MemoryMcpService {
         Ok(CallToolResult::success(vec![Content::json(response_json)?]))
     }
 
-    #[tool(description = "Retrieves relevant memories from the agent's long-term memory based on a natural language query. It uses semantic search to find information that is contextually similar to your query. Provide 'query_text' for your search. Optionally, specify 'top_k' for the number of results (default is 5).")]
+    #[tool(description = "Stores several memories into the agent's long-term memory at once. Use this instead of repeated 'store_memory' calls when remembering multiple facts, e.g. at the end of a conversation. Provide 'memories' as a list of self-contained facts. Duplicates of stored memories are listed under 'rejected'.")]
+    pub async fn add_memories(
+        &self,
+        Parameters(params): Parameters<AddMemoriesParams>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        println!("Tool 'add_memories' called with {} memories", params.memories.len());
+        let (memory_items, rejected) = select_new_memories(
+            params.memories,
+            self.state.min_memory_length,
+            self.state.dedup_threshold,
+            |text| async move { self.state.ollama.get_embedding(&text).await },
+            |embedding| async move { self.state.duplicate_of(&embedding).await },
+        )
+        .await?;
+
+        // one round-trip to Qdrant for the whole batch
+        self.state.qdrant.add_memories(&memory_items).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Qdrant error: {}", e.to_string()), None))?;
+        println!("Added {} memories to LTM (Qdrant)", memory_items.len());
+
+        {
+            let mut stm_guard = self.state.stm.lock().expect("STM lock poisoned");
+            for memory_item in &memory_items {
+                if stm_guard.len() >= STM_CAPACITY {
+                    stm_guard.pop_front();
+                }
+                stm_guard.push_back(memory_item.clone());
+            }
+        }
+
+        let response = AddMemoriesResponse {
+            stored: memory_items
+                .iter()
+                .map(|memory_item| AddMemoryResponse {
+                    id: memory_item.id.to_string(),
+                    status: "Memory added successfully".to_string(),
+                    timestamp: memory_item.timestamp,
+                })
+                .collect(),
+            rejected,
+        };
+
+        let response_json = serde_json::to_value(response)
+            .map_err(|e| rmcp::Error::internal_error(format!("Serealization error: {}", e.to_string()), None))?;
+
+        Ok(CallToolResult::success(vec![Content::json(response_json)?]))
+    }
+
+    #[tool(description = "Corrects a stored memory, e.g. when the user says a remembered fact is wrong or outdated. Provide the memory 'id' returned by 'query_memory' and the corrected 'memory' text; the old text is replaced.")]
+    pub async fn update_memory(
+        &self,
+        Parameters(params): Parameters<UpdateMemoryParams>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        println!("Tool 'update_memory' called for {} with text: {:.20}...", params.id, params.memory);
+        let id = match parse_memory_id(&params.id) {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+        if let Some(reason) = self.state.rejection_reason(&params.memory) {
+            println!("Rejected memory: {}", reason);
+            return Ok(CallToolResult::error(vec![Content::text(reason)]));
+        }
+
+        let mut memory_item = MemoryItem::new(params.memory.clone());
+        memory_item.id = id;
+        let embedding = self.state.ollama.get_embedding(&params.memory).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Ollama error: {}", e.to_string()), None))?;
+        memory_item.embedding = Some(embedding);
+
+        let updated = self.state.qdrant.update_memory(&memory_item).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Qdrant error: {}", e.to_string()), None))?;
+        if !updated {
+            return Ok(CallToolResult::error(vec![Content::text(format!("No memory with id {} is stored.", id))]));
+        }
+        println!("Updated in LTM (Qdrant): {}", id);
+
+        {
+            let mut stm_guard = self.state.stm.lock().expect("STM lock poisoned");
+            if let Some(stored) = stm_guard.iter_mut().find(|m| m.id == id) {
+                *stored = memory_item.clone();
+            }
+        }
+
+        let response = AddMemoryResponse {
+            id: id.to_string(),
+            status: "Memory updated successfully".to_string(),
+            timestamp: memory_item.timestamp,
+        };
+
+        let response_json = serde_json::to_value(response)
+            .map_err(|e| rmcp::Error::internal_error(format!("Serealization error: {}", e.to_string()), None))?;
+
+        Ok(CallToolResult::success(vec![Content::json(response_json)?]))
+    }
+
+    #[tool(description = "Forgets a stored memory that is wrong or no longer relevant. Provide the memory 'id' returned by 'query_memory'.")]
+    pub async fn delete_memory(
+        &self,
+        Parameters(params): Parameters<DeleteMemoryParams>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        println!("Tool 'delete_memory' called for {}", params.id);
+        let id = match parse_memory_id(&params.id) {
+            Ok(id) => id,
+            Err(e) => return Ok(e),
+        };
+
+        let deleted = self.state.qdrant.delete_memory(&id).await
+            .map_err(|e| rmcp::Error::internal_error(format!("Qdrant error: {}", e.to_string()), None))?;
+        if !deleted {
+            return Ok(CallToolResult::error(vec![Content::text(format!("No memory with id {} is stored.", id))]));
+        }
+
+        self.state.stm.lock().expect("STM lock poisoned").retain(|m| m.id != id);
+
+        let response = DeleteMemoryResponse {
+            id: id.to_string(),
+            status: "Memory deleted successfully".to_string(),
+        };
+
+        let response_json = serde_json::to_value(response)
+            .map_err(|e| rmcp::Error::internal_error(format!("Serealization error: {}", e.to_string()), None))?;
+
+        Ok(CallToolResult::success(vec![Content::json(response_json)?]))
+    }
+
+    #[tool(description = "Retrieves relevant memories from the agent's long-term memory based on a natural language query. It uses semantic search to find information that is contextually similar to your query. Provide 'query_text' for your search. Optionally, specify 'top_k' for the number of results (default is 5). Each result has its similarity 'score' and the 'timestamp' it was stored at, so newer phrasings of the same fact can be told apart, and its 'id' for 'update_memory' and 'delete_memory'.")]
     pub async fn query_memory(
         &self,
         Parameters(params): Parameters<QueryMemoryParams>,
@@ -126,8 +374,8 @@ impl MemoryMcpService {
         let search_results = self.state.qdrant.search_memories(query_embedding, params.top_k).await
             .map_err(|e| rmcp::Error::internal_error(format!("Qdrant search error: {}", e.to_string()), None))?;  
         let search_results = search_results.iter()
-            .map(|r| r.text.clone())
-            .collect::<Vec<String>>();
+            .map(MemoryResult::from)
+            .collect::<Vec<MemoryResult>>();
         let response = QueryMemoryResponse { results: search_results };
         let response_json = serde_json::to_value(response)
             .map_err(|e| rmcp::Error::internal_error(format!("Serealization error: {}", e.to_string()), None))?;  
@@ -148,8 +396,9 @@ impl ServerHandler for MemoryMcpService {
             server_info: Implementation::from_build_env(),
             instructions: Some(
                 "This service manages the agent's memory. \
-                Stores a memory into the agent's long-term memory. Use this to remember facts, details, or context for future recall. Provide the 'memory' to remember. \
-                Use the 'query_memory' tool to retrieve information from memory using a natural language query; it performs a semantic search to find the most relevant stored memories.".to_string()
+                Stores a memory into the agent's long-term memory. Use this to remember facts, details, or context for future recall. Provide the 'memory' to remember, or use 'add_memories' to store several at once. \
+                Use the 'query_memory' tool to retrieve information from memory using a natural language query; it performs a semantic search to find the most relevant stored memories. \
+                Use 'update_memory' or 'delete_memory' with the id of a query result to correct or forget a memory that is wrong or outdated.".to_string()
             ),
         }
     }
@@ -162,4 +411,133 @@ impl ServerHandler for MemoryMcpService {
         std::future::ready(())
     }
 
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn empty_and_too_short_memories_are_rejected() {
+        assert_eq!(
+            rejection_reason("ok", 10).as_deref(),
+            Some("Memory not stored: the memory text has 2 characters, at least 10 are required. Store a complete, self-contained fact.")
+        );
+        assert_eq!(rejection_reason("  \n ", 10).as_deref(), Some("Memory not stored: the memory text is empty."));
+        // characters, not bytes, and surrounding whitespace does not count
+        assert!(rejection_reason("  Študent  ", 8).is_some());
+        assert!(rejection_reason("  Študentka  ", 8).is_none());
+    }
+
+    #[test]
+    fn memory_ids_are_parsed_or_explained() {
+        assert_eq!(
+            parse_memory_id(" 6f1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d\n").unwrap(),
+            Uuid::parse_str("6f1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d").unwrap()
+        );
+
+        let err = parse_memory_id("memory 3").unwrap_err();
+        assert_eq!(err.is_error, Some(true));
+        assert_eq!(
+            err.content[0].as_text().map(|t| t.text.as_str()),
+            Some("'memory 3' is not a memory id. Use the 'id' of a 'query_memory' result.")
+        );
+    }
+
+    /// Embeds a memory as the count of each vowel in it, so equal texts get
+    /// equal vectors, and counts the embedded memories.
+    fn vowel_embedder(calls: &AtomicUsize) -> impl Fn(String) -> std::future::Ready<anyhow::Result<Vec<f32>>> + '_ {
+        move |text| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            let vector = "aeiou".chars().map(|v| text.chars().filter(|c| *c == v).count() as f32).collect();
+            std::future::ready(Ok(vector))
+        }
+    }
+
+    fn nothing_stored(_: Vec<f32>) -> std::future::Ready<anyhow::Result<Option<QueryResultItem>>> {
+        std::future::ready(Ok(None))
+    }
+
+    #[tokio::test]
+    async fn batch_embeds_every_valid_memory_once() {
+        let calls = AtomicUsize::new(0);
+        let memories = vec![
+            "The student studies computer science.".to_string(),
+            "ok".to_string(),
+            "Her supervisor is prof. Novak.".to_string(),
+        ];
+
+        let (stored, rejected) = select_new_memories(memories, 10, 0.95, vowel_embedder(&calls), nothing_stored)
+            .await
+            .unwrap();
+
+        let texts: Vec<&str> = stored.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["The student studies computer science.", "Her supervisor is prof. Novak."]);
+        assert!(stored.iter().all(|m| m.embedding.is_some()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(rejected.len(), 1);
+        assert!(rejected[0].starts_with("ok: Memory not stored: the memory text has 2 characters"));
+    }
+
+    #[tokio::test]
+    async fn repeated_fact_is_stored_once() {
+        let calls = AtomicUsize::new(0);
+        let memories = vec![
+            "The student studies computer science.".to_string(),
+            "Her supervisor is prof. Novak.".to_string(),
+            "The student studies computer science.".to_string(),
+        ];
+
+        let (stored, rejected) = select_new_memories(memories, 10, 0.95, vowel_embedder(&calls), nothing_stored)
+            .await
+            .unwrap();
+
+        assert_eq!(stored.len(), 2);
+        assert_eq!(
+            rejected,
+            ["The student studies computer science.: duplicates 'The student studies computer science.' in the same batch"]
+        );
+    }
+
+    #[tokio::test]
+    async fn fact_already_stored_is_not_stored_again() {
+        let calls = AtomicUsize::new(0);
+        let stored_fact = |_: Vec<f32>| {
+            std::future::ready(Ok(Some(QueryResultItem {
+                id: "6f1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d".to_string(),
+                text: "The student is enrolled in computer science.".to_string(),
+                timestamp: chrono::Utc::now(),
+                score: 0.97,
+                metadata: serde_json::Value::Null,
+            })))
+        };
+
+        let (stored, rejected) = select_new_memories(
+            vec!["The student studies computer science.".to_string()],
+            10,
+            0.95,
+            vowel_embedder(&calls),
+            stored_fact,
+        )
+        .await
+        .unwrap();
+
+        assert!(stored.is_empty());
+        assert_eq!(
+            rejected,
+            ["The student studies computer science.: duplicates the stored memory 'The student is enrolled in computer science.'"]
+        );
+    }
+
+    #[tokio::test]
+    async fn embedding_failure_fails_the_whole_batch() {
+        let failing = |_: String| std::future::ready(Err(anyhow::anyhow!("connection refused")));
+
+        let err = select_new_memories(vec!["The student studies computer science.".to_string()], 10, 0.95, failing, nothing_stored)
+            .await
+            .unwrap_err();
+        assert_eq!(err.message, "Ollama error: connection refused");
+    }
+}
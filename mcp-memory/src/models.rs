@@ -31,6 +31,11 @@ pub struct AddMemoryParams {
     pub memory: String,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema, Clone)]
+pub struct AddMemoriesParams {
+    pub memories: Vec<String>,
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct AddMemoryResponse {
     pub id: String, // UUID as string
@@ -38,6 +43,32 @@ pub struct AddMemoryResponse {
     pub timestamp: DateTime<Utc>,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AddMemoriesResponse {
+    pub stored: Vec<AddMemoryResponse>,
+    /// Reasons for the memories that were not stored.
+    pub rejected: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema, Clone)]
+pub struct UpdateMemoryParams {
+    /// Id of the memory, as returned by `query_memory`.
+    pub id: String,
+    pub memory: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema, Clone)]
+pub struct DeleteMemoryParams {
+    /// Id of the memory, as returned by `query_memory`.
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct DeleteMemoryResponse {
+    pub id: String,
+    pub status: String,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct QueryMemoryParams {
     pub query_text: String,
@@ -56,7 +87,57 @@ pub struct QueryResultItem {
     pub metadata: serde_json::Value,
 }
 
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct MemoryResult {
+    /// Pass to `update_memory` or `delete_memory` to correct the memory.
+    pub id: String,
+    pub text: String,
+    /// Similarity to the query, higher is closer.
+    pub score: f32,
+    /// When the memory was stored, e.g. `2025-03-14 09:30 UTC`.
+    pub timestamp: String,
+}
+
+impl From<&QueryResultItem> for MemoryResult {
+    fn from(item: &QueryResultItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            text: item.text.clone(),
+            score: item.score,
+            timestamp: item.timestamp.format("%Y-%m-%d %H:%M UTC").to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct QueryMemoryResponse {
-    pub results: Vec<String>,
-}
\ No newline at end of file
+    pub results: Vec<MemoryResult>,
+}
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn query_result_is_serialized_with_score_and_timestamp() {
+        let item = QueryResultItem {
+            id: "6f1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d".to_string(),
+            text: "The student studies computer science.".to_string(),
+            timestamp: Utc.with_ymd_and_hms(2025, 3, 14, 9, 30, 12).unwrap(),
+            score: 0.875,
+            metadata: serde_json::Value::Null,
+        };
+
+        let json = serde_json::to_value(QueryMemoryResponse { results: vec![MemoryResult::from(&item)] }).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"results": [{
+                "id": "6f1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d",
+                "text": "The student studies computer science.",
+                "score": 0.875,
+                "timestamp": "2025-03-14 09:30 UTC",
+            }]})
+        );
+    }
+}
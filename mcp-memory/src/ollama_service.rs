@@ -1,6 +1,9 @@
 // src/ollama_service.rs
+use std::sync::Arc;
+
 use reqwest::Client as HttpClient;
 use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
 use crate::config::Config; // Assuming config.rs is in the same crate root
 
 #[derive(Serialize)]
@@ -20,18 +23,31 @@ pub struct OllamaService {
     client: HttpClient,
     ollama_endpoint: String,
     embedding_model: String,
+    /// Shared by the clones, so all requests of the process together stay
+    /// under `OLLAMA_MAX_CONCURRENCY`.
+    permits: Arc<Semaphore>,
 }
 
 impl OllamaService {
     pub fn new(config: &Config) -> Self {
+        let limit = match config.ollama_max_concurrency {
+            0 => Semaphore::MAX_PERMITS,
+            limit => limit,
+        };
         Self {
             client: HttpClient::new(),
             ollama_endpoint: config.ollama_endpoint.clone(),
             embedding_model: config.embedding_model.clone(),
+            permits: Arc::new(Semaphore::new(limit)),
         }
     }
 
     pub async fn get_embedding(&self, text: &str) -> Result<Vec<f32>, anyhow::Error> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|e| anyhow::anyhow!("Ollama request failed: {}", e))?;
         let request_url = format!("{}/api/embeddings", self.ollama_endpoint);
         // println!("Requesting embedding from: {} for model: {}", request_url, self.embedding_model);
         let response = self.client
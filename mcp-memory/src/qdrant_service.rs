@@ -4,7 +4,7 @@ use std::sync::Arc;
 use qdrant_client::prelude::{Payload, QdrantClient}; 
 use qdrant_client::qdrant::point_id::PointIdOptions;
 use qdrant_client::qdrant::{
-    vectors_config, CreateCollection, Distance, PointId, PointStruct, SearchPoints, VectorParams,
+    vectors_config, Condition, CreateCollection, Distance, Filter, PointId, PointStruct, ScrollPoints, SearchPoints, VectorParams,
     VectorsConfig, // Keep Value if directly constructing qdrant::Value, not strictly needed here with serde_json conversion
 };
 use crate::config::Config;
@@ -17,12 +17,14 @@ use uuid::Uuid; // Ensure Uuid is imported
 pub struct QdrantService {
     client: Arc<QdrantClient>,
     collection_name: String,
+    embedding_model: String,
 }
 
 impl fmt::Debug for QdrantService {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("QdrantService")
             .field("collection_name", &self.collection_name)
+            .field("embedding_model", &self.embedding_model)
             .finish()
     }
 }
@@ -78,32 +80,52 @@ impl QdrantService {
             }
         }
 
-        Ok(Self {
+        let service = Self {
             client: client.into(),
             collection_name: config.qdrant_collection_name.clone(),
-        })
-    }
+            embedding_model: config.embedding_model.clone(),
+        };
 
-    pub async fn add_memory(&self, memory_item: &MemoryItem) -> Result<()> {
-        let embedding = memory_item
-            .embedding
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Embedding not found for memory item"))?;
+        if let Some(stored) = service.stored_embedding_model().await? {
+            let message = format!(
+                "Collection '{}' holds memories embedded with '{}', but EMBEDDING_MODEL is '{}'. Re-embed the memories with '{}' or set EMBEDDING_MODEL={}.",
+                service.collection_name, stored, service.embedding_model, service.embedding_model, stored
+            );
+            if !config.warn_on_model_mismatch {
+                return Err(anyhow::anyhow!(message));
+            }
+            println!("Re-embedding required: {}", message);
+        }
 
-        // Ensure metadata is included in the payload
-        let payload_json = serde_json::json!({
-            "text": memory_item.text,
-            "timestamp": memory_item.timestamp.to_rfc3339(),
-        });
+        Ok(service)
+    }
 
-        let payload: Payload = payload_json
-            .try_into()
-            .map_err(|e| anyhow::anyhow!("Failed to convert JSON to Qdrant Payload: {}", e))?;
+    /// Embedding model of a stored memory that was embedded with another
+    /// model than the configured one, if there is such a memory. Memories
+    /// stored before the model was recorded are not considered.
+    async fn stored_embedding_model(&self) -> Result<Option<String>> {
+        let request = ScrollPoints {
+            collection_name: self.collection_name.clone(),
+            filter: Some(Filter::must_not([
+                Condition::is_empty("embedding_model"),
+                Condition::matches("embedding_model", self.embedding_model.clone()),
+            ])),
+            limit: Some(1),
+            with_payload: Some(true.into()),
+            ..Default::default()
+        };
+        let response = self.client.scroll(&request).await?;
+        Ok(response
+            .result
+            .first()
+            .and_then(|point| point.payload.get("embedding_model"))
+            .and_then(|v| v.as_str())
+            .map(|m| m.to_string()))
+    }
 
-        let point_id: PointId = memory_item.id.to_string().into(); // Convert UUID string to PointId
+    pub async fn add_memory(&self, memory_item: &MemoryItem) -> Result<()> {
+        let point = self.to_point(memory_item)?;
 
-        let point = PointStruct::new(point_id, embedding.clone(), payload);
-        
         // Using upsert_points which is async. upsert_points_blocking is also async,
         // the naming can be a bit confusing but both are suitable for async contexts.
         // upsert_points is generally fine.
@@ -114,6 +136,63 @@ impl QdrantService {
         Ok(())
     }
 
+    /// Stores all `items` with a single upsert. Every item needs its
+    /// embedding; if one is missing nothing is stored.
+    pub async fn add_memories(&self, items: &[MemoryItem]) -> Result<()> {
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        let points = items
+            .iter()
+            .map(|item| self.to_point(item))
+            .collect::<Result<Vec<PointStruct>>>()?;
+
+        self.client
+            .upsert_points(self.collection_name.clone(), None, points, None)
+            .await?;
+        println!("Upserted {} points to collection '{}'", items.len(), self.collection_name);
+        Ok(())
+    }
+
+    /// Replaces the text and embedding of a stored memory, keeping its id.
+    /// Returns `false` if no memory with that id is stored.
+    pub async fn update_memory(&self, memory_item: &MemoryItem) -> Result<bool> {
+        if !self.has_memory(&memory_item.id).await? {
+            return Ok(false);
+        }
+        self.add_memory(memory_item).await?;
+        Ok(true)
+    }
+
+    /// Removes the memory with `id`. Returns `false` if no such memory is
+    /// stored.
+    pub async fn delete_memory(&self, id: &Uuid) -> Result<bool> {
+        if !self.has_memory(id).await? {
+            return Ok(false);
+        }
+
+        let point_id: PointId = id.to_string().into();
+        self.client
+            .delete_points(self.collection_name.clone(), None, &vec![point_id].into(), None)
+            .await?;
+        println!("Deleted point {} from collection '{}'", id, self.collection_name);
+        Ok(true)
+    }
+
+    async fn has_memory(&self, id: &Uuid) -> Result<bool> {
+        let point_id: PointId = id.to_string().into();
+        let response = self
+            .client
+            .get_points(self.collection_name.clone(), None, &[point_id], Some(false), Some(false), None)
+            .await?;
+        Ok(!response.result.is_empty())
+    }
+
+    fn to_point(&self, memory_item: &MemoryItem) -> Result<PointStruct> {
+        memory_point(memory_item, &self.embedding_model)
+    }
+
     pub async fn search_memories(
         &self,
         query_embedding: Vec<f32>,
@@ -197,3 +276,56 @@ impl QdrantService {
         Ok(results)
     }
 }
+
+/// Point of `memory_item` under its own id, so storing an updated memory
+/// replaces the old one.
+fn memory_point(memory_item: &MemoryItem, embedding_model: &str) -> Result<PointStruct> {
+    let embedding = memory_item
+        .embedding
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("Embedding not found for memory item"))?;
+
+    // Ensure metadata is included in the payload
+    let payload_json = serde_json::json!({
+        "text": memory_item.text,
+        "timestamp": memory_item.timestamp.to_rfc3339(),
+        "embedding_model": embedding_model,
+    });
+
+    let payload: Payload = payload_json
+        .try_into()
+        .map_err(|e| anyhow::anyhow!("Failed to convert JSON to Qdrant Payload: {}", e))?;
+
+    let point_id: PointId = memory_item.id.to_string().into(); // Convert UUID string to PointId
+
+    Ok(PointStruct::new(point_id, embedding.clone(), payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn updated_memory_keeps_its_point_id() {
+        let mut memory_item = MemoryItem::new("The student studies mathematics.".to_string());
+        memory_item.embedding = Some(vec![0.1, 0.2]);
+        let original = memory_point(&memory_item, "bge-m3").unwrap();
+
+        memory_item.text = "The student studies computer science.".to_string();
+        let updated = memory_point(&memory_item, "bge-m3").unwrap();
+
+        assert_eq!(updated.id, original.id);
+        assert_eq!(
+            updated.id.and_then(|id| id.point_id_options),
+            Some(PointIdOptions::Uuid(memory_item.id.to_string()))
+        );
+        assert_eq!(updated.payload["text"].as_str().map(String::as_str), Some("The student studies computer science."));
+        assert_eq!(updated.payload["embedding_model"].as_str().map(String::as_str), Some("bge-m3"));
+    }
+
+    #[test]
+    fn memory_without_embedding_is_not_stored() {
+        let memory_item = MemoryItem::new("The student studies mathematics.".to_string());
+        assert!(memory_point(&memory_item, "bge-m3").is_err());
+    }
+}
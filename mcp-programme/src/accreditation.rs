@@ -0,0 +1,197 @@
+//! Extractor for the accreditation of a study programme.
+//!
+//! Programme pages mention accreditation in free text ("The programme was
+//! accredited by NAKVIS on 12. 3. 2015 ..."), if at all. Feed the raw HTML
+//! into `Accreditation::parse(html, url)`, which keeps the sentences about
+//! accreditation and picks the accrediting body and dates out of them.
+
+use chrono::NaiveDate;
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+use crate::open_days::find_date;
+
+#[derive(Debug, Clone)]
+pub struct Accreditation {
+    /// Whether the page states the programme is accredited, rather than
+    /// only mentioning accreditation.
+    pub accredited: bool,
+    pub body: Option<String>,
+    pub accredited_on: Option<NaiveDate>,
+    pub valid_until: Option<NaiveDate>,
+    /// The paragraphs the details were taken from, as written on the page.
+    pub statements: Vec<String>,
+    pub source: String,
+}
+
+const KEYWORDS: [&str; 3] = ["accredit", "akredit", "nakvis"];
+
+/// Quality agencies that accredit Slovenian programmes, by the names
+/// pages use for them.
+const BODIES: [(&str, &str); 4] = [
+    ("nakvis", "Slovenian Quality Assurance Agency for Higher Education (NAKVIS)"),
+    ("slovenian quality assurance agency", "Slovenian Quality Assurance Agency for Higher Education (NAKVIS)"),
+    ("council for higher education", "Council of the Republic of Slovenia for Higher Education"),
+    ("svet rs za visoko šolstvo", "Council of the Republic of Slovenia for Higher Education"),
+];
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_about_accreditation(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    KEYWORDS.iter().any(|k| lower.contains(k))
+}
+
+/// Accrediting body named in `s`: a known agency, or else the words after
+/// "accredited by".
+fn find_body(s: &str) -> Option<String> {
+    let lower = s.to_lowercase();
+    if let Some((_, name)) = BODIES.iter().find(|(k, _)| lower.contains(k)) {
+        return Some(name.to_string());
+    }
+    let start = lower.find("accredited by ")? + "accredited by ".len();
+    let rest = s.get(start..)?;
+    let end = rest
+        .find(|c: char| matches!(c, ',' | ';' | '.' | '('))
+        .unwrap_or(rest.len());
+    let body = rest[..end]
+        .split(" on ")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_start_matches("the ");
+    (!body.is_empty()).then(|| body.to_string())
+}
+
+/// Date following one of `markers` in `s`.
+fn date_after(s: &str, markers: &[&str]) -> Option<NaiveDate> {
+    let lower = s.to_lowercase();
+    markers.iter().find_map(|m| {
+        let start = lower.find(m)? + m.len();
+        find_date(s.get(start..)?)
+    })
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+impl Accreditation {
+    /// Parses a programme page. Returns `None` when the page does not
+    /// mention accreditation.
+    pub fn parse(html: &str, url: &str) -> Option<Self> {
+        let doc = Html::parse_document(html);
+        let sel = Selector::parse("div.content p, div.content li, div.content tr").unwrap();
+
+        let mut statements: Vec<String> = vec![];
+        for el in doc.select(&sel) {
+            let line = text(&el);
+            if is_about_accreditation(&line) && !statements.iter().any(|s| s.contains(&line)) {
+                statements.push(line);
+            }
+        }
+        if statements.is_empty() {
+            return None;
+        }
+
+        let valid_markers = ["valid until", "valid to", "until", "velja do"];
+        let accredited_markers = ["accredited on", "accredited in", "accreditation on", "akreditiran dne", "akreditiran"];
+        let valid_until = statements.iter().find_map(|s| date_after(s, &valid_markers));
+        let accredited_on = statements
+            .iter()
+            .find_map(|s| date_after(s, &accredited_markers))
+            .or_else(|| statements.iter().find_map(|s| find_date(s)))
+            .filter(|d| Some(*d) != valid_until);
+
+        let accredited = statements.iter().any(|s| {
+            let lower = s.to_lowercase();
+            (lower.contains("accredited") || lower.contains("akreditiran"))
+                && !lower.contains("not accredited")
+                && !lower.contains("ni akreditiran")
+        });
+
+        Some(Self {
+            accredited,
+            body: statements.iter().find_map(|s| find_body(s)),
+            accredited_on,
+            valid_until,
+            statements,
+            source: url.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Accreditation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.accredited {
+            writeln!(f, "- **Status:** accredited")?;
+        } else {
+            writeln!(f, "- **Status:** not stated explicitly, see the statements below")?;
+        }
+        if let Some(body) = &self.body {
+            writeln!(f, "- **Accrediting body:** {}", body)?;
+        }
+        if let Some(date) = self.accredited_on {
+            writeln!(f, "- **Accredited on:** {}", date.format("%d. %m. %Y"))?;
+        }
+        if let Some(date) = self.valid_until {
+            writeln!(f, "- **Valid until:** {}", date.format("%d. %m. %Y"))?;
+        }
+        writeln!(f, "\n**As stated on the page:**")?;
+        for statement in &self.statements {
+            writeln!(f, "> {}", statement)?;
+        }
+        write!(f, "\n---\n*Source: [{}]({})*", self.source, self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAMME_PAGE: &str = r#"<html><body>
+<h1>Mathematics</h1>
+<div class="content">
+<p>The programme was accredited by NAKVIS on 12. 3. 2015.</p>
+<p>The accreditation is valid until 30. 9. 2029.</p>
+<p>The programme lasts three years.</p>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn body_and_dates_are_read_from_the_statements() {
+        let accreditation = Accreditation::parse(PROGRAMME_PAGE, "https://www.famnit.upr.si/en/mathematics").unwrap();
+
+        assert!(accreditation.accredited);
+        assert_eq!(
+            accreditation.body.as_deref(),
+            Some("Slovenian Quality Assurance Agency for Higher Education (NAKVIS)")
+        );
+        assert_eq!(accreditation.accredited_on, NaiveDate::from_ymd_opt(2015, 3, 12));
+        assert_eq!(accreditation.valid_until, NaiveDate::from_ymd_opt(2029, 9, 30));
+        assert_eq!(accreditation.statements.len(), 2);
+    }
+
+    #[test]
+    fn unknown_body_is_taken_from_the_sentence() {
+        let page = r#"<html><body><div class="content">
+<p>Accredited by the Ministry of Education, 2010.</p>
+</div></body></html>"#;
+
+        let accreditation = Accreditation::parse(page, "").unwrap();
+
+        assert_eq!(accreditation.body.as_deref(), Some("Ministry of Education"));
+        assert!(Accreditation::parse("<html><body><div class=\"content\"><p>Mathematics</p></div></body></html>", "").is_none());
+    }
+}
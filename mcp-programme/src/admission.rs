@@ -0,0 +1,345 @@
+//! Extractor for past admission score thresholds (entrance points).
+//!
+//! When enrolment is limited, the minimum (and sometimes the average)
+//! number of entrance points of the admitted candidates is published per
+//! year, either in a table ("Year | Minimum points | Average points", or one
+//! row per programme on the enrolment page) or in sentences such as "In
+//! 2023/24 the minimum number of points was 62,5". Feed the raw HTML into
+//! `AdmissionScores::parse(html, programme, url, own_page)`.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+use crate::{capacity::CapacityAudience, util::normalize_academic_year};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdmissionScore {
+    /// Academic year, normalized to `2023-2024`.
+    pub year: Option<String>,
+    pub audience: Option<CapacityAudience>,
+    pub minimum: Option<f64>,
+    pub average: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdmissionScores {
+    pub scores: Vec<AdmissionScore>,
+    pub source: String,
+}
+
+const MINIMUM_KEYWORDS: [&str; 9] = [
+    "minimum", "min.", "lowest", "threshold", "limit", "najnižj", "spodnja meja", "omejitev", "prag",
+];
+const AVERAGE_KEYWORDS: [&str; 3] = ["average", "mean", "povpreč"];
+const YEAR_KEYWORDS: [&str; 3] = ["year", "leto", "letu"];
+const POINTS_KEYWORDS: [&str; 4] = ["point", "točk", "tock", "score"];
+const ADMISSION_KEYWORDS: [&str; 8] = [
+    "enrol", "admission", "admitted", "entrance", "vpis", "sprejem", "selection", "izbir",
+];
+
+enum Column {
+    Year,
+    Audience,
+    Minimum(Option<CapacityAudience>),
+    Average(Option<CapacityAudience>),
+    Other,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn contains_any(s: &str, keywords: &[&str]) -> bool {
+    let lower = s.to_lowercase();
+    keywords.iter().any(|k| lower.contains(k))
+}
+
+fn classify(header: &str) -> Column {
+    if contains_any(header, &MINIMUM_KEYWORDS) {
+        Column::Minimum(CapacityAudience::from_label(header))
+    } else if contains_any(header, &AVERAGE_KEYWORDS) {
+        Column::Average(CapacityAudience::from_label(header))
+    } else if contains_any(header, &YEAR_KEYWORDS) {
+        Column::Year
+    } else if CapacityAudience::from_label(header).is_some() {
+        Column::Audience
+    } else {
+        Column::Other
+    }
+}
+
+fn is_year(token: &str) -> bool {
+    token.len() == 4 && token.parse::<u32>().is_ok_and(|y| (2000..2100).contains(&y))
+}
+
+/// First academic year in `s`: `2023/24`, `2023/2024`, `2023-2024` or a
+/// plain `2023`.
+fn find_year(s: &str) -> Option<String> {
+    s.split(|c: char| !(c.is_ascii_digit() || c == '/' || c == '-'))
+        .map(|t| t.trim_matches(['/', '-']))
+        .filter(|t| t.get(..4).is_some_and(is_year))
+        .find_map(normalize_academic_year)
+}
+
+/// Points written as `62`, `62,5` or `62.5`. Years, dates and ranges are
+/// not points.
+fn parse_points(token: &str) -> Option<f64> {
+    let token = token.trim_matches(|c: char| !c.is_ascii_digit());
+    if token.is_empty() || token.contains(['/', '-']) || is_year(token) {
+        return None;
+    }
+    let value: f64 = token.replace(',', ".").parse().ok()?;
+    (value <= 1000.0).then_some(value)
+}
+
+fn first_points(s: &str) -> Option<f64> {
+    s.split_whitespace().find_map(parse_points)
+}
+
+/// Points following the first of `keywords` in `s`.
+fn points_after(s: &str, keywords: &[&str]) -> Option<f64> {
+    let lower = s.to_lowercase();
+    keywords.iter().find_map(|k| {
+        let start = lower.find(k)? + k.len();
+        first_points(s.get(start..)?)
+    })
+}
+
+/// Adds a score, completing an earlier one for the same year and audience.
+fn record(scores: &mut Vec<AdmissionScore>, score: AdmissionScore) {
+    if score.minimum.is_none() && score.average.is_none() {
+        return;
+    }
+    match scores
+        .iter_mut()
+        .find(|s| s.year == score.year && s.audience == score.audience)
+    {
+        Some(existing) => {
+            existing.minimum = existing.minimum.or(score.minimum);
+            existing.average = existing.average.or(score.average);
+        }
+        None => scores.push(score),
+    }
+}
+
+fn format_points(points: Option<f64>) -> String {
+    match points {
+        Some(p) if p.fract() == 0.0 => format!("{:.0}", p),
+        Some(p) => format!("{}", p),
+        None => "–".into(),
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+fn parse_tables(doc: &Html, programme: &str, own_page: bool, scores: &mut Vec<AdmissionScore>) {
+    let table_sel = Selector::parse("table").unwrap();
+    let tr_sel = Selector::parse("tr").unwrap();
+    let cell_sel = Selector::parse("th, td").unwrap();
+    let caption_sel = Selector::parse("caption").unwrap();
+    let programme = programme.to_lowercase();
+
+    for table in doc.select(&table_sel) {
+        let rows: Vec<Vec<String>> = table
+            .select(&tr_sel)
+            .map(|tr| tr.select(&cell_sel).map(|c| text(&c)).collect())
+            .collect();
+        let Some((header, body)) = rows.split_first() else {
+            continue;
+        };
+        let columns: Vec<Column> = header.iter().map(|h| classify(h)).collect();
+        if !columns
+            .iter()
+            .any(|c| matches!(c, Column::Minimum(_) | Column::Average(_)))
+        {
+            continue;
+        }
+        let caption = table.select(&caption_sel).next().map(|c| text(&c)).unwrap_or_default();
+        let table_year = find_year(&caption).or_else(|| header.iter().find_map(|h| find_year(h)));
+
+        // one row per programme on a listing page, one row per year on the
+        // programme's own page
+        let matching: Vec<&Vec<String>> = body
+            .iter()
+            .filter(|r| {
+                r.first().is_some_and(|name| {
+                    let name = name.to_lowercase();
+                    !name.is_empty() && (name.contains(&programme) || programme.contains(&name))
+                })
+            })
+            .collect();
+        let rows: Vec<&Vec<String>> = if !matching.is_empty() {
+            matching
+        } else if own_page {
+            body.iter().collect()
+        } else {
+            continue;
+        };
+
+        for row in rows {
+            let mut year = table_year.clone();
+            let mut row_audience = None;
+            for (column, cell) in columns.iter().zip(row) {
+                match column {
+                    Column::Year => year = find_year(cell).or(year),
+                    Column::Audience => row_audience = CapacityAudience::from_label(cell),
+                    _ => {}
+                }
+            }
+            for (column, cell) in columns.iter().zip(row) {
+                let (audience, minimum, average) = match column {
+                    Column::Minimum(audience) => (audience.or(row_audience), first_points(cell), None),
+                    Column::Average(audience) => (audience.or(row_audience), None, first_points(cell)),
+                    _ => continue,
+                };
+                record(scores, AdmissionScore { year: year.clone(), audience, minimum, average });
+            }
+        }
+    }
+}
+
+fn parse_sentences(doc: &Html, scores: &mut Vec<AdmissionScore>) {
+    let sel = Selector::parse("div.content p, div.content li").unwrap();
+    for el in doc.select(&sel) {
+        let line = text(&el);
+        let is_about_scores = contains_any(&line, &POINTS_KEYWORDS)
+            && contains_any(&line, &ADMISSION_KEYWORDS)
+            && (contains_any(&line, &MINIMUM_KEYWORDS) || contains_any(&line, &AVERAGE_KEYWORDS))
+            && !contains_any(&line, &["ects", "credit", "kreditn"]);
+        if !is_about_scores {
+            continue;
+        }
+        let line_year = find_year(&line);
+        for sentence in line.split(". ") {
+            // "minimum 62,5 points (Slovenian and EU citizens); 55 points (non-EU citizens)"
+            let mut minimum_listed = false;
+            for part in sentence.split(';') {
+                let mut minimum = points_after(part, &MINIMUM_KEYWORDS);
+                let average = points_after(part, &AVERAGE_KEYWORDS);
+                if minimum.is_none() && average.is_none() && minimum_listed {
+                    minimum = first_points(part);
+                }
+                minimum_listed = minimum.is_some() && average.is_none();
+                record(
+                    scores,
+                    AdmissionScore {
+                        year: find_year(part).or_else(|| line_year.clone()),
+                        audience: CapacityAudience::from_label(part),
+                        minimum,
+                        average,
+                    },
+                );
+            }
+        }
+    }
+}
+
+impl AdmissionScores {
+    /// Scores published for `programme` on the page at `url`, newest year
+    /// first, or `None` if the page publishes none. Rows not naming the
+    /// programme are only read on the programme's `own_page`.
+    pub fn parse(html: &str, programme: &str, url: &str, own_page: bool) -> Option<Self> {
+        let doc = Html::parse_document(html);
+        let mut scores = vec![];
+        parse_tables(&doc, programme, own_page, &mut scores);
+        if scores.is_empty() && own_page {
+            parse_sentences(&doc, &mut scores);
+        }
+        if scores.is_empty() {
+            return None;
+        }
+        scores.sort_by(|a, b| b.year.cmp(&a.year));
+        Some(Self { scores, source: url.to_string() })
+    }
+}
+
+impl fmt::Display for AdmissionScores {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "| Academic year | Audience | Minimum points | Average points |")?;
+        writeln!(f, "|---|---|---|---|")?;
+        for score in &self.scores {
+            writeln!(
+                f,
+                "| {} | {} | {} | {} |",
+                score.year.as_deref().unwrap_or("not stated"),
+                score.audience.map(|a| a.to_string()).unwrap_or_else(|| "All candidates".into()),
+                format_points(score.minimum),
+                format_points(score.average),
+            )?;
+        }
+        write!(f, "\n*Source: [{}]({})*", self.source, self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWN_PAGE_TABLE: &str = r#"<html><body><div class="content">
+<table>
+<tr><th>Academic year</th><th>Minimum points</th><th>Average points</th></tr>
+<tr><td>2022/23</td><td>60</td><td>70,5</td></tr>
+<tr><td>2023/24</td><td>62,5</td><td>71</td></tr>
+</table>
+</div></body></html>"#;
+
+    const LISTING_TABLE: &str = r#"<html><body>
+<table>
+<caption>Enrolment 2024/25</caption>
+<tr><th>Programme</th><th>Minimum points (Slovenian and EU citizens)</th><th>Minimum points (non-EU citizens)</th></tr>
+<tr><td>Mathematics</td><td>55</td><td>40</td></tr>
+<tr><td>Computer Science</td><td>80</td><td>60</td></tr>
+</table>
+</body></html>"#;
+
+    #[test]
+    fn own_page_rows_are_read_per_year_newest_first() {
+        let scores = AdmissionScores::parse(OWN_PAGE_TABLE, "Mathematics", "https://www.famnit.upr.si/en/mathematics", true).unwrap();
+
+        let years: Vec<Option<&str>> = scores.scores.iter().map(|s| s.year.as_deref()).collect();
+        assert_eq!(years, [Some("2023-2024"), Some("2022-2023")]);
+        assert_eq!(scores.scores[0].minimum, Some(62.5));
+        assert_eq!(scores.scores[1].average, Some(70.5));
+        assert!(scores.to_string().contains("| 2023-2024 | All candidates | 62.5 | 71 |"));
+    }
+
+    #[test]
+    fn listing_page_reads_only_the_programme_row() {
+        let scores = AdmissionScores::parse(LISTING_TABLE, "Computer Science", "", false).unwrap();
+
+        let minimums: Vec<(Option<CapacityAudience>, Option<f64>)> =
+            scores.scores.iter().map(|s| (s.audience, s.minimum)).collect();
+        assert_eq!(
+            minimums,
+            [(Some(CapacityAudience::Slovenian), Some(80.0)), (Some(CapacityAudience::NonEu), Some(60.0))]
+        );
+        assert_eq!(scores.scores[0].year.as_deref(), Some("2024-2025"));
+        assert!(AdmissionScores::parse(LISTING_TABLE, "Biology", "", false).is_none());
+    }
+
+    #[test]
+    fn sentence_on_the_programme_page() {
+        let page = r#"<html><body><div class="content">
+<p>In 2023/24 the minimum number of points for enrolment was 62,5.</p>
+<p>The programme has 180 ECTS credits.</p>
+</div></body></html>"#;
+
+        let scores = AdmissionScores::parse(page, "Mathematics", "", true).unwrap();
+
+        assert_eq!(
+            scores.scores,
+            [AdmissionScore { year: Some("2023-2024".into()), audience: None, minimum: Some(62.5), average: None }]
+        );
+    }
+}
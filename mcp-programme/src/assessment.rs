@@ -0,0 +1,264 @@
+//! Extractor for the assessment methods of a single course.
+//!
+//! Course names in the course tables of a programme page link to their
+//! course detail pages. Collect those links with `course_links(html)`, then
+//! feed a detail page into `CourseAssessment::parse(html, url)`, which reads
+//! the list, table or paragraphs under the assessment heading.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct AssessmentMethod {
+    pub method: String,
+    /// Share of the final grade as written on the page ("50 %").
+    pub weight: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CourseAssessment {
+    pub course: String,
+    pub url: String,
+    pub methods: Vec<AssessmentMethod>,
+    /// Paragraphs under the assessment heading that are not a method of
+    /// their own, e.g. conditions for taking the exam.
+    pub notes: Vec<String>,
+}
+
+const HEADING_KEYWORDS: [&str; 5] = [
+    "assessment",
+    "grading",
+    "examination",
+    "preverjanje",
+    "ocenjevanje",
+];
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_assessment_heading(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    s.len() < 80 && HEADING_KEYWORDS.iter().any(|k| lower.contains(k))
+}
+
+/// Percentage in `s`, written as `50 %`, `50%` or `50 odstotkov`.
+fn find_weight(s: &str) -> Option<String> {
+    let tokens: Vec<&str> = s.split_whitespace().collect();
+    tokens.iter().enumerate().find_map(|(i, token)| {
+        let token = token.trim_matches(|c: char| matches!(c, '(' | ')' | ',' | ';' | ':'));
+        if let Some(number) = token.strip_suffix('%') {
+            return number.parse::<f64>().is_ok().then(|| format!("{} %", number));
+        }
+        let next = tokens.get(i + 1).map(|t| t.trim_matches(|c: char| matches!(c, '(' | ')' | ',' | ';')));
+        (token.parse::<f64>().is_ok() && next.is_some_and(|n| n == "%" || n.starts_with("odstot")))
+            .then(|| format!("{} %", token))
+    })
+}
+
+/// Splits a line such as "Written exam: 60 %" or "Project (40%)" into the
+/// method and its weight.
+fn method(line: &str) -> AssessmentMethod {
+    let weight = find_weight(line);
+    let method = match &weight {
+        Some(_) => line
+            .split(|c: char| c.is_ascii_digit())
+            .next()
+            .unwrap_or(line)
+            .trim_end_matches(|c: char| matches!(c, ' ' | ':' | '(' | '–' | '-' | ','))
+            .to_string(),
+        None => line.to_string(),
+    };
+    AssessmentMethod {
+        method: if method.is_empty() { line.to_string() } else { method },
+        weight,
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parsers                                                         */
+/* --------------------------------------------------------------------- */
+
+/// `(course name, detail page url)` pairs linked from the course tables of
+/// a programme page.
+pub fn course_links(html: &str) -> Vec<(String, String)> {
+    let doc = Html::parse_document(html);
+    let a_sel = Selector::parse("table td a[href]").unwrap();
+
+    let mut links: Vec<(String, String)> = vec![];
+    for a in doc.select(&a_sel) {
+        let Some(href) = a.value().attr("href") else {
+            continue;
+        };
+        let name = text(&a);
+        if !href.starts_with("http") || href.ends_with(".pdf") || name.is_empty() {
+            continue;
+        }
+        if !links.iter().any(|(n, _)| *n == name) {
+            links.push((name, href.to_string()));
+        }
+    }
+    links
+}
+
+impl CourseAssessment {
+    /// Parses a course detail page. Returns `None` when the page has no
+    /// assessment heading or nothing follows it.
+    pub fn parse(html: &str, url: &str) -> Option<Self> {
+        let doc = Html::parse_document(html);
+        let h1_sel = Selector::parse("h1").unwrap();
+        let heading_sel = Selector::parse("h2, h3, h4, strong, b, dt, th").unwrap();
+        let item_sel = Selector::parse("li, tr").unwrap();
+        let cell_sel = Selector::parse("td, th").unwrap();
+
+        let course = doc.select(&h1_sel).next().map(|h| text(&h)).unwrap_or_default();
+
+        let heading = doc.select(&heading_sel).find(|h| is_assessment_heading(&text(h)))?;
+        // Table and definition-list labels are followed by their value cell;
+        // inline labels (<strong>, <b>) sit inside the block they introduce.
+        let (anchor, stops): (ElementRef, &[&str]) = match heading.value().name() {
+            "th" | "dt" => (heading, &["th", "dt"]),
+            "strong" | "b" => (
+                heading
+                    .parent()
+                    .and_then(ElementRef::wrap)
+                    .filter(|p| text(p) == text(&heading))
+                    .unwrap_or(heading),
+                &["h1", "h2", "h3", "h4"],
+            ),
+            _ => (heading, &["h1", "h2", "h3", "h4"]),
+        };
+
+        let mut methods: Vec<AssessmentMethod> = vec![];
+        let mut notes: Vec<String> = vec![];
+        for sibling in anchor.next_siblings().filter_map(ElementRef::wrap) {
+            if stops.contains(&sibling.value().name()) {
+                break;
+            }
+            let line = text(&sibling);
+            if line.is_empty() {
+                continue;
+            }
+            // a paragraph that is just a bold label starts the next section
+            if sibling.select(&heading_sel).next().is_some_and(|b| text(&b) == line) {
+                break;
+            }
+            let items: Vec<String> = sibling
+                .select(&item_sel)
+                .map(|item| match item.value().name() {
+                    "tr" => item.select(&cell_sel).map(|c| text(&c)).collect::<Vec<_>>().join(" "),
+                    _ => text(&item),
+                })
+                .filter(|l| !l.is_empty())
+                .collect();
+            if !items.is_empty() {
+                methods.extend(items.iter().map(|l| method(l)));
+            } else if find_weight(&line).is_some() {
+                methods.extend(line.split(';').map(str::trim).filter(|l| !l.is_empty()).map(method));
+            } else {
+                notes.push(line);
+            }
+        }
+        // an inline label's own paragraph may carry the text ("Assessment: exam 100 %")
+        let inline = matches!(heading.value().name(), "strong" | "b");
+        if inline && methods.is_empty() && notes.is_empty() {
+            let own = heading.parent().and_then(ElementRef::wrap).map(|p| text(&p)).unwrap_or_default();
+            let rest = own.split_once(':').map(|(_, r)| r.trim()).unwrap_or_default();
+            methods.extend(rest.split(';').map(str::trim).filter(|l| !l.is_empty()).map(method));
+        }
+
+        (!methods.is_empty() || !notes.is_empty()).then(|| Self {
+            course,
+            url: url.to_string(),
+            methods,
+            notes,
+        })
+    }
+}
+
+impl fmt::Display for CourseAssessment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Assessment: {}\n", self.course)?;
+        for m in &self.methods {
+            match &m.weight {
+                Some(weight) => writeln!(f, "- {} – {}", m.method, weight)?,
+                None => writeln!(f, "- {}", m.method)?,
+            }
+        }
+        if !self.notes.is_empty() {
+            if !self.methods.is_empty() {
+                writeln!(f)?;
+            }
+            for note in &self.notes {
+                writeln!(f, "{}\n", note)?;
+            }
+        }
+        write!(f, "\n---\n*Source: [{}]({})*", self.url, self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COURSE_PAGE: &str = r#"<html><body>
+<h1>Programming I</h1>
+<h2>Assessment</h2>
+<ul>
+<li>Written exam: 60 %</li>
+<li>Homework assignments (40%)</li>
+</ul>
+<p>Students must pass the homework to take the exam.</p>
+<h2>Literature</h2>
+<ul><li>The C Programming Language</li></ul>
+</body></html>"#;
+
+    #[test]
+    fn methods_and_notes_stop_at_the_next_heading() {
+        let assessment = CourseAssessment::parse(COURSE_PAGE, "https://www.famnit.upr.si/en/courses/programming-i").unwrap();
+
+        assert_eq!(assessment.course, "Programming I");
+        let methods: Vec<(&str, Option<&str>)> = assessment
+            .methods
+            .iter()
+            .map(|m| (m.method.as_str(), m.weight.as_deref()))
+            .collect();
+        assert_eq!(methods, [("Written exam", Some("60 %")), ("Homework assignments", Some("40 %"))]);
+        assert_eq!(assessment.notes, ["Students must pass the homework to take the exam."]);
+    }
+
+    #[test]
+    fn inline_label_and_missing_heading() {
+        let inline = r#"<html><body><h1>Algorithms</h1>
+<p><strong>Assessment:</strong> written exam 70 %; project 30 %</p></body></html>"#;
+
+        let assessment = CourseAssessment::parse(inline, "https://www.famnit.upr.si/en/courses/algorithms").unwrap();
+
+        assert_eq!(assessment.methods.len(), 2);
+        assert_eq!(assessment.methods[1].method, "project");
+        assert!(CourseAssessment::parse("<html><body><h1>Algorithms</h1></body></html>", "").is_none());
+    }
+
+    #[test]
+    fn course_links_skip_pdfs_and_duplicates() {
+        let table = r#"<table>
+<tr><td><a href="https://www.famnit.upr.si/en/courses/programming-i">Programming I</a></td></tr>
+<tr><td><a href="https://www.famnit.upr.si/en/courses/programming-i?lang=en">Programming I</a></td></tr>
+<tr><td><a href="https://www.famnit.upr.si/syllabus.pdf">Syllabus</a></td></tr>
+</table>"#;
+
+        assert_eq!(
+            course_links(table),
+            [("Programming I".to_string(), "https://www.famnit.upr.si/en/courses/programming-i".to_string())]
+        );
+    }
+}
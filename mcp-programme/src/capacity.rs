@@ -0,0 +1,203 @@
+//! Extractor for the number of available places (razpisana mesta).
+//!
+//! Places are published either in a table with one column per audience
+//! (one row per programme on the enrolment page) or as sentences such as
+//! "Slovenian and EU citizens: 40 places" on the programme page. Feed the
+//! raw HTML into `parse_capacity(html, programme)`.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CapacityAudience {
+    Slovenian,
+    Eu,
+    NonEu,
+}
+
+impl CapacityAudience {
+    /// Audience named by a column header or sentence, checked from the most
+    /// specific wording to the least.
+    pub fn from_label(label: &str) -> Option<Self> {
+        let l = label.to_lowercase();
+        if l.contains("non-eu") || l.contains("non eu") || l.contains("third countr") || l.contains("tujci") {
+            Some(Self::NonEu)
+        } else if l.contains("slovenian") || l.contains("slovenci") || l.contains("državljani rs") {
+            Some(Self::Slovenian)
+        } else if l.split(|c: char| !c.is_alphanumeric()).any(|w| w == "eu") {
+            Some(Self::Eu)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for CapacityAudience {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapacityAudience::Slovenian => write!(f, "Slovenian citizens"),
+            CapacityAudience::Eu => write!(f, "EU citizens"),
+            CapacityAudience::NonEu => write!(f, "Non-EU citizens"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgrammeCapacity {
+    pub places: Vec<(CapacityAudience, u32)>,
+    /// Label of the column or sentence each count was read from.
+    pub labels: Vec<String>,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn first_number(s: &str) -> Option<u32> {
+    s.split(|c: char| !c.is_ascii_digit())
+        .find(|d| !d.is_empty())
+        .and_then(|d| d.parse().ok())
+}
+
+fn push_count(capacity: &mut ProgrammeCapacity, audience: CapacityAudience, count: u32, label: String) {
+    if !capacity.places.iter().any(|(a, _)| *a == audience) {
+        capacity.places.push((audience, count));
+        capacity.labels.push(label);
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+fn parse_tables(doc: &Html, programme: &str) -> ProgrammeCapacity {
+    let table_sel = Selector::parse("table").unwrap();
+    let tr_sel = Selector::parse("tr").unwrap();
+    let cell_sel = Selector::parse("th, td").unwrap();
+    let programme = programme.to_lowercase();
+
+    let mut capacity = ProgrammeCapacity { places: vec![], labels: vec![] };
+    for table in doc.select(&table_sel) {
+        let rows: Vec<Vec<String>> = table
+            .select(&tr_sel)
+            .map(|tr| tr.select(&cell_sel).map(|c| text(&c)).collect())
+            .collect();
+        let Some((header, body)) = rows.split_first() else {
+            continue;
+        };
+        let columns: Vec<(usize, CapacityAudience)> = header
+            .iter()
+            .enumerate()
+            .filter_map(|(i, h)| CapacityAudience::from_label(h).map(|a| (i, a)))
+            .collect();
+        if columns.is_empty() {
+            continue;
+        }
+
+        // one row per programme, or a single row on the programme's own page
+        let row = body
+            .iter()
+            .find(|r| {
+                r.first().is_some_and(|name| {
+                    let name = name.to_lowercase();
+                    !name.is_empty() && (name.contains(&programme) || programme.contains(&name))
+                })
+            })
+            .or_else(|| (body.len() == 1).then(|| &body[0]));
+        let Some(row) = row else {
+            continue;
+        };
+
+        for (i, audience) in columns {
+            if let Some(count) = row.get(i).and_then(|c| first_number(c)) {
+                push_count(&mut capacity, audience, count, header[i].clone());
+            }
+        }
+    }
+    capacity
+}
+
+fn parse_sentences(doc: &Html, capacity: &mut ProgrammeCapacity) {
+    let sel = Selector::parse("div.content p, div.content li").unwrap();
+    for el in doc.select(&sel) {
+        let line = text(&el);
+        let lower = line.to_lowercase();
+        if !(lower.contains("place") || lower.contains("mest")) {
+            continue;
+        }
+        // "Slovenian and EU citizens: 40 places; non-EU citizens: 10 places"
+        for part in line.split(';') {
+            let (Some(audience), Some(count)) = (CapacityAudience::from_label(part), first_number(part)) else {
+                continue;
+            };
+            push_count(capacity, audience, count, part.trim().to_string());
+        }
+    }
+}
+
+/// Places per audience for `programme`, or `None` if the page does not
+/// publish them.
+pub fn parse_capacity(html: &str, programme: &str) -> Option<ProgrammeCapacity> {
+    let doc = Html::parse_document(html);
+    let mut capacity = parse_tables(&doc, programme);
+    if capacity.places.is_empty() {
+        parse_sentences(&doc, &mut capacity);
+    }
+    (!capacity.places.is_empty()).then_some(capacity)
+}
+
+impl fmt::Display for ProgrammeCapacity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "| Audience | Places | As published |")?;
+        writeln!(f, "|---|---|---|")?;
+        for ((audience, count), label) in self.places.iter().zip(&self.labels) {
+            writeln!(f, "| {} | {} | {} |", audience, count, label.replace('|', "\\|"))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENROLMENT_TABLE_PAGE: &str = r#"<html><body>
+<table>
+<tr><th>Programme</th><th>Slovenian citizens</th><th>EU citizens</th><th>Non-EU citizens</th></tr>
+<tr><td>Mathematics</td><td>40</td><td>5</td><td>10</td></tr>
+<tr><td>Computer Science</td><td>70</td><td>10</td><td>20 places</td></tr>
+</table>
+</body></html>"#;
+
+    const PROGRAMME_SENTENCE_PAGE: &str = r#"<html><body><div class="content">
+<p>Number of places: Slovenian and EU citizens: 40 places; non-EU citizens: 10 places</p>
+</div></body></html>"#;
+
+    #[test]
+    fn table_row_of_the_programme_is_read_per_column() {
+        let capacity = parse_capacity(ENROLMENT_TABLE_PAGE, "Computer Science").unwrap();
+
+        assert_eq!(
+            capacity.places,
+            [(CapacityAudience::Slovenian, 70), (CapacityAudience::Eu, 10), (CapacityAudience::NonEu, 20)]
+        );
+        assert_eq!(capacity.labels[2], "Non-EU citizens");
+    }
+
+    #[test]
+    fn sentences_are_used_when_there_is_no_table() {
+        let capacity = parse_capacity(PROGRAMME_SENTENCE_PAGE, "Mathematics").unwrap();
+
+        assert_eq!(capacity.places, [(CapacityAudience::Slovenian, 40), (CapacityAudience::NonEu, 10)]);
+        assert!(parse_capacity("<html><body><p>No places yet.</p></body></html>", "Mathematics").is_none());
+    }
+}
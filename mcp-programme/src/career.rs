@@ -0,0 +1,220 @@
+//! Extractor for published career outcomes of graduates.
+//!
+//! Employment rates, time to first job and salaries are published, if at
+//! all, as sentences or table rows that carry a number, e.g. "87 % of our
+//! graduates find a job within six months" or "Average starting salary |
+//! 1.450 EUR". Feed each page into `parse_career_statistics(html, url)`,
+//! which keeps the lines that pair an outcome keyword with a percentage, an
+//! amount of money or a duration.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CareerMetric {
+    EmploymentRate,
+    TimeToEmployment,
+    Salary,
+}
+
+impl CareerMetric {
+    const ALL: [CareerMetric; 3] = [
+        CareerMetric::EmploymentRate,
+        CareerMetric::TimeToEmployment,
+        CareerMetric::Salary,
+    ];
+}
+
+impl fmt::Display for CareerMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CareerMetric::EmploymentRate => write!(f, "Employment rate"),
+            CareerMetric::TimeToEmployment => write!(f, "Time to employment"),
+            CareerMetric::Salary => write!(f, "Salary"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CareerStatistic {
+    pub metric: CareerMetric,
+    /// The figure as written, e.g. "87 %" or "1.450 EUR".
+    pub figure: String,
+    /// Sentence or table row the figure was found in.
+    pub text: String,
+    pub source: String,
+}
+
+const EMPLOYMENT_KEYWORDS: [&str; 4] = ["employ", "job", "zaposl", "career"];
+const SALARY_KEYWORDS: [&str; 6] = ["salary", "salaries", "wage", "income", "plač", "earn"];
+const PERCENT_WORDS: [&str; 3] = ["%", "percent", "odstot"];
+const CURRENCY_WORDS: [&str; 3] = ["€", "eur", "euro"];
+const DURATION_WORDS: [&str; 4] = ["month", "mesec", "week", "tedn"];
+/// Lines longer than this are prose about careers, not a statistic.
+const MAX_LINE_CHARS: usize = 400;
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn contains_any(haystack: &str, needles: &[&str]) -> bool {
+    let haystack = haystack.to_lowercase();
+    needles.iter().any(|n| haystack.contains(n))
+}
+
+fn starts_with_any(word: &str, prefixes: &[&str]) -> bool {
+    let word = word.to_lowercase();
+    prefixes.iter().any(|p| word.starts_with(p))
+}
+
+/// First number in `line` with a unit from `units` glued to it, written
+/// after it, or written right before it (as in "EUR 1.450"), returned
+/// together with the unit.
+fn figure_with(line: &str, units: &[&str]) -> Option<String> {
+    let words: Vec<&str> = line
+        .split_whitespace()
+        .map(|w| w.trim_end_matches(['.', ',', ';', ':', ')']))
+        .collect();
+    for (i, word) in words.iter().enumerate() {
+        let number = word.trim_matches(|c: char| !c.is_ascii_digit());
+        if number.is_empty() {
+            continue;
+        }
+        let lower = word.to_lowercase();
+        if units.iter().any(|u| lower.ends_with(u) || lower.starts_with(u)) {
+            return Some(word.to_string());
+        }
+        if let Some(next) = words.get(i + 1).filter(|next| starts_with_any(next, units)) {
+            return Some(format!("{} {}", number, next));
+        }
+        if let Some(prev) = i.checked_sub(1).map(|j| words[j]).filter(|prev| units.contains(&prev.to_lowercase().as_str())) {
+            return Some(format!("{} {}", prev, number));
+        }
+    }
+    None
+}
+
+fn classify(line: &str) -> Option<(CareerMetric, String)> {
+    if contains_any(line, &SALARY_KEYWORDS) {
+        if let Some(figure) = figure_with(line, &CURRENCY_WORDS) {
+            return Some((CareerMetric::Salary, figure));
+        }
+    }
+    if !contains_any(line, &EMPLOYMENT_KEYWORDS) {
+        return None;
+    }
+    if let Some(figure) = figure_with(line, &PERCENT_WORDS) {
+        return Some((CareerMetric::EmploymentRate, figure));
+    }
+    figure_with(line, &DURATION_WORDS).map(|figure| (CareerMetric::TimeToEmployment, figure))
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+/// Career statistics in the paragraphs, list items and table rows of one
+/// page's content.
+pub fn parse_career_statistics(html: &str, url: &str) -> Vec<CareerStatistic> {
+    let doc = Html::parse_document(html);
+    let sel = Selector::parse("div.content p, div.content li, div.content tr").unwrap();
+    let cell_sel = Selector::parse("th, td").unwrap();
+
+    let mut statistics: Vec<CareerStatistic> = vec![];
+    for el in doc.select(&sel) {
+        let line = match el.value().name() {
+            "tr" => el.select(&cell_sel).map(|c| text(&c)).collect::<Vec<_>>().join(" | "),
+            _ => text(&el),
+        };
+        if line.is_empty() || line.chars().count() > MAX_LINE_CHARS {
+            continue;
+        }
+        if statistics.iter().any(|s| s.text == line) {
+            continue;
+        }
+        if let Some((metric, figure)) = classify(&line) {
+            statistics.push(CareerStatistic {
+                metric,
+                figure,
+                text: line,
+                source: url.to_string(),
+            });
+        }
+    }
+    statistics
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl fmt::Display for CareerStatistic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "- **{}** – {} ([source]({}))", self.figure, self.text, self.source)
+    }
+}
+
+/// Statistics grouped under one heading per metric.
+pub fn render_statistics(statistics: &[CareerStatistic]) -> String {
+    let mut out = String::new();
+    for metric in CareerMetric::ALL {
+        let lines: Vec<String> = statistics
+            .iter()
+            .filter(|s| s.metric == metric)
+            .map(|s| s.to_string())
+            .collect();
+        if !lines.is_empty() {
+            out.push_str(&format!("## {}\n\n{}\n\n", metric, lines.join("\n")));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAREER_PAGE: &str = r#"<html><body><div class="content">
+<p>87 % of our graduates find a job within six months.</p>
+<p>Most graduates are employed within 3 months of graduation.</p>
+<table>
+<tr><th>Average starting salary</th><td>1.450 EUR</td></tr>
+</table>
+<p>Our career centre helps students find internships.</p>
+</div></body></html>"#;
+
+    #[test]
+    fn lines_with_an_outcome_and_a_figure_are_kept() {
+        let statistics = parse_career_statistics(CAREER_PAGE, "https://www.famnit.upr.si/en/career");
+
+        let found: Vec<(CareerMetric, &str)> = statistics.iter().map(|s| (s.metric, s.figure.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                (CareerMetric::EmploymentRate, "87 %"),
+                (CareerMetric::TimeToEmployment, "3 months"),
+                (CareerMetric::Salary, "1.450 EUR"),
+            ]
+        );
+        assert_eq!(statistics[2].text, "Average starting salary | 1.450 EUR");
+    }
+
+    #[test]
+    fn statistics_are_grouped_by_metric() {
+        let statistics = parse_career_statistics(CAREER_PAGE, "https://www.famnit.upr.si/en/career");
+
+        let markdown = render_statistics(&statistics);
+
+        assert!(markdown.starts_with("## Employment rate\n\n- **87 %**"));
+        assert!(markdown.contains("## Salary\n\n- **1.450 EUR** – Average starting salary | 1.450 EUR"));
+    }
+}
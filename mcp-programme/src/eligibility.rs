@@ -0,0 +1,196 @@
+//! Rule-based check of an applicant description against the admission
+//! requirements parsed from a programme page.
+//!
+//! Only things that can be read off both texts are decided (ECTS credits,
+//! study cycle, CEFR language level). Everything else is reported as
+//! `Unclear` rather than guessed.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EligibilityStatus {
+    Met,
+    Unmet,
+    Unclear,
+}
+
+impl fmt::Display for EligibilityStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EligibilityStatus::Met => write!(f, "met"),
+            EligibilityStatus::Unmet => write!(f, "unmet"),
+            EligibilityStatus::Unclear => write!(f, "unclear"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RequirementAssessment {
+    pub requirement: String,
+    pub status: EligibilityStatus,
+    pub reason: String,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Feature extraction                                                   */
+/* --------------------------------------------------------------------- */
+
+/// ECTS amounts mentioned in a text ("180 ECTS", "240 ECTS credits").
+fn ects_amounts(s: &str) -> Vec<u32> {
+    let words: Vec<&str> = s.split_whitespace().collect();
+    words
+        .windows(2)
+        .filter(|w| w[1].to_lowercase().starts_with("ects"))
+        .filter_map(|w| w[0].trim_matches(|c: char| !c.is_ascii_digit()).parse().ok())
+        .collect()
+}
+
+/// ECTS implied by the applicant's degree, either stated or derived from its
+/// length (one study year = 60 ECTS).
+fn applicant_ects(applicant: &str) -> Option<u32> {
+    if let Some(ects) = ects_amounts(applicant).into_iter().max() {
+        return Some(ects);
+    }
+    let lower = applicant.to_lowercase();
+    let years = [("3-year", 3), ("three-year", 3), ("3 year", 3), ("4-year", 4), ("four-year", 4), ("4 year", 4), ("2-year", 2), ("two-year", 2), ("2 year", 2)];
+    years
+        .iter()
+        .find(|(k, _)| lower.contains(k))
+        .map(|(_, y)| y * 60)
+}
+
+/// Highest completed study cycle: 0 secondary, 1 bachelor, 2 master, 3 doctoral.
+fn study_cycle(s: &str) -> Option<u8> {
+    let l = s.to_lowercase();
+    if l.contains("doctoral") || l.contains("phd") || l.contains("third-cycle") {
+        Some(3)
+    } else if l.contains("master") || l.contains("second-cycle") || l.contains("msc") {
+        Some(2)
+    } else if l.contains("bachelor") || l.contains("first-cycle") || l.contains("undergraduate") || l.contains("bsc") {
+        Some(1)
+    } else if l.contains("secondary school") || l.contains("high school") || l.contains("matura") {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+const CEFR_LEVELS: [&str; 6] = ["a1", "a2", "b1", "b2", "c1", "c2"];
+
+fn cefr_level(s: &str) -> Option<usize> {
+    s.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(|w| CEFR_LEVELS.iter().position(|l| *l == w))
+        .max()
+}
+
+/* --------------------------------------------------------------------- */
+/*  Assessment                                                           */
+/* --------------------------------------------------------------------- */
+
+fn assess_requirement(requirement: &str, applicant: &str) -> RequirementAssessment {
+    let assessment = |status, reason: String| RequirementAssessment {
+        requirement: requirement.to_string(),
+        status,
+        reason,
+    };
+
+    if let Some(required) = ects_amounts(requirement).into_iter().min() {
+        return match applicant_ects(applicant) {
+            Some(has) if has >= required => assessment(
+                EligibilityStatus::Met,
+                format!("The applicant's degree corresponds to {} ECTS; {} ECTS are required.", has, required),
+            ),
+            Some(has) => assessment(
+                EligibilityStatus::Unmet,
+                format!("The applicant's degree corresponds to {} ECTS; {} ECTS are required.", has, required),
+            ),
+            None => assessment(
+                EligibilityStatus::Unclear,
+                format!("{} ECTS are required, but the description does not state the ECTS or length of the applicant's degree.", required),
+            ),
+        };
+    }
+
+    if let Some(required) = cefr_level(requirement) {
+        return match cefr_level(applicant) {
+            Some(has) if has >= required => assessment(
+                EligibilityStatus::Met,
+                format!("Language level {} meets the required {}.", CEFR_LEVELS[has].to_uppercase(), CEFR_LEVELS[required].to_uppercase()),
+            ),
+            Some(has) => assessment(
+                EligibilityStatus::Unmet,
+                format!("Language level {} is below the required {}.", CEFR_LEVELS[has].to_uppercase(), CEFR_LEVELS[required].to_uppercase()),
+            ),
+            None => assessment(
+                EligibilityStatus::Unclear,
+                format!("Language level {} is required, but the description does not state one.", CEFR_LEVELS[required].to_uppercase()),
+            ),
+        };
+    }
+
+    if let Some(required) = study_cycle(requirement) {
+        return match study_cycle(applicant) {
+            Some(has) if has >= required => assessment(
+                EligibilityStatus::Met,
+                "The applicant's completed studies are at or above the required level.".into(),
+            ),
+            Some(_) => assessment(
+                EligibilityStatus::Unmet,
+                "The applicant's completed studies are below the required level.".into(),
+            ),
+            None => assessment(
+                EligibilityStatus::Unclear,
+                "The description does not say which studies the applicant completed.".into(),
+            ),
+        };
+    }
+
+    assessment(
+        EligibilityStatus::Unclear,
+        "This requirement cannot be checked automatically against the description.".into(),
+    )
+}
+
+pub fn assess_eligibility(requirements: &[String], applicant: &str) -> Vec<RequirementAssessment> {
+    requirements
+        .iter()
+        .map(|r| assess_requirement(r, applicant))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirements_are_checked_against_the_applicant() {
+        let requirements = [
+            "Completed first-cycle study programme worth at least 180 ECTS".to_string(),
+            "English language proficiency at level B2".to_string(),
+            "Completed master's degree".to_string(),
+            "Motivation letter".to_string(),
+        ];
+        let applicant = "I finished a 3-year bachelor in computer science and have a C1 English certificate.";
+
+        let statuses: Vec<EligibilityStatus> = assess_eligibility(&requirements, applicant)
+            .into_iter()
+            .map(|a| a.status)
+            .collect();
+
+        assert_eq!(
+            statuses,
+            [EligibilityStatus::Met, EligibilityStatus::Met, EligibilityStatus::Unmet, EligibilityStatus::Unclear]
+        );
+    }
+
+    #[test]
+    fn missing_applicant_details_are_unclear_not_guessed() {
+        let requirements = ["At least 240 ECTS".to_string()];
+
+        let assessment = &assess_eligibility(&requirements, "I studied physics.")[0];
+
+        assert_eq!(assessment.status, EligibilityStatus::Unclear);
+        assert!(assessment.reason.contains("240 ECTS are required"));
+    }
+}
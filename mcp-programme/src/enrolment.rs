@@ -0,0 +1,332 @@
+//! Extractor for the FAMNIT enrolment page.
+//!
+//! The enrolment page is written as prose split by headings. Feed its raw
+//! HTML into `EnrolmentProcedure::from(html)` to get the steps in page order,
+//! grouped by audience (domestic / international candidates).
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Audience {
+    General,
+    Domestic,
+    International,
+}
+
+impl Audience {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "domestic" | "slovenian" | "eu" => Some(Self::Domestic),
+            "international" | "foreign" => Some(Self::International),
+            _ => None,
+        }
+    }
+
+    /// Guess the audience a heading is addressed to.
+    fn from_heading(heading: &str) -> Self {
+        let h = heading.to_lowercase();
+        if ["international", "foreign", "non-eu", "third countr"]
+            .iter()
+            .any(|k| h.contains(k))
+        {
+            Audience::International
+        } else if ["slovenian", "domestic", "eu citizen", "citizens of the eu"]
+            .iter()
+            .any(|k| h.contains(k))
+        {
+            Audience::Domestic
+        } else {
+            Audience::General
+        }
+    }
+}
+
+impl fmt::Display for Audience {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Audience::General => write!(f, "All candidates"),
+            Audience::Domestic => write!(f, "Slovenian and EU candidates"),
+            Audience::International => write!(f, "International candidates"),
+        }
+    }
+}
+
+/// One step of the enrolment procedure.
+#[derive(Debug, Clone)]
+pub struct EnrolmentStep {
+    pub title: String,
+    pub details: Vec<String>,
+    pub links: Vec<(String, String)>,
+    pub deadlines: Vec<String>,
+}
+
+/// The steps that apply to one audience, in page order.
+#[derive(Debug)]
+pub struct AudienceSteps {
+    pub audience: Audience,
+    pub heading: String,
+    pub steps: Vec<EnrolmentStep>,
+}
+
+#[derive(Debug)]
+pub struct EnrolmentProcedure {
+    pub title: String,
+    pub sections: Vec<AudienceSteps>,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn links(er: &ElementRef) -> Vec<(String, String)> {
+    let a_sel = Selector::parse("a").unwrap();
+    er.select(&a_sel)
+        .filter_map(|a| {
+            let href = a.value().attr("href")?.to_string();
+            let label = text(&a);
+            Some((if label.is_empty() { href.clone() } else { label }, href))
+        })
+        .collect()
+}
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+/// Sentences that mention a deadline or a calendar date.
+fn deadlines(s: &str) -> Vec<String> {
+    s.split_inclusive(['.', ';'])
+        .map(str::trim)
+        .filter(|sentence| {
+            let l = sentence.to_lowercase();
+            l.contains("deadline")
+                || l.contains("no later than")
+                || (l.chars().any(|c| c.is_ascii_digit()) && MONTHS.iter().any(|m| l.contains(m)))
+        })
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn step_from_text(title: String, details: Vec<String>, links: Vec<(String, String)>) -> EnrolmentStep {
+    let mut found = deadlines(&title);
+    for d in &details {
+        found.extend(deadlines(d));
+    }
+    EnrolmentStep { title, details, links, deadlines: found }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+impl From<String> for EnrolmentProcedure {
+    fn from(html: String) -> Self {
+        let doc = Html::parse_document(&html);
+        let h1_sel = Selector::parse("h1").unwrap();
+        let content_sel = Selector::parse("div.content").unwrap();
+
+        let title = doc
+            .select(&h1_sel)
+            .next()
+            .map(|h| text(&h))
+            .unwrap_or_else(|| "Enrolment".into());
+
+        let mut sections: Vec<AudienceSteps> = Vec::new();
+        let mut current = AudienceSteps {
+            audience: Audience::General,
+            heading: String::new(),
+            steps: Vec::new(),
+        };
+
+        let Some(content) = doc.select(&content_sel).next() else {
+            return EnrolmentProcedure { title, sections };
+        };
+
+        // Steps are either <li> items of an ordered list or sub-headings
+        // followed by paragraphs; both are kept in the order they appear.
+        for node in content.children() {
+            let Some(er) = ElementRef::wrap(node) else {
+                continue;
+            };
+            match er.value().name() {
+                "h2" => {
+                    if !current.steps.is_empty() {
+                        sections.push(current);
+                    }
+                    let heading = text(&er);
+                    current = AudienceSteps {
+                        audience: Audience::from_heading(&heading),
+                        heading,
+                        steps: Vec::new(),
+                    };
+                }
+                "h3" | "h4" => {
+                    let heading = text(&er);
+                    if !heading.is_empty() {
+                        current.steps.push(step_from_text(heading, vec![], vec![]));
+                    }
+                }
+                "ol" | "ul" => {
+                    let li_sel = Selector::parse("li").unwrap();
+                    let is_ordered = er.value().name() == "ol";
+                    let items: Vec<(String, Vec<(String, String)>)> = er
+                        .select(&li_sel)
+                        .map(|li| (text(&li), links(&li)))
+                        .filter(|(t, _)| !t.is_empty())
+                        .collect();
+
+                    // A bullet list under a sub-heading describes that step.
+                    match current.steps.last_mut() {
+                        Some(step) if !is_ordered && step.details.is_empty() => {
+                            for (t, l) in items {
+                                step.deadlines.extend(deadlines(&t));
+                                step.details.push(t);
+                                step.links.extend(l);
+                            }
+                        }
+                        _ => {
+                            for (t, l) in items {
+                                current.steps.push(step_from_text(t, vec![], l));
+                            }
+                        }
+                    }
+                }
+                "p" => {
+                    let para = text(&er);
+                    if para.is_empty() {
+                        continue;
+                    }
+                    match current.steps.last_mut() {
+                        Some(step) => {
+                            step.deadlines.extend(deadlines(&para));
+                            step.details.push(para);
+                            step.links.extend(links(&er));
+                        }
+                        None => {
+                            current.steps.push(step_from_text(para, vec![], links(&er)));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !current.steps.is_empty() {
+            sections.push(current);
+        }
+
+        EnrolmentProcedure { title, sections }
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl EnrolmentProcedure {
+    /// Render the procedure as a numbered Markdown list. When `audience` is
+    /// given, only general sections and sections for that audience are kept.
+    pub fn to_markdown(&self, audience: Option<&Audience>) -> String {
+        let mut f = String::new();
+        writeln!(&mut f, "# {}\n", self.title).unwrap();
+
+        let selected = self.sections.iter().filter(|s| {
+            audience.map_or(true, |a| s.audience == Audience::General || &s.audience == a)
+        });
+
+        for section in selected {
+            if section.heading.is_empty() {
+                writeln!(&mut f, "## {}\n", section.audience).unwrap();
+            } else {
+                writeln!(&mut f, "## {} ({})\n", section.heading, section.audience).unwrap();
+            }
+            for (i, step) in section.steps.iter().enumerate() {
+                writeln!(&mut f, "{}. **{}**", i + 1, step.title).unwrap();
+                for d in &step.details {
+                    writeln!(&mut f, "   - {}", d).unwrap();
+                }
+                for (label, href) in &step.links {
+                    writeln!(&mut f, "   - Link: [{}]({})", label, href).unwrap();
+                }
+                for d in &step.deadlines {
+                    writeln!(&mut f, "   - Deadline: {}", d).unwrap();
+                }
+            }
+            writeln!(&mut f).unwrap();
+        }
+
+        f
+    }
+}
+
+impl fmt::Display for EnrolmentProcedure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_markdown(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENROLMENT_PAGE: &str = r#"<html><body>
+<h1>Enrolment</h1>
+<div class="content">
+<p>Candidates apply through the national eVŠ portal.</p>
+<h2>Slovenian and EU citizens</h2>
+<ol>
+<li>Submit the application on <a href="https://evs.gov.si">eVŠ</a> by 20 March 2025.</li>
+<li>Enrol after the admission results are published.</li>
+</ol>
+<h2>International candidates (non-EU)</h2>
+<h3>Apply for a visa</h3>
+<ul>
+<li>Book an appointment at the embassy.</li>
+<li>The deadline for the documents is 15 July 2025.</li>
+</ul>
+<p>See the <a href="/en/international">international office page</a>.</p>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn steps_are_grouped_by_audience_in_page_order() {
+        let procedure = EnrolmentProcedure::from(ENROLMENT_PAGE.to_string());
+
+        assert_eq!(procedure.title, "Enrolment");
+        let audiences: Vec<&Audience> = procedure.sections.iter().map(|s| &s.audience).collect();
+        assert_eq!(audiences, [&Audience::General, &Audience::Domestic, &Audience::International]);
+
+        let domestic = &procedure.sections[1];
+        assert_eq!(domestic.steps.len(), 2);
+        assert_eq!(domestic.steps[0].links, [("eVŠ".to_string(), "https://evs.gov.si".to_string())]);
+        assert_eq!(domestic.steps[0].deadlines.len(), 1);
+
+        let visa = &procedure.sections[2].steps[0];
+        assert_eq!(visa.title, "Apply for a visa");
+        assert_eq!(visa.details.len(), 3);
+        assert_eq!(visa.deadlines, ["The deadline for the documents is 15 July 2025."]);
+        assert_eq!(visa.links[0].1, "/en/international");
+    }
+
+    #[test]
+    fn audience_filter_keeps_the_general_steps() {
+        let procedure = EnrolmentProcedure::from(ENROLMENT_PAGE.to_string());
+
+        let markdown = procedure.to_markdown(Some(&Audience::International));
+
+        assert!(markdown.contains("eVŠ portal"));
+        assert!(markdown.contains("Apply for a visa"));
+        assert!(!markdown.contains("Enrol after the admission results"));
+    }
+}
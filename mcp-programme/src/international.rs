@@ -0,0 +1,259 @@
+//! Extractor for information that only concerns international applicants.
+//!
+//! Language requirements, recognition of foreign education, visa/residence
+//! and non-EU fees are spread over the enrolment page, the international
+//! students page and the programme pages. Each page is fed into
+//! `parse_international(html, url)`, which keeps the paragraphs and list
+//! items that belong to one of the topics; `InternationalInfo` groups the
+//! items of all pages per topic.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InternationalTopic {
+    Language,
+    Recognition,
+    Residence,
+    Fees,
+}
+
+impl InternationalTopic {
+    const ALL: [InternationalTopic; 4] = [
+        InternationalTopic::Language,
+        InternationalTopic::Recognition,
+        InternationalTopic::Residence,
+        InternationalTopic::Fees,
+    ];
+
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            InternationalTopic::Language => &[
+                "language certificate", "language proficiency", "knowledge of english",
+                "toefl", "ielts", "cambridge", "cefr", "b2 level", "level b2",
+            ],
+            InternationalTopic::Recognition => &[
+                "recognition", "recognised", "recognized", "equivalence", "nostrification",
+                "enic", "naric", "priznavanje", "foreign education", "foreign certificate",
+            ],
+            InternationalTopic::Residence => &[
+                "visa", "residence permit", "temporary residence", "embassy", "consulate",
+                "dovoljenje za prebivanje",
+            ],
+            InternationalTopic::Fees => &[
+                "tuition", "fee", "šolnina", "eur", "€",
+            ],
+        }
+    }
+
+    /// Topic a paragraph is about, checked in declaration order. Fee lines
+    /// only count when they concern non-EU or foreign candidates, or the
+    /// page itself is about international students.
+    fn classify(text: &str, international_page: bool) -> Option<Self> {
+        let lower = text.to_lowercase();
+        Self::ALL.into_iter().find(|topic| {
+            let matches = topic.keywords().iter().any(|k| lower.contains(k));
+            match topic {
+                InternationalTopic::Fees => {
+                    matches
+                        && (international_page
+                            || ["non-eu", "foreign", "third countr", "international", "tujci"]
+                                .iter()
+                                .any(|k| lower.contains(k)))
+                }
+                _ => matches,
+            }
+        })
+    }
+}
+
+impl fmt::Display for InternationalTopic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternationalTopic::Language => write!(f, "Language requirements"),
+            InternationalTopic::Recognition => write!(f, "Recognition of foreign education"),
+            InternationalTopic::Residence => write!(f, "Visa and residence"),
+            InternationalTopic::Fees => write!(f, "Fees for non-EU candidates"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InternationalItem {
+    pub topic: InternationalTopic,
+    pub text: String,
+    pub links: Vec<(String, String)>,
+    pub source: String,
+}
+
+#[derive(Debug, Default)]
+pub struct InternationalInfo {
+    pub items: Vec<InternationalItem>,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn links(er: &ElementRef) -> Vec<(String, String)> {
+    let a_sel = Selector::parse("a[href]").unwrap();
+    er.select(&a_sel)
+        .filter_map(|a| {
+            let href = a.value().attr("href")?.to_string();
+            if !href.starts_with("http") {
+                return None;
+            }
+            let label = text(&a);
+            Some((if label.is_empty() { href.clone() } else { label }, href))
+        })
+        .collect()
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+/// International-relevant paragraphs and list items of one page.
+pub fn parse_international(html: &str, url: &str) -> Vec<InternationalItem> {
+    let doc = Html::parse_document(html);
+    let h1_sel = Selector::parse("h1").unwrap();
+    let sel = Selector::parse("div.content p, div.content li").unwrap();
+
+    let international_page = url.contains("international")
+        || doc.select(&h1_sel).next().is_some_and(|h| {
+            let h = text(&h).to_lowercase();
+            h.contains("international") || h.contains("foreign")
+        });
+
+    let mut items: Vec<InternationalItem> = vec![];
+    for el in doc.select(&sel) {
+        // list items are handled on their own, not as part of a paragraph
+        if el.value().name() == "p" && el.ancestors().any(|a| ElementRef::wrap(a).is_some_and(|a| a.value().name() == "li")) {
+            continue;
+        }
+        let line = text(&el);
+        if line.is_empty() || items.iter().any(|i| i.text == line) {
+            continue;
+        }
+        if let Some(topic) = InternationalTopic::classify(&line, international_page) {
+            items.push(InternationalItem {
+                topic,
+                text: line,
+                links: links(&el),
+                source: url.to_string(),
+            });
+        }
+    }
+    items
+}
+
+impl InternationalInfo {
+    pub fn extend(&mut self, items: Vec<InternationalItem>) {
+        for item in items {
+            if !self.items.iter().any(|i| i.text == item.text) {
+                self.items.push(item);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+}
+
+impl fmt::Display for InternationalInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for topic in InternationalTopic::ALL {
+            let items: Vec<&InternationalItem> = self.items.iter().filter(|i| i.topic == topic).collect();
+            if items.is_empty() {
+                continue;
+            }
+            writeln!(f, "## {}\n", topic)?;
+            for item in items {
+                writeln!(f, "- {} ([source]({}))", item.text, item.source)?;
+                for (label, href) in &item.links {
+                    writeln!(f, "  - Link: [{}]({})", label, href)?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        let mut sources: Vec<&str> = vec![];
+        for item in &self.items {
+            if !sources.contains(&item.source.as_str()) {
+                sources.push(&item.source);
+            }
+        }
+        let sources: Vec<String> = sources.iter().map(|s| format!("[{}]({})", s, s)).collect();
+        write!(f, "---\n*Sources: {}*", sources.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENROLMENT_PAGE: &str = r#"<html><body>
+<h1>Enrolment</h1>
+<div class="content">
+<p>Candidates with foreign education must apply for recognition at the <a href="https://www.enic-naric.net">ENIC-NARIC centre</a>.</p>
+<p>An English language certificate at level B2 is required.</p>
+<ul>
+<li>Non-EU candidates pay a tuition fee of 2,500 EUR per year.</li>
+<li>Slovenian candidates pay a registration fee of 30 EUR.</li>
+<li>Apply for a visa at the nearest <a href="/en/embassies">embassy</a>.</li>
+</ul>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn items_are_classified_and_domestic_fees_dropped() {
+        let items = parse_international(ENROLMENT_PAGE, "https://www.famnit.upr.si/en/enrolment");
+
+        let topics: Vec<InternationalTopic> = items.iter().map(|i| i.topic).collect();
+        assert_eq!(
+            topics,
+            [
+                InternationalTopic::Recognition,
+                InternationalTopic::Language,
+                InternationalTopic::Fees,
+                InternationalTopic::Residence,
+            ]
+        );
+        assert_eq!(items[0].links, [("ENIC-NARIC centre".to_string(), "https://www.enic-naric.net".to_string())]);
+        // relative links are not kept
+        assert!(items[3].links.is_empty());
+    }
+
+    #[test]
+    fn pages_are_merged_without_duplicates() {
+        let international_page = r#"<html><body>
+<h1>International students</h1>
+<div class="content">
+<p>An English language certificate at level B2 is required.</p>
+<p>The fee is 3,000 EUR per year.</p>
+</div>
+</body></html>"#;
+
+        let mut info = InternationalInfo::default();
+        info.extend(parse_international(ENROLMENT_PAGE, "https://www.famnit.upr.si/en/enrolment"));
+        info.extend(parse_international(international_page, "https://www.famnit.upr.si/en/students"));
+
+        // on a page about international students every fee line counts
+        assert_eq!(info.items.len(), 5);
+        assert_eq!(info.items[4].topic, InternationalTopic::Fees);
+        let md = info.to_string();
+        assert!(md.starts_with("## Language requirements"));
+        assert!(md.contains("- The fee is 3,000 EUR per year. ([source](https://www.famnit.upr.si/en/students))"));
+        assert!(md.ends_with("[https://www.famnit.upr.si/en/students](https://www.famnit.upr.si/en/students)*"));
+    }
+}
@@ -0,0 +1,207 @@
+//! Extractor for library / e-resources pages.
+//!
+//! Library information is split between the faculty site and the UP-wide
+//! university library site. Feed the raw HTML of either page into
+//! `LibraryInfo::parse(html, url)`; links are kept exactly as they appear on
+//! the page so that UP-wide URLs are not rewritten.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt::{self, Write};
+
+#[derive(Debug)]
+pub struct LibraryInfo {
+    pub title: String,
+    pub source_url: String,
+    pub contact: Vec<String>,
+    pub hours: Vec<String>,
+    pub catalogue_links: Vec<(String, String)>,
+    pub e_resource_links: Vec<(String, String)>,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday",
+];
+
+fn is_contact_line(l: &str) -> bool {
+    let l = l.to_lowercase();
+    l.contains('@') || l.contains("phone") || l.contains("tel") || l.contains("+386")
+}
+
+fn is_hours_line(l: &str) -> bool {
+    let l = l.to_lowercase();
+    l.contains("opening hours")
+        || l.contains("working hours")
+        || (WEEKDAYS.iter().any(|d| l.contains(d)) && l.chars().any(|c| c.is_ascii_digit()))
+}
+
+fn is_catalogue_link(label: &str, href: &str) -> bool {
+    let (label, href) = (label.to_lowercase(), href.to_lowercase());
+    label.contains("catalog") || label.contains("cobiss") || href.contains("cobiss")
+}
+
+fn is_e_resource_link(label: &str, href: &str) -> bool {
+    let (label, href) = (label.to_lowercase(), href.to_lowercase());
+    ["e-resource", "electronic resource", "database", "journal", "remote access", "e-book"]
+        .iter()
+        .any(|k| label.contains(k))
+        || href.contains("ezproxy")
+        || href.contains("dikul")
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+impl LibraryInfo {
+    pub fn parse(html: &str, source_url: &str) -> Self {
+        let doc = Html::parse_document(html);
+        let h1_sel = Selector::parse("h1").unwrap();
+        let line_sel = Selector::parse("p, li, td").unwrap();
+        let a_sel = Selector::parse("a[href]").unwrap();
+
+        let title = doc
+            .select(&h1_sel)
+            .next()
+            .map(|h| text(&h))
+            .unwrap_or_else(|| "Library".into());
+
+        let mut contact = Vec::new();
+        let mut hours = Vec::new();
+        for el in doc.select(&line_sel) {
+            // paragraphs often pack several lines separated by <br>
+            for line in el.text().map(str::trim).filter(|l| !l.is_empty()) {
+                let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+                if is_hours_line(&line) && !hours.contains(&line) {
+                    hours.push(line);
+                } else if is_contact_line(&line) && !contact.contains(&line) {
+                    contact.push(line);
+                }
+            }
+        }
+
+        let mut catalogue_links = Vec::new();
+        let mut e_resource_links = Vec::new();
+        for a in doc.select(&a_sel) {
+            let href = a.value().attr("href").unwrap_or_default().to_string();
+            let label = text(&a);
+            let link = (if label.is_empty() { href.clone() } else { label.clone() }, href.clone());
+
+            if href.starts_with("mailto:") {
+                let email = href.trim_start_matches("mailto:").to_string();
+                if !contact.iter().any(|c| c.contains(&email)) {
+                    contact.push(email);
+                }
+            } else if is_catalogue_link(&label, &href) {
+                if !catalogue_links.contains(&link) {
+                    catalogue_links.push(link);
+                }
+            } else if is_e_resource_link(&label, &href) && !e_resource_links.contains(&link) {
+                e_resource_links.push(link);
+            }
+        }
+
+        LibraryInfo {
+            title,
+            source_url: source_url.to_string(),
+            contact,
+            hours,
+            catalogue_links,
+            e_resource_links,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contact.is_empty()
+            && self.hours.is_empty()
+            && self.catalogue_links.is_empty()
+            && self.e_resource_links.is_empty()
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl fmt::Display for LibraryInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut md = String::new();
+        let write_lines = |md: &mut String, title: &str, lines: &[String]| {
+            if !lines.is_empty() {
+                writeln!(md, "### {}\n", title).unwrap();
+                for l in lines {
+                    writeln!(md, "- {}", l).unwrap();
+                }
+                writeln!(md).unwrap();
+            }
+        };
+        let write_links = |md: &mut String, title: &str, links: &[(String, String)]| {
+            if !links.is_empty() {
+                writeln!(md, "### {}\n", title).unwrap();
+                for (label, href) in links {
+                    writeln!(md, "- [{}]({})", label, href).unwrap();
+                }
+                writeln!(md).unwrap();
+            }
+        };
+
+        writeln!(&mut md, "## {}\n", self.title).unwrap();
+        write_lines(&mut md, "Contact", &self.contact);
+        write_lines(&mut md, "Opening hours", &self.hours);
+        write_links(&mut md, "Catalogue", &self.catalogue_links);
+        write_links(&mut md, "E-resources", &self.e_resource_links);
+        writeln!(&mut md, "*Source: [{}]({})*", self.source_url, self.source_url).unwrap();
+
+        write!(f, "{}", md)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIBRARY_PAGE: &str = r#"<html><body>
+<h1>University Library</h1>
+<p>Titov trg 5, Koper<br>Phone: +386 5 611 75 00<br>
+<a href="mailto:knjiznica@upr.si">knjiznica@upr.si</a></p>
+<table><tr><td>Monday to Friday: 8.00 - 19.00</td></tr></table>
+<ul>
+<li><a href="https://plus.cobiss.net/cobiss/si/sl/bib/search">COBISS+</a></li>
+<li><a href="https://dikul.upr.si">Remote access to e-resources</a></li>
+<li><a href="https://www.famnit.upr.si/en/news">News</a></li>
+</ul>
+</body></html>"#;
+
+    #[test]
+    fn library_page_sorts_lines_and_links() {
+        let info = LibraryInfo::parse(LIBRARY_PAGE, "https://www.upr.si/en/library");
+
+        assert_eq!(info.title, "University Library");
+        assert_eq!(info.hours, ["Monday to Friday: 8.00 - 19.00"]);
+        assert_eq!(info.contact, ["Phone: +386 5 611 75 00", "knjiznica@upr.si"]);
+        assert_eq!(info.catalogue_links.len(), 1);
+        assert_eq!(info.catalogue_links[0].0, "COBISS+");
+        // UP-wide links are kept exactly as they appear on the page
+        assert_eq!(
+            info.e_resource_links,
+            [("Remote access to e-resources".to_string(), "https://dikul.upr.si".to_string())]
+        );
+
+        let md = info.to_string();
+        assert!(md.contains("### Opening hours"));
+        assert!(md.contains("*Source: [https://www.upr.si/en/library]"));
+        assert!(!md.contains("News"));
+    }
+}
@@ -20,15 +20,93 @@ use serde::Deserialize;
 use tokio::sync::{Mutex, OnceCell, mpsc};
 
 use crate::{
-    programme::{Programme, ProgrammeInfo, ProgrammeLevel, ProgrammeSection},
-    util::{get_page, parse_programme_list_page, rank_names},
+    accreditation::Accreditation,
+    admission::AdmissionScores,
+    assessment::{CourseAssessment, course_links},
+    capacity::parse_capacity,
+    career::{CareerStatistic, parse_career_statistics, render_statistics},
+    eligibility::{EligibilityStatus, assess_eligibility},
+    enrolment::{Audience, EnrolmentProcedure},
+    international::{InternationalInfo, parse_international},
+    library::LibraryInfo,
+    open_days::{OpenDay, parse_open_days, upcoming},
+    mobility::{MobilityDirection, MobilityInfo},
+    not_found::not_found,
+    organizations::{OrganizationScope, parse_student_organizations},
+    partners::{ProgrammePartners, parse_partner_list},
+    programme::{Language, Programme, ProgrammeInfo, ProgrammeLevel, ProgrammeSection},
+    progress::remaining_requirements,
+    snapshot::{ProgrammeDiff, ProgrammeSnapshot, load_history, record_snapshot},
+    timetable::{TimetableSlot, current_term, parse_timetable, timetable_links},
+    weekly_load::{DEFAULT_WEEKS_PER_SEMESTER, weekly_load},
+    util::{
+        archived_programme_page, clear_page_cache, get_page,
+        parse_programme_list_page, rank_names, rank_names_scored, NameMetric,
+    },
 };
 
+mod accreditation;
+mod admission;
+mod assessment;
+mod capacity;
+mod career;
+mod eligibility;
+mod enrolment;
+mod international;
+mod library;
+mod mobility;
+mod not_found;
+mod open_days;
+mod organizations;
+mod partners;
 mod programme;
+mod progress;
+mod snapshot;
+mod timetable;
 mod util;
+mod weekly_load;
 
 const BIND_ADDRESS: &str = "127.0.0.1:8003";
 const BASE_URL: &str = "https://www.famnit.upr.si";
+const ENROLMENT_URL: &str = "https://www.famnit.upr.si/en/education/enrolment";
+/// Library information lives partly on the faculty site and partly on the
+/// UP-wide university library site.
+const LIBRARY_URLS: [&str; 2] = [
+    "https://www.famnit.upr.si/en/students/library",
+    "https://www.upr.si/en/university-library",
+];
+const MOBILITY_URLS: [(&str, MobilityDirection); 2] = [
+    (
+        "https://www.famnit.upr.si/en/international/incoming-students",
+        MobilityDirection::Incoming,
+    ),
+    (
+        "https://www.famnit.upr.si/en/international/outgoing-students",
+        MobilityDirection::Outgoing,
+    ),
+];
+/// Admission information for foreign candidates, next to the enrolment page.
+const INTERNATIONAL_STUDENTS_URL: &str = "https://www.famnit.upr.si/en/international/international-students";
+/// The dedicated information days page and the general events calendar.
+const OPEN_DAYS_URLS: [&str; 2] = [
+    "https://www.famnit.upr.si/en/education/information-days",
+    "https://www.famnit.upr.si/en/news/events",
+];
+/// Minimum similarity for a course in the course tables to match the
+/// requested course name.
+const COURSE_MATCH_THRESHOLD: f64 = 0.5;
+/// Linked partner list pages followed per programme.
+const MAX_PARTNER_LISTS: usize = 3;
+/// Timetable overview, linking the timetables of the programmes.
+const TIMETABLE_URL: &str = "https://www.famnit.upr.si/en/students/timetables";
+/// Linked timetable pages followed per request.
+const MAX_TIMETABLE_PAGES: usize = 5;
+/// Career centre pages that may publish graduate employment surveys.
+const CAREER_URLS: [&str; 2] = [
+    "https://www.famnit.upr.si/en/students/career-centre",
+    "https://www.upr.si/en/students/career-centre",
+];
+const STUDENT_ORGANIZATIONS_URL: &str = "https://www.famnit.upr.si/en/students/student-life";
 const MEMORY_MCP_URL: &str = "http://localhost:8002/mcp";
 const SCRAPER_MCP_URL: &str = "http://localhost:7999/sse";
 
@@ -75,8 +153,134 @@ pub struct ProgrammeInfoRequest {
     pub name: String,
     /// Optional study level to filter by: 'undergraduate', 'master', 'doctoral' or 'any'.
     pub level: Option<String>,
-    // Optional. A list of specific sections to return. Valid sections: 'general_info', 'coordinators', 'about', 'goals', 'course_structure', 'field_work', 'course_tables', 'admission_requirements', 'transfer_criteria', 'advancement_requirements', 'completion_requirements', 'competencies', 'employment_opportunities'.
-    // pub sections: Option<Vec<String>>,
+    /// Optional academic year, e.g. '2023/2024', to read the archived version of the programme for that year instead of the current one.
+    pub academic_year: Option<String>,
+    /// Optional. A list of specific sections to return. Valid sections: 'general_info', 'coordinators', 'about', 'goals', 'course_structure', 'field_work', 'course_tables', 'admission_requirements', 'transfer_criteria', 'advancement_requirements', 'completion_requirements', 'competencies', 'employment_opportunities'. Omit to return every section.
+    pub sections: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EnrolmentStepsRequest {
+    /// Optional audience to filter by: 'domestic', 'international' or 'any'.
+    pub audience: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct MobilityInfoRequest {
+    /// Optional direction: 'incoming' (exchange students coming to FAMNIT), 'outgoing' (FAMNIT students going abroad) or 'any'.
+    pub direction: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StudentOrganizationsRequest {
+    /// Optional scope: 'famnit' for FAMNIT-specific societies, 'university' for UP-wide ones, or 'any'.
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EligibilityRequest {
+    /// Description of the applicant's previous education, e.g. '3-year bachelor in computer science from abroad, English B2'.
+    pub applicant: String,
+    /// Full or partial name of the target study programme.
+    pub programme: String,
+    /// Optional study level of the target programme: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RemainingRequirementsRequest {
+    /// Full or partial name of the student's study programme.
+    pub programme: String,
+    /// Optional study level of the programme: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+    /// Number of study years the student has fully completed.
+    pub completed_years: u32,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProgrammeCapacityRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AdmissionScoresRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProgrammePartnersRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct WeeklyLoadRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+    /// Teaching weeks per semester used for the estimate (default 15).
+    pub weeks_per_semester: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProgrammeAccreditationRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CourseAssessmentRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Name of the course, as listed in the programme's course tables.
+    pub course: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CourseTimetableRequest {
+    /// Name of the course.
+    pub course: String,
+    /// Optional study programme, whose page may link its own timetable.
+    pub programme: Option<String>,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CareerOutcomesRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DiffProgrammeVersionsRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct InternationalStudentInfoRequest {
+    /// Optional study programme, to include its programme-specific fees and requirements.
+    pub programme: Option<String>,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -127,9 +331,76 @@ impl Service {
             .await
     }
 
+    /// Resolves a (possibly partial) programme name and optional level to a
+    /// single programme. `Err` holds the response to return to the caller.
+    async fn resolve_programme(
+        &self,
+        name: &str,
+        level: Option<&str>,
+    ) -> Result<Programme, CallToolResult> {
+        let Ok(programmes) = self.get_or_init_programmes().await else {
+            return Err(CallToolResult::error(vec![Content::text(
+                "
+                Can't find any programmes. This is an error.
+            ",
+            )]));
+        };
+
+        let all_names: Vec<String> = programmes.iter().map(|p| p.name.clone()).collect();
+        let top_ranked_names = rank_names(all_names, name, NameMetric::from_env());
+        let best_match_name = match top_ranked_names.first() {
+            Some(name) => name,
+            None => {
+                return Err(not_found(format!(
+                    "No programme found matching the name '{}'.",
+                    name
+                )));
+            }
+        };
+
+        let mut potential_matches: Vec<Programme> = programmes
+            .iter()
+            .filter(|p| p.name.eq_ignore_ascii_case(best_match_name))
+            .cloned()
+            .collect();
+
+        if let Some(level_str) = level {
+            let target_level = match level_str.to_lowercase().as_str() {
+                "undergraduate" => Some(ProgrammeLevel::Undergraduate),
+                "master" => Some(ProgrammeLevel::Master),
+                "doctoral" => Some(ProgrammeLevel::Doctoral),
+                _ => None,
+            };
+            if let Some(level) = target_level {
+                potential_matches.retain(|p| p.level == level);
+            }
+        }
+
+        if potential_matches.len() > 1 {
+            let levels: Vec<String> = potential_matches
+                .iter()
+                .map(|p| p.level.to_string())
+                .collect();
+            let response = format!(
+                "Found '{}' at multiple levels: {}. Please specify which one you are interested in.",
+                best_match_name,
+                levels.join(", ")
+            );
+            return Err(CallToolResult::success(vec![Content::text(response)]));
+        }
+
+        match potential_matches.into_iter().next() {
+            Some(p) => Ok(p),
+            None => Err(not_found(format!(
+                "No programme found for '{}' at the specified level.",
+                best_match_name
+            ))),
+        }
+    }
+
     #[tool(
         name = "list_all_programmes",
-        description = "Lists the names of available study programmes. Can be filtered by study level to list only undergraduate, master's, or doctoral programmes."
+        description = "Lists the names of available study programmes with their duration and ECTS where the listing gives them and the language(s) each is taught in, e.g. to answer which programmes are taught in English. Can be filtered by study level to list only undergraduate, master's, or doctoral programmes."
     )]
     pub async fn list_all_programmes(
         &self,
@@ -152,20 +423,48 @@ impl Service {
             _ => None,
         };
 
+        let selected: Vec<&Programme> = programmes
+            .iter()
+            .filter(|prog| target_level.is_none() || Some(prog.level.clone()) == target_level)
+            .collect();
+
+        // the language is only stated on each programme's own page
+        let fetches: Vec<_> = selected
+            .iter()
+            .map(|prog| {
+                let url = prog.url.clone();
+                tokio::spawn(async move {
+                    get_page(url)
+                        .await
+                        .map(|html| ProgrammeInfo::from(html).languages)
+                        .unwrap_or_default()
+                })
+            })
+            .collect();
+
         let mut result_map: BTreeMap<String, Vec<String>> = BTreeMap::new();
-        for prog in programmes.iter() {
-            if target_level.is_none() || Some(prog.level.clone()) == target_level {
-                result_map
-                    .entry(prog.level.to_string())
-                    .or_default()
-                    .push(prog.name.clone());
+        for (prog, fetch) in selected.into_iter().zip(fetches) {
+            let languages: Vec<Language> = fetch.await.unwrap_or_default();
+            let mut facts: Vec<String> = vec![];
+            facts.extend(prog.duration.clone());
+            facts.extend(prog.ects.map(|e| format!("{} ECTS", e)));
+            if !languages.is_empty() {
+                let names: Vec<String> = languages.iter().map(Language::to_string).collect();
+                facts.push(format!("taught in {}", names.join(", ")));
             }
+            let entry = if facts.is_empty() {
+                prog.name.clone()
+            } else {
+                format!("{} ({})", prog.name, facts.join(", "))
+            };
+            result_map
+                .entry(prog.level.to_string())
+                .or_default()
+                .push(entry);
         }
 
         if result_map.is_empty() {
-            return Ok(CallToolResult::success(vec![Content::text(
-                "No programmes found for the specified level.",
-            )]));
+            return Ok(not_found("No programmes found for the specified level."));
         }
 
         let mut md = String::new();
@@ -217,19 +516,19 @@ impl Service {
 
     //     let names_to_rank: Vec<String> = filtered_programmes
     //         .iter()
-    //         .map(|p| format!("- {} ({})", p.name, p.level))
+    //         .map(|p| format!("{} ({})", p.name, p.level))
     //         .collect();
 
-    //     // Rank the combined strings.
-    //     let ranked_names = rank_names(names_to_rank, &request.name);
+    //     // Rank the combined strings, keeping the score so weak matches are recognizable.
+    //     let ranked_names = rank_names_scored(names_to_rank, &request.name, NameMetric::from_env());
 
-    //     // The ranked list already contains the formatted strings, so we just take the top K and join them.
     //     let top_k = ranked_names
     //         .into_iter()
     //         .take(k as usize)
+    //         .map(|(name, score)| format!("- {} ({:.2})", name, score))
     //         .collect::<Vec<String>>();
 
-    //     let response = top_k.join(" \n");
+    //     let response = top_k.join("\n");
 
     //     Ok(CallToolResult::success(vec![Content::text(response)]))
     // }
@@ -276,6 +575,7 @@ impl Service {
          - CompletionRequirements, \n \
          - Competencies, \n \
          - EmploymentOpportunities \n\n \
+         Pass 'sections' (e.g. ['admission_requirements']) to retrieve only the sections needed; the full profile is very long. \n\n \
          If the user is asking about the studies this tool is higly usefull.
          "
     )]
@@ -285,98 +585,69 @@ impl Service {
         _client: Peer<RoleServer>,
         _meta: Meta,
     ) -> Result<CallToolResult, rmcp::Error> {
-        let Ok(programmes) = self.get_or_init_programmes().await else {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "
-                Can't find any programmes. This is an error.
-            ",
-            )]));
+        let target_programme = match self
+            .resolve_programme(&request.name, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
         };
 
-        let all_names: Vec<String> = programmes.iter().map(|p| p.name.clone()).collect();
-        let top_ranked_names = rank_names(all_names, &request.name);
-        let best_match_name = match top_ranked_names.first() {
-            Some(name) => name,
-            None => {
-                return Ok(CallToolResult::success(vec![Content::text(format!(
-                    "No programme found matching the name '{}'.",
-                    request.name
-                ))]));
+        let sections_to_render: Option<HashSet<ProgrammeSection>> = match &request.sections {
+            Some(names) => {
+                let unknown: Vec<&str> = names
+                    .iter()
+                    .map(|s| s.trim())
+                    .filter(|s| ProgrammeSection::from_str(s).is_none())
+                    .collect();
+                if !unknown.is_empty() {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Unknown section(s): {}. Valid sections: {}.",
+                        unknown.join(", "),
+                        ProgrammeSection::NAMES.join(", ")
+                    ))]));
+                }
+                Some(
+                    names
+                        .iter()
+                        .filter_map(|s| ProgrammeSection::from_str(s.trim()))
+                        .collect(),
+                )
             }
+            None => None,
         };
 
-        let mut potential_matches: Vec<Programme> = programmes
-            .iter()
-            .filter(|p| p.name.eq_ignore_ascii_case(best_match_name))
-            .cloned()
-            .collect();
+        let mut result = String::new();
+        let mut source_url = target_programme.url.clone();
+        let mut page = None;
 
-        if let Some(level_str) = request.level {
-            let target_level = match level_str.to_lowercase().as_str() {
-                "undergraduate" => Some(ProgrammeLevel::Undergraduate),
-                "master" => Some(ProgrammeLevel::Master),
-                "doctoral" => Some(ProgrammeLevel::Doctoral),
-                _ => None,
-            };
-            if let Some(level) = target_level {
-                potential_matches.retain(|p| p.level == level);
+        if let Some(year) = request.academic_year.as_deref() {
+            match archived_programme_page(&target_programme.url, year, |url| get_page(url)).await {
+                Ok((url, html)) => {
+                    source_url = url;
+                    page = Some(html);
+                }
+                Err(note) => result.push_str(&note),
             }
         }
 
-        if potential_matches.len() > 1 {
-            let levels: Vec<String> = potential_matches
-                .iter()
-                .map(|p| p.level.to_string())
-                .collect();
-            let response = format!(
-                "Found '{}' at multiple levels: {}. Please specify which one you are interested in.",
-                best_match_name,
-                levels.join(", ")
-            );
-            return Ok(CallToolResult::success(vec![Content::text(response)]));
-        }
-
-        let target_programme = match potential_matches.first() {
-            Some(p) => p,
-            None => {
-                return Ok(CallToolResult::success(vec![Content::text(format!(
-                    "No programme found for '{}' at the specified level.",
-                    best_match_name
-                ))]));
-            }
-        };
-
-        let sections_to_render: Option<HashSet<ProgrammeSection>> = {
-            let section_names = vec![
-                "general_info",
-                "coordinators",
-                "about",
-                "goals",
-                "course_structure",
-                "field_work",
-                "course_tables",
-                "admission_requirements",
-                "transfer_criteria",
-                "advancement_requirements",
-                "completion_requirements",
-                "competencies",
-                "employment_opportunities",
-            ];
-            let set: HashSet<ProgrammeSection> = section_names
-                .into_iter()
-                .filter_map(|s| ProgrammeSection::from_str(s))
-                .collect();
-            Some(set)
+        let page = match page {
+            Some(html) => Ok(html),
+            None => get_page(&target_programme.url).await,
         };
 
-        let mut result = String::new();
-        match get_page(&target_programme.url).await {
+        match page {
             Ok(html) => {
                 let info = ProgrammeInfo::from(html);
+                if source_url == target_programme.url {
+                    if let Err(e) = record_snapshot(&source_url, &ProgrammeSnapshot::from(&info)).await {
+                        eprintln!("Could not store a snapshot of {}: {}", source_url, e);
+                    }
+                }
                 result.push_str(&info.to_markdown(sections_to_render.as_ref()));
                 result.push_str(&format!(
                     "\n\n---\n*Source: [{}]({})*",
-                    target_programme.url, target_programme.url
+                    source_url, source_url
                 ));
             }
             Err(_) => {
@@ -389,6 +660,913 @@ impl Service {
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    #[tool(
+        name = "get_enrolment_steps",
+        description = "Returns the enrolment procedure at UP FAMNIT as an ordered, numbered list of steps (application, documents, fees, confirmation) with links and deadlines where given. Use the 'audience' parameter ('domestic' or 'international') to only return the steps relevant to Slovenian/EU or international candidates."
+    )]
+    pub async fn get_enrolment_steps(
+        &self,
+        Parameters(request): Parameters<EnrolmentStepsRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let audience = request.audience.as_deref().and_then(Audience::from_str);
+
+        let html = match get_page(ENROLMENT_URL).await {
+            Ok(html) => html,
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Could not retrieve the enrolment page.",
+                )]));
+            }
+        };
+
+        let procedure = EnrolmentProcedure::from(html);
+        if procedure.sections.is_empty() {
+            return Ok(not_found(format!(
+                "No enrolment steps could be extracted. See: [{}]({})",
+                ENROLMENT_URL, ENROLMENT_URL
+            )));
+        }
+
+        let mut result = procedure.to_markdown(audience.as_ref());
+        result.push_str(&format!(
+            "\n\n---\n*Source: [{}]({})*",
+            ENROLMENT_URL, ENROLMENT_URL
+        ));
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "get_library_info",
+        description = "Returns library information for UP FAMNIT students: contact, opening hours, the library catalogue (COBISS) and links to e-resources and databases, with source URLs. Part of this information is hosted on the UP-wide university library site."
+    )]
+    pub async fn get_library_info(
+        &self,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let mut sections = Vec::new();
+        for url in LIBRARY_URLS {
+            match get_page(url).await {
+                Ok(html) => {
+                    let info = LibraryInfo::parse(&html, url);
+                    if !info.is_empty() {
+                        sections.push(info.to_string());
+                    }
+                }
+                Err(e) => eprintln!("Could not fetch library page {}: {}", url, e),
+            }
+        }
+
+        if sections.is_empty() {
+            let links: Vec<String> = LIBRARY_URLS
+                .iter()
+                .map(|url| format!("- [{}]({})", url, url))
+                .collect();
+            return Ok(not_found(format!(
+                "No library information could be extracted. See:\n{}",
+                links.join("\n")
+            )));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            sections.join("\n\n---\n\n"),
+        )]))
+    }
+
+    #[tool(
+        name = "get_mobility_info",
+        description = "Returns Erasmus+ / exchange mobility information: application periods, contacts, partner institution list and procedure, with source URLs. Incoming mobility is for exchange students coming to UP FAMNIT, outgoing mobility is for UP FAMNIT students going abroad. Use the 'direction' parameter to pick one."
+    )]
+    pub async fn get_mobility_info(
+        &self,
+        Parameters(request): Parameters<MobilityInfoRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let direction = request.direction.as_deref().and_then(MobilityDirection::from_str);
+
+        let mut sections = Vec::new();
+        for (url, page_direction) in MOBILITY_URLS {
+            if direction.as_ref().is_some_and(|d| *d != page_direction) {
+                continue;
+            }
+            match get_page(url).await {
+                Ok(html) => sections.push(MobilityInfo::parse(&html, page_direction, url).to_string()),
+                Err(e) => eprintln!("Could not fetch mobility page {}: {}", url, e),
+            }
+        }
+
+        if sections.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve mobility information.",
+            )]));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            sections.join("\n\n---\n\n"),
+        )]))
+    }
+
+    #[tool(
+        name = "list_student_organizations",
+        description = "Lists student organizations and societies (name, description, website/contact) from the UP FAMNIT student-life page, grouped into FAMNIT-specific and UP-wide organizations. Optional 'scope': 'famnit', 'university' or 'any'."
+    )]
+    pub async fn list_student_organizations(
+        &self,
+        Parameters(request): Parameters<StudentOrganizationsRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let target_scope = match request.scope.as_deref().map(str::to_lowercase).as_deref() {
+            Some("famnit") => Some(OrganizationScope::Famnit),
+            Some("university") | Some("up") => Some(OrganizationScope::University),
+            _ => None,
+        };
+
+        let html = match get_page(STUDENT_ORGANIZATIONS_URL).await {
+            Ok(html) => html,
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Could not retrieve the student-life page.",
+                )]));
+            }
+        };
+
+        let organizations = parse_student_organizations(&html);
+
+        let mut md = String::new();
+        for scope in [OrganizationScope::Famnit, OrganizationScope::University] {
+            if target_scope.as_ref().is_some_and(|t| *t != scope) {
+                continue;
+            }
+            let entries: Vec<String> = organizations
+                .iter()
+                .filter(|o| o.scope == scope)
+                .map(|o| o.to_string())
+                .collect();
+            if !entries.is_empty() {
+                md.push_str(&format!("\n### {}\n{}\n", scope, entries.join("\n")));
+            }
+        }
+
+        if md.is_empty() {
+            return Ok(not_found(format!(
+                "No student organizations are listed on the student organizations page.\n\n---\n*Source: [{}]({})*",
+                STUDENT_ORGANIZATIONS_URL, STUDENT_ORGANIZATIONS_URL
+            )));
+        }
+        md.push_str(&format!(
+            "\n\n---\n*Source: [{}]({})*",
+            STUDENT_ORGANIZATIONS_URL, STUDENT_ORGANIZATIONS_URL
+        ));
+
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "assess_eligibility",
+        description = "Checks a prospective applicant's description against the admission requirements of a target programme. Returns each requirement marked as met, unmet or unclear with a reason, based strictly on the requirements published on the programme page. Anything that cannot be determined from the description is marked unclear."
+    )]
+    pub async fn assess_eligibility(
+        &self,
+        Parameters(request): Parameters<EligibilityRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let info = match get_page(&programme.url).await {
+            Ok(html) => ProgrammeInfo::from(html),
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+
+        if info.admission_requirements.is_empty() {
+            return Ok(not_found(format!(
+                "No admission requirements are published for {} ({}).\n\n---\n*Source: [{}]({})*",
+                programme.name, programme.level, programme.url, programme.url
+            )));
+        }
+
+        let assessments = assess_eligibility(&info.admission_requirements, &request.applicant);
+
+        let mut md = format!(
+            "# Eligibility for {} ({})\n\n| Requirement | Status | Reason |\n|---|---|---|\n",
+            programme.name, programme.level
+        );
+        for a in &assessments {
+            md.push_str(&format!(
+                "| {} | {} | {} |\n",
+                a.requirement.replace('|', "\\|"),
+                a.status,
+                a.reason
+            ));
+        }
+
+        let unclear = assessments
+            .iter()
+            .filter(|a| a.status == EligibilityStatus::Unclear)
+            .count();
+        if unclear > 0 {
+            md.push_str(&format!(
+                "\n{} requirement(s) could not be determined from the description and must be confirmed with Student Services.\n",
+                unclear
+            ));
+        }
+        md.push_str(&format!(
+            "\n---\n*Source: [{}]({})*",
+            programme.url, programme.url
+        ));
+
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "remaining_requirements",
+        description = "Computes the compulsory courses and ECTS a student still needs to graduate, given their programme and the number of study years they have completed. Elective courses are reported as the required elective ECTS per year, not as specific courses."
+    )]
+    pub async fn remaining_requirements(
+        &self,
+        Parameters(request): Parameters<RemainingRequirementsRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let info = match get_page(&programme.url).await {
+            Ok(html) => ProgrammeInfo::from(html),
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+
+        let Some(plan) = remaining_requirements(&info, request.completed_years) else {
+            return Ok(not_found(format!(
+                "The page of {} ({}) has no per-year course tables, so the remaining requirements cannot be computed.\n\n---\n*Source: [{}]({})*",
+                programme.name, programme.level, programme.url, programme.url
+            )));
+        };
+
+        let md = format!(
+            "{}\n---\n*Source: [{}]({})*",
+            plan, programme.url, programme.url
+        );
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "get_programme_weekly_load",
+        description = "Estimates the weekly contact hours (lectures, seminars, tutorials, lab work) per study year of a programme from its course tables. Useful for students planning part-time work. The result is an estimate, not a timetable."
+    )]
+    pub async fn get_programme_weekly_load(
+        &self,
+        Parameters(request): Parameters<WeeklyLoadRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let info = match get_page(&programme.url).await {
+            Ok(html) => ProgrammeInfo::from(html),
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+
+        let weeks = request.weeks_per_semester.unwrap_or(DEFAULT_WEEKS_PER_SEMESTER);
+        let Some(load) = weekly_load(&info, weeks) else {
+            return Ok(not_found(format!(
+                "The page of {} ({}) has no per-year course tables, so the weekly load cannot be estimated.\n\n---\n*Source: [{}]({})*",
+                programme.name, programme.level, programme.url, programme.url
+            )));
+        };
+
+        let md = format!(
+            "{}\n---\n*Source: [{}]({})*",
+            load, programme.url, programme.url
+        );
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "get_course_assessment",
+        description = "Returns how a course of a study programme is assessed (written or oral exam, project, homework, ...) with the weight of each part, from the course detail page, with the source URL. Reports when the course has no published assessment information."
+    )]
+    pub async fn get_course_assessment(
+        &self,
+        Parameters(request): Parameters<CourseAssessmentRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let html = match get_page(&programme.url).await {
+            Ok(html) => html,
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+        let links = course_links(&html);
+        let info = ProgrammeInfo::from(html);
+        let descriptions = info
+            .course_description
+            .as_ref()
+            .map(|pdf| format!(" The short course descriptions may cover it: [{}]({}).", pdf, pdf))
+            .unwrap_or_default();
+
+        let names: Vec<String> = links.iter().map(|(name, _)| name.clone()).collect();
+        let best = rank_names_scored(names, &request.course, NameMetric::from_env())
+            .into_iter()
+            .next()
+            .filter(|(_, score)| *score >= COURSE_MATCH_THRESHOLD);
+        let Some((course, url)) = best.and_then(|(name, _)| links.into_iter().find(|(n, _)| *n == name)) else {
+            return Ok(not_found(format!(
+                "No course detail page for '{}' is linked from the page of {} ({}), so its assessment methods are not published there.{}\n\n---\n*Source: [{}]({})*",
+                request.course, programme.name, programme.level, descriptions, programme.url, programme.url
+            )));
+        };
+
+        let assessment = match get_page(&url).await {
+            Ok(html) => CourseAssessment::parse(&html, &url),
+            Err(e) => {
+                eprintln!("Could not fetch course page {}: {}", url, e);
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve the course page of '{}'.",
+                    course
+                ))]));
+            }
+        };
+
+        let md = match assessment {
+            Some(mut assessment) => {
+                if assessment.course.is_empty() {
+                    assessment.course = course;
+                }
+                assessment.to_string()
+            }
+            None => format!(
+                "The course page of {} does not describe how the course is assessed.{}\n\n---\n*Source: [{}]({})*",
+                course, descriptions, url, url
+            ),
+        };
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "get_programme_accreditation",
+        description = "Returns the accreditation of a study programme as published on its page: status, accrediting body and accreditation/validity dates, with the statements they were taken from and the source URL. Useful for recognition of the degree abroad. Reports when the page does not mention accreditation."
+    )]
+    pub async fn get_programme_accreditation(
+        &self,
+        Parameters(request): Parameters<ProgrammeAccreditationRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let accreditation = match get_page(&programme.url).await {
+            Ok(html) => Accreditation::parse(&html, &programme.url),
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+
+        let md = match accreditation {
+            Some(accreditation) => format!(
+                "# Accreditation: {} ({})\n\n{}",
+                programme.name, programme.level, accreditation
+            ),
+            None => format!(
+                "Accreditation of {} ({}) is not published on this page. Student Services can confirm the accreditation status.\n\n---\n*Source: [{}]({})*",
+                programme.name, programme.level, programme.url, programme.url
+            ),
+        };
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "get_programme_capacity",
+        description = "Returns the number of available enrolment places (razpisana mesta) of a study programme per audience (Slovenian, EU, non-EU citizens), with the source URL. Reports when the number of places is not published."
+    )]
+    pub async fn get_programme_capacity(
+        &self,
+        Parameters(request): Parameters<ProgrammeCapacityRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        // the programme page first, then the enrolment call listing all programmes
+        for url in [programme.url.as_str(), ENROLMENT_URL] {
+            let html = match get_page(url).await {
+                Ok(html) => html,
+                Err(e) => {
+                    eprintln!("Could not fetch {}: {}", url, e);
+                    continue;
+                }
+            };
+            if let Some(capacity) = parse_capacity(&html, &programme.name) {
+                let md = format!(
+                    "# Available places: {} ({})\n\n{}\n---\n*Source: [{}]({})*",
+                    programme.name, programme.level, capacity, url, url
+                );
+                return Ok(CallToolResult::success(vec![Content::text(md)]));
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "The number of available places for {} ({}) is not published on the programme page or the enrolment page. It is announced in the call for enrolment; Student Services can confirm it.\n\n---\n*Sources: [{}]({}), [{}]({})*",
+            programme.name, programme.level, programme.url, programme.url, ENROLMENT_URL, ENROLMENT_URL
+        ))]))
+    }
+
+    #[tool(
+        name = "get_admission_scores",
+        description = "Returns past admission score thresholds of a study programme: the minimum and average entrance points of admitted candidates per academic year and audience (Slovenian, EU, non-EU citizens), as published on the programme or enrolment page, with source URLs. Reports when no thresholds are published."
+    )]
+    pub async fn get_admission_scores(
+        &self,
+        Parameters(request): Parameters<AdmissionScoresRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let mut sections = vec![];
+        for url in [programme.url.as_str(), ENROLMENT_URL] {
+            let html = match get_page(url).await {
+                Ok(html) => html,
+                Err(e) => {
+                    eprintln!("Could not fetch {}: {}", url, e);
+                    continue;
+                }
+            };
+            if let Some(scores) = AdmissionScores::parse(&html, &programme.name, url, url == programme.url) {
+                sections.push(scores.to_string());
+            }
+        }
+
+        if sections.is_empty() {
+            return Ok(not_found(format!(
+                "No admission score thresholds for {} ({}) are published on the programme page or the enrolment page. Thresholds only exist for years in which enrolment was limited; Student Services can confirm past thresholds.\n\n---\n*Sources: [{}]({}), [{}]({})*",
+                programme.name, programme.level, programme.url, programme.url, ENROLMENT_URL, ENROLMENT_URL
+            )));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "# Admission scores: {} ({})\n\n{}",
+            programme.name,
+            programme.level,
+            sections.join("\n\n")
+        ))]))
+    }
+
+    #[tool(
+        name = "get_course_timetable",
+        description = "Returns when and where a course meets in the current semester: day, time, room, group and type (lecture, tutorial, ...) of each scheduled slot, one row per group, with source URLs. Optionally pass 'programme' (and 'level') to also search the programme's own timetable. Reports when the timetable is not published yet."
+    )]
+    pub async fn get_course_timetable(
+        &self,
+        Parameters(request): Parameters<CourseTimetableRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let mut pages = vec![TIMETABLE_URL.to_string()];
+        if let Some(name) = request.programme.as_deref() {
+            match self.resolve_programme(name, request.level.as_deref()).await {
+                Ok(programme) => pages.push(programme.url),
+                Err(response) => return Ok(response),
+            }
+        }
+
+        let mut slots: Vec<TimetableSlot> = vec![];
+        let mut linked = vec![];
+        for url in &pages {
+            match get_page(url.as_str()).await {
+                Ok(html) => {
+                    slots.extend(parse_timetable(&html, url));
+                    linked.extend(timetable_links(&html, url));
+                }
+                Err(e) => eprintln!("Could not fetch {}: {}", url, e),
+            }
+        }
+        linked.retain(|url| !pages.contains(url));
+        linked.dedup();
+        for url in linked.into_iter().take(MAX_TIMETABLE_PAGES) {
+            match get_page(url.as_str()).await {
+                Ok(html) => slots.extend(parse_timetable(&html, &url)),
+                Err(e) => eprintln!("Could not fetch {}: {}", url, e),
+            }
+            pages.push(url);
+        }
+
+        let sources: Vec<String> = pages.iter().map(|url| format!("[{}]({})", url, url)).collect();
+        let slots = current_term(slots, chrono::Local::now().date_naive());
+        if slots.is_empty() {
+            return Ok(not_found(format!(
+                "No timetable for the current semester is published on the checked pages yet. Timetables usually appear shortly before the semester starts.\n\n---\n*Sources: {}*",
+                sources.join(", ")
+            )));
+        }
+
+        let mut names: Vec<String> = slots.iter().map(|slot| slot.course.clone()).collect();
+        names.sort();
+        names.dedup();
+        let best = rank_names_scored(names, &request.course, NameMetric::from_env())
+            .into_iter()
+            .next()
+            .filter(|(_, score)| *score >= COURSE_MATCH_THRESHOLD);
+        let Some((course, _)) = best else {
+            return Ok(not_found(format!(
+                "'{}' is not scheduled in the published timetable of the current semester. It may be taught in the other semester, or its slots are not published yet.\n\n---\n*Sources: {}*",
+                request.course,
+                sources.join(", ")
+            )));
+        };
+
+        let course_slots: Vec<&TimetableSlot> = slots.iter().filter(|slot| slot.course == course).collect();
+        let mut md = format!("# Timetable: {}\n\n", course);
+        if let Some(term) = course_slots.iter().find_map(|slot| slot.term.as_deref()) {
+            md.push_str(&format!("*{}*\n\n", term));
+        }
+        md.push_str("| Day | Time | Room | Group | Type |\n|---|---|---|---|---|\n");
+        for slot in &course_slots {
+            md.push_str(&format!("{}\n", slot));
+        }
+
+        let mut slot_sources: Vec<&str> = course_slots.iter().map(|slot| slot.source.as_str()).collect();
+        slot_sources.dedup();
+        let slot_sources: Vec<String> = slot_sources.iter().map(|url| format!("[{}]({})", url, url)).collect();
+        md.push_str(&format!("\n---\n*Source: {}*", slot_sources.join(", ")));
+
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "get_programme_partners",
+        description = "Returns the partner companies and institutions that host field work (practical training, internships) for a study programme, with links where available, the programme's field work description and source URLs. Follows a linked partner list if the programme page has one. Reports when the programme lists no partners."
+    )]
+    pub async fn get_programme_partners(
+        &self,
+        Parameters(request): Parameters<ProgrammePartnersRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let html = match get_page(programme.url.as_str()).await {
+            Ok(html) => html,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+        let mut partners = ProgrammePartners::parse(&html, &programme.url);
+
+        // documents (PDF, Word) are only linked, not read
+        let list_pages: Vec<String> = partners
+            .partner_lists
+            .iter()
+            .filter(|url| !url.to_lowercase().ends_with(".pdf") && !url.to_lowercase().contains(".doc"))
+            .take(MAX_PARTNER_LISTS)
+            .cloned()
+            .collect();
+        for url in list_pages {
+            match get_page(url.as_str()).await {
+                Ok(html) => partners.extend(parse_partner_list(&html, &url)),
+                Err(e) => eprintln!("Could not fetch {}: {}", url, e),
+            }
+        }
+
+        if partners.is_empty() {
+            return Ok(not_found(format!(
+                "The page of {} ({}) does not describe field work or list partner organizations. The programme may not include an internship; the programme coordinator can confirm.\n\n---\n*Source: [{}]({})*",
+                programme.name, programme.level, programme.url, programme.url
+            )));
+        }
+
+        let note = if partners.partners.is_empty() {
+            "No partner organizations are named on the page; only the field work description is available.\n\n"
+        } else {
+            ""
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "# Partners: {} ({})\n\n{}{}",
+            programme.name, programme.level, note, partners
+        ))]))
+    }
+
+    #[tool(
+        name = "get_career_outcomes",
+        description = "Returns published career outcomes of a study programme's graduates (employment rate, time to first job, salaries), each with its source URL. When no statistics are published, returns the programme's description of employment opportunities and says that no statistics exist."
+    )]
+    pub async fn get_career_outcomes(
+        &self,
+        Parameters(request): Parameters<CareerOutcomesRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let html = match get_page(programme.url.as_str()).await {
+            Ok(html) => html,
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+        let mut statistics = parse_career_statistics(&html, &programme.url);
+        let info = ProgrammeInfo::from(html);
+
+        for url in CAREER_URLS {
+            match get_page(url).await {
+                Ok(html) => {
+                    let found: Vec<CareerStatistic> = parse_career_statistics(&html, url)
+                        .into_iter()
+                        .filter(|s| !statistics.iter().any(|known| known.text == s.text))
+                        .collect();
+                    statistics.extend(found);
+                }
+                Err(e) => eprintln!("Could not fetch {}: {}", url, e),
+            }
+        }
+
+        let source = format!("---\n*Source: [{}]({})*", programme.url, programme.url);
+        let opportunities = info.employment_opportunities.join("\n\n");
+
+        if statistics.is_empty() && opportunities.is_empty() {
+            return Ok(not_found(format!(
+                "No career outcomes or employment opportunities are published for {} ({}).\n\n{}",
+                programme.name, programme.level, source
+            )));
+        }
+
+        let mut md = format!("# Career outcomes: {} ({})\n\n", programme.name, programme.level);
+        if statistics.is_empty() {
+            md.push_str("No employment or salary statistics are published for this programme. The programme page describes the employment opportunities of graduates:\n\n");
+        } else {
+            md.push_str(&render_statistics(&statistics));
+            if !opportunities.is_empty() {
+                md.push_str("## Employment opportunities\n\n");
+            }
+        }
+        if !opportunities.is_empty() {
+            md.push_str(&format!("{}\n\n", opportunities));
+        }
+        md.push_str(&source);
+
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "get_international_student_info",
+        description = "Returns information specific to international (foreign, non-EU) applicants in one place: language certificates, recognition of foreign education, visa and residence permit links, and fees for non-EU candidates, each with its source URL. Optionally pass 'programme' (and 'level') to include that programme's page."
+    )]
+    pub async fn get_international_student_info(
+        &self,
+        Parameters(request): Parameters<InternationalStudentInfoRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let mut urls = vec![INTERNATIONAL_STUDENTS_URL.to_string(), ENROLMENT_URL.to_string()];
+        if let Some(name) = request.programme.as_deref() {
+            match self.resolve_programme(name, request.level.as_deref()).await {
+                Ok(programme) => urls.push(programme.url),
+                Err(response) => return Ok(response),
+            }
+        }
+
+        let mut info = InternationalInfo::default();
+        for url in &urls {
+            match get_page(url.as_str()).await {
+                Ok(html) => info.extend(parse_international(&html, url)),
+                Err(e) => eprintln!("Could not fetch {}: {}", url, e),
+            }
+        }
+
+        if info.is_empty() {
+            let links: Vec<String> = urls.iter().map(|url| format!("- [{}]({})", url, url)).collect();
+            return Ok(not_found(format!(
+                "No information for international candidates could be extracted. See:\n{}",
+                links.join("\n")
+            )));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "# Information for international candidates\n\n{}",
+            info
+        ))]))
+    }
+
+    #[tool(
+        name = "list_open_days",
+        description = "Lists upcoming open days and information sessions (informativni dnevi) for prospective students with date, location and registration link, with source URLs. Reports when none are currently scheduled."
+    )]
+    pub async fn list_open_days(
+        &self,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let mut events: Vec<OpenDay> = vec![];
+        let mut fetched = false;
+        for url in OPEN_DAYS_URLS {
+            match get_page(url).await {
+                Ok(html) => {
+                    fetched = true;
+                    for event in parse_open_days(&html, url) {
+                        if !events.iter().any(|e| e.date == event.date && e.title == event.title) {
+                            events.push(event);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Could not fetch open days page {}: {}", url, e),
+            }
+        }
+
+        if !fetched {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve the open days pages.",
+            )]));
+        }
+
+        let events = upcoming(events, chrono::Local::now().date_naive());
+        if events.is_empty() {
+            let links: Vec<String> = OPEN_DAYS_URLS
+                .iter()
+                .map(|url| format!("- [{}]({})", url, url))
+                .collect();
+            return Ok(not_found(format!(
+                "No upcoming open days or information sessions are currently scheduled. New dates are announced on:\n{}",
+                links.join("\n")
+            )));
+        }
+
+        let entries: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "# Upcoming open days and information sessions\n\n{}",
+            entries.join("\n")
+        ))]))
+    }
+
+    #[tool(
+        name = "diff_programme_versions",
+        description = "Tells what changed in a study programme since an earlier version: added, removed and re-credited courses and changed admission, transfer, advancement and completion requirements. Compares the current programme page with the last differing version stored on earlier fetches. Useful for returning students and advisors asking 'what changed'."
+    )]
+    pub async fn diff_programme_versions(
+        &self,
+        Parameters(request): Parameters<DiffProgrammeVersionsRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let programme = match self
+            .resolve_programme(&request.programme, request.level.as_deref())
+            .await
+        {
+            Ok(p) => p,
+            Err(response) => return Ok(response),
+        };
+
+        let html = match get_page(programme.url.as_str()).await {
+            Ok(html) => html,
+            Err(_) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve information for '{}'.",
+                    programme.name
+                ))]));
+            }
+        };
+        let current = ProgrammeSnapshot::from(&ProgrammeInfo::from(html));
+
+        let history = load_history(&programme.url).await;
+        let previous = history.iter().rev().find(|s| !s.same_content(&current)).cloned();
+        let first_seen = history
+            .iter()
+            .rev()
+            .take_while(|s| s.same_content(&current))
+            .last()
+            .map(|s| s.taken_at.clone());
+        if let Err(e) = record_snapshot(&programme.url, &current).await {
+            eprintln!("Could not store a snapshot of {}: {}", programme.url, e);
+        }
+
+        let source = format!("---\n*Source: [{}]({})*", programme.url, programme.url);
+        let Some(previous) = previous else {
+            return Ok(match first_seen {
+                Some(since) => CallToolResult::success(vec![Content::text(format!(
+                    "{} ({}) has not changed since it was first recorded on {}.\n\n{}",
+                    programme.name, programme.level, since, source
+                ))]),
+                None => not_found(format!(
+                    "No earlier version of {} ({}) is stored yet, so there is nothing to compare with. The current version has been saved and later changes will be reported against it.\n\n{}",
+                    programme.name, programme.level, source
+                )),
+            });
+        };
+
+        let diff = ProgrammeDiff::between(&previous, &current);
+        let mut md = format!(
+            "# Changes to {} ({})\n\nCompared with the version recorded on {}",
+            programme.name, programme.level, previous.taken_at
+        );
+        match first_seen {
+            Some(since) => md.push_str(&format!("; the current version was first seen on {}.\n\n", since)),
+            None => md.push_str(".\n\n"),
+        }
+        md.push_str(&diff.to_string());
+        md.push_str(&format!("\n{}", source));
+
+        Ok(CallToolResult::success(vec![Content::text(md)]))
+    }
+
+    #[tool(
+        name = "clear_page_cache",
+        description = "Maintenance tool: clears the cached programme pages so the next requests refetch them from the website. Use only when asked to refresh data after the website changed."
+    )]
+    pub async fn clear_page_cache(
+        &self,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let pages = clear_page_cache().await;
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Cleared {} cached pages.",
+            pages
+        ))]))
+    }
 }
 
 #[tool_handler]
@@ -0,0 +1,209 @@
+//! Extractor for the Erasmus+ / international mobility pages.
+//!
+//! Incoming and outgoing mobility are described on separate pages. Feed the
+//! raw HTML of one of them into `MobilityInfo::parse(html, direction, url)`.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MobilityDirection {
+    Incoming,
+    Outgoing,
+}
+
+impl MobilityDirection {
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "incoming" => Some(Self::Incoming),
+            "outgoing" => Some(Self::Outgoing),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MobilityDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MobilityDirection::Incoming => write!(f, "Incoming mobility (students coming to UP FAMNIT)"),
+            MobilityDirection::Outgoing => write!(f, "Outgoing mobility (UP FAMNIT students going abroad)"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MobilityInfo {
+    pub direction: MobilityDirection,
+    pub title: String,
+    pub source_url: String,
+    pub application_periods: Vec<String>,
+    pub contacts: Vec<String>,
+    pub partner_links: Vec<(String, String)>,
+    pub procedure: Vec<String>,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+
+fn is_application_period(l: &str) -> bool {
+    let l = l.to_lowercase();
+    let mentions_application = l.contains("application") || l.contains("deadline") || l.contains("nomination");
+    let mentions_date = MONTHS.iter().any(|m| l.contains(m)) && l.chars().any(|c| c.is_ascii_digit());
+    mentions_application && mentions_date
+}
+
+fn is_contact(l: &str) -> bool {
+    let l = l.to_lowercase();
+    l.contains('@') || l.contains("phone") || l.contains("+386") || l.contains("coordinator")
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+impl MobilityInfo {
+    pub fn parse(html: &str, direction: MobilityDirection, source_url: &str) -> Self {
+        let doc = Html::parse_document(html);
+        let h1_sel = Selector::parse("h1").unwrap();
+        let line_sel = Selector::parse("div.content p, div.content li").unwrap();
+        let a_sel = Selector::parse("div.content a[href]").unwrap();
+
+        let title = doc
+            .select(&h1_sel)
+            .next()
+            .map(|h| text(&h))
+            .unwrap_or_else(|| "International mobility".into());
+
+        let mut application_periods = Vec::new();
+        let mut contacts = Vec::new();
+        let mut procedure = Vec::new();
+
+        for el in doc.select(&line_sel) {
+            let line = text(&el);
+            if line.is_empty() {
+                continue;
+            }
+            if is_application_period(&line) {
+                application_periods.push(line);
+            } else if is_contact(&line) {
+                contacts.push(line);
+            } else if el.value().name() == "li" {
+                procedure.push(line);
+            }
+        }
+
+        let mut partner_links = Vec::new();
+        for a in doc.select(&a_sel) {
+            let href = a.value().attr("href").unwrap_or_default().to_string();
+            let label = text(&a);
+            if let Some(email) = href.strip_prefix("mailto:") {
+                if !contacts.iter().any(|c| c.contains(email)) {
+                    contacts.push(email.to_string());
+                }
+                continue;
+            }
+            let l = label.to_lowercase();
+            if l.contains("partner") || l.contains("bilateral") || l.contains("inter-institutional") {
+                partner_links.push((label, href));
+            }
+        }
+
+        MobilityInfo {
+            direction,
+            title,
+            source_url: source_url.to_string(),
+            application_periods,
+            contacts,
+            partner_links,
+            procedure,
+        }
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl fmt::Display for MobilityInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut md = String::new();
+        let write_list = |md: &mut String, title: &str, items: &[String]| {
+            if !items.is_empty() {
+                writeln!(md, "### {}\n", title).unwrap();
+                for item in items {
+                    writeln!(md, "- {}", item).unwrap();
+                }
+                writeln!(md).unwrap();
+            }
+        };
+
+        writeln!(&mut md, "## {}\n", self.direction).unwrap();
+        writeln!(&mut md, "**Page:** {}\n", self.title).unwrap();
+        write_list(&mut md, "Application periods", &self.application_periods);
+        write_list(&mut md, "Contacts", &self.contacts);
+        if !self.partner_links.is_empty() {
+            writeln!(&mut md, "### Partner institutions\n").unwrap();
+            for (label, href) in &self.partner_links {
+                writeln!(&mut md, "- [{}]({})", label, href).unwrap();
+            }
+            writeln!(&mut md).unwrap();
+        }
+        write_list(&mut md, "Procedure", &self.procedure);
+        writeln!(&mut md, "*Source: [{}]({})*", self.source_url, self.source_url).unwrap();
+
+        write!(f, "{}", md)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTGOING_PAGE: &str = r#"<html><body>
+<h1>Erasmus+ for students</h1>
+<div class="content">
+<p>Applications for the winter semester are open until 15 March 2025.</p>
+<p>Erasmus coordinator: Ana Novak, phone +386 5 611 75 80</p>
+<ul>
+<li>Choose a host university from the list of partners.</li>
+<li>Prepare the learning agreement.</li>
+</ul>
+<p><a href="mailto:erasmus@famnit.upr.si">Write to us</a>
+<a href="/en/partners">List of partner institutions</a>
+<a href="/en/news">News</a></p>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn outgoing_page_is_split_into_periods_contacts_and_steps() {
+        let info = MobilityInfo::parse(OUTGOING_PAGE, MobilityDirection::Outgoing, "https://www.famnit.upr.si/en/erasmus");
+
+        assert_eq!(info.title, "Erasmus+ for students");
+        assert_eq!(info.application_periods, ["Applications for the winter semester are open until 15 March 2025."]);
+        assert_eq!(
+            info.contacts,
+            ["Erasmus coordinator: Ana Novak, phone +386 5 611 75 80", "erasmus@famnit.upr.si"]
+        );
+        assert_eq!(info.procedure.len(), 2);
+        assert_eq!(
+            info.partner_links,
+            [("List of partner institutions".to_string(), "/en/partners".to_string())]
+        );
+        assert!(info.to_string().starts_with("## Outgoing mobility"));
+    }
+}
@@ -0,0 +1,269 @@
+//! Extractor for open days and information sessions (informativni dnevi).
+//!
+//! Events are listed as list items, table rows or paragraphs, each with a
+//! date somewhere in its text. Feed the raw HTML into
+//! `parse_open_days(html, url)` and keep the upcoming ones with
+//! `upcoming(events, today)`.
+
+use chrono::NaiveDate;
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone)]
+pub struct OpenDay {
+    pub title: String,
+    pub date: NaiveDate,
+    pub location: Option<String>,
+    pub registration_url: Option<String>,
+    pub source: String,
+}
+
+const KEYWORDS: [&str; 7] = [
+    "open day",
+    "information day",
+    "info day",
+    "information session",
+    "informativni dan",
+    "informativni dnevi",
+    "dan odprtih vrat",
+];
+
+const MONTHS: [(&str, &str, u32); 12] = [
+    ("january", "januar", 1),
+    ("february", "februar", 2),
+    ("march", "marec", 3),
+    ("april", "april", 4),
+    ("may", "maj", 5),
+    ("june", "junij", 6),
+    ("july", "julij", 7),
+    ("august", "avgust", 8),
+    ("september", "september", 9),
+    ("october", "oktober", 10),
+    ("november", "november", 11),
+    ("december", "december", 12),
+];
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_open_day(s: &str) -> bool {
+    let lower = s.to_lowercase();
+    KEYWORDS.iter().any(|k| lower.contains(k))
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    let word = word.to_lowercase();
+    // Slovenian month names are inflected ("februarja"), so match on prefixes
+    MONTHS
+        .iter()
+        .find(|(en, sl, _)| word.starts_with(en) || word.starts_with(&sl[..3]))
+        .map(|(_, _, n)| *n)
+}
+
+/// First date in `s`, written as `12. 2. 2025`, `12.02.2025`, `2025-02-12`,
+/// `12 February 2025`, `February 12, 2025` or `12. februarja 2025`.
+pub fn find_date(s: &str) -> Option<NaiveDate> {
+    let tokens: Vec<&str> = s
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '(' || c == ')')
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    for (i, token) in tokens.iter().enumerate() {
+        // single-token numeric forms
+        let parts: Vec<&str> = token.trim_end_matches('.').split(['.', '-', '/']).collect();
+        if let [a, b, c] = parts.as_slice() {
+            if let (Ok(a), Ok(b), Ok(c)) = (a.parse::<i32>(), b.parse::<u32>(), c.parse::<i32>()) {
+                let date = if a > 31 {
+                    NaiveDate::from_ymd_opt(a, b, c as u32)
+                } else {
+                    NaiveDate::from_ymd_opt(c, b, a as u32)
+                };
+                if date.is_some() {
+                    return date;
+                }
+            }
+        }
+
+        let number = |t: &str| t.trim_end_matches('.').parse::<u32>().ok();
+        let year = |t: &str| t.trim_end_matches('.').parse::<i32>().ok().filter(|y| *y > 1900);
+        let rest = &tokens[i + 1..];
+
+        // "12. 2. 2025"
+        if let (Some(day), [m, y, ..]) = (number(token), rest) {
+            if let (Some(month), Some(year)) = (number(m), year(y)) {
+                if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                    return Some(date);
+                }
+            }
+            // "12 February 2025", "12. februarja 2025"
+            if let (Some(month), Some(year)) = (month_number(m), year(y)) {
+                if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                    return Some(date);
+                }
+            }
+        }
+        // "February 12, 2025"
+        if let (Some(month), [d, y, ..]) = (month_number(token), rest) {
+            if let (Some(day), Some(year)) = (number(d), year(y)) {
+                if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+                    return Some(date);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Value following a label such as "Location:" or "Kraj:".
+fn labelled_value(s: &str, labels: &[&str]) -> Option<String> {
+    let lower = s.to_lowercase();
+    labels.iter().find_map(|label| {
+        let start = lower.find(label)? + label.len();
+        let value = s.get(start..)?.trim_start_matches([':', ' ']);
+        let end = value.find([';', '|']).unwrap_or(value.len());
+        let value = value[..end].trim().trim_end_matches('.');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+fn registration_link(er: &ElementRef) -> Option<String> {
+    let a_sel = Selector::parse("a[href]").unwrap();
+    er.select(&a_sel).find_map(|a| {
+        let href = a.value().attr("href")?;
+        let label = text(&a).to_lowercase();
+        let is_registration = ["regist", "sign up", "prijav", "form"]
+            .iter()
+            .any(|k| label.contains(k) || href.to_lowercase().contains(k));
+        (is_registration && href.starts_with("http")).then(|| href.to_string())
+    })
+}
+
+/// Title of an entry: its heading, bold text or link, or else its text with
+/// the labelled parts cut off.
+fn title(er: &ElementRef, line: &str) -> String {
+    let title_sel = Selector::parse("h2, h3, h4, strong, b, a").unwrap();
+    er.select(&title_sel)
+        .map(|t| text(&t))
+        .find(|t| !t.is_empty() && find_date(t).is_none())
+        .unwrap_or_else(|| {
+            line.split(['–', '|', ';'])
+                .next()
+                .unwrap_or(line)
+                .trim()
+                .to_string()
+        })
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+/// All dated open-day entries of a page. On a page dedicated to open days
+/// every dated entry counts; elsewhere the entry itself must name one.
+pub fn parse_open_days(html: &str, url: &str) -> Vec<OpenDay> {
+    let doc = Html::parse_document(html);
+    let h1_sel = Selector::parse("h1").unwrap();
+    let entry_sel = Selector::parse(
+        "div.content li, div.content tr, div.content p, div.event, article",
+    )
+    .unwrap();
+
+    let dedicated_page = doc.select(&h1_sel).next().is_some_and(|h| is_open_day(&text(&h)));
+
+    let mut events: Vec<OpenDay> = vec![];
+    for er in doc.select(&entry_sel) {
+        let line = text(&er);
+        let Some(date) = find_date(&line) else {
+            continue;
+        };
+        if !dedicated_page && !is_open_day(&line) {
+            continue;
+        }
+        let title = title(&er, &line);
+        if events.iter().any(|e| e.date == date && e.title == title) {
+            continue;
+        }
+        events.push(OpenDay {
+            title,
+            date,
+            location: labelled_value(&line, &["location", "venue", "lokacija", "kraj"]),
+            registration_url: registration_link(&er),
+            source: url.to_string(),
+        });
+    }
+    events
+}
+
+/// Events on or after `today`, soonest first.
+pub fn upcoming(mut events: Vec<OpenDay>, today: NaiveDate) -> Vec<OpenDay> {
+    events.retain(|e| e.date >= today);
+    events.sort_by_key(|e| e.date);
+    events
+}
+
+impl fmt::Display for OpenDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "- **{}** – {}", self.title, self.date.format("%d. %m. %Y"))?;
+        if let Some(location) = &self.location {
+            writeln!(f, "  - Location: {}", location)?;
+        }
+        if let Some(url) = &self.registration_url {
+            writeln!(f, "  - Registration: [{}]({})", url, url)?;
+        }
+        write!(f, "  - Source: [{}]({})", self.source, self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEWS_PAGE: &str = r#"<html><body>
+<h1>News</h1>
+<div class="content">
+<ul>
+<li><strong>Information day for bachelor programmes</strong> – 14. 2. 2025, Location: Glagoljaška 8, Koper; <a href="https://www.famnit.upr.si/en/registration">Register here</a></li>
+<li>Information day for master programmes – February 28, 2025</li>
+<li>Graduation ceremony – 20 December 2024</li>
+</ul>
+<p>Open day was held on 12. februarja 2024.</p>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn date_formats_of_the_site_are_read() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d);
+
+        assert_eq!(find_date("14. 2. 2025"), date(2025, 2, 14));
+        assert_eq!(find_date("on 2025-02-14"), date(2025, 2, 14));
+        assert_eq!(find_date("February 28, 2025"), date(2025, 2, 28));
+        assert_eq!(find_date("12. februarja 2024"), date(2024, 2, 12));
+        assert_eq!(find_date("no date here"), None);
+    }
+
+    #[test]
+    fn only_upcoming_open_days_are_kept_in_date_order() {
+        let events = parse_open_days(NEWS_PAGE, "https://www.famnit.upr.si/en/news");
+
+        // the graduation ceremony is dated but is not an open day
+        assert_eq!(events.len(), 3);
+        let first = &events[0];
+        assert_eq!(first.title, "Information day for bachelor programmes");
+        assert_eq!(first.location.as_deref(), Some("Glagoljaška 8, Koper"));
+        assert_eq!(first.registration_url.as_deref(), Some("https://www.famnit.upr.si/en/registration"));
+
+        let upcoming = upcoming(events, NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        let titles: Vec<&str> = upcoming.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Information day for bachelor programmes", "Information day for master programmes"]);
+    }
+}
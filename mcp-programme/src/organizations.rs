@@ -0,0 +1,227 @@
+//! Extractor for the student-life / student organizations page.
+//!
+//! Organizations are listed either as sub-headings followed by a description
+//! or as list items with a link. Feed the raw HTML into
+//! `parse_student_organizations(html)`.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrganizationScope {
+    Famnit,
+    University,
+}
+
+impl fmt::Display for OrganizationScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrganizationScope::Famnit => write!(f, "UP FAMNIT"),
+            OrganizationScope::University => write!(f, "University of Primorska (UP-wide)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StudentOrganization {
+    pub name: String,
+    pub description: String,
+    pub url: Option<String>,
+    pub contact: Option<String>,
+    pub scope: OrganizationScope,
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn scope_of(heading: &str, url: Option<&str>) -> OrganizationScope {
+    let h = heading.to_lowercase();
+    let up_heading = h.contains("university of primorska") || h.contains("up-wide") || h.contains("university-wide");
+    let up_url = url.is_some_and(|u| u.contains("upr.si") && !u.contains("famnit.upr.si"));
+    if up_heading || up_url {
+        OrganizationScope::University
+    } else {
+        OrganizationScope::Famnit
+    }
+}
+
+/// First web link and first mailto link inside an element.
+fn url_and_contact(er: &ElementRef) -> (Option<String>, Option<String>) {
+    let a_sel = Selector::parse("a[href]").unwrap();
+    let mut url = None;
+    let mut contact = None;
+    for a in er.select(&a_sel) {
+        let href = a.value().attr("href").unwrap_or_default();
+        if let Some(email) = href.strip_prefix("mailto:") {
+            contact.get_or_insert(email.to_string());
+        } else if href.starts_with("http") {
+            url.get_or_insert(href.to_string());
+        }
+    }
+    (url, contact)
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+pub fn parse_student_organizations(html: &str) -> Vec<StudentOrganization> {
+    let doc = Html::parse_document(html);
+    let content_sel = Selector::parse("div.content").unwrap();
+    let li_sel = Selector::parse("li").unwrap();
+    let a_sel = Selector::parse("a").unwrap();
+
+    let mut organizations: Vec<StudentOrganization> = Vec::new();
+    let Some(content) = doc.select(&content_sel).next() else {
+        return organizations;
+    };
+
+    let mut section_heading = String::new();
+    // Index of the organization opened by the last sub-heading, if any.
+    let mut open: Option<usize> = None;
+
+    for node in content.children() {
+        let Some(er) = ElementRef::wrap(node) else {
+            continue;
+        };
+        match er.value().name() {
+            "h2" => {
+                section_heading = text(&er);
+                open = None;
+            }
+            "h3" | "h4" => {
+                let name = text(&er);
+                if name.is_empty() {
+                    continue;
+                }
+                let (url, contact) = url_and_contact(&er);
+                organizations.push(StudentOrganization {
+                    scope: scope_of(&section_heading, url.as_deref()),
+                    name,
+                    description: String::new(),
+                    url,
+                    contact,
+                });
+                open = Some(organizations.len() - 1);
+            }
+            "p" => {
+                let Some(org) = open.and_then(|i| organizations.get_mut(i)) else {
+                    continue;
+                };
+                let para = text(&er);
+                if !para.is_empty() {
+                    if !org.description.is_empty() {
+                        org.description.push(' ');
+                    }
+                    org.description.push_str(&para);
+                }
+                let (url, contact) = url_and_contact(&er);
+                if org.url.is_none() {
+                    org.scope = scope_of(&section_heading, url.as_deref());
+                    org.url = url;
+                }
+                if org.contact.is_none() {
+                    org.contact = contact;
+                }
+            }
+            "ul" | "ol" => {
+                open = None;
+                for li in er.select(&li_sel) {
+                    let line = text(&li);
+                    if line.is_empty() {
+                        continue;
+                    }
+                    // "Name – description" or a linked name followed by text
+                    let (name, description) = match li.select(&a_sel).next().map(|a| text(&a)) {
+                        Some(link_text) if !link_text.is_empty() && line.starts_with(&link_text) => {
+                            let rest = line[link_text.len()..].trim_start_matches([' ', '-', '–', ':']);
+                            (link_text, rest.to_string())
+                        }
+                        _ => match line.split_once(" – ").or_else(|| line.split_once(" - ")) {
+                            Some((n, d)) => (n.trim().to_string(), d.trim().to_string()),
+                            None => (line.clone(), String::new()),
+                        },
+                    };
+                    let (url, contact) = url_and_contact(&li);
+                    organizations.push(StudentOrganization {
+                        scope: scope_of(&section_heading, url.as_deref()),
+                        name,
+                        description,
+                        url,
+                        contact,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    organizations
+}
+
+impl fmt::Display for StudentOrganization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.url {
+            Some(url) => write!(f, "- **[{}]({})**", self.name, url)?,
+            None => write!(f, "- **{}**", self.name)?,
+        }
+        if !self.description.is_empty() {
+            write!(f, ": {}", self.description)?;
+        }
+        if let Some(contact) = &self.contact {
+            write!(f, " (contact: {})", contact)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORGANIZATIONS_PAGE: &str = r#"<html><body>
+<div class="content">
+<h2>Student organizations at UP FAMNIT</h2>
+<h3>Student Council</h3>
+<p>Represents the students of the faculty.</p>
+<p>Write to <a href="mailto:ss@famnit.upr.si">ss@famnit.upr.si</a>.</p>
+<ul>
+<li><a href="https://mathclub.famnit.upr.si">Math Club</a> – weekly problem solving</li>
+<li>Chess Club - meets on Fridays</li>
+</ul>
+<h2>University of Primorska</h2>
+<ul>
+<li><a href="https://www.upr.si/en/sport">UP Sport</a>: recreational sport for all students</li>
+</ul>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn headings_and_list_items_become_organizations() {
+        let orgs = parse_student_organizations(ORGANIZATIONS_PAGE);
+
+        let names: Vec<&str> = orgs.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["Student Council", "Math Club", "Chess Club", "UP Sport"]);
+
+        let council = &orgs[0];
+        assert_eq!(council.description, "Represents the students of the faculty. Write to ss@famnit.upr.si .");
+        assert_eq!(council.contact.as_deref(), Some("ss@famnit.upr.si"));
+        assert_eq!(council.scope, OrganizationScope::Famnit);
+
+        assert_eq!(orgs[1].description, "weekly problem solving");
+        assert_eq!(orgs[1].url.as_deref(), Some("https://mathclub.famnit.upr.si"));
+        assert_eq!(orgs[2].description, "meets on Fridays");
+        assert_eq!(orgs[3].description, "recreational sport for all students");
+        assert_eq!(orgs[3].scope, OrganizationScope::University);
+    }
+}
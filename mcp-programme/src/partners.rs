@@ -0,0 +1,321 @@
+//! Extractor for the partner organizations of a study programme.
+//!
+//! Applied programmes describe their field work (practical training,
+//! internships) under its own `<h2>` section of the programme page, naming
+//! host companies in a list, as links, or in a sentence such as "in
+//! cooperation with companies such as X, Y and Z". The section may also link
+//! to a separate page listing all partners. Feed the programme page into
+//! `ProgrammePartners::parse(html, url)` and any linked list page into
+//! `parse_partner_list(html, url)`.
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Partner {
+    pub name: String,
+    pub url: Option<String>,
+    /// Page the partner was found on.
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProgrammePartners {
+    pub partners: Vec<Partner>,
+    /// Field work paragraphs of the programme page, kept for context.
+    pub field_work: Vec<String>,
+    /// Linked pages (or documents) listing the partners.
+    pub partner_lists: Vec<String>,
+    pub source: String,
+}
+
+const SECTION_KEYWORDS: [&str; 8] = [
+    "field work", "practical training", "internship", "placement", "partner", "praksa", "praktično usposabljanje",
+    "partnerj",
+];
+const LIST_LINK_KEYWORDS: [&str; 4] = ["partner", "list of", "seznam", "companies"];
+/// Phrases introducing an enumeration of partner names in running text.
+const ENUMERATION_MARKERS: [&str; 6] = [
+    "such as ", "including ", "e.g. ", "in cooperation with ", "v sodelovanju z ", "kot so ",
+];
+/// List items longer than this are descriptions, not partner names.
+const MAX_NAME_CHARS: usize = 100;
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn contains_any(haystack: &str, needles: &[&str]) -> bool {
+    let haystack = haystack.to_lowercase();
+    needles.iter().any(|n| haystack.contains(n))
+}
+
+fn absolute(href: &str, page_url: &str) -> Option<String> {
+    Url::parse(page_url).ok()?.join(href).ok().map(|u| u.to_string())
+}
+
+/// Links to the faculty or university site point at information pages,
+/// while partners have their own sites.
+fn is_faculty_link(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.ends_with("upr.si")))
+        .unwrap_or(true)
+}
+
+fn is_partner_list_link(label: &str, href: &str) -> bool {
+    contains_any(label, &LIST_LINK_KEYWORDS) || contains_any(href, &["partner"])
+}
+
+/// Capitalized names following an enumeration marker, e.g. "companies such
+/// as Cosylab, Hermes and Luka Koper." gives the three names.
+fn enumerated_names(sentence: &str) -> Vec<String> {
+    let lower = sentence.to_lowercase();
+    let Some(start) = ENUMERATION_MARKERS
+        .iter()
+        .filter_map(|marker| lower.find(marker).map(|i| i + marker.len()))
+        // "in cooperation with companies such as X" enumerates after the last marker
+        .max()
+    else {
+        return vec![];
+    };
+
+    let rest = sentence.get(start..).unwrap_or_default();
+    let rest = rest.split(['.', ';', '(']).next().unwrap_or_default();
+    rest.split(',')
+        .flat_map(|part| part.split(" and "))
+        .map(|name| name.trim().trim_start_matches("the ").trim().to_string())
+        .filter(|name| name.chars().next().is_some_and(char::is_uppercase))
+        .filter(|name| name.chars().count() <= MAX_NAME_CHARS)
+        .collect()
+}
+
+fn push_partner(partners: &mut Vec<Partner>, partner: Partner) {
+    let key = partner.name.to_lowercase();
+    if partner.name.is_empty() || partners.iter().any(|p| p.name.to_lowercase() == key) {
+        return;
+    }
+    partners.push(partner);
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parsers                                                         */
+/* --------------------------------------------------------------------- */
+
+impl ProgrammePartners {
+    pub fn parse(html: &str, url: &str) -> Self {
+        let doc = Html::parse_document(html);
+        let content_sel = Selector::parse("div.content").unwrap();
+        let a_sel = Selector::parse("a[href]").unwrap();
+        let li_sel = Selector::parse("li").unwrap();
+
+        let mut result = ProgrammePartners {
+            source: url.to_string(),
+            ..Default::default()
+        };
+
+        let Some(content) = doc.select(&content_sel).next() else {
+            return result;
+        };
+
+        let mut in_section = false;
+        for er in content.children().filter_map(ElementRef::wrap) {
+            if matches!(er.value().name(), "h2" | "h3") {
+                // an <h3> inside the field work section does not end it
+                if er.value().name() == "h2" || !in_section {
+                    in_section = contains_any(&text(&er), &SECTION_KEYWORDS);
+                }
+                continue;
+            }
+            if !in_section {
+                continue;
+            }
+
+            for a in er.select(&a_sel) {
+                let href = a.value().attr("href").unwrap_or_default();
+                let label = text(&a);
+                if href.starts_with("mailto:") || href.starts_with('#') {
+                    continue;
+                }
+                let Some(link) = absolute(href, url) else {
+                    continue;
+                };
+                if is_partner_list_link(&label, href) {
+                    if !result.partner_lists.contains(&link) {
+                        result.partner_lists.push(link);
+                    }
+                } else if !is_faculty_link(&link) && !label.is_empty() && label.chars().count() <= MAX_NAME_CHARS {
+                    push_partner(
+                        &mut result.partners,
+                        Partner { name: label, url: Some(link), source: url.to_string() },
+                    );
+                }
+            }
+
+            match er.value().name() {
+                "ul" | "ol" => {
+                    for li in er.select(&li_sel) {
+                        let name = text(&li);
+                        if name.chars().count() <= MAX_NAME_CHARS {
+                            push_partner(
+                                &mut result.partners,
+                                Partner { name, url: None, source: url.to_string() },
+                            );
+                        }
+                    }
+                }
+                "p" => {
+                    let paragraph = text(&er);
+                    for name in enumerated_names(&paragraph) {
+                        push_partner(
+                            &mut result.partners,
+                            Partner { name, url: None, source: url.to_string() },
+                        );
+                    }
+                    if !paragraph.is_empty() {
+                        result.field_work.push(paragraph);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        result
+    }
+
+    /// Adds the partners of a linked list page, skipping names already known.
+    pub fn extend(&mut self, partners: Vec<Partner>) {
+        for partner in partners {
+            push_partner(&mut self.partners, partner);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.partners.is_empty() && self.field_work.is_empty() && self.partner_lists.is_empty()
+    }
+}
+
+/// Partners on a dedicated list page: list items and the first cell of
+/// table rows inside the page content, each with its first link.
+pub fn parse_partner_list(html: &str, url: &str) -> Vec<Partner> {
+    let doc = Html::parse_document(html);
+    let item_sel = Selector::parse("div.content li, div.content tr").unwrap();
+    let cell_sel = Selector::parse("td").unwrap();
+    let a_sel = Selector::parse("a[href]").unwrap();
+
+    let mut partners = vec![];
+    for item in doc.select(&item_sel) {
+        let name = match item.value().name() {
+            "tr" => match item.select(&cell_sel).next() {
+                Some(cell) => text(&cell),
+                None => continue,
+            },
+            _ => text(&item),
+        };
+        if name.chars().count() > MAX_NAME_CHARS {
+            continue;
+        }
+        let link = item
+            .select(&a_sel)
+            .filter_map(|a| a.value().attr("href"))
+            .find(|href| !href.starts_with("mailto:") && !href.starts_with('#'))
+            .and_then(|href| absolute(href, url));
+        push_partner(&mut partners, Partner { name, url: link, source: url.to_string() });
+    }
+    partners
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl fmt::Display for ProgrammePartners {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.partners.is_empty() {
+            writeln!(f, "## Partner organizations\n")?;
+            for partner in &self.partners {
+                match &partner.url {
+                    Some(url) => write!(f, "- [{}]({})", partner.name, url)?,
+                    None => write!(f, "- {}", partner.name)?,
+                }
+                if partner.source != self.source {
+                    write!(f, " *(listed on [{}]({}))*", partner.source, partner.source)?;
+                }
+                writeln!(f)?;
+            }
+            writeln!(f)?;
+        }
+
+        if !self.partner_lists.is_empty() {
+            writeln!(f, "## Partner lists\n")?;
+            for list in &self.partner_lists {
+                writeln!(f, "- [{}]({})", list, list)?;
+            }
+            writeln!(f)?;
+        }
+
+        if !self.field_work.is_empty() {
+            writeln!(f, "## Field work\n")?;
+            for paragraph in &self.field_work {
+                writeln!(f, "{}\n", paragraph)?;
+            }
+        }
+
+        write!(f, "---\n*Source: [{}]({})*", self.source, self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAMME_PAGE: &str = r#"<html><body><div class="content">
+<h2>About the programme</h2>
+<p>Students work in cooperation with companies such as Ignore Me.</p>
+<h2>Field work</h2>
+<p>Practical training takes place in cooperation with companies such as Cosylab, Hermes and the Luka Koper.</p>
+<h3>Host companies</h3>
+<ul>
+<li><a href="https://www.result.si">Result</a></li>
+<li>Cosylab</li>
+</ul>
+<p>See the <a href="/en/partners">list of partners</a> or the <a href="https://www.famnit.upr.si/en/students">student pages</a>.</p>
+<h2>Employment opportunities</h2>
+<ul><li>Software developer</li></ul>
+</div></body></html>"#;
+
+    #[test]
+    fn only_the_field_work_section_names_partners() {
+        let partners = ProgrammePartners::parse(PROGRAMME_PAGE, "https://www.famnit.upr.si/en/computer-science");
+
+        let names: Vec<&str> = partners.partners.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Cosylab", "Hermes", "Luka Koper", "Result"]);
+        assert_eq!(partners.partners[3].url.as_deref(), Some("https://www.result.si/"));
+        assert_eq!(partners.partner_lists, ["https://www.famnit.upr.si/en/partners"]);
+        assert_eq!(partners.field_work.len(), 2);
+    }
+
+    #[test]
+    fn list_page_partners_are_merged_without_duplicates() {
+        let list_page = r#"<html><body><div class="content"><table>
+<tr><td><a href="https://www.hermes.si">Hermes</a></td><td>Koper</td></tr>
+<tr><td>Salomon</td><td>Ljubljana</td></tr>
+</table></div></body></html>"#;
+        let mut partners = ProgrammePartners::parse(PROGRAMME_PAGE, "https://www.famnit.upr.si/en/computer-science");
+
+        partners.extend(parse_partner_list(list_page, "https://www.famnit.upr.si/en/partners"));
+
+        assert_eq!(partners.partners.len(), 5);
+        assert!(partners.to_string().contains("- Salomon *(listed on [https://www.famnit.upr.si/en/partners]"));
+    }
+}
@@ -6,16 +6,23 @@
 
 use html_escape::decode_html_entities;
 use scraper::{ElementRef, Html, Selector};
-use std::{collections::{HashMap, HashSet}, fmt::{self, Write}};
+use std::{collections::HashSet, fmt::{self, Write}};
 
 
 
 
+/// Table headings naming a pool of elective courses.
+const ELECTIVE_KEYWORDS: [&str; 3] = ["elective", "optional", "izbirn"];
+
 #[derive(Debug, Clone)]
 pub struct Programme {
     pub name: String,
     pub url: String,
     pub level: ProgrammeLevel,
+    /// Duration shown next to the name on the listing page, e.g. "3 years".
+    pub duration: Option<String>,
+    /// ECTS shown next to the name on the listing page.
+    pub ects: Option<u32>,
 }
 
 
@@ -37,6 +44,56 @@ impl fmt::Display for ProgrammeLevel {
 }
 
 
+/// Language a programme is taught in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Language {
+    Slovenian,
+    English,
+    Other(String),
+}
+
+impl Language {
+    /// Languages named in a "Language of study" value such as "Slovenian
+    /// and English". Unknown names are kept as `Other`.
+    pub fn parse_list(s: &str) -> Vec<Language> {
+        let mut languages = vec![];
+        let lower = s.to_lowercase();
+        for part in lower
+            .split([',', '/', '&', ';'])
+            .flat_map(|p| p.split(" and "))
+            .map(|p| p.trim_matches(|c: char| !c.is_alphabetic()))
+            .filter(|p| !p.is_empty())
+        {
+            let mut found = vec![];
+            if part.contains("sloven") {
+                found.push(Language::Slovenian);
+            }
+            if part.contains("english") || part.contains("angl") {
+                found.push(Language::English);
+            }
+            if found.is_empty() {
+                found.push(Language::Other(part.to_string()));
+            }
+            for language in found {
+                if !languages.contains(&language) {
+                    languages.push(language);
+                }
+            }
+        }
+        languages
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::Slovenian => write!(f, "Slovenian"),
+            Language::English => write!(f, "English"),
+            Language::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
 /// One physical course row inside a timetable.
 ///
 /// *The first two columns (course name & ECTS) are always present; the rest
@@ -64,6 +121,23 @@ pub enum ProgrammeSection {
 
 // Helper to convert from string (which the LLM will provide) to the enum
 impl ProgrammeSection {
+    /// Names accepted by `from_str`, in rendering order.
+    pub const NAMES: [&'static str; 13] = [
+        "general_info",
+        "coordinators",
+        "about",
+        "goals",
+        "course_structure",
+        "field_work",
+        "course_tables",
+        "admission_requirements",
+        "transfer_criteria",
+        "advancement_requirements",
+        "completion_requirements",
+        "competencies",
+        "employment_opportunities",
+    ];
+
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "general_info" => Some(Self::GeneralInfo),
@@ -97,12 +171,49 @@ pub struct CourseRow {
     pub total: String,
 }
 
+/// Whether a course table lists the courses of a study year or a pool of
+/// electives to choose from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CourseTableKind {
+    Mandatory,
+    Elective,
+}
+
+impl fmt::Display for CourseTableKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CourseTableKind::Mandatory => write!(f, "Mandatory"),
+            CourseTableKind::Elective => write!(f, "Elective"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct CourseTable {
     pub title: String,
     pub caption: String,
+    pub kind: CourseTableKind,
     pub rows: Vec<CourseRow>,
 }
+
+impl CourseTable {
+    /// Sum of the ECTS column. Commas are read as decimal points; empty or
+    /// non-numeric cells and a "Total" row of the table itself are skipped.
+    pub fn total_ects(&self) -> f64 {
+        self.rows
+            .iter()
+            .filter(|r| !is_summary_row(&r.course))
+            .filter_map(|r| r.ects.trim().replace(',', ".").parse::<f64>().ok())
+            .sum()
+    }
+
+    fn total_ects_line(&self) -> String {
+        match self.kind {
+            CourseTableKind::Mandatory => format!("**Total ECTS: {}**", self.total_ects()),
+            CourseTableKind::Elective => format!("**Total ECTS of the listed electives: {}**", self.total_ects()),
+        }
+    }
+}
 /// Complete programme record.
 #[derive(Debug)]
 pub struct ProgrammeInfo {
@@ -112,9 +223,13 @@ pub struct ProgrammeInfo {
     pub degree_awarded:    String,
     pub duration:          String,
     pub ects_credits:      String,
+    /// Credits as a number; the lower bound when a range is given.
+    pub ects_total:        Option<u32>,
     pub structure:         String,
     pub mode_of_study:     String,
     pub language_of_study: String,
+    /// `language_of_study` as languages, for filtering.
+    pub languages:         Vec<Language>,
 
     // ── misc links ──────────────────────────────────────────────────────
     pub coordinators:        Vec<(String, String)>,
@@ -142,6 +257,13 @@ pub struct ProgrammeInfo {
 /*  Helper utilities                                                     */
 /* --------------------------------------------------------------------- */
 
+/// A "Total" or "Sum" row closing a course table rather than a course.
+fn is_summary_row(course: &str) -> bool {
+    let lower = course.trim().to_lowercase();
+    let first_word = lower.split(|c: char| !c.is_alphanumeric()).next().unwrap_or_default();
+    ["total", "sum", "skupaj"].contains(&first_word)
+}
+
 /// Case–insensitive *substring* match:  does `haystack` contain *any* `needle`?
 fn heading_is(haystack: &str, needle: &str) -> bool {
     haystack.to_ascii_lowercase()
@@ -153,6 +275,13 @@ fn text(er: &ElementRef) -> String {
     er.text().collect::<Vec<_>>().join(" ").trim().to_string()
 }
 
+/// First whole number in `s`: "180 ECTS" → 180, "180–240" → 180.
+fn leading_number(s: &str) -> Option<u32> {
+    s.split(|c: char| !c.is_ascii_digit())
+        .find(|d| !d.is_empty())
+        .and_then(|d| d.parse().ok())
+}
+
 /// Collect all `<li>` immediate children as trimmed lines.
 fn list_items(list: &ElementRef) -> Vec<String> {
     let li_sel = Selector::parse("li").unwrap();
@@ -181,21 +310,18 @@ impl From<String> for ProgrammeInfo {
 
         /* ---------- 1.  cut the document into sections by <h2> ---------- */
 
-        let mut sections: HashMap<String, Vec<ElementRef>> = HashMap::new();
-        let mut current: Option<String> = None;
+        // Kept in page order; headings may repeat, so every <h2> opens a new
+        // entry instead of being merged by title.
+        let mut sections: Vec<(String, Vec<ElementRef>)> = Vec::new();
 
         if let Some(content) = doc.select(&content_sel).next() {
             for node in content.children() {
                 if let Some(er) = ElementRef::wrap(node) {
                     match er.value().name() {
-                        "h2" => {
-                            let title = text(&er);
-                            current = Some(title.clone());
-                            sections.entry(title).or_default();
-                        }
+                        "h2" => sections.push((text(&er), Vec::new())),
                         _ => {
-                            if let Some(ref key) = current {
-                                sections.entry(key.clone()).or_default().push(er);
+                            if let Some((_, nodes)) = sections.last_mut() {
+                                nodes.push(er);
                             }
                         }
                     }
@@ -234,9 +360,11 @@ impl From<String> for ProgrammeInfo {
         let degree_awarded    = peel("degree awarded");
         let duration          = peel("duration");
         let ects_credits      = peel("ects-credits");
+        let ects_total        = leading_number(&ects_credits);
         let structure         = peel("programme structure");
         let mode_of_study     = peel("mode of study");
         let language_of_study = peel("language of study");
+        let languages         = Language::parse_list(&language_of_study);
 
         /* ---------- 3.  Links & coordinators --------------------------- */
 
@@ -273,36 +401,33 @@ impl From<String> for ProgrammeInfo {
 
         /* ---------- 4.  Helper closures over sections ------------------ */
 
-        let grab_paragraphs = |title_keyword: &str| -> Vec<String> {
+        // Nodes of every section whose heading matches, in page order.
+        let section_nodes = |title_keyword: &str| -> Vec<ElementRef> {
             sections
                 .iter()
-                .find(|(k, _)| heading_is(k, title_keyword))
-                .map(|(_, nodes)| {
-                    nodes
-                        .iter()
-                        .filter(|er| er.value().name() == "p")
-                        .map(|p| text(p))
-                        .filter(|s| !s.is_empty())
-                        .collect()
-                })
-                .unwrap_or_default()
+                .filter(|(k, _)| heading_is(k, title_keyword))
+                .flat_map(|(_, nodes)| nodes.iter().copied())
+                .collect()
+        };
+
+        let grab_paragraphs = |title_keyword: &str| -> Vec<String> {
+            section_nodes(title_keyword)
+                .iter()
+                .filter(|er| er.value().name() == "p")
+                .map(|p| text(p))
+                .filter(|s| !s.is_empty())
+                .collect()
         };
 
         let grab_list = |title_keyword: &str| -> Vec<String> {
-            sections
+            section_nodes(title_keyword)
                 .iter()
-                .find(|(k, _)| heading_is(k, title_keyword))
-                .map(|(_, nodes)| {
-                    nodes
-                        .iter()
-                        .filter(|er| {
-                            let n = er.value().name();
-                            n == "ul" || n == "ol"
-                        })
-                        .flat_map(|ul| list_items(ul))
-                        .collect()
+                .filter(|er| {
+                    let n = er.value().name();
+                    n == "ul" || n == "ol"
                 })
-                .unwrap_or_default()
+                .flat_map(|ul| list_items(ul))
+                .collect()
         };
 
         /* ---------- 5.  Tables (div.medium + table) -------------------- */
@@ -364,27 +489,28 @@ impl From<String> for ProgrammeInfo {
                 continue;
             }
 
-            for tr in table.select(&row_sel) {
-                let cells: Vec<String> = tr
-                    .select(&td_sel)
-                    .map(|td| text(&td))
-                    .collect::<Vec<_>>();
-
-                if cells.len() < 2 {
-                    continue;
-                }
-
-                let mut data_offset = 0;
-                if cells[0].ends_with('.') && cells[0].trim_end_matches('.').parse::<u32>().is_ok() {
-                    data_offset = 1;
-                }
+            // cells of each row, without a leading "1." row number
+            let table_rows: Vec<Vec<String>> = table
+                .select(&row_sel)
+                .filter_map(|tr| {
+                    let mut cells: Vec<String> = tr.select(&td_sel).map(|td| text(&td)).collect();
+                    if cells.len() < 2 {
+                        return None;
+                    }
+                    if cells[0].ends_with('.') && cells[0].trim_end_matches('.').parse::<u32>().is_ok() {
+                        cells.remove(0);
+                    }
+                    Some(cells)
+                })
+                .collect();
 
-                let get_cell = |n: usize| -> String {
-                    cells.get(data_offset + n).cloned().unwrap_or_else(String::new)
-                };
+            // elective pools list only "Course | ECTS | Total" (or less); in a
+            // wide table a short row is a total or note spanning the columns
+            let narrow = table_rows.iter().all(|cells| cells.len() < 6);
+            for cells in &table_rows {
+                let get_cell = |n: usize| -> String { cells.get(n).cloned().unwrap_or_default() };
+                let num_data_cols = cells.len();
 
-                let num_data_cols = cells.len() - data_offset;
-                
                 if num_data_cols >= 6 {
                     rows.push(CourseRow {
                         course: get_cell(0),
@@ -396,11 +522,28 @@ impl From<String> for ProgrammeInfo {
                         extra:  if num_data_cols > 7 { Some(get_cell(6)) } else { None },
                         total:  if num_data_cols > 7 { get_cell(7) } else { get_cell(6) },
                     });
+                } else if narrow && !get_cell(0).is_empty() && !is_summary_row(&get_cell(0)) {
+                    rows.push(CourseRow {
+                        course: get_cell(0),
+                        ects:   get_cell(1),
+                        l:      String::new(),
+                        s:      String::new(),
+                        t:      String::new(),
+                        lw:     String::new(),
+                        extra:  None,
+                        total:  if num_data_cols > 2 { get_cell(num_data_cols - 1) } else { String::new() },
+                    });
                 }
             }
 
             if !rows.is_empty() {
-                course_tables.push(CourseTable { title: heading, rows, caption });
+                let lower = heading.to_lowercase();
+                let kind = if narrow || ELECTIVE_KEYWORDS.iter().any(|k| lower.contains(k)) {
+                    CourseTableKind::Elective
+                } else {
+                    CourseTableKind::Mandatory
+                };
+                course_tables.push(CourseTable { title: heading, rows, caption, kind });
             }
         }
         /* ---------- 6.  Remaining narrative sections ------------------- */
@@ -433,15 +576,10 @@ impl From<String> for ProgrammeInfo {
             .collect::<Vec<_>>();
 
         // after the first <h3>   we collect next <ul>
-        let competencies_subject = sections
+        let competencies_subject = section_nodes("graduate competencies")
             .iter()
-            .find(|(k, _)| heading_is(k, "graduate competencies"))
-            .and_then(|(_, nodes)| {
-                nodes
-                    .iter()
-                    .filter(|er| er.value().name() == "ul" || er.value().name() == "ol")
-                    .nth(1)
-            })
+            .filter(|er| er.value().name() == "ul" || er.value().name() == "ol")
+            .nth(1)
             .map(|ul| list_items(ul))
             .unwrap_or_default();
 
@@ -455,9 +593,11 @@ impl From<String> for ProgrammeInfo {
             degree_awarded,
             duration,
             ects_credits,
+            ects_total,
             structure,
             mode_of_study,
             language_of_study,
+            languages,
             coordinators,
             student_services,
             course_description,
@@ -552,7 +692,16 @@ impl ProgrammeInfo {
         if should_render(&ProgrammeSection::CourseTables) {
             writeln!(&mut f, "## Course tables").unwrap();
             for table in &self.course_tables {
-                writeln!(&mut f, "### {}\n", table.title).unwrap();
+                writeln!(&mut f, "### {} ({})\n", table.title, table.kind).unwrap();
+                let has_hours = table.rows.iter().any(|r| !(r.l.is_empty() && r.s.is_empty() && r.t.is_empty() && r.lw.is_empty()));
+                if !has_hours {
+                    writeln!(&mut f, "| Course | ECTS | Total |\n|---|---|---|").unwrap();
+                    for row in &table.rows {
+                        writeln!(&mut f, "| {} | {} | {} |", row.course, row.ects, row.total).unwrap();
+                    }
+                    writeln!(&mut f, "\n{}\n\n{}\n", table.total_ects_line(), table.caption).unwrap();
+                    continue;
+                }
                 let has_extra = table.rows.first().and_then(|r| r.extra.as_ref()).is_some();
                 writeln!(&mut f, "| Course | ECTS | L | S | T | LW{}| Total |", if has_extra { " | Extra " } else { "" }).unwrap();
                 writeln!(&mut f, "|---|---|---|---|---|---{}|---|", if has_extra { "|---" } else { "" }).unwrap();
@@ -563,7 +712,7 @@ impl ProgrammeInfo {
                     }
                     writeln!(&mut f, " | {} |", row.total).unwrap();
                 }
-                writeln!(&mut f, "\n{}\n", table.caption).unwrap();
+                writeln!(&mut f, "\n{}\n\n{}\n", table.total_ects_line(), table.caption).unwrap();
             }
         }
 
@@ -614,4 +763,149 @@ impl fmt::Display for ProgrammeInfo {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.to_markdown(None))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COURSE_TABLES_PAGE: &str = r#"<html><body>
+<h1>Computer Science</h1>
+<div class="content">
+<h2>Course structure</h2>
+<h3>1st year</h3>
+<table>
+<tr><th>No.</th><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>1.</td><td>Programming I</td><td>6</td><td>45</td><td>0</td><td>30</td><td>0</td><td>180</td></tr>
+<tr><td>2.</td><td>Discrete Structures I</td><td>6</td><td>45</td><td>0</td><td>45</td><td>0</td><td>180</td></tr>
+<tr><td colspan="2">Total</td><td colspan="6">12</td></tr>
+</table>
+<h3>Elective courses</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>Total</th></tr>
+<tr><td>Computer Graphics</td><td>6</td><td>180</td></tr>
+<tr><td>Summer School of Algorithms</td><td>3</td><td>90</td></tr>
+<tr><td>Total</td><td>9</td><td>270</td></tr>
+</table>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn wide_table_skips_its_total_row() {
+        let info = ProgrammeInfo::from(COURSE_TABLES_PAGE.to_string());
+
+        let wide = &info.course_tables[0];
+        assert_eq!(wide.title, "1st year");
+        assert_eq!(wide.kind, CourseTableKind::Mandatory);
+        let courses: Vec<&str> = wide.rows.iter().map(|r| r.course.as_str()).collect();
+        assert_eq!(courses, ["Programming I", "Discrete Structures I"]);
+        assert_eq!(wide.total_ects(), 12.0);
+    }
+
+    #[test]
+    fn narrow_table_keeps_courses_but_not_its_total_row() {
+        let info = ProgrammeInfo::from(COURSE_TABLES_PAGE.to_string());
+
+        let narrow = &info.course_tables[1];
+        assert_eq!(narrow.kind, CourseTableKind::Elective);
+        let courses: Vec<&str> = narrow.rows.iter().map(|r| r.course.as_str()).collect();
+        assert_eq!(courses, ["Computer Graphics", "Summer School of Algorithms"]);
+        assert_eq!(narrow.rows[0].total, "180");
+    }
+
+    #[test]
+    fn repeated_section_headings_are_all_captured() {
+        let page = r#"<html><body><h1>Computer Science</h1><div class="content">
+<h2>Admission requirements</h2>
+<ul><li>Completed secondary school (matura)</li></ul>
+<h2>About the programme</h2>
+<p>Three years of study.</p>
+<h2>Admission requirements</h2>
+<ul><li>Candidates with foreign education need recognition</li></ul>
+</div></body></html>"#;
+
+        let info = ProgrammeInfo::from(page.to_string());
+
+        assert_eq!(
+            info.admission_requirements,
+            ["Completed secondary school (matura)", "Candidates with foreign education need recognition"]
+        );
+        assert_eq!(info.about, ["Three years of study."]);
+    }
+
+    #[test]
+    fn sections_filter_renders_only_the_requested_sections() {
+        let info = ProgrammeInfo::from(COURSE_TABLES_PAGE.to_string());
+        let only_tables = HashSet::from([ProgrammeSection::CourseTables]);
+
+        let filtered = info.to_markdown(Some(&only_tables));
+        let full = info.to_markdown(Some(&HashSet::new()));
+
+        assert!(filtered.contains("## Course tables"));
+        assert!(!filtered.contains("**Type:**"));
+        assert!(!filtered.contains("## Programme coordinator(s)"));
+        assert!(full.contains("**Type:**"));
+    }
+
+    #[test]
+    fn every_listed_section_name_is_accepted() {
+        for name in ProgrammeSection::NAMES {
+            assert!(ProgrammeSection::from_str(name).is_some(), "{}", name);
+        }
+        assert_eq!(ProgrammeSection::from_str("Course_Tables"), Some(ProgrammeSection::CourseTables));
+        assert_eq!(ProgrammeSection::from_str("syllabus"), None);
+    }
+
+    fn with_general_info(lines: &str) -> ProgrammeInfo {
+        ProgrammeInfo::from(format!(
+            r#"<html><body><h1>Mathematics</h1><div class="content"><p>{}</p></div></body></html>"#,
+            lines
+        ))
+    }
+
+    #[test]
+    fn ects_credits_are_read_in_every_published_format() {
+        let cases = [
+            ("ECTS-credits: 180", Some(180)),
+            ("Type of programme: first-cycle<br>ECTS-credits: 240 ECTS", Some(240)),
+            ("ECTS-credits: 60–120", Some(60)),
+            ("ECTS-credits: 120—180 ECTS", Some(120)),
+            ("Duration: 3 years", None),
+        ];
+
+        for (lines, expected) in cases {
+            assert_eq!(with_general_info(lines).ects_total, expected, "{}", lines);
+        }
+    }
+
+    #[test]
+    fn total_ects_reads_comma_decimals_and_skips_empty_cells() {
+        let page = r#"<html><body><h1>Mathematics</h1><div class="content">
+<h3>1st year</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>Analysis I</td><td>6</td><td>45</td><td>0</td><td>45</td><td>0</td><td>180</td></tr>
+<tr><td>Seminar</td><td>7,5</td><td>0</td><td>30</td><td>0</td><td>0</td><td>225</td></tr>
+<tr><td>Elective course</td><td></td><td></td><td></td><td></td><td></td><td></td></tr>
+</table>
+</div></body></html>"#;
+
+        let info = ProgrammeInfo::from(page.to_string());
+
+        assert_eq!(info.course_tables[0].total_ects(), 13.5);
+        assert!(info.to_string().contains("**Total ECTS: 13.5**"));
+    }
+
+    #[test]
+    fn language_of_study_is_read_in_each_phrasing() {
+        assert_eq!(Language::parse_list("Slovenian and English"), [Language::Slovenian, Language::English]);
+        assert_eq!(Language::parse_list("English"), [Language::English]);
+        assert_eq!(
+            Language::parse_list("slovenski / angleški jezik; Italian"),
+            [Language::Slovenian, Language::English, Language::Other("italian".into())]
+        );
+        assert_eq!(
+            with_general_info("Language of study: Slovenian, English").languages,
+            [Language::Slovenian, Language::English]
+        );
+    }
+}
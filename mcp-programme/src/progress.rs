@@ -0,0 +1,236 @@
+//! Remaining courses and ECTS to graduation, computed from the per-year
+//! course tables of a programme page.
+//!
+//! Year tables ("1st year", "Second year", ...) are matched by their title.
+//! Elective placeholder rows are summed into an ECTS amount instead of being
+//! listed as courses, because the student picks them from a separate pool.
+
+use std::fmt;
+
+use crate::programme::{CourseRow, CourseTable, CourseTableKind, ProgrammeInfo};
+
+#[derive(Debug, Clone)]
+pub struct YearPlan {
+    pub year: u32,
+    pub compulsory: Vec<(String, f32)>,
+    pub elective_ects: f32,
+}
+
+impl YearPlan {
+    pub fn ects(&self) -> f32 {
+        self.compulsory.iter().map(|(_, e)| e).sum::<f32>() + self.elective_ects
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RemainingPlan {
+    pub programme: String,
+    pub completed_years: u32,
+    pub total_years: u32,
+    pub remaining: Vec<YearPlan>,
+    pub completed_ects: f32,
+    /// ECTS of the whole programme as stated on its page.
+    pub programme_ects: Option<u32>,
+}
+
+impl RemainingPlan {
+    pub fn remaining_ects(&self) -> f32 {
+        self.remaining.iter().map(YearPlan::ects).sum()
+    }
+
+    pub fn remaining_courses(&self) -> usize {
+        self.remaining.iter().map(|y| y.compulsory.len()).sum()
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+/// Study year a course table belongs to, read from its title.
+pub(crate) fn table_year(title: &str) -> Option<u32> {
+    let t = title.to_lowercase();
+    if !t.contains("year") && !t.contains("letnik") {
+        return None;
+    }
+    let words = [("first", 1), ("second", 2), ("third", 3), ("fourth", 4), ("fifth", 5)];
+    if let Some((_, y)) = words.iter().find(|(w, _)| t.contains(w)) {
+        return Some(*y);
+    }
+    t.split(|c: char| !c.is_ascii_digit())
+        .find(|d| !d.is_empty())
+        .and_then(|d| d.parse().ok())
+}
+
+pub(crate) fn parse_ects(s: &str) -> Option<f32> {
+    s.trim().replace(',', ".").parse().ok()
+}
+
+fn is_elective(row: &CourseRow) -> bool {
+    let c = row.course.to_lowercase();
+    c.contains("elective") || c.contains("izbirni")
+}
+
+fn year_plan(year: u32, tables: &[&CourseTable]) -> YearPlan {
+    let mut plan = YearPlan {
+        year,
+        compulsory: vec![],
+        elective_ects: 0.0,
+    };
+    for row in tables.iter().flat_map(|t| &t.rows) {
+        // summary rows ("Total") are not courses
+        if row.course.to_lowercase().starts_with("total") {
+            continue;
+        }
+        let Some(ects) = parse_ects(&row.ects) else {
+            continue;
+        };
+        if is_elective(row) {
+            plan.elective_ects += ects;
+        } else {
+            plan.compulsory.push((row.course.clone(), ects));
+        }
+    }
+    plan
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main computation                                                     */
+/* --------------------------------------------------------------------- */
+
+/// Courses and ECTS left after `completed_years` full study years.
+///
+/// Returns `None` when the page has no course tables that can be assigned
+/// to a study year.
+pub fn remaining_requirements(info: &ProgrammeInfo, completed_years: u32) -> Option<RemainingPlan> {
+    let mut years: Vec<u32> = info
+        .course_tables
+        .iter()
+        // elective pools are options to choose from, not part of the year
+        .filter(|t| t.kind == CourseTableKind::Mandatory)
+        .filter_map(|t| table_year(&t.title))
+        .collect();
+    years.sort_unstable();
+    years.dedup();
+    let total_years = *years.last()?;
+
+    let mut remaining = vec![];
+    let mut completed_ects = 0.0;
+    for year in years {
+        let tables: Vec<&CourseTable> = info
+            .course_tables
+            .iter()
+            .filter(|t| t.kind == CourseTableKind::Mandatory && table_year(&t.title) == Some(year))
+            .collect();
+        let plan = year_plan(year, &tables);
+        if year <= completed_years {
+            completed_ects += plan.ects();
+        } else {
+            remaining.push(plan);
+        }
+    }
+
+    Some(RemainingPlan {
+        programme: info.name.clone(),
+        completed_years: completed_years.min(total_years),
+        total_years,
+        remaining,
+        completed_ects,
+        programme_ects: info.ects_total,
+    })
+}
+
+impl fmt::Display for RemainingPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Remaining requirements: {}", self.programme)?;
+        match self.programme_ects {
+            Some(total) => writeln!(
+                f,
+                "\nCompleted years: {} of {} ({} of {} ECTS)",
+                self.completed_years, self.total_years, self.completed_ects, total
+            )?,
+            None => writeln!(
+                f,
+                "\nCompleted years: {} of {} ({} ECTS)",
+                self.completed_years, self.total_years, self.completed_ects
+            )?,
+        }
+
+        if self.remaining.is_empty() {
+            return writeln!(f, "\nAll study years of the programme are completed.");
+        }
+
+        writeln!(
+            f,
+            "Remaining: {} compulsory courses, {} ECTS",
+            self.remaining_courses(),
+            self.remaining_ects()
+        )?;
+
+        for year in &self.remaining {
+            writeln!(f, "\n## Year {} ({} ECTS)", year.year, year.ects())?;
+            for (course, ects) in &year.compulsory {
+                writeln!(f, "- {} ({} ECTS)", course, ects)?;
+            }
+            if year.elective_ects > 0.0 {
+                writeln!(f, "- Elective courses of your choice: {} ECTS", year.elective_ects)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YEAR_TABLES_PAGE: &str = r#"<html><body>
+<h1>Computer Science</h1>
+<div class="content">
+<p>ECTS-credits: 180</p>
+<h2>Course structure</h2>
+<h3>1st year</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>Programming I</td><td>6</td><td>45</td><td>0</td><td>30</td><td>0</td><td>180</td></tr>
+<tr><td>Discrete Structures I</td><td>6</td><td>45</td><td>0</td><td>45</td><td>0</td><td>180</td></tr>
+</table>
+<h3>Second year</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>Algorithms</td><td>6</td><td>45</td><td>0</td><td>30</td><td>0</td><td>180</td></tr>
+<tr><td>Elective course 1</td><td>6</td><td>45</td><td>0</td><td>30</td><td>0</td><td>180</td></tr>
+</table>
+<h3>3rd year</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>Final project</td><td>10</td><td>0</td><td>0</td><td>0</td><td>0</td><td>300</td></tr>
+</table>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn completed_years_are_subtracted_and_electives_summed() {
+        let info = ProgrammeInfo::from(YEAR_TABLES_PAGE.to_string());
+
+        let plan = remaining_requirements(&info, 1).unwrap();
+
+        assert_eq!(plan.total_years, 3);
+        assert_eq!(plan.completed_ects, 12.0);
+        assert_eq!(plan.programme_ects, Some(180));
+        let years: Vec<u32> = plan.remaining.iter().map(|y| y.year).collect();
+        assert_eq!(years, [2, 3]);
+        assert_eq!(plan.remaining[0].compulsory, [("Algorithms".to_string(), 6.0)]);
+        assert_eq!(plan.remaining[0].elective_ects, 6.0);
+        assert_eq!(plan.remaining_courses(), 2);
+        assert_eq!(plan.remaining_ects(), 22.0);
+        assert!(plan.to_string().contains("- Elective courses of your choice: 6 ECTS"));
+    }
+
+    #[test]
+    fn page_without_year_tables_has_no_plan() {
+        let info = ProgrammeInfo::from("<html><body><h1>Mathematics</h1></body></html>".to_string());
+
+        assert!(remaining_requirements(&info, 1).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! Stored versions of a programme, to tell what changed since an earlier
+//! fetch.
+//!
+//! A snapshot keeps the courses (with their ECTS) and the requirement
+//! sections of a parsed programme page. Each programme has a history file
+//! under `PROGRAMME_SNAPSHOT_PATH` to which a snapshot is appended whenever
+//! the page content differs from the last stored one, so the history holds
+//! one entry per version of the programme rather than one per fetch.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::{fs, sync::Mutex};
+
+use crate::programme::ProgrammeInfo;
+
+const DEFAULT_SNAPSHOT_PATH: &str = "programme_snapshots";
+/// Oldest versions are dropped once a programme has this many.
+const MAX_SNAPSHOTS: usize = 20;
+
+/// Serializes writes to the history files.
+static SNAPSHOT_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgrammeSnapshot {
+    /// RFC 3339 time the version was first seen.
+    pub taken_at: String,
+    /// Course name -> ECTS.
+    pub courses: BTreeMap<String, String>,
+    /// Section name (as in `ProgrammeSection::NAMES`) -> its lines.
+    pub requirements: BTreeMap<String, Vec<String>>,
+}
+
+impl From<&ProgrammeInfo> for ProgrammeSnapshot {
+    fn from(info: &ProgrammeInfo) -> Self {
+        let mut courses = BTreeMap::new();
+        for row in info.course_tables.iter().flat_map(|t| &t.rows) {
+            let name = row.course.trim();
+            if !name.is_empty() {
+                courses.entry(name.to_string()).or_insert_with(|| row.ects.trim().to_string());
+            }
+        }
+
+        let requirements = [
+            ("admission_requirements", info.admission_requirements.clone()),
+            ("transfer_criteria", info.transfer_criteria.clone()),
+            ("advancement_requirements", info.advancement_requirements.clone()),
+            ("completion_requirements", info.completion_requirements.iter().cloned().collect()),
+        ]
+        .into_iter()
+        .filter(|(_, lines)| !lines.is_empty())
+        .map(|(section, lines)| (section.to_string(), lines))
+        .collect();
+
+        Self {
+            taken_at: Utc::now().to_rfc3339(),
+            courses,
+            requirements,
+        }
+    }
+}
+
+impl ProgrammeSnapshot {
+    /// Whether both snapshots describe the same version, ignoring when they
+    /// were taken.
+    pub fn same_content(&self, other: &ProgrammeSnapshot) -> bool {
+        self.courses == other.courses && self.requirements == other.requirements
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequirementChange {
+    pub section: String,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Differences from an older snapshot to a newer one.
+#[derive(Debug, Default)]
+pub struct ProgrammeDiff {
+    /// `(course, ECTS)`
+    pub added_courses: Vec<(String, String)>,
+    /// `(course, ECTS)`
+    pub removed_courses: Vec<(String, String)>,
+    /// `(course, old ECTS, new ECTS)`
+    pub changed_courses: Vec<(String, String, String)>,
+    pub changed_requirements: Vec<RequirementChange>,
+}
+
+impl ProgrammeDiff {
+    pub fn between(old: &ProgrammeSnapshot, new: &ProgrammeSnapshot) -> Self {
+        let mut diff = ProgrammeDiff::default();
+
+        for (course, ects) in &new.courses {
+            match old.courses.get(course) {
+                None => diff.added_courses.push((course.clone(), ects.clone())),
+                Some(old_ects) if old_ects != ects => {
+                    diff.changed_courses.push((course.clone(), old_ects.clone(), ects.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (course, ects) in &old.courses {
+            if !new.courses.contains_key(course) {
+                diff.removed_courses.push((course.clone(), ects.clone()));
+            }
+        }
+
+        let sections: BTreeSet<&String> =
+            old.requirements.keys().chain(new.requirements.keys()).collect();
+        for section in sections {
+            let old_lines = old.requirements.get(section).cloned().unwrap_or_default();
+            let new_lines = new.requirements.get(section).cloned().unwrap_or_default();
+            let added: Vec<String> = new_lines.iter().filter(|l| !old_lines.contains(l)).cloned().collect();
+            let removed: Vec<String> = old_lines.iter().filter(|l| !new_lines.contains(l)).cloned().collect();
+            if !added.is_empty() || !removed.is_empty() {
+                diff.changed_requirements.push(RequirementChange {
+                    section: section.clone(),
+                    added,
+                    removed,
+                });
+            }
+        }
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_courses.is_empty()
+            && self.removed_courses.is_empty()
+            && self.changed_courses.is_empty()
+            && self.changed_requirements.is_empty()
+    }
+}
+
+/* --------------------------------------------------------------------- */
+/*  Storage                                                              */
+/* --------------------------------------------------------------------- */
+
+fn history_path(programme_url: &str) -> String {
+    let dir = std::env::var("PROGRAMME_SNAPSHOT_PATH").unwrap_or_else(|_| DEFAULT_SNAPSHOT_PATH.to_string());
+    let slug: String = programme_url
+        .trim_end_matches('/')
+        .rsplit('/')
+        .take(2)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect::<Vec<_>>()
+        .join("_")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("{}/{}.json", dir, slug)
+}
+
+/// Stored versions of the programme, oldest first. A missing or unreadable
+/// history is empty.
+pub async fn load_history(programme_url: &str) -> Vec<ProgrammeSnapshot> {
+    match fs::read_to_string(history_path(programme_url)).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => vec![],
+    }
+}
+
+/// Appends `snapshot` to the programme's history unless it matches the last
+/// stored version.
+pub async fn record_snapshot(programme_url: &str, snapshot: &ProgrammeSnapshot) -> Result<()> {
+    let _guard = SNAPSHOT_LOCK.lock().await;
+    let mut history = load_history(programme_url).await;
+    if history.last().is_some_and(|last| last.same_content(snapshot)) {
+        return Ok(());
+    }
+    history.push(snapshot.clone());
+    if history.len() > MAX_SNAPSHOTS {
+        history.drain(..history.len() - MAX_SNAPSHOTS);
+    }
+
+    let path = history_path(programme_url);
+    if let Some((dir, _)) = path.rsplit_once('/') {
+        fs::create_dir_all(dir).await?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&history)?).await?;
+    Ok(())
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+fn ects(value: &str) -> String {
+    if value.is_empty() { "-".to_string() } else { format!("{} ECTS", value) }
+}
+
+impl fmt::Display for ProgrammeDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes to courses or requirements.");
+        }
+        if !self.added_courses.is_empty() {
+            writeln!(f, "## Added courses\n")?;
+            for (course, value) in &self.added_courses {
+                writeln!(f, "- {} ({})", course, ects(value))?;
+            }
+            writeln!(f)?;
+        }
+        if !self.removed_courses.is_empty() {
+            writeln!(f, "## Removed courses\n")?;
+            for (course, value) in &self.removed_courses {
+                writeln!(f, "- {} ({})", course, ects(value))?;
+            }
+            writeln!(f)?;
+        }
+        if !self.changed_courses.is_empty() {
+            writeln!(f, "## Changed courses\n")?;
+            for (course, old, new) in &self.changed_courses {
+                writeln!(f, "- {}: {} -> {}", course, ects(old), ects(new))?;
+            }
+            writeln!(f)?;
+        }
+        for change in &self.changed_requirements {
+            writeln!(f, "## Changed {}\n", change.section.replace('_', " "))?;
+            for line in &change.removed {
+                writeln!(f, "- Removed: {}", line)?;
+            }
+            for line in &change.added {
+                writeln!(f, "- Added: {}", line)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(courses: &[(&str, &str)], admission: &[&str]) -> ProgrammeSnapshot {
+        ProgrammeSnapshot {
+            taken_at: Utc::now().to_rfc3339(),
+            courses: courses.iter().map(|(c, e)| (c.to_string(), e.to_string())).collect(),
+            requirements: BTreeMap::from([(
+                "admission_requirements".to_string(),
+                admission.iter().map(|l| l.to_string()).collect(),
+            )]),
+        }
+    }
+
+    #[test]
+    fn snapshot_keeps_courses_and_requirement_sections() {
+        let page = r#"<html><body><h1>Mathematics</h1><div class="content">
+<h2>Course structure</h2>
+<h3>1st year</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>Analysis I</td><td>9</td><td>60</td><td>0</td><td>60</td><td>0</td><td>270</td></tr>
+</table>
+<h2>Admission requirements</h2>
+<ul><li>Matura</li></ul>
+</div></body></html>"#;
+
+        let snapshot = ProgrammeSnapshot::from(&ProgrammeInfo::from(page.to_string()));
+
+        assert_eq!(snapshot.courses, BTreeMap::from([("Analysis I".to_string(), "9".to_string())]));
+        assert_eq!(snapshot.requirements.keys().collect::<Vec<_>>(), ["admission_requirements"]);
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_changed_entries() {
+        let old = snapshot(&[("Analysis I", "9"), ("Physics", "6")], &["Matura"]);
+        let new = snapshot(&[("Analysis I", "10"), ("Statistics", "6")], &["Matura", "Entrance exam"]);
+
+        let diff = ProgrammeDiff::between(&old, &new);
+
+        assert_eq!(diff.added_courses, [("Statistics".to_string(), "6".to_string())]);
+        assert_eq!(diff.removed_courses, [("Physics".to_string(), "6".to_string())]);
+        assert_eq!(diff.changed_courses, [("Analysis I".to_string(), "9".to_string(), "10".to_string())]);
+        assert_eq!(diff.changed_requirements[0].added, ["Entrance exam"]);
+        assert!(diff.to_string().contains("- Analysis I: 9 ECTS -> 10 ECTS"));
+    }
+
+    #[test]
+    fn same_content_ignores_when_the_snapshot_was_taken() {
+        let mut later = snapshot(&[("Analysis I", "9")], &["Matura"]);
+        later.taken_at = "2030-01-01T00:00:00+00:00".into();
+
+        assert!(snapshot(&[("Analysis I", "9")], &["Matura"]).same_content(&later));
+        assert!(ProgrammeDiff::between(&later, &later).is_empty());
+    }
+}
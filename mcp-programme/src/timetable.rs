@@ -0,0 +1,386 @@
+//! Extractor for course timetables (urniki).
+//!
+//! Timetables are published as HTML tables in one of two layouts: a list
+//! with one row per slot ("Day | Time | Course | Room | Group"), or a weekly
+//! grid with the days as columns, the hours as rows and the course (often
+//! followed by the room) in the cells. The heading above a table names the
+//! term, e.g. "Winter semester 2025/26". Feed the raw HTML into
+//! `parse_timetable(html, url)`, keep the slots of the running term with
+//! `current_term(slots, today)`, and collect the timetable links of a page
+//! with `timetable_links(html, url)`.
+
+use chrono::{Datelike, NaiveDate};
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimetableSlot {
+    pub course: String,
+    pub day: String,
+    pub time: String,
+    pub room: Option<String>,
+    pub group: Option<String>,
+    /// Lecture, tutorial, lab work, ... when the timetable says.
+    pub kind: Option<String>,
+    /// Heading of the table the slot was found in, naming the term.
+    pub term: Option<String>,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Semester {
+    Winter,
+    Summer,
+}
+
+enum Column {
+    Day,
+    Time,
+    From,
+    To,
+    Course,
+    Room,
+    Group,
+    Kind,
+    Other,
+}
+
+const DAYS: [(&str, &str, &str); 6] = [
+    ("monday", "ponedeljek", "Monday"),
+    ("tuesday", "torek", "Tuesday"),
+    ("wednesday", "sreda", "Wednesday"),
+    ("thursday", "četrtek", "Thursday"),
+    ("friday", "petek", "Friday"),
+    ("saturday", "sobota", "Saturday"),
+];
+const WINTER_KEYWORDS: [&str; 3] = ["winter", "zimsk", "fall"];
+const SUMMER_KEYWORDS: [&str; 4] = ["summer", "letn", "spring", "poletn"];
+const TIMETABLE_KEYWORDS: [&str; 3] = ["timetable", "urnik", "schedule"];
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn contains_any(haystack: &str, needles: &[&str]) -> bool {
+    let haystack = haystack.to_lowercase();
+    needles.iter().any(|n| haystack.contains(n))
+}
+
+/// English name of the weekday `s` names (English or Slovenian, full or
+/// abbreviated to three letters).
+fn weekday(s: &str) -> Option<&'static str> {
+    let lower = s.trim().to_lowercase();
+    if lower.chars().count() < 3 {
+        return None;
+    }
+    DAYS.iter()
+        .find(|(en, sl, _)| en.starts_with(&lower) || sl.starts_with(&lower) || lower.starts_with(en) || lower.starts_with(sl))
+        .map(|(_, _, name)| *name)
+}
+
+fn column_of(header: &str) -> Column {
+    let h = header.to_lowercase();
+    let words: Vec<&str> = h.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    let has_word = |needles: &[&str]| words.iter().any(|w| needles.contains(w));
+    if has_word(&["day", "dan"]) {
+        Column::Day
+    } else if has_word(&["from", "start", "od", "začetek"]) {
+        Column::From
+    } else if has_word(&["to", "until", "end", "do", "konec"]) {
+        Column::To
+    } else if contains_any(&h, &["time", "hour", "ura", "čas"]) {
+        Column::Time
+    } else if contains_any(&h, &["course", "subject", "predmet"]) {
+        Column::Course
+    } else if contains_any(&h, &["room", "location", "predavalnica", "prostor", "lokacija"]) {
+        Column::Room
+    } else if contains_any(&h, &["group", "skupina"]) {
+        Column::Group
+    } else if contains_any(&h, &["type", "form", "oblika", "vrsta"]) {
+        Column::Kind
+    } else {
+        Column::Other
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    let s = s.trim().to_string();
+    (!s.is_empty() && s != "-").then_some(s)
+}
+
+/// Nearest heading before the table, looking through wrapping elements.
+fn table_heading(table: &ElementRef) -> Option<String> {
+    let mut current = Some(*table);
+    while let Some(element) = current {
+        let heading = element
+            .prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .find(|e| matches!(e.value().name(), "h1" | "h2" | "h3" | "h4" | "h5" | "strong"))
+            .map(|e| text(&e))
+            .filter(|t| !t.is_empty());
+        if heading.is_some() {
+            return heading;
+        }
+        current = element.parent().and_then(ElementRef::wrap).filter(|p| p.value().name() != "body");
+    }
+    None
+}
+
+/// Course and room of a grid cell such as "Algorithms (P1)" or
+/// "Algorithms, room P1".
+fn split_cell(cell: &str) -> (String, Option<String>) {
+    if let Some((course, rest)) = cell.split_once('(') {
+        let room = rest.trim_end_matches(')').trim().to_string();
+        return (course.trim().to_string(), non_empty(room));
+    }
+    let lower = cell.to_lowercase();
+    for marker in [", room ", ", predavalnica ", " - "] {
+        if let Some(i) = lower.find(marker) {
+            let room = cell.get(i + marker.len()..).unwrap_or_default().to_string();
+            return (cell.get(..i).unwrap_or(cell).trim().to_string(), non_empty(room));
+        }
+    }
+    (cell.trim().to_string(), None)
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+pub fn parse_timetable(html: &str, url: &str) -> Vec<TimetableSlot> {
+    let doc = Html::parse_document(html);
+    let table_sel = Selector::parse("table").unwrap();
+    let row_sel = Selector::parse("tr").unwrap();
+    let cell_sel = Selector::parse("th, td").unwrap();
+
+    let mut slots = vec![];
+    for table in doc.select(&table_sel) {
+        let term = table_heading(&table);
+        let mut rows = table
+            .select(&row_sel)
+            .map(|tr| tr.select(&cell_sel).map(|c| text(&c)).collect::<Vec<_>>())
+            .filter(|cells| !cells.is_empty());
+        let Some(header) = rows.next() else {
+            continue;
+        };
+
+        let grid_days: Vec<Option<&str>> = header.iter().map(|h| weekday(h)).collect();
+        if grid_days.iter().filter(|d| d.is_some()).count() >= 2 {
+            // weekly grid: first column holds the hour, the rest one day each
+            for cells in rows {
+                let Some(time) = cells.first().cloned().and_then(non_empty) else {
+                    continue;
+                };
+                for (cell, day) in cells.iter().zip(&grid_days).skip(1) {
+                    let (Some(day), Some(cell)) = (day, non_empty(cell.clone())) else {
+                        continue;
+                    };
+                    let (course, room) = split_cell(&cell);
+                    slots.push(TimetableSlot {
+                        course,
+                        day: day.to_string(),
+                        time: time.clone(),
+                        room,
+                        group: None,
+                        kind: None,
+                        term: term.clone(),
+                        source: url.to_string(),
+                    });
+                }
+            }
+            continue;
+        }
+
+        let columns: Vec<Column> = header.iter().map(|h| column_of(h)).collect();
+        let has = |wanted: fn(&Column) -> bool| columns.iter().any(wanted);
+        if !has(|c| matches!(c, Column::Day)) || !has(|c| matches!(c, Column::Course)) {
+            continue;
+        }
+
+        for cells in rows {
+            let mut slot = TimetableSlot {
+                course: String::new(),
+                day: String::new(),
+                time: String::new(),
+                room: None,
+                group: None,
+                kind: None,
+                term: term.clone(),
+                source: url.to_string(),
+            };
+            let (mut from, mut to) = (None, None);
+            for (cell, column) in cells.iter().zip(&columns) {
+                let value = non_empty(cell.clone());
+                match column {
+                    Column::Day => slot.day = value.map(|d| weekday(&d).map(str::to_string).unwrap_or(d)).unwrap_or_default(),
+                    Column::Time => slot.time = value.unwrap_or_default(),
+                    Column::From => from = value,
+                    Column::To => to = value,
+                    Column::Course => slot.course = value.unwrap_or_default(),
+                    Column::Room => slot.room = value,
+                    Column::Group => slot.group = value,
+                    Column::Kind => slot.kind = value,
+                    Column::Other => {}
+                }
+            }
+            if slot.time.is_empty() {
+                slot.time = match (from, to) {
+                    (Some(from), Some(to)) => format!("{}–{}", from, to),
+                    (Some(from), None) => from,
+                    _ => String::new(),
+                };
+            }
+            if !slot.course.is_empty() && !slot.day.is_empty() {
+                slots.push(slot);
+            }
+        }
+    }
+    slots
+}
+
+/// Semester running on `today`: winter from October to January, summer
+/// from February to September.
+pub fn semester_on(today: NaiveDate) -> Semester {
+    match today.month() {
+        2..=9 => Semester::Summer,
+        _ => Semester::Winter,
+    }
+}
+
+/// Slots of the semester running on `today`. Tables whose heading names no
+/// semester are kept, as they cannot be told apart.
+pub fn current_term(slots: Vec<TimetableSlot>, today: NaiveDate) -> Vec<TimetableSlot> {
+    let (current, other): (&[&str], &[&str]) = match semester_on(today) {
+        Semester::Winter => (&WINTER_KEYWORDS, &SUMMER_KEYWORDS),
+        Semester::Summer => (&SUMMER_KEYWORDS, &WINTER_KEYWORDS),
+    };
+    slots
+        .into_iter()
+        .filter(|slot| match &slot.term {
+            Some(term) => contains_any(term, current) || !contains_any(term, other),
+            None => true,
+        })
+        .collect()
+}
+
+/// Links to timetable pages, made absolute. Documents are left out as
+/// they cannot be parsed.
+pub fn timetable_links(html: &str, url: &str) -> Vec<String> {
+    let doc = Html::parse_document(html);
+    let a_sel = Selector::parse("a[href]").unwrap();
+    let Ok(base) = Url::parse(url) else {
+        return vec![];
+    };
+
+    let mut links = vec![];
+    for a in doc.select(&a_sel) {
+        let href = a.value().attr("href").unwrap_or_default();
+        if !contains_any(&text(&a), &TIMETABLE_KEYWORDS) && !contains_any(href, &TIMETABLE_KEYWORDS) {
+            continue;
+        }
+        let Ok(link) = base.join(href) else {
+            continue;
+        };
+        let link = link.to_string();
+        let lower = link.to_lowercase();
+        if lower.ends_with(".pdf") || lower.contains(".xls") || lower.contains(".doc") {
+            continue;
+        }
+        if link != url && !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl fmt::Display for TimetableSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "| {} | {} | {} | {} | {} |",
+            self.day,
+            self.time,
+            self.room.as_deref().unwrap_or("-"),
+            self.group.as_deref().unwrap_or("-"),
+            self.kind.as_deref().unwrap_or("-"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMETABLE_PAGE: &str = r#"<html><body>
+<h2>Winter semester 2025/26</h2>
+<table>
+<tr><th>Day</th><th>From</th><th>To</th><th>Course</th><th>Room</th><th>Type</th></tr>
+<tr><td>Mon</td><td>8:00</td><td>10:00</td><td>Algorithms</td><td>P1</td><td>Lecture</td></tr>
+<tr><td>torek</td><td>12:00</td><td>14:00</td><td>Algorithms</td><td>-</td><td>Tutorial</td></tr>
+</table>
+<h2>Summer semester 2025/26</h2>
+<table>
+<tr><th>Time</th><th>Monday</th><th>Tuesday</th></tr>
+<tr><td>8:00-10:00</td><td>Databases (P2)</td><td></td></tr>
+<tr><td>10:00-12:00</td><td></td><td>Compilers, room MP7</td></tr>
+</table>
+</body></html>"#;
+
+    #[test]
+    fn list_and_grid_layouts_give_the_same_slots() {
+        let slots = parse_timetable(TIMETABLE_PAGE, "https://www.famnit.upr.si/en/timetable");
+
+        let summary: Vec<(&str, &str, &str, Option<&str>)> = slots
+            .iter()
+            .map(|s| (s.course.as_str(), s.day.as_str(), s.time.as_str(), s.room.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Algorithms", "Monday", "8:00–10:00", Some("P1")),
+                ("Algorithms", "Tuesday", "12:00–14:00", None),
+                ("Databases", "Monday", "8:00-10:00", Some("P2")),
+                ("Compilers", "Tuesday", "10:00-12:00", Some("MP7")),
+            ]
+        );
+        assert_eq!(slots[1].kind.as_deref(), Some("Tutorial"));
+        assert_eq!(slots[2].term.as_deref(), Some("Summer semester 2025/26"));
+    }
+
+    #[test]
+    fn only_the_running_semester_is_kept() {
+        let slots = parse_timetable(TIMETABLE_PAGE, "https://www.famnit.upr.si/en/timetable");
+
+        let winter = current_term(slots.clone(), NaiveDate::from_ymd_opt(2025, 11, 3).unwrap());
+        let summer = current_term(slots, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+
+        assert_eq!(winter.len(), 2);
+        assert!(summer.iter().all(|s| s.term.as_deref() == Some("Summer semester 2025/26")));
+    }
+
+    #[test]
+    fn timetable_links_are_absolute_and_skip_documents() {
+        let page = r#"<a href="/en/timetable/computer-science">Timetable</a>
+<a href="/files/urnik.pdf">Urnik (PDF)</a>
+<a href="/en/news">News</a>"#;
+
+        assert_eq!(
+            timetable_links(page, "https://www.famnit.upr.si/en/students"),
+            ["https://www.famnit.upr.si/en/timetable/computer-science"]
+        );
+    }
+}
@@ -1,26 +1,100 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
 use anyhow::Result;
 use reagent::{Message, Role};
 use reqwest::Url;
 use rmcp::{model::{CallToolRequestParam, ClientCapabilities, ClientInfo, Implementation}, transport::SseClientTransport, ServiceExt};
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html, Selector};
+use tokio::sync::Mutex;
 
 use crate::{programme::{Programme, ProgrammeLevel}, BASE_URL, MEMORY_MCP_URL};
 
 
-pub fn rank_names(mut names: Vec<String>, query: &str) -> Vec<String> {
-    // Pre-compute the query vector once
-    let q_vec = trigram_vec(&query.to_lowercase());
+/// String similarity used to rank names against a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum NameMetric {
+    /// Cosine similarity of character trigrams. Robust to word order.
+    #[default]
+    Trigram,
+    /// Normalized edit distance. Tolerates transposed or mistyped characters.
+    Levenshtein,
+    /// Favours shared prefixes, suited to short fragments and initials.
+    JaroWinkler,
+}
 
-    names.sort_by(|a, b| {
-        let sim_a = cosine_sim(&trigram_vec(&a.to_lowercase()), &q_vec);
-        let sim_b = cosine_sim(&trigram_vec(&b.to_lowercase()), &q_vec);
-        // higher similarity ⇒ earlier in list
-        sim_b
-            .partial_cmp(&sim_a)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    names
+impl NameMetric {
+    /// Metric used to resolve programme names, set by `PROGRAMME_NAME_METRIC`
+    /// ('trigram', 'levenshtein' or 'jaro_winkler').
+    pub fn from_env() -> Self {
+        match std::env::var("PROGRAMME_NAME_METRIC").unwrap_or_default().trim() {
+            "levenshtein" => NameMetric::Levenshtein,
+            "jaro_winkler" => NameMetric::JaroWinkler,
+            _ => NameMetric::Trigram,
+        }
+    }
+
+    /// Similarity in `0.0..=1.0` of two already normalized strings.
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        match self {
+            NameMetric::Trigram => cosine_sim(&trigram_vec(a), &trigram_vec(b)),
+            NameMetric::Levenshtein => levenshtein_sim(a, b),
+            NameMetric::JaroWinkler => jaro_winkler(a, b),
+        }
+    }
+
+    /// Scores `name` against `query`. The character metrics compare the
+    /// query with the full name and with each of its words, so "kavsek"
+    /// is not penalized for the missing given name.
+    fn score(&self, name: &str, query: &str) -> f64 {
+        match self {
+            NameMetric::Trigram => self.similarity(name, query),
+            _ => name
+                .split_whitespace()
+                .map(|word| self.similarity(word, query))
+                .fold(self.similarity(name, query), f64::max),
+        }
+    }
+}
+
+/// Names ordered from most to least similar to `query`, each with its
+/// similarity in `0.0..=1.0`.
+pub fn rank_names_scored(names: Vec<String>, query: &str, metric: NameMetric) -> Vec<(String, f64)> {
+    let query = normalize_ščćžš(query).to_lowercase();
+    let mut scored: Vec<(String, f64)> = names
+        .into_iter()
+        .map(|name| {
+            let score = metric.score(&normalize_ščćžš(&name).to_lowercase(), &query);
+            (name, score.clamp(0.0, 1.0))
+        })
+        .collect();
+
+    // higher similarity ⇒ earlier in list
+    scored.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+}
+
+pub fn rank_names(names: Vec<String>, query: &str, metric: NameMetric) -> Vec<String> {
+    rank_names_scored(names, query, metric)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn normalize_ščćžš(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'š' | 'Š' => 's',
+            'č' | 'Č' => 'c',
+            'ć' | 'Ć' => 'c',
+            'ž' | 'Ž' => 'z',
+            'đ' | 'Đ' => 'd',
+            _ => c,
+        })
+        .collect()
 }
 
 /// Build a (trigram → frequency) sparse vector.
@@ -58,8 +132,258 @@ fn cosine_sim(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
     }
 }
 
+/// Edit distance normalized by the longer string, as a similarity.
+fn levenshtein_sim(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    1.0 - prev[b.len()] as f64 / longest as f64
+}
+
+/// Jaro-Winkler similarity with the usual 0.1 prefix scale over at most
+/// four characters.
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+
+const DEFAULT_PAGE_CACHE_TTL_SECS: u64 = 900;
+const DEFAULT_PAGE_CACHE_MAX_ENTRIES: usize = 256;
+
+/// Fetched pages keyed by URL, shared by all tools. The lifetime of an entry
+/// is read from `PAGE_CACHE_TTL_SECS` (`0` disables caching) and the number
+/// of entries kept from `PAGE_CACHE_MAX_ENTRIES`.
+static PAGE_CACHE: LazyLock<Mutex<PageCache>> = LazyLock::new(|| {
+    let ttl = std::env::var("PAGE_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_PAGE_CACHE_TTL_SECS);
+    let max_entries = std::env::var("PAGE_CACHE_MAX_ENTRIES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_PAGE_CACHE_MAX_ENTRIES);
+    Mutex::new(PageCache::new(Duration::from_secs(ttl), max_entries))
+});
+
+/// Map of `url -> (fetched at, content)` whose entries expire after `ttl`.
+/// Expired entries are dropped on insert; when it is still full, the oldest
+/// entry makes room for the new one.
+#[derive(Debug)]
+pub struct PageCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: HashMap<String, (Instant, String)>,
+}
+
+impl PageCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self { ttl, max_entries, entries: HashMap::new() }
+    }
+
+    pub fn get(&mut self, url: &str) -> Option<String> {
+        let (fetched_at, _) = self.entries.get(url)?;
+        if fetched_at.elapsed() >= self.ttl {
+            self.entries.remove(url);
+            return None;
+        }
+        self.entries.get(url).map(|(_, content)| content.clone())
+    }
+
+    pub fn insert(&mut self, url: String, content: String) {
+        if self.ttl.is_zero() || self.max_entries == 0 {
+            return;
+        }
+        let ttl = self.ttl;
+        self.entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+        if self.entries.len() >= self.max_entries && !self.entries.contains_key(&url) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (fetched_at, _))| *fetched_at)
+                .map(|(url, _)| url.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(url, (Instant::now(), content));
+    }
+
+    /// Drops every entry and returns how many there were.
+    pub fn clear(&mut self) -> usize {
+        let cleared = self.entries.len();
+        self.entries.clear();
+        cleared
+    }
+}
+
+/// Empties the page cache so the next requests refetch from the website.
+pub async fn clear_page_cache() -> usize {
+    PAGE_CACHE.lock().await.clear()
+}
 
+/// Returns the page content, from the cache while it is fresh.
+///
+/// Failed fetches are retried, and a page missing under `/en/` is looked up
+/// under `/sl/` (and the other way around). Errors are `PageError`s.
 pub async fn get_page<T>(url: T) -> Result<String> where T: Into<String> {
+    cached_page(&PAGE_CACHE, url.into(), |url| fetch_with_fallback(url, fetch_page)).await
+}
+
+/// `url` from `cache` while it is fresh, otherwise from `fetch`, caching
+/// the result.
+async fn cached_page<F, Fut>(cache: &Mutex<PageCache>, url: String, fetch: F) -> Result<String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    if let Some(content) = cache.lock().await.get(&url) {
+        return Ok(content);
+    }
+    let content = fetch(url.clone()).await?;
+    cache.lock().await.insert(url, content.clone());
+    Ok(content)
+}
+
+/// Fetches `url` with `fetch`, falling back to the other language prefix
+/// when the page does not exist.
+async fn fetch_with_fallback<F, Fut>(url: String, fetch: F) -> Result<String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    match fetch_with_retry(&url, &fetch).await {
+        Ok(content) => Ok(content),
+        Err(PageError::NotFound { .. }) => {
+            let Some(alternate) = swap_language(&url) else {
+                return Err(PageError::NotFound { url, alternate: None }.into());
+            };
+            match fetch_with_retry(&alternate, &fetch).await {
+                Ok(content) => Ok(content),
+                Err(PageError::NotFound { .. }) => {
+                    Err(PageError::NotFound { url, alternate: Some(alternate) }.into())
+                }
+                Err(e) => Err(e.into()),
+            }
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+const FETCH_ATTEMPTS: u32 = 3;
+const FETCH_BACKOFF_MS: u64 = 250;
+
+/// Why a page could not be fetched.
+#[derive(Debug)]
+pub enum PageError {
+    /// The page does not exist, also not under `alternate` when the other
+    /// language prefix was tried.
+    NotFound { url: String, alternate: Option<String> },
+    /// The scraper could not be reached or kept failing.
+    Transport { url: String, error: String },
+}
+
+impl std::fmt::Display for PageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageError::NotFound { url, alternate: Some(alternate) } => {
+                write!(f, "Page not found: {} (also tried {})", url, alternate)
+            }
+            PageError::NotFound { url, alternate: None } => write!(f, "Page not found: {}", url),
+            PageError::Transport { url, error } => write!(f, "Could not fetch {}: {}", url, error),
+        }
+    }
+}
+
+impl std::error::Error for PageError {}
+
+/// The same URL under the other language prefix (`/en/` ↔ `/sl/`).
+fn swap_language(url: &str) -> Option<String> {
+    if url.contains("/en/") {
+        Some(url.replacen("/en/", "/sl/", 1))
+    } else if url.contains("/sl/") {
+        Some(url.replacen("/sl/", "/en/", 1))
+    } else {
+        None
+    }
+}
+
+/// Fetches `url` with `fetch` up to `FETCH_ATTEMPTS` times, doubling the
+/// wait after each failure (250 ms, 500 ms, ...). A 404 is final and not
+/// retried.
+async fn fetch_with_retry<F, Fut>(url: &str, fetch: &F) -> std::result::Result<String, PageError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let mut last_error = String::new();
+    for attempt in 0..FETCH_ATTEMPTS {
+        match fetch(url.to_string()).await {
+            Ok(content) => return Ok(content),
+            Err(e) => {
+                last_error = e.to_string();
+                // the scraper reports the HTTP status in its error message
+                if last_error.contains("status: 404") {
+                    return Err(PageError::NotFound { url: url.to_string(), alternate: None });
+                }
+                if attempt + 1 < FETCH_ATTEMPTS {
+                    tokio::time::sleep(Duration::from_millis(FETCH_BACKOFF_MS << attempt)).await;
+                }
+            }
+        }
+    }
+    Err(PageError::Transport { url: url.to_string(), error: last_error })
+}
+
+async fn fetch_page<T>(url: T) -> Result<String> where T: Into<String> {
     let transport = SseClientTransport::start("http://localhost:7999/sse").await?;
     let client_info: rmcp::model::InitializeRequestParam = ClientInfo {
         protocol_version: Default::default(),
@@ -72,7 +396,7 @@ pub async fn get_page<T>(url: T) -> Result<String> where T: Into<String> {
     let client = client_info.serve(transport).await.inspect_err(|e| { println!("client error: {:?}", e); })?;
     let tool_result = client.clone().call_tool(CallToolRequestParam {
             name: "get_web_page_content".into(),
-            arguments: serde_json::json!({"url": url.into()}).as_object().cloned(),
+            arguments: serde_json::json!({"url": url.into(), "as_markdown": false}).as_object().cloned(),
         }).await?;
     let mut content = "".into();
     for tool_result_content in tool_result.content {
@@ -81,6 +405,39 @@ pub async fn get_page<T>(url: T) -> Result<String> where T: Into<String> {
     Ok(content)
 }
 
+/// Number directly before one of `units` in `text`, e.g. 180 in "180 ECTS"
+/// or 3 in "3-year", with the unit as written.
+fn number_before(text: &str, units: &[&str]) -> Option<(u32, String)> {
+    let text = text.replace('-', " ");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.windows(2).find_map(|pair| {
+        let unit = pair[1]
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .to_lowercase();
+        if !units.iter().any(|u| unit.starts_with(u)) {
+            return None;
+        }
+        let number = pair[0].trim_matches(|c: char| !c.is_ascii_digit()).parse().ok()?;
+        Some((number, unit))
+    })
+}
+
+/// Duration and ECTS given next to a programme name in the listing, as in
+/// "Computer Science (3 years, 180 ECTS)".
+fn listing_facts(text: &str) -> (Option<String>, Option<u32>) {
+    let duration = number_before(text, &["year", "let"]).map(|(n, unit)| {
+        if !unit.starts_with("year") {
+            format!("{} {}", n, unit)
+        } else if n == 1 {
+            "1 year".to_string()
+        } else {
+            format!("{} years", n)
+        }
+    });
+    let ects = number_before(text, &["ects", "kt"]).map(|(n, _)| n);
+    (duration, ects)
+}
+
 pub fn parse_programme_list_page(html: &str, level: ProgrammeLevel) -> Vec<Programme> {
     let doc = Html::parse_document(html);
     let selector = Selector::parse("div.content ul li a").unwrap();
@@ -90,6 +447,13 @@ pub fn parse_programme_list_page(html: &str, level: ProgrammeLevel) -> Vec<Progr
     for element in doc.select(&selector) {
         let raw_name = element.text().collect::<String>();
         let name = raw_name.split('(').next().unwrap_or("").trim().to_string();
+        // the facts may be inside the link or after it in the list item
+        let item_text = element
+            .parent()
+            .and_then(ElementRef::wrap)
+            .map(|li| li.text().collect::<String>())
+            .unwrap_or(raw_name.clone());
+        let (duration, ects) = listing_facts(&item_text);
 
         if let Some(href) = element.value().attr("href") {
             if !name.is_empty() && !href.starts_with("javascript:") {
@@ -98,6 +462,8 @@ pub fn parse_programme_list_page(html: &str, level: ProgrammeLevel) -> Vec<Progr
                         name: name.clone(),
                         url: full_url.to_string(),
                         level: level.clone(),
+                        duration: duration.clone(),
+                        ects,
                     });
                 }
             }
@@ -107,6 +473,67 @@ pub fn parse_programme_list_page(html: &str, level: ProgrammeLevel) -> Vec<Progr
 }
 
 
+/// Normalizes '2023/24', '2023/2024' or '2023-2024' to '2023-2024'.
+pub fn normalize_academic_year(year: &str) -> Option<String> {
+    let digits: Vec<&str> = year
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|p| !p.is_empty())
+        .collect();
+    let start: u32 = digits.first()?.parse().ok()?;
+    if !(2000..2100).contains(&start) {
+        return None;
+    }
+    if let Some(end) = digits.get(1) {
+        let end: u32 = end.parse().ok()?;
+        if end != start + 1 && end != (start + 1) % 100 {
+            return None;
+        }
+    }
+    Some(format!("{}-{}", start, start + 1))
+}
+
+/// Archived programme pages live under the programme URL, one folder per
+/// academic year, e.g. `…/computer-science/archive/2023-2024/`.
+fn archived_programme_url(programme_url: &str, academic_year: &str) -> String {
+    format!("{}/archive/{}/", programme_url.trim_end_matches('/'), academic_year)
+}
+
+/// Whether a fetched page looks like a programme page (has an `<h1>`).
+fn has_programme_heading(html: &str) -> bool {
+    let doc = Html::parse_document(html);
+    let h1_sel = Selector::parse("h1").unwrap();
+    doc.select(&h1_sel).next().is_some()
+}
+
+/// The archived programme page for `academic_year` as `(url, html)`, fetched
+/// with `fetch`. When the year is invalid or has no archived page, the
+/// error is a note to show above the current programme instead.
+pub async fn archived_programme_page<F, Fut>(
+    programme_url: &str,
+    academic_year: &str,
+    fetch: F,
+) -> std::result::Result<(String, String), String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let Some(year) = normalize_academic_year(academic_year) else {
+        return Err(format!(
+            "*Note: '{}' is not a valid academic year (expected e.g. 2023/2024), showing the current programme instead.*\n\n",
+            academic_year
+        ));
+    };
+    let archived_url = archived_programme_url(programme_url, &year);
+    match fetch(archived_url.clone()).await {
+        Ok(html) if has_programme_heading(&html) => Ok((archived_url, html)),
+        _ => Err(format!(
+            "*Note: no archived version for {} was found, showing the current programme instead.*\n\n",
+            year
+        )),
+    }
+}
+
+
 pub fn history_to_memory_prompt(history: Vec<Message>) -> String {
     let mut prompt = String::from("Here is a summary of a conversation.");
     for msg in history.iter().skip(2) { // Skip the system prompt and the initial memory query result
@@ -161,3 +588,232 @@ pub async fn get_memories(arguments: serde_json::Value) -> Result<String> {
 
     Ok(content)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    const URL: &str = "https://www.famnit.upr.si/en/about-faculty/staff/";
+
+    /// Fetches a fixed page and counts how often it was asked to.
+    async fn counting_fetch(cache: &Mutex<PageCache>, fetches: &AtomicUsize) -> String {
+        cached_page(cache, URL.to_string(), |_| async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok("<html>staff</html>".to_string())
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn second_fetch_within_ttl_hits_the_cache() {
+        let cache = Mutex::new(PageCache::new(Duration::from_secs(60), 8));
+        let fetches = AtomicUsize::new(0);
+
+        assert_eq!(counting_fetch(&cache, &fetches).await, "<html>staff</html>");
+        assert_eq!(counting_fetch(&cache, &fetches).await, "<html>staff</html>");
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_fetches_every_time() {
+        let cache = Mutex::new(PageCache::new(Duration::ZERO, 8));
+        let fetches = AtomicUsize::new(0);
+
+        counting_fetch(&cache, &fetches).await;
+        counting_fetch(&cache, &fetches).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn full_cache_evicts_the_oldest_entry() {
+        let mut cache = PageCache::new(Duration::from_secs(60), 2);
+        cache.insert("a".into(), "page a".into());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("b".into(), "page b".into());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("c".into(), "page c".into());
+
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b").as_deref(), Some("page b"));
+        assert_eq!(cache.get("c").as_deref(), Some("page c"));
+    }
+
+    const LISTING_PAGE: &str = r#"<html><body><div class="content"><ul>
+<li><a href="/en/education/undergraduate/computer-science">Computer Science (3 years, 180 ECTS)</a></li>
+<li><a href="/en/education/master/mathematical-sciences">Mathematical Sciences</a> – 2-year, 120 ECTS</li>
+<li><a href="/en/education/master/data-science">Data Science</a></li>
+</ul></div></body></html>"#;
+
+    #[test]
+    fn listing_facts_are_read_inside_and_after_the_link() {
+        let programmes = parse_programme_list_page(LISTING_PAGE, ProgrammeLevel::Master);
+
+        let facts: Vec<(&str, Option<&str>, Option<u32>)> = programmes
+            .iter()
+            .map(|p| (p.name.as_str(), p.duration.as_deref(), p.ects))
+            .collect();
+        assert_eq!(
+            facts,
+            [
+                ("Computer Science", Some("3 years"), Some(180)),
+                ("Mathematical Sciences", Some("2 years"), Some(120)),
+                ("Data Science", None, None),
+            ]
+        );
+        assert_eq!(programmes[0].url, "https://www.famnit.upr.si/en/education/undergraduate/computer-science");
+    }
+
+    #[test]
+    fn academic_years_are_normalized() {
+        for year in ["2023/24", "2023/2024", "2023-2024", "2023"] {
+            assert_eq!(normalize_academic_year(year).as_deref(), Some("2023-2024"), "{}", year);
+        }
+        assert_eq!(normalize_academic_year("2023/25"), None);
+        assert_eq!(normalize_academic_year("1999/00"), None);
+        assert_eq!(normalize_academic_year("last year"), None);
+    }
+
+    #[tokio::test]
+    async fn archived_year_is_fetched_from_its_archive_folder() {
+        let programme_url = "https://www.famnit.upr.si/en/education/undergraduate/computer-science/";
+        let fetched = std::sync::Mutex::new(vec![]);
+
+        let (url, html) = archived_programme_page(programme_url, "2023/24", |url| {
+            fetched.lock().unwrap().push(url);
+            async { Ok("<html><h1>Computer Science</h1></html>".to_string()) }
+        })
+        .await
+        .unwrap();
+
+        let expected = "https://www.famnit.upr.si/en/education/undergraduate/computer-science/archive/2023-2024/";
+        assert_eq!(url, expected);
+        assert_eq!(*fetched.lock().unwrap(), [expected]);
+        assert!(has_programme_heading(&html));
+    }
+
+    #[tokio::test]
+    async fn missing_or_invalid_archive_gives_a_note() {
+        let programme_url = "https://www.famnit.upr.si/en/education/undergraduate/computer-science";
+
+        let not_archived = archived_programme_page(programme_url, "2019/2020", |_| async {
+            Ok("<html><p>Page not found</p></html>".to_string())
+        })
+        .await;
+        let fetches = AtomicUsize::new(0);
+        let invalid = archived_programme_page(programme_url, "last year", |_| async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok(String::new())
+        })
+        .await;
+
+        assert!(not_archived.unwrap_err().contains("no archived version for 2019-2020"));
+        assert!(invalid.unwrap_err().contains("'last year' is not a valid academic year"));
+        assert_eq!(fetches.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn expired_entries_are_dropped_on_insert() {
+        let mut cache = PageCache::new(Duration::from_millis(5), 8);
+        cache.insert("a".into(), "page a".into());
+        std::thread::sleep(Duration::from_millis(10));
+        cache.insert("b".into(), "page b".into());
+
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.entries.contains_key("b"));
+    }
+
+    const EN_PAGE: &str = "https://www.famnit.upr.si/en/education/undergraduate/computer-science/";
+    const SL_PAGE: &str = "https://www.famnit.upr.si/sl/education/undergraduate/computer-science/";
+
+    /// The error a failed `get_web_page_content` call comes back as: the
+    /// scraper's message ("Request to '…' failed with status: 404 Not Found")
+    /// inside the MCP client's error.
+    fn scraper_error(message: String) -> anyhow::Error {
+        rmcp::ServiceError::McpError(rmcp::Error::new(rmcp::model::ErrorCode::INTERNAL_ERROR, message, None)).into()
+    }
+
+    /// Stands in for the scraper server. Serves `pages`, answers any other
+    /// URL with a 404, and fails the first `failures` requests as if the
+    /// site could not be reached.
+    struct MockScraper {
+        pages: HashMap<String, String>,
+        failures: AtomicUsize,
+        requests: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl MockScraper {
+        fn new(pages: &[(&str, &str)], failures: usize) -> Self {
+            MockScraper {
+                pages: pages.iter().map(|(u, p)| (u.to_string(), p.to_string())).collect(),
+                failures: AtomicUsize::new(failures),
+                requests: std::sync::Mutex::new(vec![]),
+            }
+        }
+
+        async fn fetch(&self, url: String) -> Result<String> {
+            self.requests.lock().unwrap().push(url.clone());
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Err(scraper_error(format!("Failed to fetch URL '{}': connection refused", url)));
+            }
+            match self.pages.get(&url) {
+                Some(page) => Ok(page.clone()),
+                None => Err(scraper_error(format!(
+                    "Request to '{}' failed with status: {}",
+                    url,
+                    reqwest::StatusCode::NOT_FOUND
+                ))),
+            }
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transient_failures_are_retried_with_backoff() {
+        let scraper = MockScraper::new(&[(EN_PAGE, "<html>en</html>")], 2);
+        let start = tokio::time::Instant::now();
+
+        let page = fetch_with_fallback(EN_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap();
+
+        assert_eq!(page, "<html>en</html>");
+        assert_eq!(scraper.requests(), [EN_PAGE; 3]);
+        assert_eq!(start.elapsed(), Duration::from_millis(FETCH_BACKOFF_MS + (FETCH_BACKOFF_MS << 1)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn persistent_failures_give_up_after_the_last_attempt() {
+        let scraper = MockScraper::new(&[(EN_PAGE, "<html>en</html>")], 10);
+
+        let error = fetch_with_fallback(EN_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap_err();
+
+        assert!(matches!(error.downcast_ref::<PageError>(), Some(PageError::Transport { .. })));
+        assert!(error.to_string().contains("connection refused"));
+        assert_eq!(scraper.requests().len(), FETCH_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn missing_page_is_not_retried_and_falls_back_to_the_other_language() {
+        let scraper = MockScraper::new(&[(SL_PAGE, "<html>sl</html>")], 0);
+
+        let page = fetch_with_fallback(EN_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap();
+
+        assert_eq!(page, "<html>sl</html>");
+        assert_eq!(scraper.requests(), [EN_PAGE, SL_PAGE]);
+    }
+
+    #[tokio::test]
+    async fn page_missing_in_both_languages_is_not_found() {
+        let scraper = MockScraper::new(&[], 0);
+
+        let error = fetch_with_fallback(SL_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap_err();
+
+        assert_eq!(error.to_string(), format!("Page not found: {} (also tried {})", SL_PAGE, EN_PAGE));
+        assert_eq!(scraper.requests(), [SL_PAGE, EN_PAGE]);
+    }
+}
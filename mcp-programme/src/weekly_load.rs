@@ -0,0 +1,169 @@
+//! Estimated weekly contact hours per study year.
+//!
+//! Course tables list the contact hours of each course per semester split
+//! into lectures (L), seminars (S), tutorials (T) and lab work (LW). The
+//! hours of a year are summed and spread over its two semesters, which
+//! gives a rough weekly load, not a timetable.
+
+use std::fmt;
+
+use crate::{
+    programme::{CourseRow, CourseTable, CourseTableKind, ProgrammeInfo},
+    progress::{parse_ects, table_year},
+};
+
+pub const DEFAULT_WEEKS_PER_SEMESTER: u32 = 15;
+
+#[derive(Debug, Clone)]
+pub struct YearLoad {
+    pub year: u32,
+    pub contact_hours: f32,
+    pub courses: usize,
+    /// Rows (usually elective placeholders) that list no hours.
+    pub courses_without_hours: usize,
+}
+
+impl YearLoad {
+    pub fn weekly_hours(&self, weeks_per_semester: u32) -> f32 {
+        self.contact_hours / (2 * weeks_per_semester.max(1)) as f32
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WeeklyLoad {
+    pub programme: String,
+    pub weeks_per_semester: u32,
+    pub years: Vec<YearLoad>,
+}
+
+/// Contact hours of a course row, `None` when no column holds a number.
+fn contact_hours(row: &CourseRow) -> Option<f32> {
+    let hours: Vec<f32> = [&row.l, &row.s, &row.t, &row.lw]
+        .into_iter()
+        .filter_map(|h| parse_ects(h))
+        .collect();
+    (!hours.is_empty()).then(|| hours.iter().sum())
+}
+
+fn year_load(year: u32, tables: &[&CourseTable]) -> YearLoad {
+    let mut load = YearLoad {
+        year,
+        contact_hours: 0.0,
+        courses: 0,
+        courses_without_hours: 0,
+    };
+    for row in tables.iter().flat_map(|t| &t.rows) {
+        // summary rows ("Total") would count every course twice
+        if row.course.to_lowercase().starts_with("total") || row.course.trim().is_empty() {
+            continue;
+        }
+        load.courses += 1;
+        match contact_hours(row) {
+            Some(hours) => load.contact_hours += hours,
+            None => load.courses_without_hours += 1,
+        }
+    }
+    load
+}
+
+/// Weekly load of every study year with a course table, or `None` when the
+/// page has no tables assignable to a year.
+pub fn weekly_load(info: &ProgrammeInfo, weeks_per_semester: u32) -> Option<WeeklyLoad> {
+    let mut years: Vec<u32> = info
+        .course_tables
+        .iter()
+        // a student attends only the chosen electives of a pool
+        .filter(|t| t.kind == CourseTableKind::Mandatory)
+        .filter_map(|t| table_year(&t.title))
+        .collect();
+    years.sort_unstable();
+    years.dedup();
+    if years.is_empty() {
+        return None;
+    }
+
+    let years = years
+        .into_iter()
+        .map(|year| {
+            let tables: Vec<&CourseTable> = info
+                .course_tables
+                .iter()
+                .filter(|t| t.kind == CourseTableKind::Mandatory && table_year(&t.title) == Some(year))
+                .collect();
+            year_load(year, &tables)
+        })
+        .collect();
+
+    Some(WeeklyLoad {
+        programme: info.name.clone(),
+        weeks_per_semester,
+        years,
+    })
+}
+
+impl fmt::Display for WeeklyLoad {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Estimated weekly contact hours: {}\n", self.programme)?;
+        writeln!(f, "| Year | Contact hours per year | Courses | ≈ Hours per week |")?;
+        writeln!(f, "|---|---|---|---|")?;
+        for year in &self.years {
+            writeln!(
+                f,
+                "| {} | {} | {} | {:.1} |",
+                year.year,
+                year.contact_hours,
+                year.courses,
+                year.weekly_hours(self.weeks_per_semester)
+            )?;
+        }
+
+        let without_hours: usize = self.years.iter().map(|y| y.courses_without_hours).sum();
+        if without_hours > 0 {
+            writeln!(
+                f,
+                "\n{} course rows (mostly electives) list no hours and are not included.",
+                without_hours
+            )?;
+        }
+        writeln!(
+            f,
+            "\n*Estimate only: lectures, seminars, tutorials and lab work summed per year and spread over two semesters of {} weeks. Actual weekly hours vary by semester and timetable, and self-study time is not included.*",
+            self.weeks_per_semester
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YEAR_TABLES_PAGE: &str = r#"<html><body>
+<h1>Computer Science</h1>
+<div class="content">
+<h2>Course structure</h2>
+<h3>1st year</h3>
+<table>
+<tr><th>Course</th><th>ECTS</th><th>L</th><th>S</th><th>T</th><th>LW</th><th>Total</th></tr>
+<tr><td>Programming I</td><td>6</td><td>45</td><td>0</td><td>30</td><td>15</td><td>180</td></tr>
+<tr><td>Discrete Structures I</td><td>6</td><td>45</td><td>0</td><td>45</td><td>0</td><td>180</td></tr>
+<tr><td>Elective course</td><td>6</td><td></td><td></td><td></td><td></td><td>180</td></tr>
+<tr><td>Total</td><td>18</td><td>90</td><td>0</td><td>75</td><td>15</td><td>540</td></tr>
+</table>
+</div>
+</body></html>"#;
+
+    #[test]
+    fn hours_are_summed_per_year_and_spread_over_two_semesters() {
+        let info = ProgrammeInfo::from(YEAR_TABLES_PAGE.to_string());
+
+        let load = weekly_load(&info, DEFAULT_WEEKS_PER_SEMESTER).unwrap();
+
+        let year = &load.years[0];
+        assert_eq!(year.year, 1);
+        assert_eq!(year.contact_hours, 180.0);
+        assert_eq!(year.courses, 3);
+        assert_eq!(year.courses_without_hours, 1);
+        assert_eq!(year.weekly_hours(DEFAULT_WEEKS_PER_SEMESTER), 6.0);
+        assert!(load.to_string().contains("| 1 | 180 | 3 | 6.0 |"));
+    }
+}
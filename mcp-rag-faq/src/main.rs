@@ -9,16 +9,19 @@ use rmcp::{
 use anyhow::Result;
 use serde::{de::IntoDeserializer, Deserialize};
 use tokio::sync::Mutex;
-use crate::rag::Rag;
+use crate::{not_found::not_found, rag::{models::chunks::ResultChunk, Rag}};
 
+mod not_found;
 mod rag;
 
 const BIND_ADDRESS: &str = "127.0.0.1:8007";
+const DEFAULT_FAQ_PAGE_SIZE: u64 = 10;
 
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let _ = dotenv::dotenv();
+    Rag::default().check_embedding_model().await?;
 
     let service = StreamableHttpService::new(
         || Ok(Service::new()),
@@ -43,6 +46,16 @@ pub struct StructRequest {
     pub k: u64
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListFaqsRequest {
+    #[schemars(description = "1-based page number. Defaults to 1.")]
+    pub page: Option<u64>,
+    #[schemars(description = "Number of FAQs per page. Defaults to 10.")]
+    pub page_size: Option<u64>,
+    #[schemars(description = "Only list FAQs with this classification (category).")]
+    pub category: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 struct Service {
     tool_router: ToolRouter<Service>,
@@ -66,11 +79,15 @@ impl Service {
         let start = SystemTime::now();
 
         let rag = Rag::default();
-        let results = match rag.search_k(question, k).await {
+        let results = match rag.search_k(question, k, None).await {
             Ok(re) => re,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         };
 
+        if results.is_empty() {
+            return Ok(not_found("No similar questions were found in the FAQ."));
+        }
+
         let resp: Vec<Content> = results
             .iter()
             .map(|c| Content::text::<String>(c.into()))
@@ -78,6 +95,52 @@ impl Service {
 
         Ok(CallToolResult::success(resp))
     }
+
+    #[tool(description = "Browse the FAQ page by page, e.g. to suggest common questions to a new student. Optionally restrict the listing to one FAQ category.")]
+    pub async fn list_faqs(
+        &self,
+        Parameters(ListFaqsRequest{page, page_size, category}): Parameters<ListFaqsRequest>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let page = page.unwrap_or(1).max(1);
+        let page_size = page_size.unwrap_or(DEFAULT_FAQ_PAGE_SIZE).max(1);
+
+        let rag = Rag::default();
+        let faqs = match rag.list_faqs(category.as_deref()).await {
+            Ok(f) => f,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+
+        if faqs.is_empty() {
+            let msg = match category {
+                Some(c) => format!("No FAQs found in category '{}'.", c),
+                None => "The FAQ is empty.".to_string(),
+            };
+            return Ok(not_found(msg));
+        }
+
+        match faq_page(&faqs, page, page_size) {
+            Ok(out) => Ok(CallToolResult::success(vec![Content::text(out)])),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
+}
+
+/// Markdown listing of the 1-based `page` of `faqs`, numbered across pages.
+fn faq_page(faqs: &[ResultChunk], page: u64, page_size: u64) -> Result<String, String> {
+    let total_pages = (faqs.len() as u64).div_ceil(page_size);
+    if page > total_pages {
+        return Err(format!(
+            "Page {} does not exist; there are {} pages of {} FAQs.",
+            page, total_pages, page_size
+        ));
+    }
+
+    let start = ((page - 1) * page_size) as usize;
+    let mut out = format!("## FAQ (page {} of {}, {} entries)\n\n", page, total_pages, faqs.len());
+    for (i, faq) in faqs.iter().enumerate().skip(start).take(page_size as usize) {
+        out.push_str(&format!("{}. **{}** _({})_\n   {}\n\n", i + 1, faq.question, faq.classification, faq.answer));
+    }
+    Ok(out.trim_end().to_string())
 }
 
 #[tool_handler]
@@ -90,3 +153,46 @@ impl ServerHandler for Service {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn faqs(n: usize) -> Vec<ResultChunk> {
+        (1..=n)
+            .map(|i| ResultChunk {
+                id: i.to_string(),
+                thread_id: i.to_string(),
+                question: format!("Question {}?", i),
+                answer: format!("Answer {}.", i),
+                keywords: vec![],
+                classification: "Enrolment".to_string(),
+                score: 0.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pages_are_numbered_across_the_listing() {
+        let faqs = faqs(5);
+
+        let first = faq_page(&faqs, 1, 2).unwrap();
+        assert!(first.starts_with("## FAQ (page 1 of 3, 5 entries)"));
+        assert!(first.contains("1. **Question 1?** _(Enrolment)_\n   Answer 1."));
+        assert!(first.contains("2. **Question 2?**"));
+        assert!(!first.contains("Question 3?"));
+
+        let last = faq_page(&faqs, 3, 2).unwrap();
+        assert!(last.starts_with("## FAQ (page 3 of 3, 5 entries)"));
+        assert!(last.ends_with("5. **Question 5?** _(Enrolment)_\n   Answer 5."));
+        assert!(!last.contains("Question 4?"));
+    }
+
+    #[test]
+    fn page_past_the_end_is_an_error() {
+        assert_eq!(
+            faq_page(&faqs(5), 4, 2),
+            Err("Page 4 does not exist; there are 3 pages of 2 FAQs.".to_string())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, fmt};
 
 use anyhow::Result;
 use ollama_rs::generation::embeddings::request::GenerateEmbeddingsRequest;
@@ -7,12 +7,44 @@ use serde::Serialize;
 
 use crate::rag::models::chunks::EmbeddedChunk;
 
+const DEFAULT_EMBEDDING_MODEL: &str = "bge-m3";
+
+/// Ollama model embedding both the ingested chunks and the search queries,
+/// read from `EMBEDDING_MODEL`. Vectors of different models cannot be
+/// compared, so a collection must be re-ingested after changing it.
+pub fn embedding_model() -> String {
+    env::var("EMBEDDING_MODEL")
+        .ok()
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string())
+}
+
 pub trait Embeddable {
     fn try_into_embed(&self) -> GenerateEmbeddingsRequest;
     fn set_embedding_vectors(&mut self, embedding_vector: Vec<EmbeddingVector>);
     fn prepare_for_upload(self, parent_doc_id: String, doc_summary: Option<String>) -> Result<Vec<EmbeddedChunk>>;
 }
 
+/// Why a search query could not be embedded.
+#[derive(Debug)]
+pub enum EmbeddingError {
+    Request(String),
+    /// The model answered without any embedding vector.
+    Empty,
+}
+
+impl fmt::Display for EmbeddingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmbeddingError::Request(e) => write!(f, "Failed embedding the query: {}", e),
+            EmbeddingError::Empty => write!(f, "Failed embedding the query: the model returned no embeddings"),
+        }
+    }
+}
+
+impl std::error::Error for EmbeddingError {}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct EmbeddingVector(pub Vec<f32>);
 
@@ -3,7 +3,7 @@ use std::env;
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use qdrant_client::{
-    qdrant::{PointStruct, SearchPoints, SearchResponse, UpsertPointsBuilder},
+    qdrant::{Condition, Filter, PointStruct, RetrievedPoint, ScrollPointsBuilder, SearchPoints, SearchResponse, UpsertPointsBuilder},
     Qdrant,
 };
 use tokio::sync::Mutex;
@@ -12,6 +12,8 @@ use crate::rag::models::chunks::EmbeddedChunk;
 
 use super::embedding::EmbeddingVector;
 
+const SCROLL_PAGE_SIZE: u32 = 256;
+
 /// Static global client for accessing the Qdrant database.
 ///
 /// This variable initializes a Qdrant client connection that is used to interact with the Qdrant vector database.
@@ -42,21 +44,74 @@ static QDRANT_CLIENT: Lazy<Mutex<Qdrant>> = Lazy::new(|| {
 ///
 /// # Errors
 /// - Returns an error if the tensor conversion fails or if the Qdrant search query encounters issues.
-pub async fn vector_search(embedding: EmbeddingVector) -> Result<SearchResponse> {
+pub async fn vector_search(embedding: EmbeddingVector, with_vectors: bool) -> Result<SearchResponse> {
     let client = QDRANT_CLIENT.lock().await;
+    let mut embedding: SearchPoints = embedding.into();
+    embedding.with_vectors = Some(with_vectors.into());
     let search_result = client.search_points(embedding).await?;
     Ok(search_result.into())
 }
 
-pub async fn vector_search_k(embedding: EmbeddingVector, k: u64) -> Result<SearchResponse> {
+/// Top `k` points of the collection, restricted to the points matching
+/// `filter` when given. `with_vectors` also returns the stored vectors.
+pub async fn vector_search_k(embedding: EmbeddingVector, k: u64, filter: Option<Filter>, with_vectors: bool) -> Result<SearchResponse> {
     println!("K: {k}");
     let client = QDRANT_CLIENT.lock().await;
     let mut embedding: SearchPoints = embedding.into();
     embedding.limit = k;
+    embedding.filter = filter;
+    embedding.with_vectors = Some(with_vectors.into());
     let search_result = client.search_points(embedding).await?;
     Ok(search_result.into())
 }
 
+/// Retrieves every point of the collection with its payload, following the
+/// scroll offsets page by page.
+pub async fn scroll_all_points() -> Result<Vec<RetrievedPoint>> {
+    let client = QDRANT_CLIENT.lock().await;
+    let qdrant_collection = env::var("QDRANT_COLLECTION").expect("QDRANT_COLLECTION not defined");
+
+    let mut points = Vec::new();
+    let mut offset = None;
+    loop {
+        let mut request = ScrollPointsBuilder::new(&qdrant_collection)
+            .limit(SCROLL_PAGE_SIZE)
+            .with_payload(true);
+        if let Some(id) = offset.take() {
+            request = request.offset(id);
+        }
+        let response = client.scroll(request).await?;
+        points.extend(response.result);
+        match response.next_page_offset {
+            Some(next) => offset = Some(next),
+            None => break,
+        }
+    }
+    Ok(points)
+}
+
+/// Embedding model of a point that was embedded with another model than
+/// `model`, if the collection has one. Points stored before the model was
+/// recorded are not considered.
+pub async fn stored_embedding_model(model: &str) -> Result<Option<String>> {
+    let client = QDRANT_CLIENT.lock().await;
+    let qdrant_collection = env::var("QDRANT_COLLECTION").expect("QDRANT_COLLECTION not defined");
+
+    let other_model = Filter::must_not([
+        Condition::is_empty("embedding_model"),
+        Condition::matches("embedding_model", model.to_string()),
+    ]);
+    let response = client
+        .scroll(ScrollPointsBuilder::new(qdrant_collection).filter(other_model).limit(1).with_payload(true))
+        .await?;
+    Ok(response
+        .result
+        .first()
+        .and_then(|point| point.payload.get("embedding_model"))
+        .and_then(|v| v.as_str())
+        .map(|m| m.to_string()))
+}
+
 pub async fn insert_chunks_to_qdrant(embedded_chunks: Vec<EmbeddedChunk>) -> Result<()> {
     println!("Upserting to qdrant...");
     let client = QDRANT_CLIENT.lock().await;
@@ -1,15 +1,21 @@
-use std::{fs::File, io::{BufWriter, Write}};
+use std::{future::Future, collections::HashSet, env, fs::File, io::{BufWriter, Write}};
 
 use anyhow::{anyhow, Result};
 use comm::{
-    embedding::EmbeddingVector,
-    qdrant::{insert_chunks_to_qdrant, vector_search},
+    embedding::{embedding_model, EmbeddingError, EmbeddingVector},
+    embedding_cache::{EmbeddingCache, EMBEDDING_CACHE},
+    qdrant::{insert_chunks_to_qdrant, stored_embedding_model, vector_search},
     OllamaClient,
 };
 use loading::load_file;
 use models::SearchResult;
-use ollama_rs::generation::embeddings::request::{EmbeddingsInput, GenerateEmbeddingsRequest};
-use processing::{chunk, dedup, hype, prepare_for_upload, prompt};
+use ollama_rs::generation::embeddings::{
+    request::{EmbeddingsInput, GenerateEmbeddingsRequest},
+    GenerateEmbeddingsResponse,
+};
+use processing::{chunk, dedup, dedup_threshold, hype, prepare_for_upload, prompt};
+use qdrant_client::qdrant::{Condition, Filter};
+use tokio::sync::Mutex;
 
 pub mod comm;
 pub mod loading;
@@ -18,7 +24,7 @@ pub mod processing;
 
 pub use models::RagProcessableFile;
 
-use crate::rag::{comm::qdrant::vector_search_k, models::chunks::ResultChunk};
+use crate::rag::{comm::qdrant::{scroll_all_points, vector_search_k}, models::chunks::ResultChunk};
 
 #[derive(Debug, Default)]
 pub struct Rag {
@@ -26,9 +32,20 @@ pub struct Rag {
 }
 
 impl Rag {
+    /// Fails when the collection holds chunks embedded with another model
+    /// than `EMBEDDING_MODEL`, whose vectors cannot be compared with the
+    /// query vectors. With `EMBEDDING_MODEL_CHECK=warn` the mismatch is only
+    /// reported.
+    pub async fn check_embedding_model(&self) -> Result<()> {
+        let configured = embedding_model();
+        let stored = stored_embedding_model(&configured).await?;
+        let warn_only = env::var("EMBEDDING_MODEL_CHECK").is_ok_and(|v| v.trim().eq_ignore_ascii_case("warn"));
+        check_model_match(stored.as_deref(), &configured, warn_only)
+    }
+
     pub async fn insert(&self, file: RagProcessableFile) -> Result<()> {
         let loaded_file = load_file(&file)?;
-        let chunked_file = chunk(loaded_file, processing::ChunkingStrategy::Word(250, 30));
+        let chunked_file = chunk(loaded_file, processing::ChunkingStrategy::from_env()?);
         let enriched_file = hype(chunked_file, &self.ollama).await;
         let embedded_chunks = prepare_for_upload(enriched_file, &self.ollama).await?;
         insert_chunks_to_qdrant(embedded_chunks).await
@@ -50,13 +67,10 @@ impl Rag {
     }
 
     pub async fn search(&self, query: String) -> Result<SearchResult> {
-        let emb_query = GenerateEmbeddingsRequest::new("bge-m3".to_owned(), EmbeddingsInput::Single(query.clone()));
-        let embedding = match self.ollama.embed(emb_query).await {
-            Ok(resp) => EmbeddingVector(resp.embeddings[0].clone()),
-            Err(e) => return Err(anyhow!(format!("Failed embedding the query: {}", e))),
-        };
-        let resp = vector_search(embedding).await?;
-        let resp = dedup(resp);
+        let embedding = self.embed_query(&query).await?;
+        let threshold = dedup_threshold();
+        let resp = vector_search(embedding, threshold.is_some()).await?;
+        let resp = dedup(resp, threshold);
         println!("{:#?}", resp);
         match prompt(query, resp, &self.ollama).await {
             Ok(r) => Ok(r),
@@ -64,14 +78,179 @@ impl Rag {
         }
     }
 
-    pub async fn search_k(&self, query: String, k: u64) -> Result<Vec<ResultChunk>> {
-        let emb_query = GenerateEmbeddingsRequest::new("bge-m3".to_owned(), EmbeddingsInput::Single(query.clone()));
-        let embedding = match self.ollama.embed(emb_query).await {
-            Ok(resp) => EmbeddingVector(resp.embeddings[0].clone()),
-            Err(e) => return Err(anyhow!(format!("Failed embedding the query: {}", e))),
-        };
-        let resp = vector_search_k(embedding, k).await?;
+    /// Top `k` chunks for `query`. With a `tag_filter`, only chunks of
+    /// documents tagged with at least one of the tags are searched.
+    pub async fn search_k(&self, query: String, k: u64, tag_filter: Option<Vec<String>>) -> Result<Vec<ResultChunk>> {
+        let filter = tags_filter(tag_filter);
+        let embedding = self.embed_query(&query).await?;
+        let threshold = dedup_threshold();
+        let resp = vector_search_k(embedding, k, filter, threshold.is_some()).await?;
         println!("HITS: {:#?}", resp);
-        Ok(dedup(resp))
+        Ok(dedup(resp, threshold))
+    }
+
+    /// All FAQ entries in collection order, one per distinct question.
+    /// `category` is matched case-insensitively against the classification.
+    pub async fn list_faqs(&self, category: Option<&str>) -> Result<Vec<ResultChunk>> {
+        let points = scroll_all_points().await?;
+        let mut seen = HashSet::new();
+        let faqs = points
+            .into_iter()
+            .map(ResultChunk::from)
+            .filter(|faq| category.is_none_or(|c| faq.classification.trim().eq_ignore_ascii_case(c.trim())))
+            .filter(|faq| seen.insert(faq.question.trim().to_lowercase()))
+            .collect();
+        Ok(faqs)
+    }
+
+    /// Embeds a search query, reusing the vector of an identical earlier query.
+    async fn embed_query(&self, query: &str) -> Result<EmbeddingVector> {
+        cached_embedding(&EMBEDDING_CACHE, query, || self.embed_once(query)).await
+    }
+
+    async fn embed_once(&self, query: &str) -> Result<EmbeddingVector, EmbeddingError> {
+        let emb_query = GenerateEmbeddingsRequest::new(embedding_model(), EmbeddingsInput::Single(query.to_owned()));
+        let resp = self
+            .ollama
+            .embed(emb_query)
+            .await
+            .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+        first_embedding(resp)
+    }
+}
+
+/// The vector of a single-input embedding response.
+fn first_embedding(resp: GenerateEmbeddingsResponse) -> Result<EmbeddingVector, EmbeddingError> {
+    resp.embeddings
+        .into_iter()
+        .next()
+        .filter(|e| !e.is_empty())
+        .map(EmbeddingVector)
+        .ok_or(EmbeddingError::Empty)
+}
+
+/// Fails when the collection holds chunks embedded with `stored` instead of
+/// the `configured` model; with `warn_only` the mismatch is only reported.
+fn check_model_match(stored: Option<&str>, configured: &str, warn_only: bool) -> Result<()> {
+    let Some(stored) = stored else {
+        return Ok(());
+    };
+    let message = format!(
+        "The collection contains chunks embedded with '{}', but EMBEDDING_MODEL is '{}'. Re-ingest the collection with '{}' or set EMBEDDING_MODEL={}.",
+        stored, configured, configured, stored
+    );
+    if warn_only {
+        eprintln!("Re-embedding required: {}", message);
+        return Ok(());
+    }
+    Err(anyhow!(message))
+}
+
+/// Filter matching chunks tagged with at least one of `tags`, none when no
+/// tags are given.
+fn tags_filter(tags: Option<Vec<String>>) -> Option<Filter> {
+    tags.filter(|tags| !tags.is_empty())
+        .map(|tags| Filter::must([Condition::matches("tags", tags)]))
+}
+
+/// Embeds `query` with `embed`, unless `cache` holds the vector of an
+/// identical earlier query.
+async fn cached_embedding<F, Fut>(cache: &Mutex<EmbeddingCache>, query: &str, embed: F) -> Result<EmbeddingVector>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<EmbeddingVector, EmbeddingError>>,
+{
+    if let Some(embedding) = cache.lock().await.get(query) {
+        return Ok(embedding);
+    }
+
+    // a failed or empty response is retried once before giving up
+    let embedding = match embed().await {
+        Ok(embedding) => embedding,
+        Err(e) => {
+            eprintln!("{}, retrying", e);
+            embed().await?
+        }
+    };
+
+    cache.lock().await.insert(query.to_owned(), embedding.clone());
+    Ok(embedding)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use qdrant_client::qdrant::{condition::ConditionOneOf, r#match::MatchValue, RepeatedStrings};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn repeated_query_reuses_the_cached_embedding() {
+        let cache = Mutex::new(EmbeddingCache::new(8));
+        let calls = AtomicUsize::new(0);
+        let embed = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(EmbeddingVector(vec![0.1, 0.2, 0.3]))
+        };
+
+        let first = cached_embedding(&cache, "kdaj je vpis?", embed).await.unwrap();
+        let second = cached_embedding(&cache, "kdaj je vpis?", embed).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.0, second.0);
+    }
+
+    #[tokio::test]
+    async fn empty_embeddings_are_an_error_after_one_retry() {
+        let empty = || GenerateEmbeddingsResponse { embeddings: vec![] };
+        assert!(matches!(first_embedding(empty()), Err(EmbeddingError::Empty)));
+        assert!(matches!(
+            first_embedding(GenerateEmbeddingsResponse { embeddings: vec![vec![]] }),
+            Err(EmbeddingError::Empty)
+        ));
+
+        let cache = Mutex::new(EmbeddingCache::new(8));
+        let calls = AtomicUsize::new(0);
+        let embed = || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            first_embedding(empty())
+        };
+
+        let err = cached_embedding(&cache, "kdaj je vpis?", embed).await.unwrap_err();
+        assert_eq!(err.to_string(), "Failed embedding the query: the model returned no embeddings");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(cache.lock().await.get("kdaj je vpis?").is_none());
+    }
+
+    #[test]
+    fn tags_filter_matches_any_of_the_tags() {
+        assert!(tags_filter(None).is_none());
+        assert!(tags_filter(Some(vec![])).is_none());
+
+        let filter = tags_filter(Some(vec!["erasmus".to_string(), "exams".to_string()])).unwrap();
+        let [condition] = filter.must.as_slice() else {
+            panic!("expected a single condition, got {:?}", filter.must);
+        };
+        let Some(ConditionOneOf::Field(field)) = &condition.condition_one_of else {
+            panic!("expected a field condition, got {:?}", condition);
+        };
+        assert_eq!(field.key, "tags");
+        assert_eq!(
+            field.r#match.as_ref().and_then(|m| m.match_value.clone()),
+            Some(MatchValue::Keywords(RepeatedStrings { strings: vec!["erasmus".to_string(), "exams".to_string()] }))
+        );
+    }
+
+    #[test]
+    fn chunks_of_another_model_fail_the_check_unless_only_warned() {
+        assert!(check_model_match(None, "bge-m3", false).is_ok());
+        assert!(check_model_match(Some("nomic-embed-text"), "bge-m3", true).is_ok());
+
+        let err = check_model_match(Some("nomic-embed-text"), "bge-m3", false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The collection contains chunks embedded with 'nomic-embed-text', but EMBEDDING_MODEL is 'bge-m3'. Re-ingest the collection with 'bge-m3' or set EMBEDDING_MODEL=nomic-embed-text."
+        );
     }
 }
@@ -1,4 +1,6 @@
-use qdrant_client::qdrant::{ScoredPoint, Value as QValue};
+use std::collections::HashMap;
+
+use qdrant_client::qdrant::{PointId, RetrievedPoint, ScoredPoint, Value as QValue};
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -14,15 +16,27 @@ pub struct ResultChunk {
 
 impl From<ScoredPoint> for ResultChunk {
     fn from(value: ScoredPoint) -> Self {
+        Self::from_payload(value.id, &value.payload, value.score)
+    }
+}
+
+// Points read by scrolling carry no similarity score
+impl From<RetrievedPoint> for ResultChunk {
+    fn from(value: RetrievedPoint) -> Self {
+        Self::from_payload(value.id, &value.payload, 0.0)
+    }
+}
+
+impl ResultChunk {
+    fn from_payload(id: Option<PointId>, payload: &HashMap<String, QValue>, score: f32) -> Self {
         // id can be numeric or uuid in Qdrant, keep debug format like before
-        let id: String = match value.id {
+        let id: String = match id {
             Some(d) => format!("{:?}", d),
             None => "Unknown".into(),
         };
 
         let get_str = |key: &str| -> String {
-            value
-                .payload
+            payload
                 .get(key)
                 .and_then(QValue::as_str)
                 .unwrap_or(&"Unknown".to_string())
@@ -35,8 +49,7 @@ impl From<ScoredPoint> for ResultChunk {
         let classification = get_str("classification");
 
         // keywords is a list of strings in your payload
-        let keywords: Vec<String> = value
-            .payload
+        let keywords: Vec<String> = payload
             .get("keywords")
             .and_then(QValue::as_list)
             .map(|lst| {
@@ -47,7 +60,7 @@ impl From<ScoredPoint> for ResultChunk {
             })
             // fallback if someone accidentally indexed as a comma separated string
             .or_else(|| {
-                let s = value.payload.get("keywords").and_then(QValue::as_str)?;
+                let s = payload.get("keywords").and_then(QValue::as_str)?;
                 let items = s
                     .split(',')
                     .map(|x| x.trim())
@@ -65,7 +78,7 @@ impl From<ScoredPoint> for ResultChunk {
             answer,
             keywords,
             classification,
-            score: value.score,
+            score,
         }
     }
 }
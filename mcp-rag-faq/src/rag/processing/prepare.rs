@@ -11,11 +11,16 @@ where
     T: Embeddable,
 {
     let descr = file.syntetic_file_description.clone();
+    let tags = file.tags.clone().unwrap_or_default();
     let embedded_file = embedd_file(file, ollama).await?;
     Ok(embedded_file
         .chunks
         .into_iter()
         .filter_map(|c| c.prepare_for_upload(embedded_file.internal_id.to_string(), descr.clone()).ok())
         .flatten()
+        .map(|mut c| {
+            c.tags = tags.clone();
+            c
+        })
         .collect())
 }
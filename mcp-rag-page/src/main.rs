@@ -7,8 +7,9 @@ use rmcp::{
 use anyhow::Result;
 use serde::{de::IntoDeserializer, Deserialize};
 use tokio::sync::Mutex;
-use crate::rag::Rag;
+use crate::{not_found::not_found, rag::Rag};
 
+mod not_found;
 mod rag;
 
 const BIND_ADDRESS: &str = "127.0.0.1:8005";
@@ -18,6 +19,7 @@ const BIND_ADDRESS: &str = "127.0.0.1:8005";
 async fn main() -> Result<()> {
     init_default_tracing();
     let _ = dotenv::dotenv();
+    Rag::default().check_embedding_model().await?;
 
     let service = StreamableHttpService::new(
         || Ok(Service::new()),
@@ -39,7 +41,9 @@ async fn main() -> Result<()> {
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct StructRequest {
     pub question: String,
-    pub k: u64
+    pub k: u64,
+    #[schemars(description = "Only search documents with at least one of these tags (the subfolders they were ingested from), e.g. ['rules'].")]
+    pub tag_filter: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone)]
@@ -58,18 +62,47 @@ impl Service {
     #[tool(description = "Consult university general knowledge base. Given a question will return 'k' passages that may contain answers. Use question form and keep questions percise with long forms and named entities. Recommended k is 1 or 2")]
     pub async fn ask_about_general_information(
         &self, 
-        Parameters(StructRequest{question, k}): Parameters<StructRequest>,
+        Parameters(StructRequest{question, k, tag_filter}): Parameters<StructRequest>,
         client: Peer<RoleServer>,
         meta: Meta
     ) -> Result<CallToolResult, rmcp::Error> {
         let start = SystemTime::now();
 
         let rag = Rag::default();
-        let results = match rag.search_k(question, k).await {
+        let results = match rag.search_k(question, k, tag_filter).await {
             Ok(re) => re,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         };
 
+        if results.is_empty() {
+            return Ok(not_found("No passages on this were found in the university web pages."));
+        }
+
+        let resp: Vec<Content> = results
+            .iter()
+            .map(|c| Content::text::<String>(c.into()))
+            .collect();
+
+        Ok(CallToolResult::success(resp))
+    }
+
+    #[tool(description = "Search the web page, rules and FAQ knowledge bases at once. Given a question will return the 'k' best passages across all of them, each tagged with the collection it came from and its score. Use for questions that could be answered by any of them. Use question form and keep questions percise with long forms and named entities. Recommended k is 3 to 5")]
+    pub async fn search_all_collections(
+        &self,
+        Parameters(StructRequest{question, k, tag_filter}): Parameters<StructRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let rag = Rag::default();
+        let results = match rag.search_all(question, k, tag_filter).await {
+            Ok(re) => re,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+
+        if results.is_empty() {
+            return Ok(not_found("No passages found in any collection."));
+        }
+
         let resp: Vec<Content> = results
             .iter()
             .map(|c| Content::text::<String>(c.into()))
@@ -0,0 +1,73 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    env,
+};
+
+use once_cell::sync::Lazy;
+use tokio::sync::Mutex;
+
+use super::embedding::EmbeddingVector;
+
+const DEFAULT_EMBEDDING_CACHE_SIZE: usize = 256;
+
+/// Static global cache of query embeddings.
+///
+/// The planner often sends the same question several times; cached vectors
+/// skip the Ollama round trip. The size is read from `EMBEDDING_CACHE_SIZE`
+/// (`0` disables caching).
+pub static EMBEDDING_CACHE: Lazy<Mutex<EmbeddingCache>> = Lazy::new(|| {
+    let capacity = env::var("EMBEDDING_CACHE_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_EMBEDDING_CACHE_SIZE);
+    Mutex::new(EmbeddingCache::new(capacity))
+});
+
+/// Bounded least-recently-used map of `query -> embedding`.
+#[derive(Debug)]
+pub struct EmbeddingCache {
+    capacity: usize,
+    entries: HashMap<String, EmbeddingVector>,
+    // front = least recently used
+    order: VecDeque<String>,
+}
+
+impl EmbeddingCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&mut self, query: &str) -> Option<EmbeddingVector> {
+        let embedding = self.entries.get(query)?.clone();
+        self.touch(query);
+        Some(embedding)
+    }
+
+    pub fn insert(&mut self, query: String, embedding: EmbeddingVector) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.insert(query.clone(), embedding).is_some() {
+            self.touch(&query);
+            return;
+        }
+        self.order.push_back(query);
+        while self.order.len() > self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+
+    fn touch(&mut self, query: &str) {
+        if let Some(pos) = self.order.iter().position(|q| q == query) {
+            if let Some(q) = self.order.remove(pos) {
+                self.order.push_back(q);
+            }
+        }
+    }
+}
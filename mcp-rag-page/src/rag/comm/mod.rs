@@ -6,14 +6,35 @@ use ollama_rs::{
     },
     Ollama,
 };
+use once_cell::sync::Lazy;
 use question::Question;
 use std::env;
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
+pub mod chunk_cache;
 pub mod embedding;
+pub mod embedding_cache;
 pub mod qdrant;
 pub mod question;
 
+const DEFAULT_OLLAMA_MAX_CONCURRENCY: usize = 4;
+
+/// Permits for requests to Ollama, shared by every client of the process,
+/// so ingestion and searches together stay under the endpoint's capacity.
+/// The size is read from `OLLAMA_MAX_CONCURRENCY` (`0` removes the cap).
+static OLLAMA_PERMITS: Lazy<Semaphore> = Lazy::new(|| {
+    let limit = env::var("OLLAMA_MAX_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .unwrap_or(DEFAULT_OLLAMA_MAX_CONCURRENCY);
+    Semaphore::new(if limit == 0 { Semaphore::MAX_PERMITS } else { limit })
+});
+
+async fn ollama_permit() -> SemaphorePermit<'static> {
+    OLLAMA_PERMITS.acquire().await.expect("Ollama semaphore is never closed")
+}
+
 #[derive(Debug)]
 pub struct OllamaClient {
     ollama: Ollama,
@@ -46,14 +67,18 @@ impl Default for OllamaClient {
 
 impl OllamaClient {
     pub async fn generate(&self, question: Question) -> Result<GenerationResponse, OllamaError> {
+        let _permit = ollama_permit().await;
         self.ollama.generate((&question).into()).await
     }
 
+    /// The permit is held until the stream is opened, not while it is read.
     pub async fn generate_stream(&self, question: Question) -> Result<GenerationResponseStream, OllamaError> {
+        let _permit = ollama_permit().await;
         self.ollama.generate_stream((&question).into()).await
     }
 
     pub async fn embed(&self, req: GenerateEmbeddingsRequest) -> Result<GenerateEmbeddingsResponse, OllamaError> {
+        let _permit = ollama_permit().await;
         self.ollama.generate_embeddings(req).await
     }
 
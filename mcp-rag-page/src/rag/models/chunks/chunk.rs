@@ -1,4 +1,4 @@
-use crate::rag::comm::embedding::{Embeddable, EmbeddingVector};
+use crate::rag::comm::embedding::{embedding_model, Embeddable, EmbeddingVector};
 use anyhow::{anyhow, Result};
 use ollama_rs::generation::embeddings::request::{EmbeddingsInput, GenerateEmbeddingsRequest};
 use serde_json::Value;
@@ -14,7 +14,7 @@ pub struct Chunk {
 
 impl Embeddable for Chunk {
     fn try_into_embed(&self) -> GenerateEmbeddingsRequest {
-        GenerateEmbeddingsRequest::new("bge-m3".to_owned(), EmbeddingsInput::Single(self.text.clone()))
+        GenerateEmbeddingsRequest::new(embedding_model(), EmbeddingsInput::Single(self.text.clone()))
     }
 
     fn set_embedding_vectors(&mut self, embedding_vectors: Vec<EmbeddingVector>) {
@@ -34,6 +34,7 @@ impl Embeddable for Chunk {
             doc_seq_num: self.seq_num,
             content: self.text,
             additional_data: Value::Null,
+            tags: vec![],
             doc_summary,
         }])
     }
@@ -0,0 +1,98 @@
+use qdrant_client::qdrant::ScoredPoint;
+use serde::Serialize;
+
+/// A search hit from any of the RAG collections.
+///
+/// Collections store different payloads: page and rules passages keep the
+/// text in `chunk`, FAQ entries in `question` and `answer`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectionHit {
+    pub collection: String,
+    pub score: f32,
+    pub source: String,
+    pub content: String,
+}
+
+impl CollectionHit {
+    pub fn from_point(collection: &str, point: ScoredPoint) -> Self {
+        let get_str = |key: &str| -> Option<String> {
+            point
+                .payload
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_owned())
+                .filter(|s| !s.trim().is_empty())
+        };
+
+        let content = match (get_str("chunk"), get_str("question"), get_str("answer")) {
+            (Some(chunk), _, _) => chunk,
+            (None, Some(question), Some(answer)) => format!("Question:\n{}\n\nAnswer:\n{}", question, answer),
+            (None, question, answer) => question.or(answer).unwrap_or_else(|| "Unknown".to_owned()),
+        };
+
+        let source = get_str("document_name")
+            .map(|name| name.replace("_", "/").replace(".md", ""))
+            .unwrap_or_else(|| "Unknown".to_owned());
+
+        Self {
+            collection: collection.to_owned(),
+            score: point.score,
+            source,
+            content,
+        }
+    }
+}
+
+impl Into<String> for &CollectionHit {
+    fn into(self) -> String {
+        format!(r#"
+            ---
+            Collection: {}
+            Score: {:.3}
+            Source: {}
+
+            Passage content:
+
+            {}
+
+            ---
+
+            "#,
+            self.collection,
+            self.score,
+            self.source,
+            self.content
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn point(payload: &[(&str, &str)], score: f32) -> ScoredPoint {
+        ScoredPoint {
+            payload: payload.iter().map(|(k, v)| (k.to_string(), (*v).into())).collect::<HashMap<_, _>>(),
+            score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn passages_and_faq_entries_are_tagged_with_collection_and_source() {
+        let passage = CollectionHit::from_point(
+            "page",
+            point(&[("chunk", "Enrolment is in September."), ("document_name", "www.famnit.upr.si_sl_studij.md")], 0.7),
+        );
+        assert_eq!(passage.collection, "page");
+        assert_eq!(passage.source, "www.famnit.upr.si/sl/studij");
+        assert_eq!(passage.content, "Enrolment is in September.");
+
+        let faq = CollectionHit::from_point("faq", point(&[("question", "When is enrolment?"), ("answer", "In September.")], 0.6));
+        assert_eq!(faq.collection, "faq");
+        assert_eq!(faq.source, "Unknown");
+        assert_eq!(faq.content, "Question:\nWhen is enrolment?\n\nAnswer:\nIn September.");
+    }
+}
@@ -1,9 +1,11 @@
 mod chunk;
+mod collection_hit;
 mod embedded_chunk;
 mod hype_chunk;
 mod result_chunk;
 
 pub use chunk::Chunk;
+pub use collection_hit::CollectionHit;
 pub use embedded_chunk::EmbeddedChunk;
 pub use hype_chunk::HypeChunk;
 pub use result_chunk::ResultChunk;
@@ -0,0 +1,74 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use crate::rag::models::chunks::CollectionHit;
+
+/// Merges the hits of several collections into the `k` best by score.
+///
+/// `results` is in the configured collection order, which breaks ties, so
+/// an equally good hit from an earlier collection wins. Passages found in
+/// more than one collection are kept once.
+pub fn fuse(results: Vec<Vec<CollectionHit>>, k: usize) -> Vec<CollectionHit> {
+    let mut hits: Vec<(usize, CollectionHit)> = results
+        .into_iter()
+        .enumerate()
+        .flat_map(|(rank, hits)| hits.into_iter().map(move |hit| (rank, hit)))
+        .collect();
+
+    hits.sort_by(|(rank_a, a), (rank_b, b)| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(Ordering::Equal)
+            .then(rank_a.cmp(rank_b))
+    });
+
+    let mut seen = HashSet::new();
+    hits.into_iter()
+        .map(|(_, hit)| hit)
+        .filter(|hit| seen.insert(hit.content.trim().to_lowercase()))
+        .take(k)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(collection: &str, score: f32, content: &str) -> CollectionHit {
+        CollectionHit {
+            collection: collection.to_string(),
+            score,
+            source: "Unknown".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn hits_are_ordered_by_score_and_keep_their_collection() {
+        let fused = fuse(
+            vec![
+                vec![hit("page", 0.71, "Enrolment is in September."), hit("page", 0.42, "The library opens at 8.")],
+                vec![hit("rules", 0.88, "Exams are held in three periods.")],
+                vec![hit("faq", 0.64, "Question:\nWhen is enrolment?\n\nAnswer:\nIn September.")],
+            ],
+            3,
+        );
+
+        let summary: Vec<(&str, f32)> = fused.iter().map(|h| (h.collection.as_str(), h.score)).collect();
+        assert_eq!(summary, [("rules", 0.88), ("page", 0.71), ("faq", 0.64)]);
+        assert!(Into::<String>::into(&fused[0]).contains("Collection: rules"));
+    }
+
+    #[test]
+    fn ties_go_to_the_earlier_collection_and_duplicates_are_kept_once() {
+        let fused = fuse(
+            vec![
+                vec![hit("page", 0.8, "Exams are held in three periods.")],
+                vec![hit("rules", 0.8, "exams are held in three periods. "), hit("rules", 0.8, "Article 3 applies.")],
+            ],
+            5,
+        );
+
+        let summary: Vec<(&str, &str)> = fused.iter().map(|h| (h.collection.as_str(), h.content.as_str())).collect();
+        assert_eq!(summary, [("page", "Exams are held in three periods."), ("rules", "Article 3 applies.")]);
+    }
+}
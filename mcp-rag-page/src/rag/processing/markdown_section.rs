@@ -0,0 +1,163 @@
+use crate::rag::{
+    loading::loaded_data::LoadedFile,
+    models::{chunks::Chunk, ChunkedFile},
+};
+
+use super::ChunkSize;
+
+/// Deepest heading level that starts a new section; deeper headings stay
+/// in the text of their section.
+const MAX_SECTION_LEVEL: usize = 3;
+
+/// A run of text under one heading, with the headings above it.
+struct Section {
+    headings: Vec<(usize, String)>,
+    body: Vec<String>,
+}
+
+/// Level and title of a `#`, `##` or `###` heading line.
+fn section_heading(line: &str) -> Option<(usize, String)> {
+    let line = line.trim_start();
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > MAX_SECTION_LEVEL {
+        return None;
+    }
+    let title = line[level..].strip_prefix([' ', '\t'])?.trim();
+    (!title.is_empty()).then(|| (level, title.trim_end_matches('#').trim().to_string()))
+}
+
+/// Splits a markdown document at its headings. Lines inside fenced code
+/// blocks are never read as headings.
+fn split_sections(content: &str) -> Vec<Section> {
+    let mut sections = vec![Section { headings: vec![], body: vec![] }];
+    let mut in_fence = false;
+
+    for line in content.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let heading = if in_fence { None } else { section_heading(line) };
+        match heading {
+            Some((level, title)) => {
+                let mut headings = sections.last().map(|s| s.headings.clone()).unwrap_or_default();
+                headings.retain(|(l, _)| *l < level);
+                headings.push((level, title));
+                sections.push(Section { headings, body: vec![] });
+            }
+            None => {
+                if let Some(section) = sections.last_mut() {
+                    section.body.push(line.to_string());
+                }
+            }
+        }
+    }
+    sections
+}
+
+/// Heading path of a section as markdown heading lines, e.g.
+/// `# Rules\n## Exams`.
+fn heading_prefix(headings: &[(usize, String)]) -> String {
+    headings
+        .iter()
+        .map(|(level, title)| format!("{} {}", "#".repeat(*level), title))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// One chunk per `#`/`##`/`###` section, each starting with the headings
+/// it sits under. Sections longer than `max_words` are split by words.
+pub fn markdown_section_chunking(file: LoadedFile, max_words: &ChunkSize) -> ChunkedFile<Chunk> {
+    let max_words = (*max_words).max(1) as usize;
+
+    let mut chunks = Vec::new();
+    let mut chunk_id = 0;
+
+    for section in split_sections(&file.content) {
+        let body = section.body.join("\n");
+        let body = body.trim();
+        if body.is_empty() {
+            continue;
+        }
+
+        let words: Vec<&str> = body.split_whitespace().collect();
+        let parts = if words.len() <= max_words {
+            vec![body.to_string()]
+        } else {
+            words.chunks(max_words).map(|w| w.join(" ")).collect()
+        };
+
+        let prefix = heading_prefix(&section.headings);
+        for part in parts {
+            let text = if prefix.is_empty() {
+                part
+            } else {
+                format!("{}\n\n{}", prefix, part)
+            };
+            chunks.push(Chunk {
+                seq_num: chunk_id,
+                text,
+                embedding_vector: None,
+            });
+            chunk_id += 1;
+        }
+    }
+
+    (file, chunks).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rag::models::RagProcessableFileType;
+
+    use super::*;
+
+    const RULES: &str = "# Study Rules
+Introduction to the rules.
+## Exams
+### Registration
+Students register for exams in the student portal.
+#### Late registration
+Late registration costs a fee.
+```
+# not a heading
+```
+## Enrolment
+Enrolment is in September.";
+
+    fn file(content: &str) -> LoadedFile {
+        LoadedFile {
+            file_type: RagProcessableFileType::Markdown,
+            content: content.to_string(),
+            original_file_description: None,
+            syntetic_file_description: None,
+            internal_id: "rules".to_string(),
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn each_chunk_starts_with_its_heading_path() {
+        let chunked = markdown_section_chunking(file(RULES), &100);
+
+        let texts: Vec<&str> = chunked.chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, [
+            "# Study Rules\n\nIntroduction to the rules.",
+            "# Study Rules\n## Exams\n### Registration\n\nStudents register for exams in the student portal.\n#### Late registration\nLate registration costs a fee.\n```\n# not a heading\n```",
+            "# Study Rules\n## Enrolment\n\nEnrolment is in September.",
+        ]);
+    }
+
+    #[test]
+    fn long_sections_repeat_the_heading_path_in_every_part() {
+        let chunked = markdown_section_chunking(file("# Rules\n## Exams\none two three four five"), &2);
+
+        let texts: Vec<&str> = chunked.chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, [
+            "# Rules\n## Exams\n\none two",
+            "# Rules\n## Exams\n\nthree four",
+            "# Rules\n## Exams\n\nfive",
+        ]);
+        let seq: Vec<i32> = chunked.chunks.iter().map(|c| c.seq_num).collect();
+        assert_eq!(seq, [0, 1, 2]);
+    }
+}
@@ -1,5 +1,11 @@
+use markdown_section::markdown_section_chunking;
+use sentence::sentence_chunking;
 use simple::simple_word_chunking;
 
+use std::{env, str::FromStr};
+
+use anyhow::{anyhow, bail};
+
 use super::{
     loading::loaded_data::LoadedFile,
     models::{chunks::Chunk, ChunkedFile},
@@ -7,13 +13,17 @@ use super::{
 
 mod dedup_embeddings;
 mod embedd_file;
+mod fusion;
 mod hype;
+mod markdown_section;
 mod prepare;
 mod prompt;
+mod sentence;
 mod simple;
 mod summarize;
 
-pub use dedup_embeddings::dedup;
+pub use dedup_embeddings::{dedup, dedup_threshold};
+pub use fusion::fuse;
 pub use hype::hype;
 pub use prepare::prepare_for_upload;
 pub use prompt::prompt;
@@ -21,12 +31,97 @@ pub use prompt::prompt;
 type ChunkSize = i32;
 type ChunkOverlap = i32;
 
+#[derive(Debug, Clone, PartialEq)]
 pub enum ChunkingStrategy {
     Word(ChunkSize, ChunkOverlap),
+    /// At most the given number of sentences per chunk, repeating the last
+    /// sentences of a chunk at the start of the next one.
+    Sentence(ChunkSize, ChunkOverlap),
+    /// One chunk per `#`/`##`/`###` section of a markdown document, headed
+    /// by its heading path; longer sections are split by words.
+    MarkdownSection { max_words: ChunkSize },
+}
+
+const DEFAULT_CHUNKING_STRATEGY: ChunkingStrategy = ChunkingStrategy::Word(250, 30);
+
+/// Parses `word:<size>:<overlap>`, `sentence:<sentences>:<overlap>` or
+/// `markdown:<max words>`, e.g. `word:512:128` or `sentence:8:2`.
+impl FromStr for ChunkingStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split(':').map(str::trim).collect();
+        let number = |part: &str| -> anyhow::Result<ChunkSize> {
+            match part.parse::<ChunkSize>() {
+                Ok(n) if n >= 0 => Ok(n),
+                _ => Err(anyhow!("'{}' in chunking strategy '{}' is not a non-negative number", part, s)),
+            }
+        };
+        let strategy = match (parts[0].to_lowercase().as_str(), &parts[1..]) {
+            ("word", [size, overlap]) => ChunkingStrategy::Word(number(size)?, number(overlap)?),
+            ("sentence", [size, overlap]) => ChunkingStrategy::Sentence(number(size)?, number(overlap)?),
+            ("markdown", [max_words]) => ChunkingStrategy::MarkdownSection { max_words: number(max_words)? },
+            _ => bail!(
+                "Invalid chunking strategy '{}', expected 'word:<size>:<overlap>', 'sentence:<sentences>:<overlap>' or 'markdown:<max words>'",
+                s
+            ),
+        };
+        match strategy {
+            ChunkingStrategy::Word(0, _)
+            | ChunkingStrategy::Sentence(0, _)
+            | ChunkingStrategy::MarkdownSection { max_words: 0 } => {
+                bail!("Chunk size in chunking strategy '{}' must be at least 1", s)
+            }
+            ChunkingStrategy::Word(size, overlap) | ChunkingStrategy::Sentence(size, overlap) if overlap >= size => {
+                bail!("Overlap in chunking strategy '{}' must be smaller than the chunk size", s)
+            }
+            strategy => Ok(strategy),
+        }
+    }
+}
+
+impl ChunkingStrategy {
+    /// Strategy from `CHUNK_STRATEGY`, or word chunks of 250 with an overlap
+    /// of 30 when unset.
+    pub fn from_env() -> anyhow::Result<Self> {
+        match env::var("CHUNK_STRATEGY") {
+            Ok(s) if !s.trim().is_empty() => s.parse(),
+            _ => Ok(DEFAULT_CHUNKING_STRATEGY),
+        }
+    }
 }
 
 pub fn chunk(file: LoadedFile, strategy: ChunkingStrategy) -> ChunkedFile<Chunk> {
     match &strategy {
         ChunkingStrategy::Word(size, overlap) => simple_word_chunking(file, size, overlap),
+        ChunkingStrategy::Sentence(max_sentences, overlap) => sentence_chunking(file, max_sentences, overlap),
+        ChunkingStrategy::MarkdownSection { max_words } => markdown_section_chunking(file, max_words),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strategies_are_parsed_from_their_spec() {
+        assert_eq!("word:512:128".parse::<ChunkingStrategy>().unwrap(), ChunkingStrategy::Word(512, 128));
+        assert_eq!(" Sentence : 8 : 2 ".parse::<ChunkingStrategy>().unwrap(), ChunkingStrategy::Sentence(8, 2));
+        assert_eq!(
+            "markdown:300".parse::<ChunkingStrategy>().unwrap(),
+            ChunkingStrategy::MarkdownSection { max_words: 300 }
+        );
+    }
+
+    #[test]
+    fn invalid_specs_are_rejected_with_the_reason() {
+        let error = |s: &str| s.parse::<ChunkingStrategy>().unwrap_err().to_string();
+
+        assert!(error("paragraph:5").starts_with("Invalid chunking strategy 'paragraph:5'"));
+        assert!(error("word:512").starts_with("Invalid chunking strategy 'word:512'"));
+        assert_eq!(error("word:big:10"), "'big' in chunking strategy 'word:big:10' is not a non-negative number");
+        assert_eq!(error("sentence:-1:0"), "'-1' in chunking strategy 'sentence:-1:0' is not a non-negative number");
+        assert_eq!(error("markdown:0"), "Chunk size in chunking strategy 'markdown:0' must be at least 1");
+        assert_eq!(error("word:100:100"), "Overlap in chunking strategy 'word:100:100' must be smaller than the chunk size");
     }
 }
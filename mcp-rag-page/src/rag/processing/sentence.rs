@@ -0,0 +1,147 @@
+use crate::rag::{
+    loading::loaded_data::LoadedFile,
+    models::{chunks::Chunk, ChunkedFile},
+};
+
+use super::{ChunkOverlap, ChunkSize};
+
+/// Abbreviations that end with a period without ending the sentence,
+/// Slovenian ones first ("dr. Novak", "2. odst. 5. čl.").
+const ABBREVIATIONS: [&str; 34] = [
+    "dr", "mr", "mag", "prof", "doc", "izr", "red", "ga", "gdč", "g", "št", "čl", "odst", "tč",
+    "al", "pogl", "npr", "itd", "ipd", "idr", "tj", "oz", "ur", "str", "gl", "sv", "prim",
+    "e.g", "i.e", "etc", "art", "no", "vs", "cf",
+];
+
+/// Whether `token`, the last word before a possible sentence boundary,
+/// really ends a sentence given the `next` word.
+fn ends_sentence(token: &str, next: Option<&str>) -> bool {
+    let trimmed = token.trim_end_matches(['"', '\'', ')', '»', '“', '”']);
+    if !trimmed.ends_with(['.', '!', '?']) {
+        return false;
+    }
+    let Some(next) = next else {
+        return true;
+    };
+    // lowercase or numeric continuations ("1. 2. 2024", "dr. med.") stay in the sentence
+    if next.chars().next().is_some_and(|c| c.is_lowercase() || c.is_ascii_digit()) {
+        return false;
+    }
+    if trimmed.ends_with(['!', '?']) {
+        return true;
+    }
+    let word = trimmed
+        .trim_end_matches('.')
+        .trim_start_matches(['"', '\'', '(', '«', '„', '“'])
+        .to_lowercase();
+    // initials ("J. Novak") and ordinals ("12. Člen"); a year still ends a sentence
+    let is_initial = word.chars().count() == 1;
+    let is_ordinal = (1..=2).contains(&word.len()) && word.chars().all(|c| c.is_ascii_digit());
+    !(is_initial || is_ordinal || ABBREVIATIONS.contains(&word.as_str()))
+}
+
+/// Splits `text` into sentences, keeping abbreviations and initials inside
+/// the sentence they belong to.
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut sentences = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        current.push(token);
+        if ends_sentence(token, tokens.get(i + 1).copied()) {
+            sentences.push(current.join(" "));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        sentences.push(current.join(" "));
+    }
+    sentences
+}
+
+pub fn sentence_chunking(file: LoadedFile, max_sentences: &ChunkSize, overlap: &ChunkOverlap) -> ChunkedFile<Chunk> {
+    let max_sentences = (*max_sentences).max(1) as usize;
+    let overlap = (*overlap).max(0) as usize;
+
+    let sentences = split_sentences(&file.content);
+
+    let mut chunks = Vec::new();
+    let mut start_index = 0;
+    let mut chunk_id = 0;
+
+    while start_index < sentences.len() {
+        let end_index = std::cmp::min(start_index + max_sentences, sentences.len());
+        let text = sentences[start_index..end_index].join(" ");
+
+        chunks.push(Chunk {
+            seq_num: chunk_id,
+            text,
+            embedding_vector: None,
+        });
+        chunk_id += 1;
+
+        if end_index >= sentences.len() {
+            break;
+        }
+
+        // always advance, even if the overlap is as large as the chunk
+        let step = max_sentences.saturating_sub(overlap).max(1);
+        start_index += step;
+    }
+
+    (file, chunks).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rag::models::RagProcessableFileType;
+
+    use super::*;
+
+    fn file(content: &str) -> LoadedFile {
+        LoadedFile {
+            file_type: RagProcessableFileType::Text,
+            content: content.to_string(),
+            original_file_description: None,
+            syntetic_file_description: None,
+            internal_id: "rules".to_string(),
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn abbreviations_and_initials_do_not_end_a_sentence() {
+        assert_eq!(
+            split_sentences("Prošnjo odobri dr. Novak. Rok je 1. 10. 2024. Vloge pregleda J. Kos! Kdo odloča?"),
+            [
+                "Prošnjo odobri dr. Novak.",
+                "Rok je 1. 10. 2024.",
+                "Vloge pregleda J. Kos!",
+                "Kdo odloča?",
+            ]
+        );
+        assert_eq!(
+            split_sentences("See 2. odst. 5. čl. of the rules. Ask Prof. Horvat."),
+            ["See 2. odst. 5. čl. of the rules.", "Ask Prof. Horvat."]
+        );
+    }
+
+    #[test]
+    fn chunks_repeat_the_overlapping_sentences() {
+        let chunked = sentence_chunking(file("One. Two. Three. Four. Five."), &3, &1);
+
+        let texts: Vec<&str> = chunked.chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["One. Two. Three.", "Three. Four. Five."]);
+        let seq: Vec<i32> = chunked.chunks.iter().map(|c| c.seq_num).collect();
+        assert_eq!(seq, [0, 1]);
+    }
+
+    #[test]
+    fn overlap_as_large_as_the_chunk_still_advances() {
+        let chunked = sentence_chunking(file("One. Two. Three."), &2, &2);
+
+        let texts: Vec<&str> = chunked.chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["One. Two.", "Two. Three."]);
+    }
+}
@@ -7,8 +7,9 @@ use rmcp::{
 use anyhow::Result;
 use serde::{de::IntoDeserializer, Deserialize};
 use tokio::sync::Mutex;
-use crate::rag::Rag;
+use crate::{not_found::not_found, rag::Rag};
 
+mod not_found;
 mod rag;
 
 const BIND_ADDRESS: &str = "127.0.0.1:8006";
@@ -18,6 +19,7 @@ const BIND_ADDRESS: &str = "127.0.0.1:8006";
 async fn main() -> Result<()> {
     init_default_tracing();
     let _ = dotenv::dotenv();
+    Rag::default().check_embedding_model().await?;
 
     let service = StreamableHttpService::new(
         || Ok(Service::new()),
@@ -39,7 +41,17 @@ async fn main() -> Result<()> {
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct StructRequest {
     pub question: String,
-    pub k: u64
+    pub k: u64,
+    #[schemars(description = "Only search documents with at least one of these tags (the subfolders they were ingested from), e.g. ['rules'].")]
+    pub tag_filter: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RegulationArticleRequest {
+    #[schemars(description = "Name or URL of the rules document, e.g. 'Rules on the Student Survey'.")]
+    pub document: String,
+    #[schemars(description = "Article number, e.g. 47.")]
+    pub article: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -58,18 +70,22 @@ impl Service {
     #[tool(description = "Consult rules and acts. Given a question will return 'k' passages that may contain answers to a question regarding rules and acts. Always use a question form and keep questions percise with long forms and named entities. Recommended k is 2 or 3")]
     pub async fn ask_about_rules_and_acts(
         &self, 
-        Parameters(StructRequest{question, k}): Parameters<StructRequest>,
+        Parameters(StructRequest{question, k, tag_filter}): Parameters<StructRequest>,
         client: Peer<RoleServer>,
         meta: Meta
     ) -> Result<CallToolResult, rmcp::Error> {
         let start = SystemTime::now();
 
         let rag = Rag::default();
-        let results = match rag.search_k(question, k).await {
+        let results = match rag.search_k(question, k, tag_filter).await {
             Ok(re) => re,
             Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
         };
 
+        if results.is_empty() {
+            return Ok(not_found("No passages on this were found in the rules and acts."));
+        }
+
         let resp: Vec<Content> = results
             .iter()
             .map(|c| Content::text::<String>(c.into()))
@@ -77,6 +93,22 @@ impl Service {
 
         Ok(CallToolResult::success(resp))
     }
+
+    #[tool(description = "Return the full text of a specific article of a rules document or act, e.g. 'Article 47 of the study rules'. Give the document name (or URL) and the article number.")]
+    pub async fn get_regulation_article(
+        &self,
+        Parameters(RegulationArticleRequest{document, article}): Parameters<RegulationArticleRequest>,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let rag = Rag::default();
+        match rag.get_article(&document, article).await {
+            Ok(Some(found)) => Ok(CallToolResult::success(vec![Content::text(found.to_string())])),
+            Ok(None) => Ok(not_found(format!(
+                "Article {} was not found in a document matching '{}'.",
+                article, document
+            ))),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        }
+    }
 }
 
 #[tool_handler]
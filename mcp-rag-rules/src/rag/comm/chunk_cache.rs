@@ -0,0 +1,76 @@
+use std::{
+    collections::HashMap,
+    env,
+    fs::File,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{BufReader, BufWriter},
+};
+
+use anyhow::Result;
+use ollama_rs::generation::embeddings::request::GenerateEmbeddingsRequest;
+use serde::{Deserialize, Serialize};
+
+use super::embedding::EmbeddingVector;
+
+const DEFAULT_CHUNK_CACHE_PATH: &str = "./resources/embedding_cache.json";
+
+/// Embeddings of ingested chunks, persisted between runs so a re-ingest
+/// only embeds chunks whose text changed.
+///
+/// Entries are keyed by the embedding model and a hash of the embedded
+/// text, so switching the model never reuses vectors of the old one. The
+/// file is read from `CHUNK_EMBEDDING_CACHE` (default
+/// `./resources/embedding_cache.json`).
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChunkEmbeddingCache {
+    entries: HashMap<String, Vec<Vec<f32>>>,
+}
+
+fn cache_path() -> String {
+    env::var("CHUNK_EMBEDDING_CACHE").unwrap_or_else(|_| DEFAULT_CHUNK_CACHE_PATH.to_string())
+}
+
+impl ChunkEmbeddingCache {
+    /// Loads the cache file, or starts empty if it is missing or unreadable.
+    pub fn load() -> Self {
+        File::open(cache_path())
+            .ok()
+            .and_then(|f| serde_json::from_reader(BufReader::new(f)).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let writer = BufWriter::new(File::create(cache_path())?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// `model:hash` key of the text an embedding request asks for.
+    pub fn key(request: &GenerateEmbeddingsRequest) -> String {
+        let request = serde_json::to_value(request).unwrap_or_default();
+        let model = request["model"].as_str().unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        request["input"].to_string().hash(&mut hasher);
+        format!("{}:{:016x}", model, hasher.finish())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<EmbeddingVector>> {
+        self.entries
+            .get(key)
+            .map(|vectors| vectors.iter().cloned().map(EmbeddingVector).collect())
+    }
+
+    pub fn insert(&mut self, key: String, embeddings: &[EmbeddingVector]) {
+        self.entries.insert(key, embeddings.iter().map(|e| e.0.clone()).collect());
+    }
+
+    /// Drops the vectors of every model other than `model`.
+    pub fn retain_model(&mut self, model: &str) {
+        let prefix = format!("{}:", model);
+        self.entries.retain(|key, _| key.starts_with(&prefix));
+    }
+}
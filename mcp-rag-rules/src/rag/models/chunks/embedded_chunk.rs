@@ -1,7 +1,7 @@
 use qdrant_client::qdrant::PointStruct;
 use serde_json::{Map, Value};
 
-use crate::rag::comm::embedding::EmbeddingVector;
+use crate::rag::comm::embedding::{embedding_model, EmbeddingVector};
 
 #[derive(Debug)]
 pub struct EmbeddedChunk {
@@ -12,6 +12,8 @@ pub struct EmbeddedChunk {
     pub doc_summary: String,
     pub content: String,
     pub additional_data: Value,
+    /// Subfolders the source document was found in.
+    pub tags: Vec<String>,
 }
 
 impl Into<PointStruct> for EmbeddedChunk {
@@ -22,7 +24,40 @@ impl Into<PointStruct> for EmbeddedChunk {
         payload.insert("doc_summary".to_string(), Value::String(self.doc_summary));
         payload.insert("content".to_string(), Value::String(self.content));
         payload.insert("additional_data".to_string(), self.additional_data);
+        payload.insert("embedding_model".to_string(), Value::String(embedding_model()));
+        payload.insert("tags".to_string(), Value::Array(self.tags.into_iter().map(Value::String).collect()));
 
         PointStruct::new(self.id, self.embedding_vector.0, payload)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use qdrant_client::qdrant::Value as QValue;
+
+    use super::*;
+
+    #[test]
+    fn document_tags_are_stored_in_the_payload() {
+        let chunk = EmbeddedChunk {
+            embedding_vector: EmbeddingVector(vec![0.1, 0.2]),
+            id: "5f0c2b8e-6a51-4d3e-9a0f-2f1d0c9b7e11".to_string(),
+            doc_id: "study-rules".to_string(),
+            doc_seq_num: 3,
+            doc_summary: String::new(),
+            content: "Exams are held in three periods.".to_string(),
+            additional_data: Value::Null,
+            tags: vec!["rules".to_string(), "exams".to_string()],
+        };
+
+        let point: PointStruct = chunk.into();
+        let tags: Vec<&str> = point.payload["tags"]
+            .as_list()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(QValue::as_str)
+            .map(String::as_str)
+            .collect();
+        assert_eq!(tags, ["rules", "exams"]);
+    }
+}
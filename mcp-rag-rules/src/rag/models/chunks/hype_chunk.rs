@@ -1,4 +1,4 @@
-use crate::rag::comm::embedding::{Embeddable, EmbeddingVector};
+use crate::rag::comm::embedding::{embedding_model, Embeddable, EmbeddingVector};
 use anyhow::{anyhow, Result};
 use ollama_rs::generation::embeddings::request::{EmbeddingsInput, GenerateEmbeddingsRequest};
 use serde::Serialize;
@@ -34,7 +34,7 @@ impl HypeChunk {
 
 impl Embeddable for HypeChunk {
     fn try_into_embed(&self) -> GenerateEmbeddingsRequest {
-        GenerateEmbeddingsRequest::new("bge-m3".to_owned(), EmbeddingsInput::Multiple(self.questions.clone()))
+        GenerateEmbeddingsRequest::new(embedding_model(), EmbeddingsInput::Multiple(self.questions.clone()))
     }
 
     fn set_embedding_vectors(&mut self, embedding_vector: Vec<EmbeddingVector>) {
@@ -63,6 +63,7 @@ impl Embeddable for HypeChunk {
                 doc_seq_num: self.seq_num,
                 content: self.text.clone(),
                 additional_data: Value::String(question.to_string()),
+                tags: vec![],
                 doc_summary: doc_summary.clone(),
             });
         }
@@ -1,6 +1,10 @@
-use qdrant_client::qdrant::ScoredPoint;
+use std::{collections::HashMap, env};
+
+use chrono::{Local, NaiveDate};
+use qdrant_client::qdrant::{PointId, RetrievedPoint, ScoredPoint, Value};
 use serde::Serialize;
-use serde_json::Value;
+
+const DEFAULT_SOURCE_MAX_AGE_DAYS: i64 = 180;
 
 #[derive(Debug, Serialize)]
 pub struct ResultChunk {
@@ -10,38 +14,55 @@ pub struct ResultChunk {
     pub chunk: String,
     pub seq_num: i32,
     pub document_name: String,
+    /// Page the chunk was scraped from, when the document name is one.
+    pub source_url: Option<String>,
     pub score: f32,
+    /// Date the chunk was ingested (`YYYY-MM-DD`), absent for older points.
+    pub ingested_at: Option<NaiveDate>,
 }
 
 impl From<ScoredPoint> for ResultChunk {
     fn from(value: ScoredPoint) -> Self {
-        let id: String = match value.id {
+        Self::from_payload(value.id, &value.payload, value.score)
+    }
+}
+
+// Points read by scrolling carry no similarity score
+impl From<RetrievedPoint> for ResultChunk {
+    fn from(value: RetrievedPoint) -> Self {
+        Self::from_payload(value.id, &value.payload, 0.0)
+    }
+}
+
+impl ResultChunk {
+    fn from_payload(id: Option<PointId>, payload: &HashMap<String, Value>, score: f32) -> Self {
+        let id: String = match id {
             Some(d) => format!("{:?}", d),
             None => "Unknown".into(),
         };
 
-        let question = match value.payload.get("question") {
+        let question = match payload.get("question") {
             Some(d) => d.as_str().map_or("Unknown", |v| v).to_owned(),
             None => "Unknown".to_owned(),
         };
         
-        let document_id = match value.payload.get("document_id") {
+        let document_id = match payload.get("document_id") {
             Some(d) => d.as_str().map_or("Unknown", |v| v).to_owned(),
             None => "Unknown".to_owned(),
         };
 
         
-        let chunk = match value.payload.get("chunk") {
+        let chunk = match payload.get("chunk") {
             Some(d) => d.as_str().map_or("Unknown", |v| v).to_owned(),
             None => "Unknown".to_owned(),
         };
 
-        let seq_num = match value.payload.get("seq_num") {
+        let seq_num = match payload.get("seq_num") {
             Some(d) => d.as_integer().unwrap_or(-1) as i32,
             None => -1,
         };
 
-        let document_name = match value.payload.get("document_name") {
+        let document_name = match payload.get("document_name") {
             Some(d) => d.as_str().map_or("Unknown", |v| v)
                 .to_owned()
                 .replace("_", "/")
@@ -49,6 +70,17 @@ impl From<ScoredPoint> for ResultChunk {
             None => "Unknown".to_owned(),
         };
 
+        let source_url = payload
+            .get("source_url")
+            .and_then(|d| d.as_str())
+            .map(|d| d.to_owned())
+            .or_else(|| url_from_document_name(&document_name));
+
+        let ingested_at = payload
+            .get("ingested_at")
+            .and_then(|d| d.as_str())
+            .and_then(|d| NaiveDate::parse_from_str(d.get(..10).unwrap_or(d), "%Y-%m-%d").ok());
+
         Self {
             id,
             question,
@@ -56,16 +88,62 @@ impl From<ScoredPoint> for ResultChunk {
             chunk,
             seq_num,
             document_name,
-            score: value.score,
+            source_url,
+            ingested_at,
+            score,
+        }
+    }
+}
+
+impl ResultChunk {
+    /// Whether the chunk was ingested longer than `SOURCE_MAX_AGE_DAYS`
+    /// (default 180) ago. Chunks without an ingestion date are never stale.
+    pub fn is_stale(&self) -> bool {
+        let max_age_days = env::var("SOURCE_MAX_AGE_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(DEFAULT_SOURCE_MAX_AGE_DAYS);
+        self.ingested_at
+            .is_some_and(|d| (Local::now().date_naive() - d).num_days() > max_age_days)
+    }
+
+    /// Line telling the agent the passage may be outdated, naming where to
+    /// verify it. Empty for fresh chunks.
+    fn staleness_note(&self) -> String {
+        let url = self.source_url.as_deref().unwrap_or(&self.document_name);
+        match self.ingested_at {
+            Some(date) if self.is_stale() => format!(
+                "Possibly outdated source: indexed on {}; verify at [{}]({})",
+                date, url, url
+            ),
+            _ => String::new(),
         }
     }
 }
 
+/// Scraped pages are stored under their address with `/` written as `_`,
+/// e.g. `www.famnit.upr.si/sl/studij`, while uploaded documents carry a
+/// plain file name.
+fn url_from_document_name(document_name: &str) -> Option<String> {
+    if document_name.starts_with("http") {
+        return Some(document_name.to_owned());
+    }
+    let host = document_name.split('/').next()?;
+    if host.contains('.') && !host.contains(' ') && document_name.contains('/') {
+        Some(format!("https://{}", document_name))
+    } else {
+        None
+    }
+}
+
 impl Into<String> for &ResultChunk {
     fn into(self) -> String {
         format!(r#"
             ---
             Source: {}
+            URL: {}
+            Score: {:.3}
+            {}
 
             Passage content: 
             
@@ -75,7 +153,51 @@ impl Into<String> for &ResultChunk {
 
             "#,
             self.document_name,
+            self.source_url.as_deref().unwrap_or("none"),
+            self.score,
+            self.staleness_note(),
             self.chunk
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(payload: &[(&str, &str)], score: f32) -> ScoredPoint {
+        ScoredPoint {
+            payload: payload.iter().map(|(k, v)| (k.to_string(), (*v).into())).collect(),
+            score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn scraped_page_is_rendered_with_its_source_url_and_score() {
+        let chunk = ResultChunk::from(point(
+            &[("chunk", "Enrolment is in September."), ("document_name", "www.famnit.upr.si_sl_studij.md")],
+            0.8123,
+        ));
+        assert_eq!(chunk.source_url.as_deref(), Some("https://www.famnit.upr.si/sl/studij"));
+
+        let out: String = (&chunk).into();
+        assert!(out.contains("Source: www.famnit.upr.si/sl/studij\n"));
+        assert!(out.contains("URL: https://www.famnit.upr.si/sl/studij\n"));
+        assert!(out.contains("Score: 0.812\n"));
+        assert!(out.contains("Enrolment is in September."));
+    }
+
+    #[test]
+    fn uploaded_document_has_no_url_unless_one_is_stored() {
+        let uploaded = ResultChunk::from(point(&[("chunk", "..."), ("document_name", "Study rules.md")], 0.5));
+        assert_eq!(uploaded.source_url, None);
+        assert!(Into::<String>::into(&uploaded).contains("URL: none\n"));
+
+        let stored = ResultChunk::from(point(
+            &[("document_name", "Study rules.md"), ("source_url", "https://www.famnit.upr.si/en/rules")],
+            0.5,
+        ));
+        assert_eq!(stored.source_url.as_deref(), Some("https://www.famnit.upr.si/en/rules"));
+    }
+}
@@ -0,0 +1,229 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::rag::models::chunks::ResultChunk;
+
+/// A single article of a rules document, reassembled from its chunks.
+#[derive(Debug)]
+pub struct RegulationArticle {
+    pub document_title: String,
+    pub document_url: String,
+    pub number: u32,
+    pub text: String,
+}
+
+impl fmt::Display for RegulationArticle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# {}: Article {}\n", self.document_title, self.number)?;
+        writeln!(f, "{}\n", self.text)?;
+        write!(f, "---\n*Source: [{}]({})*", self.document_url, self.document_url)
+    }
+}
+
+/// Article number of a markdown heading such as `### **Article 7** (Principles)`,
+/// `## Člen 3` or `### 5. člen`.
+pub fn article_heading_number(line: &str) -> Option<u32> {
+    let line = line.trim();
+    if !line.starts_with('#') {
+        return None;
+    }
+    let words: Vec<String> = line
+        .trim_start_matches('#')
+        .split_whitespace()
+        .take(2)
+        .map(|w| w.trim_matches(|c: char| c == '*' || c == '.').to_lowercase())
+        .collect();
+    match words.as_slice() {
+        [marker, n, ..] if marker == "article" || marker == "člen" => n.parse().ok(),
+        [n, marker, ..] if marker == "člen" => n.parse().ok(),
+        _ => None,
+    }
+}
+
+/// First H1 heading of a chunk, which the ingestion repeats in every chunk.
+fn document_title(chunk: &str) -> Option<String> {
+    chunk
+        .lines()
+        .find(|l| l.starts_with("# "))
+        .map(|l| l.trim_start_matches("# ").trim().to_string())
+}
+
+/// Text of article `number`, reading the chunks in order.
+///
+/// Chunks repeat the document and section headings as a prefix, so an
+/// article split across chunks reappears with its heading at the top of the
+/// next chunk. The article ends at the next heading of any level.
+pub fn extract_article(chunks: &[&str], number: u32) -> Option<String> {
+    let mut lines: Vec<&str> = vec![];
+    for chunk in chunks {
+        let mut inside = false;
+        for line in chunk.lines() {
+            if line.starts_with("Document location:") {
+                continue;
+            }
+            if article_heading_number(line) == Some(number) {
+                if lines.is_empty() {
+                    lines.push(line.trim());
+                }
+                inside = true;
+                continue;
+            }
+            if line.trim_start().starts_with('#') {
+                if inside {
+                    break;
+                }
+                continue;
+            }
+            if inside {
+                lines.push(line);
+            }
+        }
+    }
+
+    let text = lines.join("\n").trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Chunks grouped per document and ordered by sequence number, one entry per
+/// chunk (each chunk is stored once per generated question).
+pub fn group_documents(chunks: Vec<ResultChunk>) -> BTreeMap<String, Vec<ResultChunk>> {
+    let mut documents: BTreeMap<String, Vec<ResultChunk>> = BTreeMap::new();
+    for chunk in chunks {
+        let doc = documents.entry(chunk.document_name.clone()).or_default();
+        if !doc.iter().any(|c| c.seq_num == chunk.seq_num) {
+            doc.push(chunk);
+        }
+    }
+    for doc in documents.values_mut() {
+        doc.sort_by_key(|c| c.seq_num);
+    }
+    documents
+}
+
+/// How well a document matches the requested name: its URL contains the
+/// query, or the share of query words found in the title.
+fn document_score(query: &str, url: &str, title: &str) -> f32 {
+    let query = query.trim().to_lowercase();
+    if url.to_lowercase().contains(&query) {
+        return 1.0;
+    }
+    let title = title.to_lowercase();
+    let words: Vec<&str> = query.split_whitespace().filter(|w| w.len() > 2).collect();
+    if words.is_empty() {
+        return 0.0;
+    }
+    words.iter().filter(|w| title.contains(*w)).count() as f32 / words.len() as f32
+}
+
+/// Looks up article `number` in the document best matching `document`.
+pub fn find_article(
+    documents: &BTreeMap<String, Vec<ResultChunk>>,
+    document: &str,
+    number: u32,
+) -> Option<RegulationArticle> {
+    let mut candidates: Vec<(f32, &String, String, &Vec<ResultChunk>)> = documents
+        .iter()
+        .map(|(url, chunks)| {
+            let title = chunks
+                .first()
+                .and_then(|c| document_title(&c.chunk))
+                .unwrap_or_else(|| url.clone());
+            (document_score(document, url, &title), url, title, chunks)
+        })
+        .filter(|(score, ..)| *score > 0.0)
+        .collect();
+    // only the best matching documents, never fall back to an unrelated one
+    let best = candidates.iter().map(|c| c.0).fold(0.0, f32::max);
+    candidates.retain(|c| c.0 == best);
+
+    candidates.into_iter().find_map(|(_, url, title, chunks)| {
+        let texts: Vec<&str> = chunks.iter().map(|c| c.chunk.as_str()).collect();
+        extract_article(&texts, number).map(|text| RegulationArticle {
+            document_title: title,
+            document_url: url.clone(),
+            number,
+            text,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST_CHUNK: &str = "# Study Rules of UP FAMNIT
+## I. General Provisions
+### **Article 1** (Content)
+These rules govern undergraduate and master's study.
+### **Article 2** (Enrolment)
+A candidate enrols in the first year
+after being accepted in the application procedure.";
+
+    // the ingestion repeats the headings, so article 2 continues under its own heading
+    const SECOND_CHUNK: &str = "# Study Rules of UP FAMNIT
+## I. General Provisions
+### **Article 2** (Enrolment)
+Enrolment into a higher year requires the completed obligations of the previous year.
+## II. Examinations
+### **Article 3** (Exam periods)
+Exams are held in three periods.";
+
+    fn chunk(document_name: &str, seq_num: i32, text: &str) -> ResultChunk {
+        ResultChunk {
+            id: format!("{}-{}", document_name, seq_num),
+            question: String::new(),
+            document_id: document_name.to_string(),
+            chunk: text.to_string(),
+            seq_num,
+            document_name: document_name.to_string(),
+            source_url: None,
+            score: 0.0,
+            ingested_at: None,
+        }
+    }
+
+    #[test]
+    fn article_headings_in_english_and_slovenian_are_numbered() {
+        assert_eq!(article_heading_number("### **Article 7** (Principles)"), Some(7));
+        assert_eq!(article_heading_number("## Člen 3"), Some(3));
+        assert_eq!(article_heading_number("### 5. člen"), Some(5));
+        assert_eq!(article_heading_number("Article 7 applies."), None);
+        assert_eq!(article_heading_number("## II. Examinations"), None);
+    }
+
+    #[test]
+    fn article_split_across_chunks_is_reassembled_exactly() {
+        assert_eq!(
+            extract_article(&[FIRST_CHUNK, SECOND_CHUNK], 2).as_deref(),
+            Some("### **Article 2** (Enrolment)
+A candidate enrols in the first year
+after being accepted in the application procedure.
+Enrolment into a higher year requires the completed obligations of the previous year.")
+        );
+        assert_eq!(
+            extract_article(&[FIRST_CHUNK, SECOND_CHUNK], 3).as_deref(),
+            Some("### **Article 3** (Exam periods)\nExams are held in three periods.")
+        );
+        assert_eq!(extract_article(&[FIRST_CHUNK, SECOND_CHUNK], 9), None);
+    }
+
+    #[test]
+    fn article_is_looked_up_in_the_best_matching_document() {
+        let other = "# Rules on Student Housing\n### **Article 1** (Content)\nRooms are assigned yearly.";
+        let documents = group_documents(vec![
+            // stored once per generated question and out of order
+            chunk("www.famnit.upr.si/en/study-rules", 1, SECOND_CHUNK),
+            chunk("www.famnit.upr.si/en/study-rules", 0, FIRST_CHUNK),
+            chunk("www.famnit.upr.si/en/study-rules", 1, SECOND_CHUNK),
+            chunk("www.famnit.upr.si/en/housing", 0, other),
+        ]);
+        assert_eq!(documents["www.famnit.upr.si/en/study-rules"].len(), 2);
+
+        let article = find_article(&documents, "study rules", 1).unwrap();
+        assert_eq!(article.document_title, "Study Rules of UP FAMNIT");
+        assert_eq!(article.document_url, "www.famnit.upr.si/en/study-rules");
+        assert_eq!(article.text, "### **Article 1** (Content)\nThese rules govern undergraduate and master's study.");
+        assert!(article.to_string().ends_with("*Source: [www.famnit.upr.si/en/study-rules](www.famnit.upr.si/en/study-rules)*"));
+
+        assert!(find_article(&documents, "library regulations", 1).is_none());
+    }
+}
@@ -1,14 +1,126 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, env};
 
-use qdrant_client::qdrant::SearchResponse;
+use qdrant_client::qdrant::{vector_output, vectors_output::VectorsOptions, ScoredPoint, SearchResponse};
 
 use crate::rag::models::chunks::ResultChunk;
 
-pub fn dedup(search: SearchResponse) -> Vec<ResultChunk> {
-    let mut result_chunks: Vec<ResultChunk> = search.result.into_iter().map(|r| r.into()).collect();
+/// Cosine similarity above which `dedup` collapses two hits, read from
+/// `DEDUP_SIMILARITY_THRESHOLD`. Unset, only exact duplicates are dropped.
+pub fn dedup_threshold() -> Option<f32> {
+    env::var("DEDUP_SIMILARITY_THRESHOLD")
+        .ok()
+        .and_then(|v| v.trim().parse::<f32>().ok())
+}
 
+/// Drops repeated hits, keeping the best scoring one of each.
+///
+/// With a `threshold`, a hit whose vector has a cosine similarity of at
+/// least `threshold` with an already kept hit is dropped as well; this needs
+/// the search to return vectors, hits without one are always kept.
+pub fn dedup(search: SearchResponse, threshold: Option<f32>) -> Vec<ResultChunk> {
     let mut seen = HashSet::new();
-    result_chunks.retain(|chunk| seen.insert((chunk.document_name.clone(), chunk.seq_num)));
+    let mut kept_vectors: Vec<Vec<f32>> = vec![];
+    let mut result_chunks = vec![];
+
+    for point in search.result {
+        let vector = dense_vector(&point);
+        let chunk: ResultChunk = point.into();
+        if !seen.insert((chunk.document_name.clone(), chunk.seq_num)) {
+            continue;
+        }
+
+        if let (Some(threshold), Some(vector)) = (threshold, vector) {
+            if kept_vectors.iter().any(|kept| cosine_similarity(kept, &vector) >= threshold) {
+                continue;
+            }
+            kept_vectors.push(vector);
+        }
+        result_chunks.push(chunk);
+    }
 
     result_chunks
 }
+
+fn dense_vector(point: &ScoredPoint) -> Option<Vec<f32>> {
+    match point.vectors.as_ref()?.vectors_options.as_ref()? {
+        VectorsOptions::Vector(output) => match &output.vector {
+            Some(vector_output::Vector::Dense(dense)) => Some(dense.data.clone()),
+            _ => None,
+        },
+        VectorsOptions::Vectors(_) => None,
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[cfg(test)]
+mod tests {
+    use qdrant_client::qdrant::{DenseVector, PointId, VectorOutput, VectorsOutput};
+
+    use super::*;
+
+    fn point(id: u64, seq_num: i64, question: &str, vector: Option<Vec<f32>>) -> ScoredPoint {
+        let vectors = vector.map(|data| VectorsOutput {
+            vectors_options: Some(VectorsOptions::Vector(VectorOutput {
+                vector: Some(vector_output::Vector::Dense(DenseVector { data })),
+                ..Default::default()
+            })),
+        });
+        ScoredPoint {
+            id: Some(PointId::from(id)),
+            payload: [
+                ("question".to_string(), question.into()),
+                ("document_name".to_string(), "www.famnit.upr.si_sl_studij.md".into()),
+                ("seq_num".to_string(), seq_num.into()),
+            ]
+            .into(),
+            vectors,
+            ..Default::default()
+        }
+    }
+
+    fn questions(chunks: &[ResultChunk]) -> Vec<&str> {
+        chunks.iter().map(|c| c.question.as_str()).collect()
+    }
+
+    #[test]
+    fn hits_at_or_above_the_threshold_are_collapsed() {
+        // unit vectors with a cosine similarity of 0.96 and 0.94 to the first
+        let search = SearchResponse {
+            result: vec![
+                point(1, 0, "best", Some(vec![1.0, 0.0])),
+                point(2, 1, "just above", Some(vec![0.96, 0.28])),
+                point(3, 2, "just below", Some(vec![0.94, 0.3412])),
+                point(4, 3, "no vector", None),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(questions(&dedup(search.clone(), Some(0.95))), ["best", "just below", "no vector"]);
+        assert_eq!(questions(&dedup(search, None)), ["best", "just above", "just below", "no vector"]);
+    }
+
+    #[test]
+    fn repeated_hits_are_dropped_without_a_threshold() {
+        let search = SearchResponse {
+            result: vec![point(1, 0, "first", None), point(1, 0, "first", None), point(2, 1, "second", None)],
+            ..Default::default()
+        };
+
+        assert_eq!(questions(&dedup(search, None)), ["first", "second"]);
+    }
+
+    #[test]
+    fn cosine_similarity_of_a_zero_vector_is_zero() {
+        assert!((cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::rag::{
     comm::{
+        chunk_cache::ChunkEmbeddingCache,
         embedding::{Embeddable, EmbeddingVector},
         OllamaClient,
     },
@@ -7,14 +8,21 @@ use crate::rag::{
 };
 use anyhow::{anyhow, Result};
 use ollama_rs::generation::embeddings::request::GenerateEmbeddingsRequest;
+use std::future::Future;
 
 pub async fn embedd_file<T>(mut file: ChunkedFile<T>, ollama: &OllamaClient) -> Result<ChunkedFile<T>>
 where
     T: Embeddable,
 {
     let requests: Vec<GenerateEmbeddingsRequest> = file.chunks.iter().map(|c| c.try_into_embed()).collect();
+    let mut cache = ChunkEmbeddingCache::load();
+    let keys = embedd_missing(requests, &mut cache, |missing| embedd_all(missing, ollama)).await;
+    // saved before checking for failures, so an interrupted file resumes where it stopped
+    if let Err(e) = cache.save() {
+        eprintln!("Could not save the chunk embedding cache: {}", e);
+    }
 
-    let all_embeddings = embedd_all(requests, &ollama).await;
+    let all_embeddings: Vec<Vec<EmbeddingVector>> = keys.iter().filter_map(|key| cache.get(key)).collect();
 
     if file.chunks.len() != all_embeddings.len() {
         return Err(anyhow!("Not all embeddings were successful."));
@@ -29,15 +37,99 @@ where
     Ok(file)
 }
 
-async fn embedd_all(requests: Vec<GenerateEmbeddingsRequest>, ollama: &OllamaClient) -> Vec<Vec<EmbeddingVector>> {
+/// Embeds with `embed` only the requests whose text was not embedded with
+/// the same model before, adds the new vectors to `cache` and drops those of
+/// other models. Returns the cache key of every request.
+async fn embedd_missing<F, Fut>(requests: Vec<GenerateEmbeddingsRequest>, cache: &mut ChunkEmbeddingCache, embed: F) -> Vec<String>
+where
+    F: FnOnce(Vec<GenerateEmbeddingsRequest>) -> Fut,
+    Fut: Future<Output = Vec<Option<Vec<EmbeddingVector>>>>,
+{
+    let keys: Vec<String> = requests.iter().map(ChunkEmbeddingCache::key).collect();
+
+    let (cached, missing): (Vec<_>, Vec<_>) = requests
+        .into_iter()
+        .zip(keys.iter())
+        .partition(|(_, key)| cache.contains(key));
+    println!("Embedding {} chunks, {} cached", missing.len(), cached.len());
+
+    let (missing_requests, missing_keys): (Vec<_>, Vec<_>) = missing.into_iter().unzip();
+    let new_embeddings = embed(missing_requests).await;
+    for (key, embeddings) in missing_keys.into_iter().zip(new_embeddings) {
+        if let Some(embeddings) = embeddings.filter(|e| !e.is_empty()) {
+            cache.insert(key.clone(), &embeddings);
+        }
+    }
+    if let Some((model, _)) = keys.first().and_then(|k| k.rsplit_once(':')) {
+        cache.retain_model(model);
+    }
+    keys
+}
+
+async fn embedd_all(requests: Vec<GenerateEmbeddingsRequest>, ollama: &OllamaClient) -> Vec<Option<Vec<EmbeddingVector>>> {
     let futures = requests.into_iter().map(|r| async move { ollama.embed(r).await.ok() });
 
     let results = futures::future::join_all(futures).await;
     results
         .into_iter()
-        .filter_map(|resp| match resp {
-            Some(r) => Some(r.embeddings.into_iter().map(|e| EmbeddingVector(e)).collect()),
-            None => None,
-        })
+        .map(|resp| resp.map(|r| r.embeddings.into_iter().map(|e| EmbeddingVector(e)).collect()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ollama_rs::generation::embeddings::request::EmbeddingsInput;
+
+    use super::*;
+
+    fn requests(model: &str, texts: &[&str]) -> Vec<GenerateEmbeddingsRequest> {
+        texts
+            .iter()
+            .map(|t| GenerateEmbeddingsRequest::new(model.to_string(), EmbeddingsInput::Single(t.to_string())))
+            .collect()
+    }
+
+    /// Embeds every request as `[1.0]`, counting the requests sent.
+    fn counting_embedder(sent: &AtomicUsize) -> impl FnOnce(Vec<GenerateEmbeddingsRequest>) -> std::future::Ready<Vec<Option<Vec<EmbeddingVector>>>> + '_ {
+        move |requests| {
+            sent.fetch_add(requests.len(), Ordering::SeqCst);
+            std::future::ready(vec![Some(vec![EmbeddingVector(vec![1.0])]); requests.len()])
+        }
+    }
+
+    #[tokio::test]
+    async fn identical_chunks_are_not_embedded_again() {
+        let mut cache = ChunkEmbeddingCache::default();
+        let texts = ["Exams are held in three periods.", "Enrolment is in September."];
+
+        let sent = AtomicUsize::new(0);
+        let keys = embedd_missing(requests("bge-m3", &texts), &mut cache, counting_embedder(&sent)).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+        assert!(keys.iter().all(|k| cache.contains(k)));
+
+        let sent = AtomicUsize::new(0);
+        let again = embedd_missing(requests("bge-m3", &texts), &mut cache, counting_embedder(&sent)).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 0);
+        assert_eq!(again, keys);
+
+        let sent = AtomicUsize::new(0);
+        embedd_missing(requests("bge-m3", &[texts[0], "A new chunk."]), &mut cache, counting_embedder(&sent)).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn another_model_embeds_again_and_drops_the_old_vectors() {
+        let mut cache = ChunkEmbeddingCache::default();
+        let texts = ["Exams are held in three periods."];
+        let sent = AtomicUsize::new(0);
+        let old = embedd_missing(requests("bge-m3", &texts), &mut cache, counting_embedder(&sent)).await;
+
+        let sent = AtomicUsize::new(0);
+        let new = embedd_missing(requests("nomic-embed-text", &texts), &mut cache, counting_embedder(&sent)).await;
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+        assert!(cache.contains(&new[0]));
+        assert!(!cache.contains(&old[0]));
+    }
+}
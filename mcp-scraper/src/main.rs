@@ -1,10 +1,62 @@
-use reqwest::Url;
+use std::{env, error::Error, sync::LazyLock};
+
+use reqwest::{redirect::Policy, Client, Url};
 use rmcp::{model::{CallToolResult, Content, ServerCapabilities, ServerInfo}, schemars, serde, tool, transport::SseServer, ServerHandler};
 use anyhow::Result;
 use scraper::{ElementRef, Html, Node, Selector};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+use crate::rate_limit::RATE_LIMITER;
+
+mod rate_limit;
 
 const BIND_ADDRESS: &str = "127.0.0.1:7999";
+const DEFAULT_MAX_REDIRECTS: usize = 10;
+const DEFAULT_CONTENT_SELECTOR: &str = "div.app";
+/// Upper bound for `colspan`/`rowspan`, so a malformed attribute cannot
+/// blow up a markdown table.
+const MAX_TABLE_SPAN: usize = 50;
+/// Stands in for whitespace at the edges of text nodes during markdown
+/// conversion, which html2md would otherwise drop around inline elements
+/// ("See[link](url)for more").
+const TEXT_EDGE_MARK: char = '\u{E000}';
+/// Stands in for the line breaks of a markdown table during markdown
+/// conversion, so the table survives as one paragraph.
+const TABLE_ROW_MARK: char = '\u{E001}';
+
+/// Client shared by all fetches, with redirects capped at
+/// `SCRAPER_MAX_REDIRECTS`.
+static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    let max_redirects = env::var("SCRAPER_MAX_REDIRECTS")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_REDIRECTS);
+    http_client(max_redirects)
+});
+
+/// Client following at most `max_redirects` redirects, so a redirect loop
+/// fails instead of hanging.
+fn http_client(max_redirects: usize) -> Client {
+    let policy = Policy::custom(move |attempt| {
+        if attempt.previous().len() > max_redirects {
+            let last_url = attempt
+                .previous()
+                .last()
+                .map(|u| u.to_string())
+                .unwrap_or_default();
+            attempt.error(format!(
+                "gave up after {} redirects, last URL reached: {}",
+                max_redirects, last_url
+            ))
+        } else {
+            attempt.follow()
+        }
+    });
+    Client::builder()
+        .redirect(policy)
+        .build()
+        .expect("failed to build reqwest client")
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -21,6 +73,41 @@ async fn main() -> Result<()> {
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct StructRequest {
     pub url: String,
+    /// Optional CSS selector of the element holding the page content, for
+    /// pages not wrapped in the usual `div.app` (e.g. `div.content`).
+    pub selector: Option<String>,
+    /// Return the content as markdown (default). Set to `false` to get the
+    /// reconstructed HTML, e.g. to re-parse tables.
+    pub as_markdown: Option<bool>,
+    /// Convert `<table>` elements to markdown tables (default false).
+    pub tables_as_markdown: Option<bool>,
+    /// Return every element matching the selector, in page order, instead
+    /// of only the first (default false). For pages whose content is split
+    /// across several `div.app` blocks.
+    pub all_matches: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct LinksRequest {
+    pub url: String,
+    /// Optional CSS selector of the element whose links are listed
+    /// (default `div.app`).
+    pub selector: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageLink {
+    pub text: String,
+    pub url: String,
+}
+
+/// How `reconstruct_element_html_with_absolute_links` renders an element.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReconstructOptions {
+    /// Prepare the HTML for `html_to_markdown`.
+    pub for_markdown: bool,
+    /// Emit tables as markdown tables instead of `<table>` HTML.
+    pub tables_as_markdown: bool,
 }
 
 
@@ -33,16 +120,28 @@ impl Service {
         Self {}
     }
 
-    #[tool(description = "Get the current page content")]
+    #[tool(description = "Get the current page content. Optional 'selector' is the CSS selector of the content element (default 'div.app'). Content is returned as markdown unless 'as_markdown' is false, in which case the HTML is returned. Set 'tables_as_markdown' to render tables as markdown tables in either case. Set 'all_matches' to true if the page content is split across several matching elements.")]
     pub async fn get_web_page_content(
         &self,
         #[tool(aggr)] url_arg: StructRequest,
     ) -> Result<CallToolResult, rmcp::Error> {
-        let content = match extract_and_absolutize_div_content(&url_arg.url).await {
-            Ok(Some(html_output)) => {
-                //html2md::rewrite_html(&html_output, false)
-		html_output
-            }
+        let selector_str = url_arg.selector.as_deref().unwrap_or(DEFAULT_CONTENT_SELECTOR);
+        let Ok(selector) = Selector::parse(selector_str) else {
+            return Err(rmcp::Error::new(
+                rmcp::model::ErrorCode::INVALID_PARAMS,
+                format!("Invalid CSS selector: {}", selector_str),
+                None
+            ));
+        };
+        let as_markdown = url_arg.as_markdown.unwrap_or(true);
+        let options = ReconstructOptions {
+            for_markdown: as_markdown,
+            tables_as_markdown: url_arg.tables_as_markdown.unwrap_or(false),
+        };
+        let all_matches = url_arg.all_matches.unwrap_or(false);
+        let content = match extract_and_absolutize_div_content(&url_arg.url, &selector, options, all_matches).await {
+            Ok(Some(html_output)) if as_markdown => html_to_markdown(&html_output),
+            Ok(Some(html_output)) => html_output,
             Ok(None) => return Err(rmcp::Error::new(
                 rmcp::model::ErrorCode::INVALID_PARAMS,
                 "No content found",
@@ -58,6 +157,42 @@ impl Service {
             content
         )]))
     }
+
+    #[tool(description = "List the links of a page as JSON [{text, url}] with absolute URLs, without fetching the linked pages. Use it to decide which page to open with get_web_page_content. Optional 'selector' is the CSS selector of the content element (default 'div.app').")]
+    pub async fn get_web_page_links(
+        &self,
+        #[tool(aggr)] request: LinksRequest,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let selector_str = request.selector.as_deref().unwrap_or(DEFAULT_CONTENT_SELECTOR);
+        let Ok(selector) = Selector::parse(selector_str) else {
+            return Err(rmcp::Error::new(
+                rmcp::model::ErrorCode::INVALID_PARAMS,
+                format!("Invalid CSS selector: {}", selector_str),
+                None
+            ));
+        };
+        let (base_url, html_content) = match fetch_html(&request.url).await {
+            Ok(page) => page,
+            Err(e) => return Err(rmcp::Error::new(
+                rmcp::model::ErrorCode::INTERNAL_ERROR,
+                e,
+                None
+            )),
+        };
+        let Some(links) = extract_links(&html_content, &base_url, &selector) else {
+            return Err(rmcp::Error::new(
+                rmcp::model::ErrorCode::INVALID_PARAMS,
+                "No content found",
+                None
+            ));
+        };
+        let json = serde_json::to_string_pretty(&links).map_err(|e| rmcp::Error::new(
+            rmcp::model::ErrorCode::INTERNAL_ERROR,
+            e.to_string(),
+            None
+        ))?;
+        Ok(CallToolResult::success(vec![Content::text(json)]))
+    }
 }
 
 #[tool(tool_box)]
@@ -74,26 +209,71 @@ impl ServerHandler for Service {
 }
 
 
-/// Fetches a webpage, looks for a specific div (e.g., `div.app`),
-/// and returns its HTML content with all internal links (href, src) made absolute.
+/// Fetches a webpage, looks for the element matching `selector` (by default
+/// `div.app`), and returns its HTML content with all internal links (href, src) made absolute.
 ///
 /// # Arguments
 /// * `page_url_str`: The URL of the page to process.
+/// * `selector`: The element holding the page content.
+/// * `options`: How the element is rendered.
+/// * `all_matches`: Concatenate every matching element instead of taking the first.
 ///
 /// # Returns
-/// * `Ok(Some(String))` containing the processed HTML of the div if found.
-/// * `Ok(None)` if the target div is not found.
+/// * `Ok(Some(String))` containing the processed HTML of the element if found.
+/// * `Ok(None)` if no element matches the selector.
 /// * `Err(String)` if any error occurs during fetching, parsing, or processing.
-pub async fn extract_and_absolutize_div_content(page_url_str: &str) -> Result<Option<String>, String> {
+pub async fn extract_and_absolutize_div_content(page_url_str: &str, selector: &Selector, options: ReconstructOptions, all_matches: bool) -> Result<Option<String>, String> {
+    let (base_url, html_content) = fetch_html(page_url_str).await?;
+    Ok(extract_content(&html_content, &base_url, selector, options, all_matches))
+}
+
+/// The element(s) matching `selector` in `html`, reconstructed with absolute
+/// links. With `all_matches`, matches are joined in page order; a match
+/// nested inside an earlier one is already part of it and is skipped.
+fn extract_content(html: &str, base_url: &Url, selector: &Selector, options: ReconstructOptions, all_matches: bool) -> Option<String> {
+    // Parse the HTML document using the scraper crate
+    let document = Html::parse_document(html);
+
+    if !all_matches {
+        // Find the first element matching the selector
+        let content_div_element_ref = document.select(selector).next()?;
+        return Some(reconstruct_element_html_with_absolute_links(content_div_element_ref, base_url, options));
+    }
+
+    let mut matched = vec![];
+    let mut parts = vec![];
+    for element in document.select(selector) {
+        if element.ancestors().any(|a| matched.contains(&a.id())) {
+            continue;
+        }
+        matched.push(element.id());
+        parts.push(reconstruct_element_html_with_absolute_links(element, base_url, options));
+    }
+
+    if parts.is_empty() {
+        // Target element was not found on the page
+        return None;
+    }
+    Some(parts.join("\n"))
+}
+
+/// Fetches a webpage and returns its parsed URL, the base for resolving
+/// relative links, together with the response body.
+async fn fetch_html(page_url_str: &str) -> Result<(Url, String), String> {
     // Parse the page URL. This will also serve as the base for resolving relative links.
     let base_url = match Url::parse(page_url_str) {
         Ok(url) => url,
         Err(e) => return Err(format!("Invalid page URL '{}': {}", page_url_str, e)),
     };
 
-    // Fetch the page content using reqwest
-    let response = match reqwest::get(page_url_str).await {
+    // Fetch the page content using reqwest, politely throttled
+    let _permit = RATE_LIMITER.acquire().await;
+    let response = match HTTP_CLIENT.get(page_url_str).send().await {
         Ok(resp) => resp,
+        Err(e) if e.is_redirect() => {
+            let reason = e.source().map(|s| s.to_string()).unwrap_or_else(|| e.to_string());
+            return Err(format!("Failed to fetch URL '{}': {}", page_url_str, reason));
+        }
         Err(e) => return Err(format!("Failed to fetch URL '{}': {}", page_url_str, e)),
     };
 
@@ -112,32 +292,42 @@ pub async fn extract_and_absolutize_div_content(page_url_str: &str) -> Result<Op
         Err(e) => return Err(format!("Failed to read response text from '{}': {}", page_url_str, e)),
     };
 
-    // Parse the HTML document using the scraper crate
-    let document = Html::parse_document(&html_content);
-
-    // Define the CSS selector for the target div.
-    // The Python code: `soup.find('div', class_=lambda x: x and set(x.split()).issuperset({"app"}))`
-    // This finds a div that has the class "app". It can have other classes as well.
-    // The equivalent CSS selector is "div.app".
-    let div_selector_str = "div.app";
-    let div_selector = match Selector::parse(div_selector_str) {
-        Ok(selector) => selector,
-        // This error should ideally not happen for a hardcoded valid selector.
-        Err(_) => return Err(format!("Internal error: Invalid CSS selector: {}", div_selector_str)),
-    };
+    Ok((base_url, html_content))
+}
 
-    // Find the first div element matching the selector
-    if let Some(content_div_element_ref) = document.select(&div_selector).next() {
-        // If the div is found, reconstruct its HTML with absolute links
-        let processed_html = reconstruct_element_html_with_absolute_links(content_div_element_ref, &base_url);
-        Ok(Some(processed_html))
-    } else {
-        // Target div was not found on the page
-        Ok(None)
+/// Links inside the first element matching `selector`, made absolute and
+/// deduplicated by URL. E-mail, phone, script and in-page anchor links are
+/// left out. Returns `None` if no element matches the selector.
+fn extract_links(html: &str, base_url: &Url, selector: &Selector) -> Option<Vec<PageLink>> {
+    let document = Html::parse_document(html);
+    let a_sel = Selector::parse("a[href]").unwrap();
+    let content = document.select(selector).next()?;
+
+    let mut links: Vec<PageLink> = vec![];
+    for a in content.select(&a_sel) {
+        let Some(href) = a.value().attr("href") else {
+            continue;
+        };
+        if href.trim().is_empty() || is_passthrough_link(href) {
+            continue;
+        }
+        let Ok(mut url) = base_url.join(href.trim()) else {
+            continue;
+        };
+        url.set_fragment(None);
+        let url = url.to_string();
+        let text = a.text().collect::<Vec<_>>().join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+        // an image link may come first; keep the first label with text
+        match links.iter_mut().find(|l| l.url == url) {
+            Some(link) if link.text.is_empty() => link.text = text,
+            Some(_) => {}
+            None => links.push(PageLink { text, url }),
+        }
     }
+    Some(links)
 }
 
-fn reconstruct_element_html_with_absolute_links(element: ElementRef, base_url: &Url) -> String {
+fn reconstruct_element_html_with_absolute_links(element: ElementRef, base_url: &Url, options: ReconstructOptions) -> String {
     let tag_name = element.value().name();
 
     // Skip <script> and <style> tags entirely
@@ -154,6 +344,16 @@ fn reconstruct_element_html_with_absolute_links(element: ElementRef, base_url: &
         }
     }
 
+    if options.tables_as_markdown && tag_name.eq_ignore_ascii_case("table") {
+        let table = table_to_markdown(element);
+        return if options.for_markdown {
+            let mark = TABLE_ROW_MARK.to_string();
+            format!("<p>{}{}{}</p>", mark, table.replace('\n', &mark), mark)
+        } else {
+            format!("\n\n{}\n\n", table)
+        };
+    }
+
     let mut html = String::new();
 
     // Start tag
@@ -169,7 +369,7 @@ fn reconstruct_element_html_with_absolute_links(element: ElementRef, base_url: &
         html.push_str(name);
         html.push_str("=\"");
 
-        if name == "href" || name == "src" {
+        if (name == "href" || name == "src") && !is_passthrough_link(value) {
             match base_url.join(value) {
                 Ok(abs_url) => html.push_str(abs_url.as_str()),
                 Err(_) => html.push_str(value),
@@ -186,12 +386,15 @@ fn reconstruct_element_html_with_absolute_links(element: ElementRef, base_url: &
     // Children nodes
     for child_node_ref in element.children() {
         match child_node_ref.value() {
+            Node::Text(text_node) if options.for_markdown => {
+                html.push_str(&mark_edges(&text_node.text));
+            }
             Node::Text(text_node) => {
                 html.push_str(&text_node.text);
             }
             Node::Element(_) => {
                 if let Some(child_element_ref) = ElementRef::wrap(child_node_ref) {
-                    html.push_str(&reconstruct_element_html_with_absolute_links(child_element_ref, base_url));
+                    html.push_str(&reconstruct_element_html_with_absolute_links(child_element_ref, base_url, options));
                 }
             }
             Node::Comment(_) => {} // Skip comments for now
@@ -206,3 +409,320 @@ fn reconstruct_element_html_with_absolute_links(element: ElementRef, base_url: &
 
     html
 }
+
+/// Links that must not be resolved against the page URL: e-mail, phone and
+/// script links, and in-page anchors.
+fn is_passthrough_link(value: &str) -> bool {
+    let value = value.trim_start().to_ascii_lowercase();
+    value.starts_with('#')
+        || ["mailto:", "tel:", "javascript:"]
+            .iter()
+            .any(|scheme| value.starts_with(scheme))
+}
+
+/// Replaces leading and trailing whitespace of a text node with
+/// `TEXT_EDGE_MARK`. Whitespace-only nodes are left alone.
+fn mark_edges(text: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+    let mut marked = String::new();
+    if text.starts_with(char::is_whitespace) {
+        marked.push(TEXT_EDGE_MARK);
+    }
+    marked.push_str(trimmed);
+    if text.ends_with(char::is_whitespace) {
+        marked.push(TEXT_EDGE_MARK);
+    }
+    marked
+}
+
+/// Converts HTML reconstructed with `for_markdown` to markdown. Links are
+/// already absolute, so they stay fully qualified in the output.
+fn html_to_markdown(html: &str) -> String {
+    html2md::rewrite_html(html, false)
+        .lines()
+        .map(|line| {
+            let line = line
+                .trim_start_matches(TEXT_EDGE_MARK)
+                .trim_end_matches(TEXT_EDGE_MARK)
+                .replace(TEXT_EDGE_MARK, " ");
+            if line.contains(TABLE_ROW_MARK) {
+                // html2md escaped the backslash of escaped pipes in cells
+                line.replace("\\\\|", "\\|").replace(TABLE_ROW_MARK, "\n")
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders a table as a GitHub-flavored markdown table with its first row
+/// as the header. Spanned cells are flattened: a `colspan` cell keeps its
+/// text in the first column and leaves the others empty, a `rowspan` cell
+/// repeats its text in every row it covers.
+fn table_to_markdown(table: ElementRef) -> String {
+    let tr_sel = Selector::parse("tr").unwrap();
+    let span = |cell: &ElementRef, attr: &str| {
+        cell.value()
+            .attr(attr)
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(1)
+            .clamp(1, MAX_TABLE_SPAN)
+    };
+
+    let mut rows: Vec<Vec<String>> = vec![];
+    // (column, text, rows still covered) of cells spanning several rows
+    let mut row_spans: Vec<(usize, String, usize)> = vec![];
+    for tr in table.select(&tr_sel) {
+        let mut cells = tr
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|c| matches!(c.value().name(), "td" | "th"));
+        let mut row: Vec<String> = vec![];
+        loop {
+            if let Some(spanned) = row_spans.iter_mut().find(|(col, _, left)| *col == row.len() && *left > 0) {
+                spanned.2 -= 1;
+                row.push(spanned.1.clone());
+                continue;
+            }
+            let Some(cell) = cells.next() else {
+                break;
+            };
+            let text = cell
+                .text()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .replace('|', "\\|");
+            let rowspan = span(&cell, "rowspan");
+            if rowspan > 1 {
+                row_spans.push((row.len(), text.clone(), rowspan - 1));
+            }
+            row.push(text);
+            for _ in 1..span(&cell, "colspan") {
+                row.push(String::new());
+            }
+        }
+        row_spans.retain(|(_, _, left)| *left > 0);
+        if row.iter().any(|c| !c.is_empty()) {
+            rows.push(row);
+        }
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if width == 0 {
+        return String::new();
+    }
+    let line = |cells: &[String]| {
+        let padded: Vec<&str> = (0..width).map(|i| cells.get(i).map(String::as_str).unwrap_or("")).collect();
+        format!("| {} |", padded.join(" | "))
+    };
+    let mut md = vec![line(&rows[0]), format!("|{}", " --- |".repeat(width))];
+    md.extend(rows[1..].iter().map(|r| line(r)));
+    md.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    /// Serves `/hop/<n>`, redirecting to `/hop/<n + 1>` until `hops`
+    /// redirects were made and answering with a page after that.
+    async fn redirecting_server(hops: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let read = socket.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..read]);
+                let hop: usize = request
+                    .split_whitespace()
+                    .nth(1)
+                    .and_then(|path| path.strip_prefix("/hop/"))
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or(0);
+                let response = if hop < hops {
+                    format!(
+                        "HTTP/1.1 302 Found\r\nLocation: /hop/{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                        hop + 1
+                    )
+                } else {
+                    "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\npage".to_string()
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        base
+    }
+
+    #[tokio::test]
+    async fn redirects_within_the_cap_are_followed() {
+        let base = redirecting_server(3).await;
+        let response = http_client(3).get(format!("{}/hop/0", base)).send().await.unwrap();
+        assert!(response.url().as_str().ends_with("/hop/3"));
+        assert_eq!(response.text().await.unwrap(), "page");
+    }
+
+    #[tokio::test]
+    async fn redirect_chain_past_the_cap_fails() {
+        let base = redirecting_server(usize::MAX).await;
+        let error = http_client(3).get(format!("{}/hop/0", base)).send().await.unwrap_err();
+        assert!(error.is_redirect());
+        let reason = error.source().unwrap().to_string();
+        assert_eq!(
+            reason,
+            format!("gave up after 3 redirects, last URL reached: {}/hop/3", base)
+        );
+    }
+
+    const BASE: &str = "https://www.famnit.upr.si/en/about-faculty/";
+
+    fn base_url() -> Url {
+        Url::parse(BASE).unwrap()
+    }
+
+    fn app_selector() -> Selector {
+        Selector::parse(DEFAULT_CONTENT_SELECTOR).unwrap()
+    }
+
+    #[test]
+    fn mailto_and_tel_links_are_kept_verbatim() {
+        let html = r#"<html><body><div class="app">
+            <a href="mailto:info@famnit.upr.si">E-mail</a>
+            <a href="tel:+386 5 611 75 70">Phone</a>
+            <a href="contact">Contact</a>
+        </div></body></html>"#;
+
+        let content = extract_content(html, &base_url(), &app_selector(), ReconstructOptions::default(), false).unwrap();
+
+        assert!(content.contains(r#"href="mailto:info@famnit.upr.si""#));
+        assert!(content.contains(r#"href="tel:+386 5 611 75 70""#));
+        assert!(content.contains(r#"href="https://www.famnit.upr.si/en/about-faculty/contact""#));
+        assert!(is_passthrough_link(" MAILTO:info@famnit.upr.si"));
+        assert!(is_passthrough_link("javascript:void(0)"));
+        assert!(is_passthrough_link("#top"));
+        assert!(!is_passthrough_link("/en/contact#map"));
+    }
+
+    #[test]
+    fn content_selector_overrides_the_default() {
+        let html = r#"<html><body><div class="content"><p>News of the week</p></div></body></html>"#;
+        let selector = Selector::parse("div.content").unwrap();
+
+        assert_eq!(extract_content(html, &base_url(), &app_selector(), ReconstructOptions::default(), false), None);
+        let content = extract_content(html, &base_url(), &selector, ReconstructOptions::default(), false).unwrap();
+        assert_eq!(content, r#"<div class="content"><p>News of the week</p></div>"#);
+    }
+
+    #[test]
+    fn markdown_keeps_tables_and_absolute_links() {
+        let html = r#"<html><body><div class="app">
+            <p>See the <a href="/en/education/fees">fees page</a> for details.</p>
+            <table>
+                <tr><th>Programme</th><th>Fee</th></tr>
+                <tr><td>Mathematics</td><td>3000 EUR</td></tr>
+            </table>
+        </div></body></html>"#;
+        let options = ReconstructOptions { for_markdown: true, tables_as_markdown: true };
+
+        let markdown = html_to_markdown(&extract_content(html, &base_url(), &app_selector(), options, false).unwrap());
+
+        assert!(markdown.contains("See the [fees page](https://www.famnit.upr.si/en/education/fees) for details."), "{}", markdown);
+        assert!(markdown.contains("| Programme | Fee |\n| --- | --- |\n| Mathematics | 3000 EUR |"), "{}", markdown);
+
+        let raw = extract_content(html, &base_url(), &app_selector(), ReconstructOptions::default(), false).unwrap();
+        assert!(raw.contains("<table>"));
+        assert!(raw.contains(r#"<a href="https://www.famnit.upr.si/en/education/fees">fees page</a>"#));
+    }
+
+    #[test]
+    fn spanned_cells_are_flattened() {
+        let html = r#"<table>
+            <tr><th>Year</th><th colspan="2">Semester</th></tr>
+            <tr><td rowspan="2">1st</td><td>Winter</td><td>Summer</td></tr>
+            <tr><td>Analysis I</td><td>Analysis II</td></tr>
+            <tr><td>2nd</td><td colspan="2">Elective | block</td></tr>
+        </table>"#;
+        let document = Html::parse_fragment(html);
+        let table = document.select(&Selector::parse("table").unwrap()).next().unwrap();
+
+        assert_eq!(
+            table_to_markdown(table),
+            "| Year | Semester |  |\n\
+             | --- | --- | --- |\n\
+             | 1st | Winter | Summer |\n\
+             | 1st | Analysis I | Analysis II |\n\
+             | 2nd | Elective \\| block |  |"
+        );
+
+        let html = r#"<table><tr><td colspan="100000">Wide</td></tr><tr><td>a</td></tr></table>"#;
+        let document = Html::parse_fragment(html);
+        let table = document.select(&Selector::parse("table").unwrap()).next().unwrap();
+        let markdown = table_to_markdown(table);
+        assert_eq!(markdown.lines().nth(1).unwrap().matches("---").count(), MAX_TABLE_SPAN);
+    }
+
+    #[test]
+    fn links_are_absolutized_deduplicated_and_filtered() {
+        let html = r##"<html><body>
+            <a href="/en/outside">Outside the content</a>
+            <div class="app">
+                <a href="/en/education/"><img src="/img/study.png"></a>
+                <a href="/en/education/">Study</a>
+                <a href="../research">Research</a>
+                <a href="https://www.upr.si/en/">University</a>
+                <a href="/en/education/#fees">Study fees</a>
+                <a href="#main">Skip to content</a>
+                <a href="mailto:info@famnit.upr.si">E-mail</a>
+                <a href="tel:+386 5 611 75 70">Phone</a>
+                <a href=" ">Empty</a>
+            </div>
+        </body></html>"##;
+
+        let links: Vec<(String, String)> = extract_links(html, &base_url(), &app_selector())
+            .unwrap()
+            .into_iter()
+            .map(|l| (l.text, l.url))
+            .collect();
+
+        let link = |text: &str, url: &str| (text.to_string(), url.to_string());
+        assert_eq!(links, [
+            link("Study", "https://www.famnit.upr.si/en/education/"),
+            link("Research", "https://www.famnit.upr.si/en/research"),
+            link("University", "https://www.upr.si/en/"),
+        ]);
+        assert!(extract_links(html, &base_url(), &Selector::parse("div.missing").unwrap()).is_none());
+    }
+
+    #[test]
+    fn all_matches_concatenates_every_content_block() {
+        let html = r#"<html><body>
+            <div class="app"><p>First block</p><div class="app"><p>Nested block</p></div></div>
+            <aside>Sidebar</aside>
+            <div class="app"><p>Second block</p></div>
+        </body></html>"#;
+
+        let first = extract_content(html, &base_url(), &app_selector(), ReconstructOptions::default(), false).unwrap();
+        assert!(first.contains("First block"));
+        assert!(!first.contains("Second block"));
+
+        let all = extract_content(html, &base_url(), &app_selector(), ReconstructOptions::default(), true).unwrap();
+        assert_eq!(all.matches("First block").count(), 1);
+        assert_eq!(all.matches("Nested block").count(), 1);
+        assert!(all.contains("Second block"));
+        assert!(!all.contains("Sidebar"));
+        assert!(all.find("First block") < all.find("Second block"));
+    }
+}
@@ -0,0 +1,96 @@
+use std::{
+    env,
+    sync::LazyLock,
+    time::Duration,
+};
+
+use tokio::{
+    sync::{Mutex, Semaphore, SemaphorePermit},
+    time::{Instant, sleep_until},
+};
+
+const DEFAULT_REQUESTS_PER_SECOND: f64 = 2.0;
+const DEFAULT_MAX_CONCURRENT: usize = 2;
+
+/// Shared limiter for every outbound fetch, configured with
+/// `SCRAPER_REQUESTS_PER_SECOND` and `SCRAPER_MAX_CONCURRENT`.
+pub static RATE_LIMITER: LazyLock<RateLimiter> = LazyLock::new(|| {
+    let requests_per_second = env::var("SCRAPER_REQUESTS_PER_SECOND")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|rps| *rps > 0.0)
+        .unwrap_or(DEFAULT_REQUESTS_PER_SECOND);
+    let max_concurrent = env::var("SCRAPER_MAX_CONCURRENT")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(DEFAULT_MAX_CONCURRENT);
+    RateLimiter::new(requests_per_second, max_concurrent)
+});
+
+/// Spaces requests at least `1 / requests_per_second` apart and caps how
+/// many are in flight at once.
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+    in_flight: Semaphore,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64, max_concurrent: usize) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / requests_per_second),
+            next_slot: Mutex::new(Instant::now()),
+            in_flight: Semaphore::new(max_concurrent),
+        }
+    }
+
+    /// Waits for a free slot. The request may be sent while the returned
+    /// permit is held.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit = self
+            .in_flight
+            .acquire()
+            .await
+            .expect("rate limiter semaphore is never closed");
+
+        let slot = {
+            let mut next_slot = self.next_slot.lock().await;
+            let slot = (*next_slot).max(Instant::now());
+            *next_slot = slot + self.interval;
+            slot
+        };
+        sleep_until(slot).await;
+
+        permit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn requests_are_spaced_by_the_interval() {
+        let limiter = RateLimiter::new(20.0, 4);
+        let start = Instant::now();
+        for _ in 0..3 {
+            drop(limiter.acquire().await);
+        }
+        // the first request goes at once, the next two wait 50 ms each
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert!(start.elapsed() < Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn in_flight_requests_are_capped() {
+        let limiter = RateLimiter::new(1000.0, 1);
+        let permit = limiter.acquire().await;
+        let blocked = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(blocked.is_err());
+
+        drop(permit);
+        let freed = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(freed.is_ok());
+    }
+}
@@ -0,0 +1,282 @@
+//! Course index built from the FAMNIT programme pages.
+//!
+//! Staff profiles only list course names. To tell in which programme, level
+//! and year a course is taught, the course tables of every programme page are
+//! collected into a flat list of `CourseOccurrence`s.
+
+use std::collections::BTreeMap;
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::{profile::StaffProfile, util::name_similarity};
+
+/// Programme listing pages, one per study level.
+pub const PROGRAMME_LISTINGS: [(&str, &str); 3] = [
+    ("https://www.famnit.upr.si/en/education/undergraduate", "Undergraduate"),
+    ("https://www.famnit.upr.si/en/education/master", "Master's"),
+    ("https://www.famnit.upr.si/en/education/doctoral", "Doctoral"),
+];
+
+/// Minimum similarity for a profile course name to match a table row.
+const COURSE_MATCH_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Clone)]
+pub struct CourseOccurrence {
+    pub course: String,
+    pub programme: String,
+    pub level: String,
+    /// Title of the course table, usually the study year or "Elective courses".
+    pub table: String,
+}
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Extract `(programme name, programme url)` pairs from a listing page.
+pub fn parse_programme_links(html: &str) -> Vec<(String, String)> {
+    let doc = Html::parse_document(html);
+    let a_sel = Selector::parse("div.content ul li a").unwrap();
+
+    doc.select(&a_sel)
+        .filter_map(|a| {
+            let url = a.value().attr("href")?.to_string();
+            let name = text(&a).split('(').next().unwrap_or_default().trim().to_string();
+            (!name.is_empty()).then_some((name, url))
+        })
+        .collect()
+}
+
+/// A distinct instructor of a programme and the programme courses they teach.
+#[derive(Debug, Clone)]
+pub struct RosterEntry {
+    pub name: String,
+    pub title: String,
+    pub department: Option<String>,
+    pub courses: Vec<String>,
+}
+
+/// Collect every course row of the course tables on a programme page.
+pub fn parse_course_occurrences(html: &str, programme: &str, level: &str) -> Vec<CourseOccurrence> {
+    let doc = Html::parse_document(html);
+    let table_sel = Selector::parse("div.content table").unwrap();
+    let row_sel = Selector::parse("tr").unwrap();
+    let th_sel = Selector::parse("th").unwrap();
+    let td_sel = Selector::parse("td").unwrap();
+
+    let mut occurrences = Vec::new();
+    for table in doc.select(&table_sel) {
+        let headers: Vec<String> = table.select(&th_sel).map(|th| text(&th).to_lowercase()).collect();
+        if !(headers.iter().any(|h| h.contains("ects")) && headers.iter().any(|h| h.contains("course"))) {
+            continue;
+        }
+
+        let title = table
+            .prev_siblings()
+            .filter_map(ElementRef::wrap)
+            .next()
+            .or_else(|| table.parent().and_then(|p| p.prev_siblings().filter_map(ElementRef::wrap).next()))
+            .map(|el| text(&el))
+            .unwrap_or_default();
+
+        for tr in table.select(&row_sel) {
+            let cells: Vec<String> = tr.select(&td_sel).map(|td| text(&td)).collect();
+            // leading "1." style index column
+            let offset = cells
+                .first()
+                .filter(|c| c.ends_with('.') && c.trim_end_matches('.').parse::<u32>().is_ok())
+                .map_or(0, |_| 1);
+            let Some(course) = cells.get(offset).filter(|c| !c.is_empty()) else {
+                continue;
+            };
+            occurrences.push(CourseOccurrence {
+                course: course.clone(),
+                programme: programme.to_string(),
+                level: level.to_string(),
+                table: title.clone(),
+            });
+        }
+    }
+    occurrences
+}
+
+/// All occurrences of a course in the index, best matches first.
+pub fn find_course<'a>(index: &'a [CourseOccurrence], course: &str) -> Vec<&'a CourseOccurrence> {
+    let mut matches: Vec<(&CourseOccurrence, f64)> = index
+        .iter()
+        .map(|o| (o, name_similarity(&o.course, course)))
+        .filter(|(_, sim)| *sim >= COURSE_MATCH_THRESHOLD)
+        .collect();
+    matches.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    matches.into_iter().map(|(o, _)| o).collect()
+}
+
+/// Whether a course name from a staff profile refers to a course table row.
+pub fn same_course(a: &str, b: &str) -> bool {
+    name_similarity(a, b) >= COURSE_MATCH_THRESHOLD
+}
+
+/// Cross-references the courses of a programme with the teaching lists of
+/// staff profiles. Returns one entry per instructor, sorted by name, and the
+/// courses no profile claims.
+pub fn build_roster(courses: &[String], profiles: &[(String, StaffProfile)]) -> (Vec<RosterEntry>, Vec<String>) {
+    let mut roster: Vec<RosterEntry> = Vec::new();
+    let mut resolved = vec![false; courses.len()];
+
+    for (name, profile) in profiles {
+        let mut taught = Vec::new();
+        for (i, course) in courses.iter().enumerate() {
+            if profile.teaching_en.iter().any(|t| same_course(t, course)) {
+                resolved[i] = true;
+                taught.push(course.clone());
+            }
+        }
+        if taught.is_empty() {
+            continue;
+        }
+        roster.push(RosterEntry {
+            name: name.clone(),
+            title: profile.title_en.clone(),
+            department: profile.department_en.clone().or(profile.department_sl.clone()),
+            courses: taught,
+        });
+    }
+    roster.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let unresolved = courses
+        .iter()
+        .zip(resolved)
+        .filter(|(_, r)| !r)
+        .map(|(c, _)| c.clone())
+        .collect();
+    (roster, unresolved)
+}
+
+/// Maps every course listed on a profile, in English and in Slovenian, to
+/// the staff members teaching it, sorted by name.
+pub fn build_teacher_index(profiles: &[(String, StaffProfile)]) -> BTreeMap<String, Vec<String>> {
+    let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, profile) in profiles {
+        for course in profile.teaching_en.iter().chain(&profile.teaching_sl) {
+            let course = course.trim();
+            if course.is_empty() {
+                continue;
+            }
+            let teachers = index.entry(course.to_string()).or_default();
+            if !teachers.contains(name) {
+                teachers.push(name.clone());
+            }
+        }
+    }
+    for teachers in index.values_mut() {
+        teachers.sort();
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAMME_PAGE: &str = r#"<html><body><div class="content">
+        <h3>1st year</h3>
+        <table>
+            <tr><th>No.</th><th>Course</th><th>ECTS</th></tr>
+            <tr><td>1.</td><td>Discrete Mathematics I</td><td>6</td></tr>
+            <tr><td>2.</td><td>Linear Algebra</td><td>6</td></tr>
+            <tr><td>3.</td><td>Topology</td><td>6</td></tr>
+        </table>
+        <h3>Elective courses</h3>
+        <table>
+            <tr><th>Course</th><th>ECTS</th></tr>
+            <tr><td>Graph Theory</td><td>6</td></tr>
+        </table>
+        <table>
+            <tr><th>Semester</th><th>Dates</th></tr>
+            <tr><td>Winter</td><td>October - January</td></tr>
+        </table>
+    </div></body></html>"#;
+
+    /// A staff profile listing `courses` as "Slovenian / English" pairs.
+    fn staff(name: &str, courses: &[(&str, &str)]) -> (String, StaffProfile) {
+        let fields: String = courses
+            .iter()
+            .map(|(sl, en)| format!(r#"<div class="field">{} / {}</div>"#, sl, en))
+            .collect();
+        let html = format!(
+            r#"<html><body><h1 itemprop="name">{}</h1><h2 itemprop="title">docent <span>Assistant Professor</span></h2><table><tr><td class="subjects">{}</td></tr></table></body></html>"#,
+            name, fields
+        );
+        (name.to_string(), StaffProfile::from(html))
+    }
+
+    #[test]
+    fn profile_courses_are_found_in_programme_tables() {
+        let index = parse_course_occurrences(PROGRAMME_PAGE, "Mathematics", "Undergraduate");
+        let (_, profile) = staff("Jana Novak", &[
+            ("Teorija grafov", "Graph Theory"),
+            ("Diskretna matematika I", "Discrete Mathematics I"),
+            ("Kriptografija", "Cryptography"),
+        ]);
+
+        let load: Vec<Vec<(String, String, String)>> = profile
+            .teaching_en
+            .iter()
+            .map(|course| {
+                find_course(&index, course)
+                    .into_iter()
+                    .map(|o| (o.programme.clone(), o.level.clone(), o.table.clone()))
+                    .collect()
+            })
+            .collect();
+
+        let occurrence = |table: &str| ("Mathematics".to_string(), "Undergraduate".to_string(), table.to_string());
+        assert_eq!(load, [vec![occurrence("Elective courses")], vec![occurrence("1st year")], vec![]]);
+    }
+
+    #[test]
+    fn roster_lists_each_instructor_once() {
+        let courses: Vec<String> = ["Discrete Mathematics I", "Graph Theory", "Linear Algebra", "Topology"]
+            .map(String::from)
+            .to_vec();
+        let profiles = vec![
+            staff("Marko Kos", &[("Linearna algebra", "Linear Algebra"), ("Diskretna matematika I", "Discrete Mathematics I")]),
+            staff("Jana Novak", &[("Diskretna matematika I", "Discrete Mathematics I"), ("Teorija grafov", "Graph Theory")]),
+            staff("Ana Horvat", &[("Podatkovne baze", "Databases")]),
+        ];
+
+        let (roster, unresolved) = build_roster(&courses, &profiles);
+
+        let entries: Vec<(&str, &[String])> = roster.iter().map(|e| (e.name.as_str(), e.courses.as_slice())).collect();
+        assert_eq!(entries, [
+            ("Jana Novak", &courses[..2]),
+            ("Marko Kos", &[courses[0].clone(), courses[2].clone()][..]),
+        ]);
+        assert_eq!(roster[0].title, "Assistant Professor");
+        assert_eq!(unresolved, ["Topology"]);
+    }
+
+    #[test]
+    fn teacher_index_maps_courses_in_both_languages_to_staff() {
+        let profiles = vec![
+            staff("Marko Kos", &[("Podatkovne baze", "Databases"), ("Operacijski sistemi", "Operating Systems")]),
+            staff("Jana Novak", &[("Podatkovne baze", "Databases"), ("Podatkovne baze", "Databases")]),
+            staff("Ana Horvat", &[("Operacijski sistemi", "Operating Systems")]),
+        ];
+
+        let index = build_teacher_index(&profiles);
+
+        assert_eq!(index["Databases"], ["Jana Novak", "Marko Kos"]);
+        assert_eq!(index["Podatkovne baze"], ["Jana Novak", "Marko Kos"]);
+        assert_eq!(index["Operating Systems"], ["Ana Horvat", "Marko Kos"]);
+
+        let courses: Vec<String> = index.keys().cloned().collect();
+        let best = crate::util::rank_names(courses, "databases", crate::util::NameMetric::Trigram);
+        assert_eq!(best[0], "Databases");
+    }
+}
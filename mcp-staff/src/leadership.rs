@@ -0,0 +1,296 @@
+//! Management of the faculty: dean, vice-deans and secretary.
+//!
+//! The management page names each function either as a heading followed by
+//! the person ("Dean" / "Prof. Jana Novak, PhD, jana.novak@famnit.upr.si"),
+//! as a prefix of the same line ("Vice-Dean for Education: ..."), or as the
+//! first cell of a table row. The page is read on every request, so a change
+//! of leadership shows up as soon as the page is updated.
+
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+pub const LEADERSHIP_URL: &str = "https://www.famnit.upr.si/en/about-faculty/management";
+
+/// Ordered as the functions are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LeadershipRole {
+    Dean,
+    ViceDean,
+    Secretary,
+}
+
+impl LeadershipRole {
+    /// Role named at the start of `s`. Vice-deans are checked first as their
+    /// title contains "dean".
+    fn classify(s: &str) -> Option<Self> {
+        let lower = s.trim().to_lowercase();
+        if lower.contains("office") || lower.starts_with("dekanat") {
+            return None;
+        }
+        if ["vice-dean", "vice dean", "prodekan"].iter().any(|k| lower.starts_with(k)) {
+            Some(LeadershipRole::ViceDean)
+        } else if ["dean", "dekan"].iter().any(|k| lower.starts_with(k)) {
+            Some(LeadershipRole::Dean)
+        } else if ["secretary", "tajnik", "tajnica"].iter().any(|k| lower.starts_with(k)) {
+            Some(LeadershipRole::Secretary)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LeadershipMember {
+    pub role: LeadershipRole,
+    /// Function as written on the page, e.g. "Vice-Dean for Education".
+    pub title: String,
+    pub name: String,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub profile_url: Option<String>,
+}
+
+/// Words after which a line stops naming the person.
+const CONTACT_MARKERS: [&str; 6] = ["e-mail", "email", "tel", "phone", "office", "room"];
+/// Role headings longer than this are sentences mentioning a role.
+const MAX_ROLE_CHARS: usize = 60;
+/// Longer "names" are sentences about the role, not a person.
+const MAX_NAME_CHARS: usize = 80;
+
+/* --------------------------------------------------------------------- */
+/*  Helper utilities                                                     */
+/* --------------------------------------------------------------------- */
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn email(er: &ElementRef, line: &str) -> Option<String> {
+    let a_sel = Selector::parse("a[href^='mailto:']").unwrap();
+    er.select(&a_sel)
+        .filter_map(|a| a.value().attr("href"))
+        .map(|href| href.trim_start_matches("mailto:").to_string())
+        .next()
+        .or_else(|| {
+            line.split_whitespace()
+                .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '@' && c != '.' && c != '-' && c != '_'))
+                .find(|w| w.contains('@') && w.contains('.'))
+                .map(str::to_string)
+        })
+}
+
+/// Phone number starting at the first "+" or at a digit following a
+/// "tel"/"phone" label.
+fn phone(line: &str) -> Option<String> {
+    let lower = line.to_lowercase();
+    let start = line.find('+').or_else(|| {
+        ["tel", "phone"].iter().filter_map(|k| lower.find(k)).min().and_then(|i| {
+            line.get(i..)?.find(|c: char| c.is_ascii_digit()).map(|j| i + j)
+        })
+    })?;
+    let number: String = line
+        .get(start..)?
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '+' | ' ' | '(' | ')' | '/' | '-'))
+        .collect();
+    let number = number.trim().to_string();
+    (number.chars().filter(char::is_ascii_digit).count() >= 6).then_some(number)
+}
+
+/// Person named in `line`: the text before the first contact detail.
+fn person_name(line: &str) -> String {
+    let lower = line.to_lowercase();
+    let end = CONTACT_MARKERS
+        .iter()
+        .filter_map(|m| lower.find(m))
+        .chain(line.find('@').and_then(|i| line.get(..i)?.rfind(' ')))
+        .chain(line.find('+'))
+        .min()
+        .unwrap_or(line.len());
+    line.get(..end)
+        .unwrap_or(line)
+        .trim()
+        .trim_end_matches([',', ';', ':', '-', '–', '|'])
+        .trim()
+        .to_string()
+}
+
+/// Splits "Vice-Dean for Education: Prof. Jana Novak" into its role title
+/// and the rest of the line.
+fn split_role(line: &str) -> Option<(LeadershipRole, String, String)> {
+    let role = LeadershipRole::classify(line)?;
+    let (title, rest) = line
+        .split_once(':')
+        .or_else(|| line.split_once(" – "))
+        .or_else(|| line.split_once(" - "))?;
+    Some((role, title.trim().to_string(), rest.trim().to_string()))
+}
+
+fn member(role: LeadershipRole, title: &str, person: &ElementRef, line: &str, url: &str) -> Option<LeadershipMember> {
+    let staff_sel = Selector::parse("a[href*='/staff/'], a[href*='/osebje/']").unwrap();
+    let profile = person.select(&staff_sel).next();
+    let name = profile
+        .map(|a| text(&a))
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| person_name(line));
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return None;
+    }
+    Some(LeadershipMember {
+        role,
+        title: title.to_string(),
+        name,
+        email: email(person, line),
+        phone: phone(line),
+        profile_url: profile
+            .and_then(|a| a.value().attr("href"))
+            .and_then(|href| Url::parse(url).ok()?.join(href).ok())
+            .map(|u| u.to_string()),
+    })
+}
+
+/* --------------------------------------------------------------------- */
+/*  Main parser                                                          */
+/* --------------------------------------------------------------------- */
+
+pub fn parse_leadership(html: &str, url: &str) -> Vec<LeadershipMember> {
+    let doc = Html::parse_document(html);
+    let sel = Selector::parse("div.content h2, div.content h3, div.content h4, div.content p, div.content li, div.content tr").unwrap();
+    let cell_sel = Selector::parse("th, td").unwrap();
+
+    let mut members: Vec<LeadershipMember> = vec![];
+    let mut current: Option<(LeadershipRole, String)> = None;
+    for el in doc.select(&sel) {
+        let line = text(&el);
+        if line.is_empty() {
+            continue;
+        }
+
+        match el.value().name() {
+            "h2" | "h3" | "h4" => {
+                current = LeadershipRole::classify(&line)
+                    .filter(|_| line.chars().count() <= MAX_ROLE_CHARS)
+                    .map(|role| (role, line.clone()));
+            }
+            "tr" => {
+                let cells: Vec<ElementRef> = el.select(&cell_sel).collect();
+                let Some(role) = cells.first().and_then(|c| LeadershipRole::classify(&text(c))) else {
+                    continue;
+                };
+                let title = text(&cells[0]);
+                let rest = cells[1..].iter().map(text).collect::<Vec<_>>().join(", ");
+                members.extend(member(role, &title, &el, &rest, url));
+            }
+            _ => {
+                // a list item inside a handled row or a nested list is seen twice
+                if members.iter().any(|m| line.contains(&m.name)) {
+                    continue;
+                }
+                if let Some((role, title, rest)) = split_role(&line) {
+                    members.extend(member(role, &title, &el, &rest, url));
+                } else if let Some(role) = LeadershipRole::classify(&line).filter(|_| line.chars().count() <= MAX_ROLE_CHARS && !line.contains('@')) {
+                    // "<p><strong>Dean</strong></p>" heading the next paragraph
+                    current = Some((role, line.clone()));
+                } else if let Some((role, title)) = &current {
+                    members.extend(member(*role, title, &el, &line, url));
+                    // a single person follows the dean and secretary headings
+                    if *role != LeadershipRole::ViceDean {
+                        current = None;
+                    }
+                }
+            }
+        }
+    }
+    members
+}
+
+/* --------------------------------------------------------------------- */
+/*  Markdown renderer                                                    */
+/* --------------------------------------------------------------------- */
+
+impl fmt::Display for LeadershipMember {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self.profile_url {
+            Some(url) => format!("[{}]({})", self.name, url),
+            None => self.name.clone(),
+        };
+        write!(
+            f,
+            "| {} | {} | {} | {} |",
+            self.title,
+            name,
+            self.email.as_deref().unwrap_or("-"),
+            self.phone.as_deref().unwrap_or("-"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANAGEMENT_PAGE: &str = r#"<html><body><div class="content">
+        <h2>Dean</h2>
+        <p><a href="/en/about-faculty/staff/jana.novak/">Prof. Jana Novak, PhD</a>, e-mail: <a href="mailto:dean@famnit.upr.si">dean@famnit.upr.si</a>, tel: +386 5 611 75 70</p>
+        <h2>Vice-Deans</h2>
+        <ul>
+            <li>Vice-Dean for Education: Assoc. Prof. Marko Kos, PhD, marko.kos@famnit.upr.si</li>
+            <li>Vice-Dean for Research: Prof. Ana Horvat, PhD</li>
+        </ul>
+        <h3>Dean's Office</h3>
+        <p>Phone: +386 5 611 75 00</p>
+        <table>
+            <tr><td>Secretary</td><td>Maja Zupan</td><td>maja.zupan@famnit.upr.si</td><td>+386 5 611 75 71</td></tr>
+        </table>
+    </div></body></html>"#;
+
+    #[test]
+    fn dean_vice_deans_and_secretary_are_read_from_each_layout() {
+        let members = parse_leadership(MANAGEMENT_PAGE, LEADERSHIP_URL);
+
+        let summary: Vec<(LeadershipRole, &str, &str)> = members
+            .iter()
+            .map(|m| (m.role, m.title.as_str(), m.name.as_str()))
+            .collect();
+        assert_eq!(summary, [
+            (LeadershipRole::Dean, "Dean", "Prof. Jana Novak, PhD"),
+            (LeadershipRole::ViceDean, "Vice-Dean for Education", "Assoc. Prof. Marko Kos, PhD"),
+            (LeadershipRole::ViceDean, "Vice-Dean for Research", "Prof. Ana Horvat, PhD"),
+            (LeadershipRole::Secretary, "Secretary", "Maja Zupan"),
+        ]);
+
+        let dean = &members[0];
+        assert_eq!(dean.email.as_deref(), Some("dean@famnit.upr.si"));
+        assert_eq!(dean.phone.as_deref(), Some("+386 5 611 75 70"));
+        assert_eq!(dean.profile_url.as_deref(), Some("https://www.famnit.upr.si/en/about-faculty/staff/jana.novak/"));
+        assert_eq!(members[1].email.as_deref(), Some("marko.kos@famnit.upr.si"));
+        assert_eq!(members[2].email, None);
+        assert_eq!(members[3].phone.as_deref(), Some("+386 5 611 75 71"));
+    }
+
+    #[test]
+    fn member_is_rendered_as_a_table_row() {
+        let members = parse_leadership(MANAGEMENT_PAGE, LEADERSHIP_URL);
+
+        assert_eq!(
+            members[0].to_string(),
+            "| Dean | [Prof. Jana Novak, PhD](https://www.famnit.upr.si/en/about-faculty/staff/jana.novak/) | dean@famnit.upr.si | +386 5 611 75 70 |"
+        );
+        assert_eq!(members[2].to_string(), "| Vice-Dean for Research | Prof. Ana Horvat, PhD | - | - |");
+    }
+
+    #[test]
+    fn office_headings_and_slovenian_titles_are_classified() {
+        assert_eq!(LeadershipRole::classify("Dean's Office"), None);
+        assert_eq!(LeadershipRole::classify("Dekanat"), None);
+        assert_eq!(LeadershipRole::classify("Prodekanja za študijske zadeve"), Some(LeadershipRole::ViceDean));
+        assert_eq!(LeadershipRole::classify("Dekan"), Some(LeadershipRole::Dean));
+        assert_eq!(LeadershipRole::classify("Tajnica fakultete"), Some(LeadershipRole::Secretary));
+    }
+}
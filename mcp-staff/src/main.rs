@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::{BTreeMap, HashMap}, sync::Arc};
 
 use reagent::{init_default_tracing, Agent, Message};
 use rmcp::{
@@ -8,10 +8,23 @@ use anyhow::Result;
 use serde::Deserialize;
 use tokio::sync::{mpsc, Mutex, OnceCell};
 
-use crate::{profile::StaffProfile, util::{get_page, rank_names, staff_html_to_markdown}};
+use crate::{
+    courses::{CourseOccurrence, PROGRAMME_LISTINGS, build_roster, build_teacher_index, find_course, parse_course_occurrences, parse_programme_links, same_course},
+    leadership::{LEADERSHIP_URL, parse_leadership},
+    not_found::not_found,
+    profile::{StaffProfile, build_department_index, consultation_hours_rows},
+    research_group::{RESEARCH_GROUPS_URL, ResearchGroup, parse_group_links},
+    supervisors::find_supervisors,
+    util::{NameMetric, clear_page_cache, get_page, name_similarity, rank_names, rank_names_scored, staff_html_to_markdown},
+};
 
 
+mod courses;
+mod leadership;
+mod not_found;
 mod profile;
+mod research_group;
+mod supervisors;
 mod util;
 
 const BIND_ADDRESS: &str = "127.0.0.1:8001";
@@ -50,6 +63,9 @@ pub struct SimilarNamesRequest {
     pub name: String,
     /// Number of names to return (default is 5).
     pub k: Option<i64>,
+    /// Similarity metric: 'trigram' (default), 'levenshtein' for misspelled
+    /// or transposed names, 'jaro_winkler' for short fragments or initials.
+    pub metric: Option<NameMetric>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -60,6 +76,52 @@ pub struct StaffProfilesRequest {
     pub k: Option<i64>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StaffByCourseRequest {
+    /// Full or partial course name, in English or Slovenian, e.g. 'Databases'.
+    pub course: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StaffByDepartmentRequest {
+    /// Full or partial department name, e.g. 'Mathematics'.
+    pub department: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ConsultationHoursRequest {
+    /// Full or partial department name, e.g. 'Department of Mathematics'.
+    pub department: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct StaffTeachingLoadRequest {
+    /// Full or partial name of the staff member.
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProgrammeStaffRequest {
+    /// Full or partial name of the study programme.
+    pub programme: String,
+    /// Optional study level: 'undergraduate', 'master', 'doctoral' or 'any'.
+    pub level: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ResearchGroupRequest {
+    /// Full or partial name of the staff member.
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct PhdSupervisorsRequest {
+    /// Research topic, e.g. 'graph theory' or 'machine learning'.
+    pub topic: String,
+    /// Number of supervisors to return (default is 5).
+    pub k: Option<i64>,
+}
+
 // --- Service Implementation ---
 
 #[derive(Debug, Clone)]
@@ -67,6 +129,14 @@ struct Service {
     tool_router: ToolRouter<Service>,
     // Use OnceCell for lazy async initialization of the staff list.
     all_staff: Arc<OnceCell<HashMap<String, String>>>,
+    // Courses of all programme pages, built on first use.
+    course_index: Arc<OnceCell<Vec<CourseOccurrence>>>,
+    // Course -> staff teaching it, from all profiles, built on first use.
+    teacher_index: Arc<OnceCell<BTreeMap<String, Vec<String>>>>,
+    // Department -> staff in it, from all profiles, built on first use.
+    department_index: Arc<OnceCell<BTreeMap<String, Vec<String>>>>,
+    // Parsed profiles keyed by profile URL.
+    profiles: Arc<Mutex<HashMap<String, StaffProfile>>>,
 }
 
 #[tool_router]
@@ -75,6 +145,10 @@ impl Service {
         Self {
             tool_router: Self::tool_router(),
             all_staff: Arc::new(OnceCell::new()),
+            course_index: Arc::new(OnceCell::new()),
+            teacher_index: Arc::new(OnceCell::new()),
+            department_index: Arc::new(OnceCell::new()),
+            profiles: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -87,9 +161,81 @@ impl Service {
         }).await
     }
 
+    /// Returns the parsed profile behind `profile_url`, fetching it only once.
+    async fn get_or_fetch_profile(&self, profile_url: &str) -> Result<StaffProfile> {
+        if let Some(profile) = self.profiles.lock().await.get(profile_url) {
+            return Ok(profile.clone());
+        }
+        let profile = StaffProfile::from(get_page(profile_url).await?).with_base_url(profile_url);
+        self.profiles.lock().await.insert(profile_url.to_string(), profile.clone());
+        Ok(profile)
+    }
+
+    /// Profiles of all staff, sorted by name. Profiles that cannot be
+    /// fetched are skipped; fetched ones stay cached in `profiles`.
+    async fn fetch_all_profiles(&self) -> Result<Vec<(String, StaffProfile)>> {
+        let staff_map = self.get_or_init_staff_list().await?;
+        let mut names: Vec<&String> = staff_map.keys().collect();
+        names.sort();
+        println!("Reading {} staff profiles...", names.len());
+        let mut profiles = Vec::new();
+        for (i, name) in names.iter().enumerate() {
+            match self.get_or_fetch_profile(&staff_map[*name]).await {
+                Ok(profile) => profiles.push(((*name).clone(), profile)),
+                Err(e) => eprintln!("Failed to fetch profile for {}: {}", name, e),
+            }
+            if (i + 1) % 25 == 0 {
+                println!("Read {}/{} staff profiles", i + 1, names.len());
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Helper function to index the courses of all staff profiles exactly once.
+    async fn get_or_init_teacher_index(&self) -> Result<&BTreeMap<String, Vec<String>>> {
+        self.teacher_index.get_or_try_init(|| async {
+            let index = build_teacher_index(&self.fetch_all_profiles().await?);
+            println!("Course index ready: {} courses", index.len());
+            Ok::<_, anyhow::Error>(index)
+        }).await
+    }
+
+    /// Helper function to index the departments of all staff profiles exactly
+    /// once. The staff listing has no departments, so every profile is read.
+    async fn get_or_init_department_index(&self) -> Result<&BTreeMap<String, Vec<String>>> {
+        self.department_index.get_or_try_init(|| async {
+            let index = build_department_index(&self.fetch_all_profiles().await?);
+            println!("Department index ready: {} departments", index.len());
+            Ok::<_, anyhow::Error>(index)
+        }).await
+    }
+
+    /// Helper function to scrape the course tables of all programmes exactly once.
+    async fn get_or_init_course_index(&self) -> Result<&Vec<CourseOccurrence>> {
+        self.course_index.get_or_try_init(|| async {
+            let mut index = Vec::new();
+            for (listing_url, level) in PROGRAMME_LISTINGS {
+                let listing_html = match get_page(listing_url).await {
+                    Ok(html) => html,
+                    Err(e) => {
+                        eprintln!("Could not fetch programme listing {}: {}", listing_url, e);
+                        continue;
+                    }
+                };
+                for (programme, url) in parse_programme_links(&listing_html) {
+                    match get_page(&url).await {
+                        Ok(html) => index.append(&mut parse_course_occurrences(&html, &programme, level)),
+                        Err(e) => eprintln!("Could not fetch programme page {}: {}", url, e),
+                    }
+                }
+            }
+            Ok::<_, anyhow::Error>(index)
+        }).await
+    }
+
     #[tool(
         name = "get_similar_staff_names",
-        description = "Given a name and optionally k (default 5), the tool returns top k similar names of employees to the queried name, based on levenstein distance. Used to lookup names."
+        description = "Given a name and optionally k (default 5), the tool returns top k similar names of employees to the queried name, based on string similarity. Optional 'metric': 'trigram' (default), 'levenshtein' (typos, transposed letters) or 'jaro_winkler' (short fragments, initials). Each name is followed by its similarity score (0-1); treat low scores as uncertain matches. Used to lookup names."
     )]
     pub async fn get_similar_staff_names(
         &self,
@@ -110,9 +256,13 @@ impl Service {
             return Ok(CallToolResult::error(vec![Content::text("Could not retrieve staff list.")]));
         }
 
-        let ranked_names = rank_names(all_names, &request.name);
-        let top_k = ranked_names.into_iter().take(k as usize).collect::<Vec<String>>();
-        let response = top_k.join(" \n");
+        let ranked_names = rank_names_scored(all_names, &request.name, request.metric.unwrap_or_default());
+        let top_k = ranked_names
+            .into_iter()
+            .take(k as usize)
+            .map(|(name, score)| format!("- {} ({:.2})", name, score))
+            .collect::<Vec<String>>();
+        let response = top_k.join("\n");
 
         Ok(CallToolResult::success(vec![Content::text(response)]))
     }
@@ -141,7 +291,7 @@ impl Service {
             return Ok(CallToolResult::error(vec![Content::text("Could not retrieve staff list.")]));
         }
 
-        let top_names = rank_names(all_names, &request.name)
+        let top_names = rank_names(all_names, &request.name, NameMetric::Trigram)
             .into_iter()
             .take(k as usize)
             .collect::<Vec<String>>();
@@ -150,9 +300,8 @@ impl Service {
 
         for name in top_names {
             if let Some(profile_url) = staff_map.get(&name) {
-                match get_page(profile_url).await {
-                    Ok(profile_page_html) => {
-                        let profile = StaffProfile::from(profile_page_html);
+                match self.get_or_fetch_profile(profile_url).await {
+                    Ok(profile) => {
                         result.push_str("\n---\n\n");
                         result.push_str(&profile.to_string());
                     }
@@ -167,6 +316,495 @@ impl Service {
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
+
+    #[tool(
+        name = "get_staff_teaching_load",
+        description = "Given a staff member's name, returns the courses they teach together with the programme, study level and year/table each course appears in. Use for questions like 'what does professor X teach'."
+    )]
+    pub async fn get_staff_teaching_load(
+        &self,
+        Parameters(request): Parameters<StaffTeachingLoadRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let Ok(staff_map) = self.get_or_init_staff_list().await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve inital staff list. This is an error."
+            )]))
+        };
+
+        let all_names: Vec<String> = staff_map.keys().cloned().collect();
+        let Some(name) = rank_names(all_names, &request.name, NameMetric::Trigram).into_iter().next() else {
+            return Ok(CallToolResult::error(vec![Content::text("Could not retrieve staff list.")]));
+        };
+
+        let Some(profile_url) = staff_map.get(&name) else {
+            return Ok(not_found(format!("No profile found for {}.", name)));
+        };
+
+        let profile = match self.get_or_fetch_profile(profile_url).await {
+            Ok(profile) => profile,
+            Err(e) => {
+                eprintln!("Failed to fetch profile for {}: {}", name, e);
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve profile for {}.", name
+                ))]));
+            }
+        };
+
+        if profile.teaching_en.is_empty() {
+            return Ok(not_found(format!(
+                "{} has no courses listed on their profile.\n\n*Source: [{}]({})*",
+                name, profile_url, profile_url
+            )));
+        }
+
+        let index = match self.get_or_init_course_index().await {
+            Ok(index) => index.as_slice(),
+            Err(e) => {
+                eprintln!("Could not build course index: {}", e);
+                &[]
+            }
+        };
+
+        let mut result = format!("# Teaching load of {}\n\n", name);
+        for course in &profile.teaching_en {
+            result.push_str(&format!("## {}\n", course));
+            let occurrences = find_course(index, course);
+            if occurrences.is_empty() {
+                result.push_str("- Not found in any current programme course table.\n\n");
+                continue;
+            }
+            for o in occurrences {
+                result.push_str(&format!("- {} ({}) — {}\n", o.programme, o.level, o.table));
+            }
+            result.push('\n');
+        }
+        result.push_str(&format!("---\n*Source: [{}]({})*", profile_url, profile_url));
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "get_staff_by_course",
+        description = "Given a course name (English or Slovenian, may be partial), returns the staff members who list that course on their profile, with links to their profiles. Use for questions like 'who teaches Databases'. The first call is slow as it reads all staff profiles."
+    )]
+    pub async fn get_staff_by_course(
+        &self,
+        Parameters(request): Parameters<StaffByCourseRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let Ok(staff_map) = self.get_or_init_staff_list().await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve inital staff list. This is an error."
+            )]))
+        };
+
+        let index = match self.get_or_init_teacher_index().await {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("Could not build the course index: {}", e);
+                return Ok(CallToolResult::error(vec![Content::text("Could not retrieve the staff profiles.")]));
+            }
+        };
+
+        let course_names: Vec<String> = index.keys().cloned().collect();
+        let Some(best) = rank_names(course_names, &request.course, NameMetric::Trigram).into_iter().next() else {
+            return Ok(not_found(format!(
+                "No staff profile lists a course matching '{}'.", request.course
+            )));
+        };
+
+        // the same course may be spelled slightly differently on profiles
+        let mut result = String::new();
+        for (course, teachers) in index.iter().filter(|(course, _)| same_course(course, &best)) {
+            result.push_str(&format!("## {}\n", course));
+            for name in teachers {
+                match staff_map.get(name) {
+                    Some(url) => result.push_str(&format!("- [{}]({})\n", name, url)),
+                    None => result.push_str(&format!("- {}\n", name)),
+                }
+            }
+            result.push('\n');
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "# Staff teaching {}\n\n{}",
+            best,
+            result.trim_end()
+        ))]))
+    }
+
+    #[tool(
+        name = "list_staff_by_department",
+        description = "Lists the staff members of a department (e.g. 'Mathematics', 'Department of Computer Science') with links to their profiles. The department name may be partial. The first call is slow as it reads all staff profiles."
+    )]
+    pub async fn list_staff_by_department(
+        &self,
+        Parameters(request): Parameters<StaffByDepartmentRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let Ok(staff_map) = self.get_or_init_staff_list().await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve inital staff list. This is an error."
+            )]))
+        };
+
+        let index = match self.get_or_init_department_index().await {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("Could not build the department index: {}", e);
+                return Ok(CallToolResult::error(vec![Content::text("Could not retrieve the staff profiles.")]));
+            }
+        };
+
+        let departments: Vec<String> = index.keys().cloned().collect();
+        let Some(department) = rank_names(departments, &request.department, NameMetric::Trigram).into_iter().next() else {
+            return Ok(not_found(format!(
+                "No staff found for department '{}'.", request.department
+            )));
+        };
+
+        let mut result = format!("# Staff of {}\n\n", department);
+        for name in &index[&department] {
+            match staff_map.get(name) {
+                Some(url) => result.push_str(&format!("- [{}]({})\n", name, url)),
+                None => result.push_str(&format!("- {}\n", name)),
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "list_consultation_hours",
+        description = "Lists the office/consultation hours of all staff in a department, so students can find someone available. Pass the department name as 'department'. Staff without published hours are listed as 'not published'."
+    )]
+    pub async fn list_consultation_hours(
+        &self,
+        Parameters(request): Parameters<ConsultationHoursRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let profiles = match self.fetch_all_profiles().await {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                eprintln!("Could not read the staff profiles: {}", e);
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "Could not retrieve inital staff list. This is an error."
+                )]));
+            }
+        };
+
+        let Some((department_name, rows)) = consultation_hours_rows(&profiles, &request.department) else {
+            return Ok(not_found(format!(
+                "No staff found for department '{}'.", request.department
+            )));
+        };
+
+        let result = format!(
+            "# Office hours: {}\n\n| Staff member | Office hours |\n|-------|-------|\n{}\n",
+            department_name,
+            rows.join("\n")
+        );
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "get_programme_staff",
+        description = "Given a study programme name (and optionally its level), returns the distinct staff members teaching in that programme with their titles, departments and the programme courses they teach. Courses with no identifiable instructor are listed separately."
+    )]
+    pub async fn get_programme_staff(
+        &self,
+        Parameters(request): Parameters<ProgrammeStaffRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let Ok(staff_map) = self.get_or_init_staff_list().await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve inital staff list. This is an error."
+            )]))
+        };
+
+        let index = match self.get_or_init_course_index().await {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("Could not build course index: {}", e);
+                return Ok(CallToolResult::error(vec![Content::text("Could not retrieve the programme course tables.")]));
+            }
+        };
+
+        let level = request
+            .level
+            .as_deref()
+            .map(|l| l.to_lowercase())
+            .filter(|l| l != "any");
+        let candidates: Vec<&CourseOccurrence> = index
+            .iter()
+            .filter(|o| level.as_ref().is_none_or(|l| o.level.to_lowercase().starts_with(l.as_str())))
+            .collect();
+
+        let mut programme_names: Vec<String> = candidates.iter().map(|o| o.programme.clone()).collect();
+        programme_names.sort();
+        programme_names.dedup();
+        let Some(programme) = rank_names(programme_names, &request.programme, NameMetric::Trigram).into_iter().next() else {
+            return Ok(not_found(format!(
+                "No programme found matching '{}'.", request.programme
+            )));
+        };
+
+        let mut courses: Vec<String> = candidates
+            .iter()
+            .filter(|o| o.programme == programme)
+            .map(|o| o.course.clone())
+            .collect();
+        courses.sort();
+        courses.dedup();
+
+        let mut names: Vec<&String> = staff_map.keys().collect();
+        names.sort();
+        let mut profiles = Vec::new();
+        for name in names {
+            match self.get_or_fetch_profile(&staff_map[name]).await {
+                Ok(profile) => profiles.push((name.clone(), profile)),
+                Err(e) => eprintln!("Failed to fetch profile for {}: {}", name, e),
+            }
+        }
+
+        let (roster, unresolved) = build_roster(&courses, &profiles);
+
+        let mut result = format!("# Teaching staff of {}\n\n", programme);
+        if roster.is_empty() {
+            result.push_str("No instructors could be matched to the courses of this programme.\n");
+        } else {
+            result.push_str("| Staff member | Title | Department | Courses |\n|-------|-------|-------|-------|\n");
+            for entry in &roster {
+                result.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    entry.name,
+                    entry.title,
+                    entry.department.as_deref().unwrap_or("-"),
+                    entry.courses.join(", ")
+                ));
+            }
+        }
+        if !unresolved.is_empty() {
+            result.push_str(&format!(
+                "\nNo instructor could be identified for: {}. These are often electives or courses taught by external lecturers.\n",
+                unresolved.join(", ")
+            ));
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "get_staff_research_group",
+        description = "Given a staff member's name, finds the research group or lab they belong to and returns a short summary of the group with its members and source URL."
+    )]
+    pub async fn get_staff_research_group(
+        &self,
+        Parameters(request): Parameters<ResearchGroupRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let Ok(staff_map) = self.get_or_init_staff_list().await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve inital staff list. This is an error."
+            )]))
+        };
+
+        let all_names: Vec<String> = staff_map.keys().cloned().collect();
+        let Some(name) = rank_names(all_names, &request.name, NameMetric::Trigram).into_iter().next() else {
+            return Ok(CallToolResult::error(vec![Content::text("Could not retrieve staff list.")]));
+        };
+
+        let Some(profile_url) = staff_map.get(&name) else {
+            return Ok(not_found(format!("No profile found for {}.", name)));
+        };
+
+        let profile = match self.get_or_fetch_profile(profile_url).await {
+            Ok(profile) => profile,
+            Err(e) => {
+                eprintln!("Failed to fetch profile for {}: {}", name, e);
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Could not retrieve profile for {}.", name
+                ))]));
+            }
+        };
+
+        // a group linked from the profile, otherwise a group page listing them
+        let mut group = None;
+        for url in &profile.group_links {
+            if let Ok(html) = get_page(url).await {
+                group = ResearchGroup::parse(&html, url);
+            }
+            if group.is_some() {
+                break;
+            }
+        }
+        if group.is_none() {
+            let groups = match get_page(RESEARCH_GROUPS_URL).await {
+                Ok(html) => parse_group_links(&html),
+                Err(e) => {
+                    eprintln!("Could not fetch research groups overview: {}", e);
+                    vec![]
+                }
+            };
+            for (_, url) in groups {
+                let Ok(html) = get_page(&url).await else {
+                    continue;
+                };
+                if let Some(candidate) = ResearchGroup::parse(&html, &url).filter(|g| g.has_member(&name)) {
+                    group = Some(candidate);
+                    break;
+                }
+            }
+        }
+
+        let Some(group) = group else {
+            let fields = if profile.research_fields.is_empty() {
+                String::new()
+            } else {
+                format!(" Their listed research fields are: {}.", profile.research_fields.join(", "))
+            };
+            return Ok(not_found(format!(
+                "No research group or lab could be found for {}.{}\n\n*Source: [{}]({})*",
+                name, fields, profile_url, profile_url
+            )));
+        };
+
+        let result = format!("# Research group of {}\n\n{}", name, group);
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "find_phd_supervisors",
+        description = "Given a research topic, returns staff involved in doctoral studies (teaching doctoral courses or coordinating a doctoral programme) whose research fields match the topic, best match first, with contact information. Use it for prospective PhD students looking for a supervisor."
+    )]
+    pub async fn find_phd_supervisors(
+        &self,
+        Parameters(request): Parameters<PhdSupervisorsRequest>,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let Ok(staff_map) = self.get_or_init_staff_list().await else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Could not retrieve inital staff list. This is an error."
+            )]))
+        };
+
+        let index = match self.get_or_init_course_index().await {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("Could not build course index: {}", e);
+                return Ok(CallToolResult::error(vec![Content::text("Could not retrieve the programme course tables.")]));
+            }
+        };
+        let mut doctoral_courses: Vec<String> = index
+            .iter()
+            .filter(|o| o.level == "Doctoral")
+            .map(|o| o.course.clone())
+            .collect();
+        doctoral_courses.sort();
+        doctoral_courses.dedup();
+
+        let mut names: Vec<&String> = staff_map.keys().collect();
+        names.sort();
+        let mut profiles = Vec::new();
+        for name in names {
+            let url = &staff_map[name];
+            match self.get_or_fetch_profile(url).await {
+                Ok(profile) => profiles.push((name.clone(), url.clone(), profile)),
+                Err(e) => eprintln!("Failed to fetch profile for {}: {}", name, e),
+            }
+        }
+
+        let k = request.k.unwrap_or(5).max(1) as usize;
+        let matches = find_supervisors(&request.topic, &profiles, &doctoral_courses);
+        if matches.is_empty() {
+            return Ok(not_found(format!(
+                "No staff involved in doctoral studies list research fields matching '{}'. Try a broader or related topic, or contact the coordinator of the doctoral programme.",
+                request.topic
+            )));
+        }
+
+        let entries: Vec<String> = matches.iter().take(k).map(|m| m.to_string()).collect();
+        let result = format!(
+            "# Possible PhD supervisors for '{}'\n\nRanked by how well their research fields match the topic. Supervision has to be agreed with the supervisor and approved by the faculty.\n\n{}",
+            request.topic,
+            entries.join("\n\n")
+        );
+        Ok(CallToolResult::success(vec![Content::text(result)]))
+    }
+
+    #[tool(
+        name = "get_faculty_leadership",
+        description = "Returns the current management of the faculty: the dean, the vice-deans with their areas and the secretary, with e-mail, phone and profile links where published, and the source URL. Use for questions like 'who is the dean'."
+    )]
+    pub async fn get_faculty_leadership(
+        &self,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let html = match get_page(LEADERSHIP_URL).await {
+            Ok(html) => html,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+        let mut members = parse_leadership(&html, LEADERSHIP_URL);
+
+        if members.is_empty() {
+            return Ok(not_found(format!(
+                "No dean, vice-deans or secretary could be read from the management page. See [{}]({}).",
+                LEADERSHIP_URL, LEADERSHIP_URL
+            )));
+        }
+
+        // link the staff profile when the page names the person without one
+        if let Ok(staff_map) = self.get_or_init_staff_list().await {
+            for member in members.iter_mut().filter(|m| m.profile_url.is_none()) {
+                let name = member.name.to_lowercase();
+                member.profile_url = staff_map
+                    .iter()
+                    .find(|(staff, _)| name.contains(&staff.to_lowercase()) || name_similarity(staff, &member.name) >= 0.8)
+                    .map(|(_, url)| url.clone());
+            }
+        }
+
+        members.sort_by_key(|m| m.role);
+        let rows: Vec<String> = members.iter().map(|m| m.to_string()).collect();
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "# Faculty leadership\n\n| Function | Name | E-mail | Phone |\n|---|---|---|---|\n{}\n\n---\n*Source: [{}]({})*",
+            rows.join("\n"),
+            LEADERSHIP_URL,
+            LEADERSHIP_URL
+        ))]))
+    }
+
+    #[tool(
+        name = "clear_page_cache",
+        description = "Maintenance tool: clears the cached staff pages and parsed profiles so the next requests refetch them from the website. Use only when asked to refresh data after the website changed."
+    )]
+    pub async fn clear_page_cache(
+        &self,
+        _client: Peer<RoleServer>,
+        _meta: Meta,
+    ) -> Result<CallToolResult, rmcp::Error> {
+        let pages = clear_page_cache().await;
+        let profiles = {
+            let mut profiles = self.profiles.lock().await;
+            let cleared = profiles.len();
+            profiles.clear();
+            cleared
+        };
+        Ok(CallToolResult::success(vec![Content::text(format!(
+            "Cleared {} cached pages and {} parsed profiles.",
+            pages, profiles
+        ))]))
+    }
 }
 
 #[tool_handler]
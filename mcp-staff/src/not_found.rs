@@ -0,0 +1,35 @@
+//! Uniform result for lookups that found nothing.
+
+use rmcp::model::{CallToolResult, Content};
+use serde_json::json;
+
+/// Tool result for a lookup that found nothing, as
+/// `{"not_found": true, "message": "..."}`. The flag tells the agent that
+/// the tool worked but has no data, so it neither retries nor treats the
+/// message as information; the message says what was searched and where
+/// to look instead, and can be passed on to the user.
+pub fn not_found(message: impl Into<String>) -> CallToolResult {
+    let body = json!({
+        "not_found": true,
+        "message": message.into().trim(),
+    });
+    CallToolResult::success(vec![Content::text(body.to_string())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_found_is_a_successful_result_with_a_flagged_json_body() {
+        let result = not_found("  Nothing was found for 'erasmus'.\n");
+
+        assert_eq!(result.is_error, Some(false));
+        let [content] = result.content.as_slice() else {
+            panic!("expected a single content block, got {:?}", result.content);
+        };
+        let text = &content.as_text().expect("text content").text;
+        let body: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(body, json!({"not_found": true, "message": "Nothing was found for 'erasmus'."}));
+    }
+}
@@ -1,10 +1,11 @@
-use scraper::{Html, Selector};
-use std::fmt;
+use reqwest::Url;
+use scraper::{ElementRef, Html, Selector};
+use std::{collections::BTreeMap, fmt};
 
 
 /// A single staff-profile as shown on the
 /// https://www.famnit.upr.si … /staff/<name> pages.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StaffProfile {
     pub full_name: String,
     pub title_sl: String,
@@ -18,11 +19,17 @@ pub struct StaffProfile {
     pub research_fields: Vec<String>,
     pub bibliography_si: Option<String>,
     pub bibliography_en: Option<String>,
+    pub orcid: Option<String>,
+    pub scholar: Option<String>,
     pub teaching_sl: Vec<String>,
     pub teaching_en: Vec<String>,
     pub coordinator_sl: Vec<String>,
     pub coordinator_en: Vec<String>,
-    pub _photo_url: Option<String>,
+    pub consultation_hours: Option<String>,
+    /// Links on the profile that point to a research group or lab page.
+    pub group_links: Vec<String>,
+    /// Absolute once the profile has gone through `with_base_url`.
+    pub photo_url: Option<String>,
 }
 
 impl From<String> for StaffProfile {
@@ -73,7 +80,7 @@ impl From<String> for StaffProfile {
         let phone         = first_text("td.phone");
         let email         = first_text("td.email a");
         let website       = attr("td.website a", "href");
-        let _photo_url     = attr("img.person-img-pedagoska", "src");
+        let photo_url      = attr("img.person-img-pedagoska", "src").filter(|s| !s.trim().is_empty());
 
         // department (two <div class="field"> nodes, SLO → ENG)
         let binding = Selector::parse("td.departments .field").unwrap();
@@ -102,6 +109,16 @@ impl From<String> for StaffProfile {
             }
         }
 
+        // publication profiles linked anywhere on the page
+        let profile_link = |needle: &str| -> Option<String> {
+            doc.select(&Selector::parse("a[href]").unwrap())
+                .filter_map(|a| a.value().attr("href"))
+                .find(|href| href.contains(needle))
+                .map(|href| href.trim().to_owned())
+        };
+        let orcid   = profile_link("orcid.org");
+        let scholar = profile_link("scholar.google");
+
         // helper that splits "SLO / English" blocks --------------------------
         fn split_pair(block: &str) -> (String, String) {
             // e.g. "Računalništvo / Computer Science"
@@ -129,6 +146,30 @@ impl From<String> for StaffProfile {
             if !en.is_empty() { coordinator_en.push(en) }
         }
 
+        // consultation hours ("Govorilne ure / Consultations"); identical
+        // SLO / English halves are kept once
+        let consultation_hours = consultation_hours(&doc).map(|hours| {
+            let (sl, en) = split_pair(&hours);
+            if hours.matches('/').count() == 1 && sl.eq_ignore_ascii_case(&en) { sl } else { hours }
+        });
+
+        // research group / lab links anywhere on the profile
+        let group_links = doc
+            .select(&Selector::parse("a[href]").unwrap())
+            .filter(|a| {
+                let label = a.text().collect::<String>().to_lowercase();
+                let href = a.value().attr("href").unwrap_or("").to_lowercase();
+                is_group_link(&label) || is_group_link(&href)
+            })
+            .filter_map(|a| a.value().attr("href").map(|h| h.to_owned()))
+            .filter(|h| h.starts_with("http"))
+            .fold(Vec::new(), |mut links, href| {
+                if !links.contains(&href) {
+                    links.push(href);
+                }
+                links
+            });
+
         // assemble -----------------------------------------------------------
         StaffProfile {
             full_name,
@@ -143,17 +184,97 @@ impl From<String> for StaffProfile {
             research_fields,
             bibliography_si,
             bibliography_en,
+            orcid,
+            scholar,
             teaching_sl,
             teaching_en,
             coordinator_sl,
             coordinator_en,
-            _photo_url,
+            consultation_hours,
+            group_links,
+            photo_url,
         }
     }
 }
 
+const CONSULTATION_LABELS: [&str; 4] = ["office hours", "consultation", "govorilne ure", "konzultacije"];
+
+fn is_consultation_label(s: &str) -> bool {
+    let label = s.to_lowercase();
+    CONSULTATION_LABELS.iter().any(|k| label.contains(k))
+}
+
+fn collapse(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Consultation hours from a table row labelled "Office hours" /
+/// "Govorilne ure", or from a block where such a label is followed by the
+/// hours.
+fn consultation_hours(doc: &Html) -> Option<String> {
+    let row_sel = Selector::parse("tr").unwrap();
+    let cell_sel = Selector::parse("th, td").unwrap();
+    let from_row = doc.select(&row_sel).find_map(|row| {
+        let mut cells = row.select(&cell_sel);
+        if !is_consultation_label(&cells.next()?.text().collect::<String>()) {
+            return None;
+        }
+        let value = cells
+            .map(|c| c.text().collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let value = collapse(&value);
+        (!value.is_empty()).then_some(value)
+    });
+    if from_row.is_some() {
+        return from_row;
+    }
+
+    let label_sel = Selector::parse("h2, h3, h4, dt, strong, b, .label").unwrap();
+    doc.select(&label_sel).find_map(|label| {
+        let label_text = collapse(&label.text().collect::<String>());
+        if label_text.len() > 60 || !is_consultation_label(&label_text) {
+            return None;
+        }
+        // "<p><strong>Govorilne ure / Consultations:</strong> Tue 10-12</p>"
+        let inline = Some(label)
+            .filter(|l| matches!(l.value().name(), "strong" | "b" | "span"))
+            .and_then(|l| l.parent())
+            .and_then(ElementRef::wrap)
+            .map(|p| collapse(&p.text().collect::<Vec<_>>().join(" ")))
+            .and_then(|p| p.strip_prefix(&label_text).map(|rest| rest.trim_start_matches([':', ' ']).to_string()))
+            .filter(|rest| !rest.is_empty());
+        inline.or_else(|| {
+            let next = label.next_siblings().find_map(ElementRef::wrap)?;
+            let value = collapse(&next.text().collect::<Vec<_>>().join(" "));
+            (!value.is_empty()).then_some(value)
+        })
+    })
+}
+
+fn is_group_link(s: &str) -> bool {
+    let words: Vec<&str> = s.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    words.windows(2).any(|w| w == ["research", "group"] || w == ["raziskovalna", "skupina"])
+        || words.iter().any(|w| ["lab", "laboratory", "laboratorij"].contains(w))
+}
+
 
 impl StaffProfile {
+    /// Department in English, or in Slovenian when only that is given.
+    pub fn department(&self) -> Option<&str> {
+        self.department_en.as_deref().or(self.department_sl.as_deref())
+    }
+
+    /// Resolves a relative photo URL against the profile page it was parsed from.
+    pub fn with_base_url(mut self, profile_url: &str) -> Self {
+        if let Some(photo) = &self.photo_url
+            && let Ok(absolute) = Url::parse(profile_url).and_then(|base| base.join(photo.trim()))
+        {
+            self.photo_url = Some(absolute.to_string());
+        }
+        self
+    }
+
     /// Render the profile as a Markdown string.
     pub fn to_markdown(&self) -> String {
         // ------- collect rows we actually have -----------------------------
@@ -176,9 +297,15 @@ impl StaffProfile {
                 format!("[{}](mailto:{})", email, email),
             ));
         }
+        if let Some(ref hours) = self.consultation_hours {
+            rows.push(("Office hours".into(), hours.clone()));
+        }
         if let Some(ref site) = self.website {
             rows.push(("Website".into(), site.clone()));
         }
+        if let Some(ref photo) = self.photo_url {
+            rows.push(("Photo".into(), format!("![photo]({})", photo)));
+        }
         if self.department_sl.is_some() || self.department_en.is_some() {
             rows.push((
                 "Department".into(),
@@ -196,7 +323,11 @@ impl StaffProfile {
                 self.research_fields.join(", "),
             ));
         }
-        if self.bibliography_si.is_some() || self.bibliography_en.is_some() {
+        if self.bibliography_si.is_some()
+            || self.bibliography_en.is_some()
+            || self.orcid.is_some()
+            || self.scholar.is_some()
+        {
             let mut links = Vec::new();
             if let Some(ref si) = self.bibliography_si {
                 links.push(format!("[SI]({})", si));
@@ -204,6 +335,12 @@ impl StaffProfile {
             if let Some(ref en) = self.bibliography_en {
                 links.push(format!("[EN]({})", en));
             }
+            if let Some(ref orcid) = self.orcid {
+                links.push(format!("[ORCID]({})", orcid));
+            }
+            if let Some(ref scholar) = self.scholar {
+                links.push(format!("[Google Scholar]({})", scholar));
+            }
             rows.push(("Bibliography".into(), links.join(" · ")));
         }
         if !self.teaching_sl.is_empty() || !self.teaching_en.is_empty() {
@@ -242,8 +379,165 @@ impl StaffProfile {
     }
 }
 
+/// Maps every department to the staff members in it, sorted by name.
+/// Profiles without a department are left out.
+pub fn build_department_index(profiles: &[(String, StaffProfile)]) -> BTreeMap<String, Vec<String>> {
+    let mut index: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, profile) in profiles {
+        if let Some(department) = profile.department().map(str::trim).filter(|d| !d.is_empty()) {
+            index.entry(department.to_string()).or_default().push(name.clone());
+        }
+    }
+    for names in index.values_mut() {
+        names.sort();
+        names.dedup();
+    }
+    index
+}
+
+/// Office-hours table rows (`| name | hours |`) of the staff whose
+/// department contains `department`, with the department name as written on
+/// the first matching profile. Staff without published hours are listed as
+/// "not published".
+pub fn consultation_hours_rows(profiles: &[(String, StaffProfile)], department: &str) -> Option<(String, Vec<String>)> {
+    let query = department.to_lowercase();
+    let mut department_name = None;
+    let mut rows = Vec::new();
+    for (name, profile) in profiles {
+        let Some(department) = profile.department() else {
+            continue;
+        };
+        if !department.to_lowercase().contains(&query) {
+            continue;
+        }
+        department_name.get_or_insert_with(|| department.to_string());
+        let hours = profile.consultation_hours.as_deref().unwrap_or("not published");
+        rows.push(format!("| {} | {} |", name, hours.replace('|', "\\|")));
+    }
+    department_name.map(|department| (department, rows))
+}
+
 impl fmt::Display for StaffProfile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.to_markdown())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE_URL: &str = "https://www.famnit.upr.si/en/about-faculty/staff/jana.novak/";
+
+    const PROFILE: &str = r#"<html><body>
+        <h1 itemprop="name">Jana Novak</h1>
+        <h2 itemprop="title">izredna profesorica <span>Associate Professor</span></h2>
+        <img class="person-img-pedagoska" src="/images/staff/novak.jpg">
+        <table>
+            <tr><th>Kabinet</th><td class="kabinet">FAMNIT-GL 12</td></tr>
+            <tr><th>E-mail</th><td class="email"><a href="mailto:jana.novak@famnit.upr.si">jana.novak@famnit.upr.si</a></td></tr>
+            <tr><th>Oddelek</th><td class="departments"><div class="field">Oddelek za matematiko</div><div class="field">Department of Mathematics</div></td></tr>
+            <tr><th>Govorilne ure / Office hours</th><td>Tuesday 10:00-12:00</td></tr>
+            <tr><th>Predmeti</th><td class="subjects"><div class="field">Teorija grafov / Graph Theory</div></td></tr>
+        </table>
+        <p>
+            <a href="https://orcid.org/0000-0002-1825-0097">ORCID</a>
+            <a href="https://scholar.google.com/citations?user=abc123">Google Scholar</a>
+        </p>
+    </body></html>"#;
+
+    /// A profile with a department in both languages and `extra` markup.
+    fn staff(name: &str, department: Option<(&str, &str)>, extra: &str) -> (String, StaffProfile) {
+        let department = department
+            .map(|(sl, en)| format!(r#"<table><tr><td class="departments"><div class="field">{}</div><div class="field">{}</div></td></tr></table>"#, sl, en))
+            .unwrap_or_default();
+        let html = format!(r#"<html><body><h1 itemprop="name">{}</h1>{}{}</body></html>"#, name, department, extra);
+        (name.to_string(), StaffProfile::from(html))
+    }
+
+    #[test]
+    fn consultation_hours_are_listed_for_department_mates() {
+        let profiles = vec![
+            staff("Jana Novak", Some(("Oddelek za matematiko", "Department of Mathematics")), "<table><tr><th>Office hours</th><td>Tuesday 10:00-12:00</td></tr></table>"),
+            staff("Marko Kos", Some(("Oddelek za matematiko", "Department of Mathematics")), ""),
+            staff("Ana Horvat", Some(("Oddelek za informatiko", "Department of Computer Science")), "<table><tr><th>Office hours</th><td>Monday 9-11</td></tr></table>"),
+        ];
+
+        let (department, rows) = consultation_hours_rows(&profiles, "mathematics").unwrap();
+
+        assert_eq!(department, "Department of Mathematics");
+        assert_eq!(rows, ["| Jana Novak | Tuesday 10:00-12:00 |", "| Marko Kos | not published |"]);
+        assert!(consultation_hours_rows(&profiles, "physics").is_none());
+    }
+
+    #[test]
+    fn department_index_filters_staff_by_department() {
+        let profiles = vec![
+            staff("Marko Kos", Some(("Oddelek za matematiko", "Department of Mathematics")), ""),
+            staff("Ana Horvat", Some(("Oddelek za informatiko", "Department of Computer Science")), ""),
+            staff("Jana Novak", Some(("Oddelek za matematiko", "Department of Mathematics")), ""),
+            staff("Maja Zupan", None, ""),
+        ];
+
+        let index = build_department_index(&profiles);
+
+        assert_eq!(index.keys().collect::<Vec<_>>(), ["Department of Computer Science", "Department of Mathematics"]);
+        assert_eq!(index["Department of Mathematics"], ["Jana Novak", "Marko Kos"]);
+        assert_eq!(index["Department of Computer Science"], ["Ana Horvat"]);
+
+        let departments: Vec<String> = index.keys().cloned().collect();
+        let best = crate::util::rank_names(departments, "Mathematics", crate::util::NameMetric::Trigram);
+        assert_eq!(best[0], "Department of Mathematics");
+    }
+
+    #[test]
+    fn relative_photo_url_is_made_absolute() {
+        let profile = StaffProfile::from(PROFILE.to_string()).with_base_url(PROFILE_URL);
+
+        assert_eq!(profile.photo_url.as_deref(), Some("https://www.famnit.upr.si/images/staff/novak.jpg"));
+        assert!(profile.to_markdown().contains("| Photo | ![photo](https://www.famnit.upr.si/images/staff/novak.jpg) |"));
+
+        let (_, absolute) = staff("Marko Kos", None, r#"<img class="person-img-pedagoska" src="https://cdn.upr.si/kos.jpg">"#);
+        let absolute = absolute.with_base_url(PROFILE_URL);
+        assert_eq!(absolute.photo_url.as_deref(), Some("https://cdn.upr.si/kos.jpg"));
+    }
+
+    #[test]
+    fn consultation_hours_are_read_from_a_row_or_a_labelled_block() {
+        let profile = StaffProfile::from(PROFILE.to_string());
+        assert_eq!(profile.consultation_hours.as_deref(), Some("Tuesday 10:00-12:00"));
+        assert!(profile.to_markdown().contains("| Office hours | Tuesday 10:00-12:00 |"));
+
+        let (_, inline) = staff("Marko Kos", None, "<p><strong>Govorilne ure / Consultations:</strong> Wednesday 14-16</p>");
+        assert_eq!(inline.consultation_hours.as_deref(), Some("Wednesday 14-16"));
+
+        let (_, heading) = staff("Marko Kos", None, "<h3>Office hours</h3><p>Monday 9-11, room FAMNIT-GL 3</p>");
+        assert_eq!(heading.consultation_hours.as_deref(), Some("Monday 9-11, room FAMNIT-GL 3"));
+
+        let (_, same_halves) = staff("Marko Kos", None, "<table><tr><th>Konzultacije</th><td>Po dogovoru / po dogovoru</td></tr></table>");
+        assert_eq!(same_halves.consultation_hours.as_deref(), Some("Po dogovoru"));
+    }
+
+    #[test]
+    fn missing_consultation_hours_are_none() {
+        let (_, profile) = staff("Marko Kos", None, "<p><strong>Research</strong> Graph theory</p>");
+
+        assert_eq!(profile.consultation_hours, None);
+        assert!(!profile.to_markdown().contains("Office hours"));
+    }
+
+    #[test]
+    fn orcid_and_scholar_links_are_extracted_and_rendered() {
+        let profile = StaffProfile::from(PROFILE.to_string());
+
+        assert_eq!(profile.orcid.as_deref(), Some("https://orcid.org/0000-0002-1825-0097"));
+        assert_eq!(profile.scholar.as_deref(), Some("https://scholar.google.com/citations?user=abc123"));
+        let markdown = profile.to_markdown();
+        assert!(markdown.contains("[ORCID](https://orcid.org/0000-0002-1825-0097)"));
+        assert!(markdown.contains("[Google Scholar](https://scholar.google.com/citations?user=abc123)"));
+
+        let (_, without) = staff("Marko Kos", None, "");
+        assert_eq!((&without.orcid, &without.scholar), (&None, &None));
+        assert!(!without.to_markdown().contains("Bibliography"));
+    }
+}
@@ -0,0 +1,204 @@
+//! Research groups and labs of the faculty.
+//!
+//! A staff member's group is found either through a link on their profile
+//! or by looking for their name among the members listed on each group
+//! page of the research groups overview.
+
+use scraper::{ElementRef, Html, Selector};
+use std::fmt;
+
+use crate::util::name_similarity;
+
+pub const RESEARCH_GROUPS_URL: &str = "https://www.famnit.upr.si/en/research/research-groups";
+
+/// Maximum length of the summary taken from the group page.
+const SUMMARY_MAX_CHARS: usize = 600;
+/// Minimum similarity for a listed member to match the staff member's name.
+const MEMBER_MATCH_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Clone)]
+pub struct ResearchGroup {
+    pub name: String,
+    pub url: String,
+    pub summary: String,
+    pub members: Vec<String>,
+}
+
+fn text(er: &ElementRef) -> String {
+    er.text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Extract `(group name, group url)` pairs from the research groups overview.
+pub fn parse_group_links(html: &str) -> Vec<(String, String)> {
+    let doc = Html::parse_document(html);
+    let a_sel = Selector::parse("div.content a[href]").unwrap();
+
+    doc.select(&a_sel)
+        .filter_map(|a| {
+            let url = a.value().attr("href")?.to_string();
+            let name = text(&a);
+            (url.starts_with("http") && !name.is_empty()).then_some((name, url))
+        })
+        .collect()
+}
+
+impl ResearchGroup {
+    /// Parses a group page. Members are taken from the list following a
+    /// "Members" / "Člani" heading, or else from links to staff profiles.
+    pub fn parse(html: &str, url: &str) -> Option<Self> {
+        let doc = Html::parse_document(html);
+        let h1_sel = Selector::parse("h1").unwrap();
+        let p_sel = Selector::parse("div.content p").unwrap();
+        let heading_sel = Selector::parse("div.content h2, div.content h3").unwrap();
+        let li_sel = Selector::parse("li").unwrap();
+        let staff_sel = Selector::parse("div.content a[href*='/staff/'], div.content a[href*='/osebje/']").unwrap();
+
+        let name = doc.select(&h1_sel).next().map(|h| text(&h)).filter(|n| !n.is_empty())?;
+
+        let mut summary = String::new();
+        for p in doc.select(&p_sel) {
+            let para = text(&p);
+            if para.is_empty() {
+                continue;
+            }
+            if !summary.is_empty() && summary.len() + para.len() > SUMMARY_MAX_CHARS {
+                break;
+            }
+            if !summary.is_empty() {
+                summary.push(' ');
+            }
+            summary.push_str(&para);
+        }
+
+        let mut members: Vec<String> = doc
+            .select(&heading_sel)
+            .find(|h| {
+                let h = text(h).to_lowercase();
+                h.contains("member") || h.contains("člani") || h.contains("team")
+            })
+            .and_then(|h| h.next_siblings().filter_map(ElementRef::wrap).find(|e| matches!(e.value().name(), "ul" | "ol")))
+            .map(|list| list.select(&li_sel).map(|li| text(&li)).filter(|m| !m.is_empty()).collect())
+            .unwrap_or_default();
+        if members.is_empty() {
+            for a in doc.select(&staff_sel) {
+                let member = text(&a);
+                if !member.is_empty() && !members.contains(&member) {
+                    members.push(member);
+                }
+            }
+        }
+
+        Some(Self {
+            name,
+            url: url.to_string(),
+            summary,
+            members,
+        })
+    }
+
+    /// Whether `name` is listed as a member. Member entries may carry titles
+    /// ("Assoc. Prof. Jana Novak, PhD"), so containment is checked before
+    /// similarity.
+    pub fn has_member(&self, name: &str) -> bool {
+        let name_lower = name.to_lowercase();
+        self.members.iter().any(|m| {
+            m.to_lowercase().contains(&name_lower)
+                || name_similarity(m, name) >= MEMBER_MATCH_THRESHOLD
+        })
+    }
+}
+
+impl fmt::Display for ResearchGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "## {}\n", self.name)?;
+        if !self.summary.is_empty() {
+            writeln!(f, "{}\n", self.summary)?;
+        }
+        if !self.members.is_empty() {
+            writeln!(f, "**Members:**")?;
+            for member in &self.members {
+                writeln!(f, "- {}", member)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "*Source: [{}]({})*", self.url, self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROUP_URL: &str = "https://www.famnit.upr.si/en/research/research-groups/discrete-mathematics";
+
+    const GROUP_PAGE: &str = r#"<html><body>
+        <h1>Discrete Mathematics Research Group</h1>
+        <div class="content">
+            <p>The group studies symmetries of graphs and other combinatorial structures.</p>
+            <p></p>
+            <p>It runs a weekly seminar open to students.</p>
+            <h2>Members</h2>
+            <ul>
+                <li>Prof. Jana Novak, PhD</li>
+                <li>Assoc. Prof. Marko Kos, PhD</li>
+            </ul>
+            <p>Contact: <a href="https://www.famnit.upr.si/en/about-faculty/staff/ana.horvat/">Ana Horvat</a></p>
+        </div>
+    </body></html>"#;
+
+    #[test]
+    fn group_page_is_parsed_with_its_member_list() {
+        let group = ResearchGroup::parse(GROUP_PAGE, GROUP_URL).unwrap();
+
+        assert_eq!(group.name, "Discrete Mathematics Research Group");
+        assert!(group.summary.starts_with("The group studies symmetries of graphs"));
+        assert!(group.summary.contains("It runs a weekly seminar"));
+        assert_eq!(group.members, ["Prof. Jana Novak, PhD", "Assoc. Prof. Marko Kos, PhD"]);
+        assert!(group.has_member("Jana Novak"));
+        assert!(group.has_member("marko kos"));
+        assert!(!group.has_member("Ana Horvat"));
+
+        let markdown = group.to_string();
+        assert!(markdown.starts_with("## Discrete Mathematics Research Group"));
+        assert!(markdown.contains("- Assoc. Prof. Marko Kos, PhD"));
+        assert!(markdown.ends_with(&format!("*Source: [{}]({})*", GROUP_URL, GROUP_URL)));
+    }
+
+    #[test]
+    fn members_fall_back_to_linked_staff_profiles() {
+        let html = r#"<html><body><h1>Bioinformatics Lab</h1><div class="content">
+            <p>Led by <a href="https://www.famnit.upr.si/en/about-faculty/staff/ana.horvat/">Ana Horvat</a>
+            with <a href="https://www.famnit.upr.si/sl/o-fakulteti/osebje/maja.zupan/">Maja Zupan</a>.</p>
+            <p>Head: <a href="https://www.famnit.upr.si/en/about-faculty/staff/ana.horvat/">Ana Horvat</a></p>
+        </div></body></html>"#;
+
+        let group = ResearchGroup::parse(html, GROUP_URL).unwrap();
+
+        assert_eq!(group.members, ["Ana Horvat", "Maja Zupan"]);
+        assert!(group.has_member("Maja Zupan"));
+    }
+
+    #[test]
+    fn page_without_a_title_is_not_a_group() {
+        assert!(ResearchGroup::parse(r#"<html><body><div class="content"><p>Page moved.</p></div></body></html>"#, GROUP_URL).is_none());
+    }
+
+    #[test]
+    fn overview_lists_absolute_group_links() {
+        let html = r#"<html><body><div class="content">
+            <a href="https://www.famnit.upr.si/en/research/research-groups/discrete-mathematics">Discrete Mathematics</a>
+            <a href="/en/research/projects">Projects</a>
+            <a href="https://www.famnit.upr.si/en/research/research-groups/bioinformatics"> </a>
+        </div></body></html>"#;
+
+        assert_eq!(parse_group_links(html), [(
+            "Discrete Mathematics".to_string(),
+            "https://www.famnit.upr.si/en/research/research-groups/discrete-mathematics".to_string()
+        )]);
+    }
+}
@@ -0,0 +1,204 @@
+//! Candidate PhD supervisors by research area.
+//!
+//! Staff who teach a course of a doctoral programme or coordinate one are
+//! matched against a topic through the research fields listed on their
+//! profiles.
+
+use std::fmt;
+
+use crate::{
+    courses::same_course,
+    profile::StaffProfile,
+    util::{fold_diacritics, name_similarity},
+};
+
+/// Minimum match between the topic and a research field.
+pub const TOPIC_MATCH_THRESHOLD: f64 = 0.5;
+
+const STOPWORDS: [&str; 8] = ["and", "the", "for", "with", "from", "into", "in", "of"];
+
+#[derive(Debug, Clone)]
+pub struct SupervisorMatch {
+    pub name: String,
+    pub profile_url: String,
+    pub profile: StaffProfile,
+    pub score: f64,
+    pub matched_fields: Vec<String>,
+    /// How the staff member is involved in doctoral studies.
+    pub doctoral_roles: Vec<String>,
+}
+
+fn words(s: &str) -> Vec<String> {
+    fold_diacritics(&s.to_lowercase())
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() >= 3 && !STOPWORDS.contains(w))
+        .map(str::to_string)
+        .collect()
+}
+
+/// How well `field` covers `topic`: the share of topic words found in the
+/// field ("graph" matches "graphs"), or their trigram similarity if higher.
+pub fn topic_score(topic: &str, field: &str) -> f64 {
+    let topic_words = words(topic);
+    if topic_words.is_empty() {
+        return 0.0;
+    }
+    let field_words = words(field);
+    let same_stem = |a: &str, b: &str| {
+        a == b || (a.len().min(b.len()) >= 4 && (a.starts_with(b) || b.starts_with(a)))
+    };
+    let covered = topic_words
+        .iter()
+        .filter(|t| field_words.iter().any(|f| same_stem(t, f)))
+        .count();
+    (covered as f64 / topic_words.len() as f64).max(name_similarity(topic, field))
+}
+
+/// Doctoral programmes the staff member coordinates and doctoral courses
+/// they teach.
+pub fn doctoral_roles(profile: &StaffProfile, doctoral_courses: &[String]) -> Vec<String> {
+    let mut roles: Vec<String> = profile
+        .coordinator_en
+        .iter()
+        .chain(profile.coordinator_sl.iter())
+        .filter(|c| {
+            let c = c.to_lowercase();
+            c.contains("doctoral") || c.contains("phd") || c.contains("doktorsk")
+        })
+        .map(|c| format!("Coordinator: {}", c))
+        .collect();
+    for course in doctoral_courses {
+        if profile.teaching_en.iter().any(|t| same_course(t, course)) {
+            roles.push(format!("Teaches: {}", course));
+        }
+    }
+    roles.dedup();
+    roles
+}
+
+/// Staff involved in doctoral studies whose research fields match `topic`,
+/// best match first. `profiles` holds `(name, profile url, profile)`.
+pub fn find_supervisors(
+    topic: &str,
+    profiles: &[(String, String, StaffProfile)],
+    doctoral_courses: &[String],
+) -> Vec<SupervisorMatch> {
+    let mut matches: Vec<SupervisorMatch> = profiles
+        .iter()
+        .filter_map(|(name, url, profile)| {
+            let doctoral_roles = doctoral_roles(profile, doctoral_courses);
+            if doctoral_roles.is_empty() {
+                return None;
+            }
+            let scored: Vec<(String, f64)> = profile
+                .research_fields
+                .iter()
+                .map(|f| (f.clone(), topic_score(topic, f)))
+                .filter(|(_, score)| *score >= TOPIC_MATCH_THRESHOLD)
+                .collect();
+            let score = scored.iter().map(|(_, s)| *s).fold(0.0, f64::max);
+            (!scored.is_empty()).then(|| SupervisorMatch {
+                name: name.clone(),
+                profile_url: url.clone(),
+                profile: profile.clone(),
+                score,
+                matched_fields: scored.into_iter().map(|(f, _)| f).collect(),
+                doctoral_roles,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    matches
+}
+
+impl fmt::Display for SupervisorMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = if self.profile.title_en.is_empty() { &self.profile.title_sl } else { &self.profile.title_en };
+        writeln!(f, "### {} ({:.2})\n", self.name, self.score)?;
+        if !title.is_empty() {
+            writeln!(f, "- Title: {}", title)?;
+        }
+        writeln!(f, "- Matching research fields: {}", self.matched_fields.join(", "))?;
+        writeln!(f, "- Doctoral studies: {}", self.doctoral_roles.join("; "))?;
+        if let Some(email) = &self.profile.email {
+            writeln!(f, "- Email: [{}](mailto:{})", email, email)?;
+        }
+        if let Some(office) = &self.profile.office {
+            writeln!(f, "- Office: {}", office)?;
+        }
+        if let Some(phone) = &self.profile.phone {
+            writeln!(f, "- Phone: {}", phone)?;
+        }
+        write!(f, "- Profile: [{}]({})", self.profile_url, self.profile_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A staff profile with research fields, taught courses and coordinated
+    /// programmes, the latter two as "Slovenian / English" pairs.
+    fn staff(name: &str, research: &[&str], teaching: &[&str], coordinator: &[&str]) -> (String, String, StaffProfile) {
+        let fields = |values: &[&str]| -> String {
+            values.iter().map(|v| format!(r#"<div class="field">{}</div>"#, v)).collect()
+        };
+        let html = format!(
+            r#"<html><body><h1 itemprop="name">{}</h1><table>
+                <tr><td class="email"><a href="mailto:x@famnit.upr.si">x@famnit.upr.si</a></td></tr>
+                <tr><td class="research">{}</td></tr>
+                <tr><td class="subjects">{}</td></tr>
+                <tr class="last"><td class="text">{}</td></tr>
+            </table></body></html>"#,
+            name,
+            fields(research),
+            fields(teaching),
+            fields(coordinator)
+        );
+        let url = format!("https://www.famnit.upr.si/en/about-faculty/staff/{}/", name.to_lowercase().replace(' ', "."));
+        (name.to_string(), url, StaffProfile::from(html))
+    }
+
+    #[test]
+    fn topic_words_match_research_fields_by_stem() {
+        assert_eq!(topic_score("graph theory", "Graph theory and combinatorics"), 1.0);
+        assert_eq!(topic_score("graphs", "Graph theory"), 1.0);
+        assert_eq!(topic_score("Kriptografija", "kriptografija"), 1.0);
+        assert!(topic_score("machine learning", "Graph theory") < TOPIC_MATCH_THRESHOLD);
+        assert_eq!(topic_score("of the", "Graph theory"), 0.0);
+    }
+
+    #[test]
+    fn only_staff_in_doctoral_studies_are_ranked_by_topic() {
+        let doctoral_courses = vec!["Selected Topics in Graph Theory".to_string()];
+        let profiles = vec![
+            staff("Marko Kos", &["Graph theory"], &["Izbrana poglavja iz teorije grafov / Selected Topics in Graph Theory"], &[]),
+            staff("Jana Novak", &["Spectral graph theory"], &[], &["Matematika (doktorski študij) / Mathematical Sciences (doctoral programme)"]),
+            staff("Ana Horvat", &["Spectral graph theory"], &["Teorija grafov / Graph Theory"], &[]),
+            staff("Maja Zupan", &["Bioinformatics"], &[], &["Bioinformatika (doktorski študij) / Bioinformatics (doctoral programme)"]),
+        ];
+
+        let matches = find_supervisors("spectral graph theory", &profiles, &doctoral_courses);
+
+        let ranked: Vec<&str> = matches.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(ranked, ["Jana Novak", "Marko Kos"]);
+        assert!(matches[0].score > matches[1].score);
+        assert!(matches[1].score >= TOPIC_MATCH_THRESHOLD);
+        assert_eq!(matches[0].doctoral_roles, [
+            "Coordinator: Mathematical Sciences (doctoral programme)",
+            "Coordinator: Matematika (doktorski študij)",
+        ]);
+        assert_eq!(matches[1].doctoral_roles, ["Teaches: Selected Topics in Graph Theory"]);
+
+        let entry = matches[0].to_string();
+        assert!(entry.contains("- Matching research fields: Spectral graph theory"));
+        assert!(entry.contains("- Email: [x@famnit.upr.si](mailto:x@famnit.upr.si)"));
+        assert!(entry.ends_with("- Profile: [https://www.famnit.upr.si/en/about-faculty/staff/jana.novak/](https://www.famnit.upr.si/en/about-faculty/staff/jana.novak/)"));
+    }
+}
@@ -1,21 +1,66 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
 use anyhow::Result;
 use reagent::{Message, Role};
-use rmcp::{model::{CallToolRequestParam, ClientCapabilities, ClientInfo, Implementation}, transport::SseClientTransport, ServiceExt};
+use rmcp::{model::{CallToolRequestParam, ClientCapabilities, ClientInfo, Implementation}, schemars, transport::SseClientTransport, ServiceExt};
 use scraper::{Html, Selector};
+use tokio::sync::Mutex;
+use serde::Deserialize;
 
 use crate::{MEMORY_MCP_URL, SCRAPER_MCP_URL};
 
-pub fn rank_names(names: Vec<String>, query: &str) -> Vec<String> {
-    let normalized_query = normalize_ščćžš(query);
-    let q_vec = trigram_vec(&normalized_query.to_lowercase());
+/// String similarity used to rank names against a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NameMetric {
+    /// Cosine similarity of character trigrams. Robust to word order.
+    #[default]
+    Trigram,
+    /// Normalized edit distance. Tolerates transposed or mistyped characters.
+    Levenshtein,
+    /// Favours shared prefixes, suited to short fragments and initials.
+    JaroWinkler,
+}
+
+impl NameMetric {
+    /// Similarity in `0.0..=1.0` of two lowercased strings.
+    fn similarity(&self, a: &str, b: &str) -> f64 {
+        match self {
+            NameMetric::Trigram => cosine_sim(&trigram_vec(a), &trigram_vec(b)),
+            NameMetric::Levenshtein => levenshtein_sim(&fold_diacritics(a), &fold_diacritics(b)),
+            NameMetric::JaroWinkler => jaro_winkler(&fold_diacritics(a), &fold_diacritics(b)),
+        }
+    }
+
+    /// Scores `name` against `query`. The character metrics compare the
+    /// query with the full name and with each of its words, so "kavsek"
+    /// is not penalized for the missing given name.
+    fn score(&self, name: &str, query: &str) -> f64 {
+        match self {
+            NameMetric::Trigram => self.similarity(name, query),
+            _ => name
+                .split_whitespace()
+                .map(|word| self.similarity(word, query))
+                .fold(self.similarity(name, query), f64::max),
+        }
+    }
+}
+
+/// Names ordered from most to least similar to `query`, each with its
+/// similarity in `0.0..=1.0`.
+pub fn rank_names_scored(names: Vec<String>, query: &str, metric: NameMetric) -> Vec<(String, f64)> {
+    // diacritics are folded while scoring, the returned names keep them
+    let normalized_query = query.to_lowercase();
 
     let mut scored_names: Vec<(String, f64)> = names
         .into_iter()
         .map(|original_name| {
-            let normalized_name = normalize_ščćžš(&original_name);
-            let name_vec = trigram_vec(&normalized_name.to_lowercase());
-            let similarity = cosine_sim(&name_vec, &q_vec);
+            let normalized_name = original_name.to_lowercase();
+            let similarity = metric.score(&normalized_name, &normalized_query).clamp(0.0, 1.0);
             (original_name, similarity) // Pair the original name with its score
         })
         .collect();
@@ -27,17 +72,45 @@ pub fn rank_names(names: Vec<String>, query: &str) -> Vec<String> {
             .unwrap_or(std::cmp::Ordering::Equal)
             .then_with(|| a_name.cmp(b_name))
     });
-    scored_names.into_iter().map(|(name, _)| name).collect()
+    scored_names
 }
 
-fn normalize_ščćžš(s: &str) -> String {
+pub fn rank_names(names: Vec<String>, query: &str, metric: NameMetric) -> Vec<String> {
+    rank_names_scored(names, query, metric)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Trigram cosine similarity of two names, ignoring case and diacritics.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    cosine_sim(
+        &trigram_vec(&a.to_lowercase()),
+        &trigram_vec(&b.to_lowercase()),
+    )
+}
+
+/// Strips diacritics so "Kavšek" and "Kavsek" compare equal. Covers the
+/// Slovenian letters and their neighbours (č, ć, š, ž, đ) as precomposed
+/// characters, and drops combining marks, so decomposed input such as
+/// "s\u{30C}" folds the same way.
+pub fn fold_diacritics(s: &str) -> String {
     s.chars()
+        .filter(|c| !('\u{300}'..='\u{36F}').contains(c))
         .map(|c| match c {
-            'š' | 'Š' => 's',
-            'č' | 'Č' => 'c',
-            'ć' | 'Ć' => 'c',
-            'ž' | 'Ž' => 'z',
-            'đ' | 'Đ' => 'd',
+            'š' => 's',
+            'Š' => 'S',
+            'č' | 'ć' => 'c',
+            'Č' | 'Ć' => 'C',
+            'ž' => 'z',
+            'Ž' => 'Z',
+            'đ' => 'd',
+            'Đ' => 'D',
+            'á' | 'à' | 'ä' => 'a',
+            'é' | 'è' | 'ë' => 'e',
+            'í' | 'ì' => 'i',
+            'ó' | 'ò' | 'ö' => 'o',
+            'ú' | 'ù' | 'ü' => 'u',
             _ => c,
         })
         .collect()
@@ -45,10 +118,11 @@ fn normalize_ščćžš(s: &str) -> String {
 
 /// Build a (trigram → frequency) sparse vector.
 fn trigram_vec(s: &str) -> HashMap<String, usize> {
+    let s = fold_diacritics(s);
     let chars: Vec<char> = s.chars().collect();
     if chars.len() < 3 {
         // For very short strings use the whole string as one “token”
-        return HashMap::from([(s.to_string(), 1)]);
+        return HashMap::from([(s, 1)]);
     }
 
     let mut v = HashMap::new();
@@ -78,6 +152,69 @@ fn cosine_sim(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
     }
 }
 
+/// Edit distance normalized by the longer string, as a similarity.
+fn levenshtein_sim(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    1.0 - prev[b.len()] as f64 / longest as f64
+}
+
+/// Jaro-Winkler similarity with the usual 0.1 prefix scale over at most
+/// four characters.
+fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+        for j in start..end {
+            if !b_matched[j] && b[j] == *ca {
+                a_matched[i] = true;
+                b_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let a_seq = a.iter().zip(&a_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let b_seq = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| c);
+    let transpositions = a_seq.zip(b_seq).filter(|(x, y)| x != y).count() / 2;
+
+    let m = matches as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
 
 /// New helper function to serialize a conversation history into a single prompt for the memory agent.
 pub fn history_to_memory_prompt(history: Vec<Message>) -> String {
@@ -102,7 +239,194 @@ pub fn history_to_memory_prompt(history: Vec<Message>) -> String {
 
 
 
+const DEFAULT_PAGE_CACHE_TTL_SECS: u64 = 900;
+const DEFAULT_PAGE_CACHE_MAX_ENTRIES: usize = 256;
+
+/// Fetched pages keyed by URL, shared by all tools. The lifetime of an entry
+/// is read from `PAGE_CACHE_TTL_SECS` (`0` disables caching) and the number
+/// of entries kept from `PAGE_CACHE_MAX_ENTRIES`.
+static PAGE_CACHE: LazyLock<Mutex<PageCache>> = LazyLock::new(|| {
+    let ttl = std::env::var("PAGE_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_PAGE_CACHE_TTL_SECS);
+    let max_entries = std::env::var("PAGE_CACHE_MAX_ENTRIES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_PAGE_CACHE_MAX_ENTRIES);
+    Mutex::new(PageCache::new(Duration::from_secs(ttl), max_entries))
+});
+
+/// Map of `url -> (fetched at, content)` whose entries expire after `ttl`.
+/// Expired entries are dropped on insert; when it is still full, the oldest
+/// entry makes room for the new one.
+#[derive(Debug)]
+pub struct PageCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: HashMap<String, (Instant, String)>,
+}
+
+impl PageCache {
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self { ttl, max_entries, entries: HashMap::new() }
+    }
+
+    pub fn get(&mut self, url: &str) -> Option<String> {
+        let (fetched_at, _) = self.entries.get(url)?;
+        if fetched_at.elapsed() >= self.ttl {
+            self.entries.remove(url);
+            return None;
+        }
+        self.entries.get(url).map(|(_, content)| content.clone())
+    }
+
+    pub fn insert(&mut self, url: String, content: String) {
+        if self.ttl.is_zero() || self.max_entries == 0 {
+            return;
+        }
+        let ttl = self.ttl;
+        self.entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < ttl);
+        if self.entries.len() >= self.max_entries && !self.entries.contains_key(&url) {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (fetched_at, _))| *fetched_at)
+                .map(|(url, _)| url.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(url, (Instant::now(), content));
+    }
+
+    /// Drops every entry and returns how many there were.
+    pub fn clear(&mut self) -> usize {
+        let cleared = self.entries.len();
+        self.entries.clear();
+        cleared
+    }
+}
+
+/// Empties the page cache so the next requests refetch from the website.
+pub async fn clear_page_cache() -> usize {
+    PAGE_CACHE.lock().await.clear()
+}
+
+/// Returns the page content, from the cache while it is fresh.
+///
+/// Failed fetches are retried, and a page missing under `/en/` is looked up
+/// under `/sl/` (and the other way around). Errors are `PageError`s.
 pub async fn get_page<T>(url: T) -> Result<String> where T: Into<String> {
+    cached_page(&PAGE_CACHE, url.into(), |url| fetch_with_fallback(url, fetch_page)).await
+}
+
+/// `url` from `cache` while it is fresh, otherwise from `fetch`, caching
+/// the result.
+async fn cached_page<F, Fut>(cache: &Mutex<PageCache>, url: String, fetch: F) -> Result<String>
+where
+    F: FnOnce(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    if let Some(content) = cache.lock().await.get(&url) {
+        return Ok(content);
+    }
+    let content = fetch(url.clone()).await?;
+    cache.lock().await.insert(url, content.clone());
+    Ok(content)
+}
+
+/// Fetches `url` with `fetch`, falling back to the other language prefix
+/// when the page does not exist.
+async fn fetch_with_fallback<F, Fut>(url: String, fetch: F) -> Result<String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    match fetch_with_retry(&url, &fetch).await {
+        Ok(content) => Ok(content),
+        Err(PageError::NotFound { .. }) => {
+            let Some(alternate) = swap_language(&url) else {
+                return Err(PageError::NotFound { url, alternate: None }.into());
+            };
+            match fetch_with_retry(&alternate, &fetch).await {
+                Ok(content) => Ok(content),
+                Err(PageError::NotFound { .. }) => {
+                    Err(PageError::NotFound { url, alternate: Some(alternate) }.into())
+                }
+                Err(e) => Err(e.into()),
+            }
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+const FETCH_ATTEMPTS: u32 = 3;
+const FETCH_BACKOFF_MS: u64 = 250;
+
+/// Why a page could not be fetched.
+#[derive(Debug)]
+pub enum PageError {
+    /// The page does not exist, also not under `alternate` when the other
+    /// language prefix was tried.
+    NotFound { url: String, alternate: Option<String> },
+    /// The scraper could not be reached or kept failing.
+    Transport { url: String, error: String },
+}
+
+impl std::fmt::Display for PageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PageError::NotFound { url, alternate: Some(alternate) } => {
+                write!(f, "Page not found: {} (also tried {})", url, alternate)
+            }
+            PageError::NotFound { url, alternate: None } => write!(f, "Page not found: {}", url),
+            PageError::Transport { url, error } => write!(f, "Could not fetch {}: {}", url, error),
+        }
+    }
+}
+
+impl std::error::Error for PageError {}
+
+/// The same URL under the other language prefix (`/en/` ↔ `/sl/`).
+fn swap_language(url: &str) -> Option<String> {
+    if url.contains("/en/") {
+        Some(url.replacen("/en/", "/sl/", 1))
+    } else if url.contains("/sl/") {
+        Some(url.replacen("/sl/", "/en/", 1))
+    } else {
+        None
+    }
+}
+
+/// Fetches `url` with `fetch` up to `FETCH_ATTEMPTS` times, doubling the
+/// wait after each failure (250 ms, 500 ms, ...). A 404 is final and not
+/// retried.
+async fn fetch_with_retry<F, Fut>(url: &str, fetch: &F) -> std::result::Result<String, PageError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String>>,
+{
+    let mut last_error = String::new();
+    for attempt in 0..FETCH_ATTEMPTS {
+        match fetch(url.to_string()).await {
+            Ok(content) => return Ok(content),
+            Err(e) => {
+                last_error = e.to_string();
+                // the scraper reports the HTTP status in its error message
+                if last_error.contains("status: 404") {
+                    return Err(PageError::NotFound { url: url.to_string(), alternate: None });
+                }
+                if attempt + 1 < FETCH_ATTEMPTS {
+                    tokio::time::sleep(Duration::from_millis(FETCH_BACKOFF_MS << attempt)).await;
+                }
+            }
+        }
+    }
+    Err(PageError::Transport { url: url.to_string(), error: last_error })
+}
+
+async fn fetch_page<T>(url: T) -> Result<String> where T: Into<String> {
     let transport = SseClientTransport::start(SCRAPER_MCP_URL).await?;
     let client_info: rmcp::model::InitializeRequestParam = ClientInfo {
         protocol_version: Default::default(),
@@ -123,7 +447,7 @@ pub async fn get_page<T>(url: T) -> Result<String> where T: Into<String> {
         .clone()
         .call_tool(CallToolRequestParam {
             name: "get_web_page_content".into(),
-            arguments: serde_json::json!({"url": url.into()}).as_object().cloned(),
+            arguments: serde_json::json!({"url": url.into(), "as_markdown": false}).as_object().cloned(),
         })
         .await?;
 
@@ -220,4 +544,246 @@ pub fn staff_html_to_markdown(html: &str) -> HashMap<String, String> {
     names
 }
 
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    const URL: &str = "https://www.famnit.upr.si/en/about-faculty/staff/";
+
+    /// Fetches a fixed page and counts how often it was asked to.
+    async fn counting_fetch(cache: &Mutex<PageCache>, fetches: &AtomicUsize) -> String {
+        cached_page(cache, URL.to_string(), |_| async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            Ok("<html>staff</html>".to_string())
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn second_fetch_within_ttl_hits_the_cache() {
+        let cache = Mutex::new(PageCache::new(Duration::from_secs(60), 8));
+        let fetches = AtomicUsize::new(0);
+
+        assert_eq!(counting_fetch(&cache, &fetches).await, "<html>staff</html>");
+        assert_eq!(counting_fetch(&cache, &fetches).await, "<html>staff</html>");
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn zero_ttl_fetches_every_time() {
+        let cache = Mutex::new(PageCache::new(Duration::ZERO, 8));
+        let fetches = AtomicUsize::new(0);
+
+        counting_fetch(&cache, &fetches).await;
+        counting_fetch(&cache, &fetches).await;
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn full_cache_evicts_the_oldest_entry() {
+        let mut cache = PageCache::new(Duration::from_secs(60), 2);
+        cache.insert("a".into(), "page a".into());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("b".into(), "page b".into());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert("c".into(), "page c".into());
+
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b").as_deref(), Some("page b"));
+        assert_eq!(cache.get("c").as_deref(), Some("page c"));
+    }
+
+    #[test]
+    fn expired_entries_are_dropped_on_insert() {
+        let mut cache = PageCache::new(Duration::from_millis(5), 8);
+        cache.insert("a".into(), "page a".into());
+        std::thread::sleep(Duration::from_millis(10));
+        cache.insert("b".into(), "page b".into());
+
+        assert_eq!(cache.entries.len(), 1);
+        assert!(cache.entries.contains_key("b"));
+    }
+
+    const EN_PAGE: &str = "https://www.famnit.upr.si/en/about-faculty/staff/";
+    const SL_PAGE: &str = "https://www.famnit.upr.si/sl/about-faculty/staff/";
+
+    /// The error a failed `get_web_page_content` call comes back as: the
+    /// scraper's message ("Request to '…' failed with status: 404 Not Found")
+    /// inside the MCP client's error.
+    fn scraper_error(message: String) -> anyhow::Error {
+        rmcp::ServiceError::McpError(rmcp::Error::new(rmcp::model::ErrorCode::INTERNAL_ERROR, message, None)).into()
+    }
+
+    /// Stands in for the scraper server. Serves `pages`, answers any other
+    /// URL with a 404, and fails the first `failures` requests as if the
+    /// site could not be reached.
+    struct MockScraper {
+        pages: HashMap<String, String>,
+        failures: AtomicUsize,
+        requests: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl MockScraper {
+        fn new(pages: &[(&str, &str)], failures: usize) -> Self {
+            MockScraper {
+                pages: pages.iter().map(|(u, p)| (u.to_string(), p.to_string())).collect(),
+                failures: AtomicUsize::new(failures),
+                requests: std::sync::Mutex::new(vec![]),
+            }
+        }
+
+        async fn fetch(&self, url: String) -> Result<String> {
+            self.requests.lock().unwrap().push(url.clone());
+            if self.failures.load(Ordering::SeqCst) > 0 {
+                self.failures.fetch_sub(1, Ordering::SeqCst);
+                return Err(scraper_error(format!("Failed to fetch URL '{}': connection refused", url)));
+            }
+            match self.pages.get(&url) {
+                Some(page) => Ok(page.clone()),
+                None => Err(scraper_error(format!(
+                    "Request to '{}' failed with status: {}",
+                    url,
+                    reqwest::StatusCode::NOT_FOUND
+                ))),
+            }
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transient_failures_are_retried_with_backoff() {
+        let scraper = MockScraper::new(&[(EN_PAGE, "<html>en</html>")], 2);
+        let start = tokio::time::Instant::now();
+
+        let page = fetch_with_fallback(EN_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap();
 
+        assert_eq!(page, "<html>en</html>");
+        assert_eq!(scraper.requests(), [EN_PAGE; 3]);
+        assert_eq!(start.elapsed(), Duration::from_millis(FETCH_BACKOFF_MS + (FETCH_BACKOFF_MS << 1)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn persistent_failures_give_up_after_the_last_attempt() {
+        let scraper = MockScraper::new(&[(EN_PAGE, "<html>en</html>")], 10);
+
+        let error = fetch_with_fallback(EN_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap_err();
+
+        assert!(matches!(error.downcast_ref::<PageError>(), Some(PageError::Transport { .. })));
+        assert!(error.to_string().contains("connection refused"));
+        assert_eq!(scraper.requests().len(), FETCH_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn missing_page_is_not_retried_and_falls_back_to_the_other_language() {
+        let scraper = MockScraper::new(&[(SL_PAGE, "<html>sl</html>")], 0);
+
+        let page = fetch_with_fallback(EN_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap();
+
+        assert_eq!(page, "<html>sl</html>");
+        assert_eq!(scraper.requests(), [EN_PAGE, SL_PAGE]);
+    }
+
+    #[tokio::test]
+    async fn page_missing_in_both_languages_is_not_found() {
+        let scraper = MockScraper::new(&[], 0);
+
+        let error = fetch_with_fallback(SL_PAGE.to_string(), |url| scraper.fetch(url)).await.unwrap_err();
+
+        assert_eq!(error.to_string(), format!("Page not found: {} (also tried {})", SL_PAGE, EN_PAGE));
+        assert_eq!(scraper.requests(), [SL_PAGE, EN_PAGE]);
+    }
+
+    const METRICS: [NameMetric; 3] = [NameMetric::Trigram, NameMetric::Levenshtein, NameMetric::JaroWinkler];
+
+    fn staff_names() -> Vec<String> {
+        ["Branko Kavšek", "Marko Kos", "Jana Novak", "Ana Horvat"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn transposed_letters_rank_the_intended_name_first() {
+        for metric in [NameMetric::Levenshtein, NameMetric::JaroWinkler] {
+            assert_eq!(rank_names(staff_names(), "Kasvek", metric)[0], "Branko Kavšek", "{:?}", metric);
+            assert_eq!(rank_names(staff_names(), "Novka", metric)[0], "Jana Novak", "{:?}", metric);
+        }
+        assert!((levenshtein_sim("kasvek", "kavsek") - 2.0 / 3.0).abs() < 1e-9);
+        assert!(jaro_winkler("martha", "marhta") > levenshtein_sim("martha", "marhta"));
+    }
+
+    #[test]
+    fn diacritics_are_folded_for_every_metric() {
+        assert_eq!(fold_diacritics("Čeh Šuštar Žužek Đurić Ćosić"), "Ceh Sustar Zuzek Duric Cosic");
+        assert_eq!(fold_diacritics("Kavs\u{30C}ek"), "Kavsek");
+
+        for metric in METRICS {
+            let ranked = rank_names_scored(staff_names(), "Branko Kavsek", metric);
+            assert_eq!(ranked[0].0, "Branko Kavšek", "{:?}", metric);
+            assert!((ranked[0].1 - 1.0).abs() < 1e-9, "{:?}", metric);
+        }
+    }
+
+    #[test]
+    fn short_queries_fall_back_to_whole_string_tokens() {
+        assert_eq!(trigram_vec("ka"), HashMap::from([("ka".to_string(), 1)]));
+        assert_eq!(name_similarity("KA", "ka"), 1.0);
+
+        // a two-letter fragment shares no trigram with any name
+        let trigram = rank_names_scored(staff_names(), "ka", NameMetric::Trigram);
+        assert!(trigram.iter().all(|(_, score)| *score == 0.0));
+
+        let jaro_winkler = rank_names_scored(staff_names(), "ka", NameMetric::JaroWinkler);
+        assert_eq!(jaro_winkler[0].0, "Branko Kavšek");
+        assert!(jaro_winkler[0].1 > jaro_winkler[1].1);
+    }
+
+    #[test]
+    fn famnit_surnames_without_diacritics_rank_first() {
+        let names: Vec<String> = [
+            "Dragan Marušič",
+            "Martin Milanič",
+            "Štefko Miklavič",
+            "Klavdija Kutnar",
+            "Branko Kavšek",
+            "Jernej Vičič",
+            "Ademir Hujdurović",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        for (query, expected) in [
+            ("Marusic", "Dragan Marušič"),
+            ("Milanic", "Martin Milanič"),
+            ("Stefko Miklavic", "Štefko Miklavič"),
+            ("Branko Kavsek", "Branko Kavšek"),
+            ("Vicic", "Jernej Vičič"),
+            ("Hujdurovic", "Ademir Hujdurović"),
+        ] {
+            // the original spelling is returned, so it can key the staff map
+            assert_eq!(rank_names(names.clone(), query, NameMetric::default())[0], expected, "{}", query);
+        }
+    }
+
+    #[test]
+    fn scores_are_sorted_descending_within_unit_range() {
+        for metric in METRICS {
+            for query in ["Jana Novak", "novak", "Horvt", "k", ""] {
+                let ranked = rank_names_scored(staff_names(), query, metric);
+
+                assert_eq!(ranked.len(), staff_names().len());
+                assert!(ranked.iter().all(|(_, score)| (0.0..=1.0).contains(score)), "{:?} {:?}", metric, ranked);
+                assert!(ranked.windows(2).all(|w| w[0].1 >= w[1].1), "{:?} {:?}", metric, ranked);
+
+                let names: Vec<String> = ranked.into_iter().map(|(name, _)| name).collect();
+                assert_eq!(rank_names(staff_names(), query, metric), names);
+            }
+        }
+        let (best, score) = &rank_names_scored(staff_names(), "jana novak", NameMetric::Trigram)[0];
+        assert_eq!(best, "Jana Novak");
+        assert!((score - 1.0).abs() < 1e-9);
+    }
+}